@@ -1,4 +1,4 @@
-export type Role = 'employee' | 'issuer' | 'verifier';
+export type Role = 'employee' | 'issuer' | 'verifier' | 'processor'; // processors co-sign credentials naming them
 
 export interface PeerNodeInfo {
   nodeId: string;
@@ -22,11 +22,258 @@ export interface PendingCredentialRequest {
   paymentMode: PaymentMode;
   requestedAt: string;
   status: RequestStatus;
+  undeliveredResponse?: CredentialMessage | null; // not yet acknowledged by the employee
+  deliveredAt?: string | null;
+  approvals?: OfficerApproval[];
+  approvalsRequired?: number; // 0 when the issuer approves on its own
+  draft?: CredentialDraft | null; // signed credential awaiting officer approvals
+}
+
+export interface ValidityWindow {
+  validFrom?: string | null;
+  expiresAt?: string | null;
+}
+
+// Officers of an organizational issuer; `threshold` of them must approve each request
+export interface ApprovalPolicy {
+  officers: string[];
+  threshold: number; // at least 1
+}
+
+export interface PublishedApprovalPolicy {
+  issuerNodeId: string; // key the first version was published under
+  version: number;
+  policy: ApprovalPolicy;
+  publishedAt: string;
+}
+
+export interface PolicySignature {
+  signerNodeId: string;
+  signature: number[];
+}
+
+// Signed by the issuer for version 1, by `threshold` of the previous officers after
+export interface SignedApprovalPolicy {
+  policy: PublishedApprovalPolicy;
+  signatures: PolicySignature[];
+}
+
+export interface ApprovalPolicyStatus {
+  published?: SignedApprovalPolicy | null; // absent while the issuer approves on its own
+  proposed?: SignedApprovalPolicy | null; // awaiting the current officers
+}
+
+export interface RequestTerms {
+  employeeNodeId: string;
+  employeeName: string;
+  grossSalary: string;
+  netSalary: string;
+  currency: string;
+  payPeriod: string;
+  paymentMode: PaymentMode;
+}
+
+// An officer's signature over one form of a credential, carried by the credential
+export interface CredentialApproval {
+  officerNodeId: string;
+  policyVersion: number;
+  approvedAt: string;
+  signature: number[];
+}
+
+export interface OfficerApproval {
+  requestId: string;
+  issuerNodeId: string;
+  officerNodeId: string;
+  credential: CredentialApproval;
+  selectiveCredential: CredentialApproval;
+  thresholdCredential?: CredentialApproval | null;
+}
+
+// Every form of the credential the issuer signed for a request
+export interface CredentialDraft {
+  credential: SignedIncomeCredential;
+  selectiveCredential: SelectiveCredential;
+  thresholdCredential?: ThresholdCredential | null;
+}
+
+// Binds a credential signing key to an issuer node; signed by both keys
+export interface KeyBinding {
+  nodeId: string;
+  signingKey: string;
+  boundAt: string;
+}
+
+export interface SignedKeyBinding {
+  binding: KeyBinding;
+  signature: number[]; // signing key
+  nodeSignature: number[]; // node key
+}
+
+// Retires a signing key: signed by the node key only
+export interface KeyUnbinding {
+  nodeId: string;
+  signingKey: string;
+  unboundAt: string;
+}
+
+export interface SignedKeyUnbinding {
+  unbinding: KeyUnbinding;
+  nodeSignature: number[];
+}
+
+// DID Core document of a did:iroh (served by the node) or did:key
+export interface VerificationMethod {
+  id: string;
+  type: 'Multikey';
+  controller: string;
+  publicKeyMultibase: string;
+}
+
+export interface DidDocument {
+  '@context': string[];
+  id: string;
+  verificationMethod: VerificationMethod[];
+  authentication?: string[];
+  assertionMethod?: string[]; // keys that sign the subject's credentials
+  service?: {
+    id: string;
+    type: 'IrohEndpoint';
+    serviceEndpoint: {
+      endpointId: string; // node answering for the DID, after any key rotation
+      relayUrls?: string[];
+      directAddresses?: string[];
+    };
+  }[];
+}
+
+// Statement by an issuer key that it has been replaced; signed by the previous key
+export interface KeyRotation {
+  previousNodeId: string;
+  newNodeId: string;
+  rotatedAt: string; // credentials the previous key signs from then on are refused
+  reason?: string | null;
+}
+
+export interface SignedKeyRotation {
+  rotation: KeyRotation;
+  signature: number[];
+}
+
+export interface SignedRevocationNotice {
+  notice: {
+    credentialId: string;
+    issuerNodeId: string;
+    reason?: string | null;
+    revokedAt: string;
+  };
+  signature: number[];
+}
+
+export interface SignedStatusList {
+  list: {
+    issuerNodeId: string;
+    version: number;
+    encodedList: number[]; // DEFLATE-compressed bitstring
+    issuedAt: string;
+    nextUpdate: string;
+  };
+  signature: number[];
+}
+
+// What a verifier node knows about its issuers, for verifyCredentialFile
+export interface OfflineTrustStore {
+  trustedIssuers: string[];
+  rotations?: SignedKeyRotation[];
+  keyBindings?: SignedKeyBinding[];
+  keyUnbindings?: SignedKeyUnbinding[];
+  approvalPolicies?: SignedApprovalPolicy[];
+}
+
+export interface OfflineRevocations {
+  statusLists?: SignedStatusList[];
+  notices?: SignedRevocationNotice[];
+}
+
+export interface ApprovalProgress {
+  requestId: string;
+  terms: RequestTerms;
+  requestedAt: string;
+  draft?: CredentialDraft | null; // absent until the issuer signs the credential
+  policyVersion: number; // of the issuer's approval policy
+  approvedBy: string[];
+  approvalsRequired: number;
+  status: RequestStatus;
+}
+
+// W3C VC 2.0 form of an income credential, with an eddsa-jcs-2022 proof
+export interface VcParty {
+  id?: string; // did:key
+  name?: string;
+}
+
+export interface VcCoSignature {
+  signer: string; // did:key
+  role: SignerRole;
+  signedAt: string;
+  signatureValue: string; // multibase
+}
+
+export interface VcApproval {
+  officer: string; // did:key
+  policyVersion: number;
+  approvedAt: string;
+  signatureValue: string; // multibase
+}
+
+export interface VerifiableCredential {
+  '@context': (string | Record<string, unknown>)[]; // VC 2.0, then the embedded income terms
+  id: string;
+  type: string[];
+  issuer: VcParty;
+  validFrom?: string;
+  validUntil?: string;
+  credentialSubject: {
+    id: string; // did:key of the employee
+    name: string;
+    payrollProcessor?: VcParty;
+    grossSalary: string;
+    netSalary: string;
+    currency: string;
+    payPeriod: string;
+    paymentMode: PaymentMode;
+  };
+  issuedAt: string;
+  credentialStatus?: {
+    type: 'BitstringStatusListEntry';
+    statusPurpose: 'revocation';
+    statusListIndex: string;
+    statusListCredential: string; // did:iroh URL of the issuer's status list
+  };
+  credentialSignature: {
+    format: SignatureFormat;
+    signatureValue: string; // multibase
+    signingKey?: string; // did:key, when not the issuer's node key
+    coSignatures?: VcCoSignature[];
+    approvals?: VcApproval[];
+  };
+  proof?: {
+    type: 'DataIntegrityProof';
+    cryptosuite: 'eddsa-jcs-2022';
+    created: string;
+    verificationMethod: string;
+    proofPurpose: 'assertionMethod';
+    proofValue?: string;
+  };
 }
 
 export interface ReceivedCredentialResponse {
   requestId: string;
   credential?: SignedIncomeCredential;
+  selectiveCredential?: SelectiveCredential | null; // absent from older issuers
+  thresholdCredential?: ThresholdCredential | null; // absent from older issuers
+  verifiableCredential?: VerifiableCredential | null; // absent from older issuers
+  jws?: string | null; // JWT-VC, absent from older issuers
   error?: string;
   receivedAt: string;
   issuerNodeId: string;
@@ -39,6 +286,18 @@ export interface VerifiedCredentialRecord {
   issuerNodeId: string;
   isValid: boolean;
   isTrusted: boolean;
+  legacySignature: boolean;
+  validity: ValidityStatus;
+  isRevoked: boolean;
+  revocationReason?: string;
+  statusCheck: StatusCheck;
+  statusListVersion?: number;
+  holderBinding: HolderBinding;
+  requestSatisfied?: boolean | null; // set when answering a presentation request
+  bundleIndex?: number | null; // position within a bundled presentation
+  bundleSize?: number | null;
+  signers?: SignerCheck[]; // the issuer and every declared co-signer
+  approvals?: ApprovalCheck | null; // set when the issuer published an approval policy
   verifiedAt: string;
   verifierNodeId: string;
 }
@@ -58,8 +317,276 @@ export interface SignedIncomeCredential {
     payPeriod: string;
     paymentMode: PaymentMode;
     issuedAt: string;
+    validFrom?: string;
+    expiresAt?: string;
+    statusIndex?: number;
   };
   signature: number[]; // Vec<u8> from Rust
+  format?: SignatureFormat; // absent on credentials signed before canonical payloads
+  coSignatures?: CoSignature[]; // e.g. the payroll processor's
+  signingKey?: string | null; // set when not signed with the issuer's node key
+  approvals?: CredentialApproval[]; // officers of an organizational issuer
+}
+
+export type SignerRole = 'issuer' | 'payroll_processor';
+
+export interface CoSignature {
+  signerNodeId: string;
+  role: SignerRole;
+  signedAt: string;
+  signature: number[];
+}
+
+// Officer approvals on a credential, against the policy version it names
+export interface ApprovalCheck {
+  policyVersion: number;
+  threshold: number;
+  approvedBy: string[];
+  superseded?: boolean; // the next version was published before issuance
+}
+
+export interface SignerCheck {
+  nodeId: string;
+  role: SignerRole;
+  signed: boolean; // an unsigned declared party makes the credential invalid
+  isTrusted: boolean;
+}
+
+export type DisclosableField =
+  | 'employee_name'
+  | 'employer_name'
+  | 'payroll_processor_name'
+  | 'gross_salary'
+  | 'net_salary'
+  | 'currency'
+  | 'pay_period'
+  | 'payment_mode';
+
+export interface Disclosure {
+  field: DisclosableField;
+  value: string;
+  salt: string;
+}
+
+export interface SelectiveCredential {
+  credential: {
+    id: string;
+    employeeNodeId: string;
+    employerNodeId: string;
+    payrollProcessorNodeId?: string | null; // always disclosed, must co-sign
+    issuedAt: string;
+    validFrom?: string | null;
+    expiresAt?: string | null;
+    statusIndex?: number | null;
+    digests: string[]; // salted digests of every disclosable field
+    signature: number[];
+    coSignatures?: CoSignature[];
+    signingKey?: string | null;
+    approvals?: CredentialApproval[];
+  };
+  disclosures: Disclosure[];
+}
+
+export interface DisclosedClaims {
+  employeeName?: string | null;
+  employerName?: string | null;
+  payrollProcessorNodeId?: string | null;
+  payrollProcessorName?: string | null;
+  grossSalary?: string | null;
+  netSalary?: string | null;
+  currency?: string | null;
+  payPeriod?: string | null;
+  paymentMode?: PaymentMode | null;
+  netSalaryAtLeast?: string | null; // proven lower bound of a hidden net salary
+}
+
+export interface SignedAmountCommitment {
+  credentialId: string;
+  employeeNodeId: string;
+  employerNodeId: string;
+  payrollProcessorNodeId?: string | null; // must co-sign
+  currency: string;
+  payPeriod: string;
+  issuedAt: string;
+  validFrom?: string | null;
+  expiresAt?: string | null;
+  statusIndex?: number | null;
+  commitment: number[]; // Pedersen commitment to the net salary in hundredths
+  signature: number[];
+  coSignatures?: CoSignature[];
+  signingKey?: string | null;
+  approvals?: CredentialApproval[];
+}
+
+export interface ThresholdCredential {
+  credential: SignedAmountCommitment;
+  netSalary: number; // hundredths
+  blinding: number[];
+}
+
+export interface ThresholdProof {
+  credential: SignedAmountCommitment;
+  threshold: string; // e.g. "5000.00"
+  proof: number[];
+}
+
+export interface VerifiedThresholdRecord {
+  presentationId: string;
+  proof: ThresholdProof;
+  claims: DisclosedClaims; // currency, pay period and netSalaryAtLeast
+  employeeNodeId: string;
+  issuerNodeId: string;
+  isValid: boolean;
+  isTrusted: boolean;
+  validity: ValidityStatus;
+  isRevoked: boolean;
+  revocationReason?: string | null;
+  statusCheck: StatusCheck;
+  statusListVersion?: number | null;
+  holderBinding: HolderBinding;
+  requestSatisfied?: boolean | null;
+  verifiedAt: string;
+  verifierNodeId: string;
+}
+
+export interface VerifiedDisclosureRecord {
+  presentationId: string;
+  credential: SelectiveCredential; // only the presented disclosures
+  claims: DisclosedClaims; // hidden fields are null
+  employeeNodeId: string;
+  issuerNodeId: string;
+  isValid: boolean;
+  isTrusted: boolean;
+  validity: ValidityStatus;
+  isRevoked: boolean;
+  revocationReason?: string | null;
+  statusCheck: StatusCheck;
+  statusListVersion?: number | null;
+  holderBinding: HolderBinding;
+  requestSatisfied?: boolean | null;
+  verifiedAt: string;
+  verifierNodeId: string;
+}
+
+export type SignatureFormat = 'legacy_statement' | 'canonical_v1';
+
+export type ValidityStatus = 'valid' | 'not_yet_valid' | 'expired' | 'stale' | 'invalid_timestamp' | 'unknown';
+
+export type KeyProtection = 'node_key' | 'passphrase';
+
+export interface WalletStatus {
+  locked: boolean;
+  protection: KeyProtection;
+}
+
+export type StatusCheck = 'not_applicable' | 'active' | 'revoked' | 'unavailable' | 'unknown';
+
+export type HolderBinding =
+  | 'not_checked'
+  | 'connection'
+  | 'envelope'
+  | 'unbound'
+  | 'invalid_envelope';
+
+export interface PresentationChallenge {
+  nonce: string;
+  expiresAt: string;
+}
+
+export interface PresentationEnvelope {
+  holderNodeId: string;
+  verifierNodeId: string;
+  nonce: string;
+  createdAt: string;
+  signature: number[];
+}
+
+export interface VerifiablePresentation {
+  credentials: SignedIncomeCredential[];
+  envelope: PresentationEnvelope; // one holder signature over the whole bundle
+}
+
+export interface CredentialCheck {
+  credentialId: string;
+  issuerNodeId: string;
+  isValid: boolean;
+  isTrusted: boolean;
+  legacySignature: boolean;
+  validity: ValidityStatus;
+  isRevoked: boolean;
+  revocationReason?: string | null;
+  statusCheck: StatusCheck;
+  statusListVersion?: number | null;
+  signers: SignerCheck[];
+  approvals?: ApprovalCheck | null;
+  message: string;
+}
+
+export interface PresentationVerdict {
+  presentationId: string;
+  isValid: boolean; // every credential is valid
+  holderBinding: HolderBinding;
+  requestSatisfied?: boolean | null;
+  credentials: CredentialCheck[];
+  message: string;
+}
+
+export interface PresentationConstraints {
+  issuers?: string[]; // empty accepts any issuer
+  currency?: string | null;
+  payPeriodFrom?: string | null; // "YYYY-MM"
+  payPeriodTo?: string | null;
+  minNetSalary?: string | null;
+}
+
+export interface ReceivedPresentationRequest {
+  requestId: string;
+  verifierNodeId: string;
+  constraints: PresentationConstraints;
+  nonce: string;
+  expiresAt: string;
+  receivedAt: string;
+  status: RequestStatus;
+  presentedCredentialIds: string[];
+  isValid?: boolean | null;
+  message?: string | null; // verifier's verdict or the decline reason
+}
+
+export interface SentPresentationRequest {
+  requestId: string;
+  employeeNodeId: string;
+  constraints: PresentationConstraints;
+  nonce: string;
+  expiresAt: string;
+  sentAt: string;
+  status: RequestStatus;
+  declineReason?: string | null;
+}
+
+export interface VerificationPolicy {
+  maxAgeDays?: number;
+  statusListMaxAgeMinutes?: number;
+  acceptLegacySignatures?: boolean; // validity and revocation of such credentials are unknown
+  acceptUnavailableStatus?: boolean; // otherwise a credential whose status list cannot be fetched is rejected
+}
+
+export interface PayrollProcessor {
+  nodeId: string;
+  name: string;
+}
+
+export interface IssuerProfile {
+  organizationName: string;
+  legalIdentifier: string;
+  jurisdiction: string;
+  defaultPayrollProcessor?: PayrollProcessor | null;
+}
+
+export interface SignedIssuerProfile {
+  profile: IssuerProfile;
+  issuerNodeId: string;
+  signedAt: string;
+  signature: number[];
 }
 
 export type CredentialMessage =
@@ -83,21 +610,162 @@ export type CredentialMessage =
       type: 'issueResponse';
       requestId: string;
       credential?: SignedIncomeCredential;
+      selectiveCredential?: SelectiveCredential | null;
+      thresholdCredential?: ThresholdCredential | null;
+      verifiableCredential?: VerifiableCredential | null;
+      jws?: string | null;
       error?: string;
     }
+  | {
+      type: 'challengeRequest';
+      requestId: string;
+    }
+  | {
+      type: 'challenge';
+      requestId: string;
+      nonce: string;
+      expiresAt: string;
+    }
   | {
       type: 'presentCredential';
       presentationId: string;
       credential: SignedIncomeCredential;
+      envelope?: PresentationEnvelope; // needed when presenting from another node
     }
+  | {
+      type: 'presentJws';
+      presentationId: string;
+      jws: string; // compact JWS signed by the issuer
+      envelope?: PresentationEnvelope;
+    }
+  | {
+      type: 'presentDisclosure';
+      presentationId: string;
+      credential: SelectiveCredential;
+      envelope?: PresentationEnvelope;
+    }
+  | {
+      type: 'presentThresholdProof';
+      presentationId: string;
+      proof: ThresholdProof;
+      envelope?: PresentationEnvelope;
+    }
+  | {
+      type: 'presentCredentials';
+      presentationId: string;
+      presentation: VerifiablePresentation;
+    }
+  | ({ type: 'presentationResult' } & PresentationVerdict)
   | {
       type: 'verificationResult';
       presentationId: string;
       isValid: boolean;
       isTrusted: boolean;
+      legacySignature: boolean;
+      validity: ValidityStatus;
+      isRevoked: boolean;
+      revocationReason?: string;
+      statusCheck: StatusCheck;
+      statusListVersion?: number;
+      holderBinding: HolderBinding;
+      requestSatisfied?: boolean | null;
+      signers?: SignerCheck[];
+      approvals?: ApprovalCheck | null;
       issuerNodeId: string;
       message: string;
     }
+  | {
+      type: 'coSignRequest';
+      requestId: string;
+      credential: SignedIncomeCredential;
+    }
+  | {
+      type: 'coSignResponse';
+      requestId: string;
+      coSignature: CoSignature;
+    }
+  | {
+      type: 'presentationRequest';
+      requestId: string;
+      constraints: PresentationConstraints;
+      nonce: string;
+      expiresAt: string;
+    }
+  | {
+      type: 'presentationDeclined';
+      requestId: string;
+      reason?: string | null;
+    }
+  | {
+      type: 'approvalQueueRequest';
+      requestId: string;
+    }
+  | {
+      type: 'approvalQueue';
+      requestId: string;
+      requests: ApprovalProgress[];
+      policyProposal?: SignedApprovalPolicy | null;
+    }
+  | {
+      type: 'submitApproval';
+      approval: OfficerApproval;
+    }
+  | ({ type: 'approvalRecorded' } & ApprovalProgress)
+  | {
+      type: 'submitPolicyApproval';
+      version: number;
+      signature: PolicySignature;
+    }
+  | ({ type: 'policyApprovalRecorded' } & ApprovalPolicyStatus)
+  | {
+      type: 'approvalPolicyRequest';
+      requestId: string;
+    }
+  | {
+      type: 'approvalPolicies';
+      requestId: string;
+      policies: SignedApprovalPolicy[];
+    }
+  | {
+      type: 'keyBindingRequest';
+      requestId: string;
+      nodeId: string;
+      signingKey: string;
+    }
+  | {
+      type: 'keyBindingResponse';
+      requestId: string;
+      binding: SignedKeyBinding;
+    }
+  | {
+      type: 'didDocumentRequest';
+      requestId: string;
+      did: string; // did:iroh of the node or of a key it rotated from
+    }
+  | {
+      type: 'didDocumentResponse';
+      requestId: string;
+      document: DidDocument;
+      bindings: SignedKeyBinding[]; // one per signing key the document lists
+    }
+  | {
+      type: 'keyHistoryRequest';
+      requestId: string;
+    }
+  | {
+      type: 'keyHistory';
+      requestId: string;
+      rotations: SignedKeyRotation[]; // oldest first
+    }
+  | {
+      type: 'profileRequest';
+      requestId: string;
+    }
+  | {
+      type: 'profileResponse';
+      requestId: string;
+      profile: SignedIssuerProfile;
+    }
   | {
       type: 'error';
       requestId: string;
@@ -116,7 +784,25 @@ export type AcceptEvent =
   | { type: 'accepted'; nodeId: string }
   | { type: 'messageReceived'; nodeId: string; message: CredentialMessage }
   | { type: 'responseSent'; nodeId: string; message: CredentialMessage; bytesSent: number }
-  | { type: 'closed'; nodeId: string; error?: string };
+  | { type: 'closed'; nodeId: string; error?: string }
+  | { type: 'responseDelivered'; nodeId: string; requestId: string; error?: string }
+  | { type: 'revocationReceived'; issuerNodeId: string; credentialId: string }
+  | { type: 'keyRotated'; previousNodeId: string; newNodeId: string };
+
+export interface IssuedCredentialRecord {
+  credentialId: string;
+  requestId: string;
+  employeeNodeId: string;
+  issuedAt: string;
+  statusIndex?: number;
+  approvals?: OfficerApproval[];
+  revocation?: {
+    credentialId: string;
+    issuerNodeId: string;
+    reason?: string;
+    revokedAt: string;
+  };
+}
 
 export type ConnectionStatus = 'connecting' | 'connected' | 'disconnected' | 'error';
 
@@ -128,29 +814,89 @@ export interface PeerConnection {
 }
 
 export interface API {
-  spawn(role: Role, secretKey?: string): Promise<PeerNodeInfo>;
+  spawn(role: Role, secretKey?: string, dbName?: string, issuerProfile?: IssuerProfile, signingKey?: string): Promise<PeerNodeInfo>;
   getNodeInfo(): PeerNodeInfo | null;
   connect(nodeId: string, payload: string): Promise<void>;
   getConnections(): PeerConnection[];
   subscribeToAcceptEvents(callback: (event: AcceptEvent) => void): () => void;
   subscribeToConnections(callback: () => void): () => void;
   isInitialized(): boolean;
+  flush(): Promise<void>; // rejects if a change was not saved to IndexedDB
 
   // Issuer methods
   getPendingRequests(): Promise<PendingCredentialRequest[]>;
   approveRequest(requestId: string): Promise<void>;
   rejectRequest(requestId: string, reason?: string): Promise<void>;
+  getIssuerProfile(): Promise<IssuerProfile>;
+  setIssuerProfile(profile: IssuerProfile): Promise<void>;
+  getApprovalPolicy(): Promise<ApprovalPolicyStatus>;
+  setApprovalPolicy(policy: ApprovalPolicy): Promise<ApprovalPolicyStatus>; // later changes await the officers
+  rotateKey(newSecretKey: string, reason?: string): Promise<SignedKeyRotation>;
+  getKeyBinding(): Promise<SignedKeyBinding | null>;
+  unbindSigningKey(signingKey: string): Promise<SignedKeyUnbinding>; // verifiers then refuse its credentials
+  getDid(): string;
+  getDidDocument(): Promise<DidDocument>;
+  resolveDid(did: string): Promise<DidDocument>; // did:iroh or did:key
+
+  // Officer methods
+  requestApprovalQueue(issuerNodeId: string): Promise<ApprovalProgress[]>;
+  requestPolicyProposal(issuerNodeId: string): Promise<SignedApprovalPolicy | null>;
+  approveAsOfficer(issuerNodeId: string, requestId: string): Promise<ApprovalProgress>; // checks the signed credential first
+  approvePolicyAsOfficer(issuerNodeId: string, version: number): Promise<ApprovalPolicyStatus>;
 
   // Verifier methods
+  requestIssuerProfile(issuerNodeId: string): Promise<SignedIssuerProfile>;
   addTrustedIssuer(nodeId: string): Promise<void>;
   removeTrustedIssuer(nodeId: string): Promise<void>;
   isTrustedIssuer(nodeId: string): Promise<boolean>;
   getTrustedIssuers(): Promise<string[]>;
+  getKeyRotations(): Promise<SignedKeyRotation[]>;
+  exportTrustStore(): Promise<OfflineTrustStore>;
+  exportRevocations(): Promise<OfflineRevocations>;
   getVerifiedCredentials(): Promise<VerifiedCredentialRecord[]>;
   getVerifiedCredential(presentationId: string): Promise<VerifiedCredentialRecord | null>;
+  getVerifiedPresentation(presentationId: string): Promise<VerifiedCredentialRecord[]>;
+  getVerifiedDisclosures(): Promise<VerifiedDisclosureRecord[]>;
+  getVerifiedThresholdProofs(): Promise<VerifiedThresholdRecord[]>;
+  requestPresentation(
+    employeeNodeId: string,
+    constraints: PresentationConstraints,
+    expiresInMinutes?: number
+  ): Promise<SentPresentationRequest>;
+  getSentPresentationRequests(): Promise<SentPresentationRequest[]>;
 
   // Employee methods
   getReceivedCredentials(): Promise<ReceivedCredentialResponse[]>;
   getReceivedCredential(requestId: string): Promise<ReceivedCredentialResponse | null>;
+  requestChallenge(verifierNodeId: string): Promise<PresentationChallenge>;
+  signPresentation(
+    verifierNodeId: string,
+    nonce: string,
+    credentials: SignedIncomeCredential[]
+  ): Promise<PresentationEnvelope>;
+  getPresentationRequests(): Promise<ReceivedPresentationRequest[]>;
+  getMatchingCredentials(requestId: string): Promise<SignedIncomeCredential[]>;
+  approvePresentationRequest(requestId: string, credentialIds: string[]): Promise<ReceivedPresentationRequest>;
+  provePresentationRequest(requestId: string, credentialId: string): Promise<ReceivedPresentationRequest>;
+  presentCredentials(
+    verifierNodeId: string,
+    credentials: SignedIncomeCredential[]
+  ): Promise<PresentationVerdict>;
+  presentDisclosure(
+    verifierNodeId: string,
+    credentialId: string,
+    fields: DisclosableField[]
+  ): Promise<CredentialMessage>;
+  presentThresholdProof(
+    verifierNodeId: string,
+    credentialId: string,
+    threshold: string
+  ): Promise<CredentialMessage>;
+  presentJws(verifierNodeId: string, jws: string): Promise<CredentialMessage>;
+  rejectPresentationRequest(requestId: string, reason?: string): Promise<void>;
+  getWalletStatus(): Promise<WalletStatus>;
+  lockWallet(): Promise<void>;
+  unlockWallet(passphrase?: string): Promise<void>;
+  setWalletPassphrase(passphrase?: string): Promise<void>;
 }
 
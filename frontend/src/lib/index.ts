@@ -1,5 +1,5 @@
 import { log } from './log';
-import type { API } from './api';
+import type { API, Role } from './api';
 
 // We want to only ever create the API once, therefore we define a module-level
 // singleton that holds the promise to create the API.
@@ -7,7 +7,7 @@ import type { API } from './api';
 // an async singleton instance to the wasm API.
 let apiInstance: Promise<API> | null = null;
 
-export async function initApi(role: 'employee' | 'issuer' | 'verifier', secretKey?: string): Promise<API> {
+export async function initApi(role: Role, secretKey?: string): Promise<API> {
   if (!apiInstance) {
     apiInstance = importAndInitOnce(role, secretKey);
   }
@@ -18,7 +18,7 @@ export function resetApi() {
   apiInstance = null;
 }
 
-async function importAndInitOnce(role: 'employee' | 'issuer' | 'verifier', secretKey?: string): Promise<API> {
+async function importAndInitOnce(role: Role, secretKey?: string): Promise<API> {
   try {
     log.info('Importing WASM module');
     const { PeerNodeAPI } = await import('./peer-node');
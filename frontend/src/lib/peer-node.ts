@@ -1,15 +1,40 @@
-import { PeerNode, generate_key } from 'trust-triangle';
+import { PeerNode, credential_to_qr, generate_key, verify_credential_file, verify_qr_credential } from 'trust-triangle';
 import { log } from './log';
 import type { 
   API, 
+  ApprovalPolicy,
+  ApprovalPolicyStatus,
+  ApprovalProgress,
   PeerNodeInfo, 
   Role, 
   AcceptEvent,
   ConnectEvent,
+  CredentialCheck,
+  CredentialMessage,
+  DisclosableField,
+  OfflineRevocations,
+  OfflineTrustStore,
   PeerConnection,
   PendingCredentialRequest,
+  IssuerProfile,
+  SignedIssuerProfile,
+  PresentationChallenge,
+  PresentationConstraints,
+  PresentationEnvelope,
+  PresentationVerdict,
+  ReceivedPresentationRequest,
+  SentPresentationRequest,
+  SignedApprovalPolicy,
+  SignedIncomeCredential,
+  SignedKeyBinding,
+  SignedKeyUnbinding,
+  DidDocument,
+  SignedKeyRotation,
   ReceivedCredentialResponse,
   VerifiedCredentialRecord,
+  VerifiedDisclosureRecord,
+  VerifiedThresholdRecord,
+  WalletStatus,
 } from './api';
 
 type PeerNodeState = {
@@ -26,20 +51,39 @@ export class PeerNodeAPI implements API {
 
   private constructor() {}
 
-  static async create(role: Role, secretKey?: string): Promise<PeerNodeAPI> {
+  static async create(
+    role: Role,
+    secretKey?: string,
+    dbName?: string,
+    issuerProfile?: IssuerProfile,
+    signingKey?: string
+  ): Promise<PeerNodeAPI> {
     log.info(`Spawning peer node with role: ${role}`);
     const api = new PeerNodeAPI();
-    await api.spawn(role, secretKey);
+    await api.spawn(role, secretKey, dbName, issuerProfile, signingKey);
     return api;
   }
 
-  async spawn(role: Role, secretKey?: string): Promise<PeerNodeInfo> {
+  async spawn(
+    role: Role,
+    secretKey?: string,
+    dbName?: string,
+    issuerProfile?: IssuerProfile,
+    signingKey?: string // signs credentials instead of the node key
+  ): Promise<PeerNodeInfo> {
     if (this.state) {
       throw new Error('Peer node already spawned');
     }
 
     try {
-      const peerNode = await PeerNode.spawn(secretKey ?? null, role);
+      // One IndexedDB database per role so wallets in the same browser stay separate
+      const peerNode = await PeerNode.spawn(
+        secretKey ?? null,
+        role,
+        dbName ?? `trust-triangle-${role}`,
+        issuerProfile ? JSON.stringify(issuerProfile) : null,
+        signingKey ?? null
+      );
       const nodeId = peerNode.node_id();
       const key = peerNode.secret_key();
       
@@ -280,6 +324,19 @@ export class PeerNodeAPI implements API {
     this.state = null;
   }
 
+  async flush(): Promise<void> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      await this.state.peerNode.flush();
+    } catch (error) {
+      log.error('Failed to save changes to IndexedDB', error);
+      throw error;
+    }
+  }
+
   // Issuer methods
 
   async getPendingRequests(): Promise<PendingCredentialRequest[]> {
@@ -306,6 +363,7 @@ export class PeerNodeAPI implements API {
     try {
       log.info(`Approving request: ${requestId}`);
       await this.state.peerNode.approve_request(requestId);
+      await this.state.peerNode.flush();
       log.info(`Request ${requestId} approved successfully`);
     } catch (error) {
       log.error('Failed to approve request', error);
@@ -321,6 +379,7 @@ export class PeerNodeAPI implements API {
     try {
       log.info(`Rejecting request: ${requestId}`);
       await this.state.peerNode.reject_request(requestId, reason ?? null);
+      await this.state.peerNode.flush();
       log.info(`Request ${requestId} rejected successfully`);
     } catch (error) {
       log.error('Failed to reject request', error);
@@ -328,8 +387,237 @@ export class PeerNodeAPI implements API {
     }
   }
 
+  async getIssuerProfile(): Promise<IssuerProfile> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const profileJson = await this.state.peerNode.get_issuer_profile();
+      return JSON.parse(profileJson) as IssuerProfile;
+    } catch (error) {
+      log.error('Failed to get issuer profile', error);
+      throw error;
+    }
+  }
+
+  async setIssuerProfile(profile: IssuerProfile): Promise<void> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      await this.state.peerNode.set_issuer_profile(JSON.stringify(profile));
+      log.info(`Issuer profile updated: ${profile.organizationName}`);
+    } catch (error) {
+      log.error('Failed to set issuer profile', error);
+      throw error;
+    }
+  }
+
+  async getApprovalPolicy(): Promise<ApprovalPolicyStatus> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const statusJson = await this.state.peerNode.get_approval_policy();
+      return JSON.parse(statusJson) as ApprovalPolicyStatus;
+    } catch (error) {
+      log.error('Failed to get approval policy', error);
+      throw error;
+    }
+  }
+
+  async setApprovalPolicy(policy: ApprovalPolicy): Promise<ApprovalPolicyStatus> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const statusJson = await this.state.peerNode.set_approval_policy(JSON.stringify(policy));
+      const status = JSON.parse(statusJson) as ApprovalPolicyStatus;
+      if (status.proposed) {
+        log.info(`Approval policy version ${status.proposed.policy.version} proposed to the officers`);
+      } else {
+        log.info(`Approval policy updated: ${policy.threshold} of ${policy.officers.length} officers`);
+      }
+      return status;
+    } catch (error) {
+      log.error('Failed to set approval policy', error);
+      throw error;
+    }
+  }
+
+  async getKeyBinding(): Promise<SignedKeyBinding | null> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const bindingJson = await this.state.peerNode.get_key_binding();
+      return JSON.parse(bindingJson) as SignedKeyBinding | null;
+    } catch (error) {
+      log.error('Failed to get key binding', error);
+      throw error;
+    }
+  }
+
+  async unbindSigningKey(signingKey: string): Promise<SignedKeyUnbinding> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const unbindingJson = await this.state.peerNode.unbind_signing_key(signingKey);
+      return JSON.parse(unbindingJson) as SignedKeyUnbinding;
+    } catch (error) {
+      log.error('Failed to unbind signing key', error);
+      throw error;
+    }
+  }
+
+  getDid(): string {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+    return this.state.peerNode.did();
+  }
+
+  async getDidDocument(): Promise<DidDocument> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const documentJson = await this.state.peerNode.get_did_document();
+      return JSON.parse(documentJson) as DidDocument;
+    } catch (error) {
+      log.error('Failed to get DID document', error);
+      throw error;
+    }
+  }
+
+  async resolveDid(did: string): Promise<DidDocument> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const documentJson = await this.state.peerNode.resolve_did(did);
+      return JSON.parse(documentJson) as DidDocument;
+    } catch (error) {
+      log.error(`Failed to resolve ${did}`, error);
+      throw error;
+    }
+  }
+
+  async rotateKey(newSecretKey: string, reason?: string): Promise<SignedKeyRotation> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const rotationJson = await this.state.peerNode.rotate_key(newSecretKey, reason);
+      const rotation = JSON.parse(rotationJson) as SignedKeyRotation;
+      log.info(`Issuer key rotated to ${rotation.rotation.newNodeId}; respawn the node with the new key`);
+      return rotation;
+    } catch (error) {
+      log.error('Failed to rotate issuer key', error);
+      throw error;
+    }
+  }
+
+  // Officer methods
+
+  async requestApprovalQueue(issuerNodeId: string): Promise<ApprovalProgress[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const requestsJson = await this.state.peerNode.request_approval_queue(issuerNodeId);
+      return JSON.parse(requestsJson) as ApprovalProgress[];
+    } catch (error) {
+      log.error(`Failed to fetch approval queue of issuer ${issuerNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async requestPolicyProposal(issuerNodeId: string): Promise<SignedApprovalPolicy | null> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const proposalJson = await this.state.peerNode.request_policy_proposal(issuerNodeId);
+      return JSON.parse(proposalJson) as SignedApprovalPolicy | null;
+    } catch (error) {
+      log.error(`Failed to fetch the policy proposal of issuer ${issuerNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async approveAsOfficer(issuerNodeId: string, requestId: string): Promise<ApprovalProgress> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      log.info(`Approving request ${requestId} of issuer ${issuerNodeId} as officer`);
+      const progressJson = await this.state.peerNode.approve_as_officer(issuerNodeId, requestId);
+      return JSON.parse(progressJson) as ApprovalProgress;
+    } catch (error) {
+      log.error(`Failed to approve request ${requestId} as officer`, error);
+      throw error;
+    }
+  }
+
+  async approvePolicyAsOfficer(issuerNodeId: string, version: number): Promise<ApprovalPolicyStatus> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      log.info(`Signing version ${version} of the approval policy of issuer ${issuerNodeId}`);
+      const statusJson = await this.state.peerNode.approve_policy_as_officer(issuerNodeId, version);
+      return JSON.parse(statusJson) as ApprovalPolicyStatus;
+    } catch (error) {
+      log.error(`Failed to sign version ${version} of the approval policy of issuer ${issuerNodeId}`, error);
+      throw error;
+    }
+  }
+
   // Verifier methods
 
+  async requestIssuerProfile(issuerNodeId: string): Promise<SignedIssuerProfile> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const profileJson = await this.state.peerNode.request_issuer_profile(issuerNodeId);
+      return JSON.parse(profileJson) as SignedIssuerProfile;
+    } catch (error) {
+      log.error(`Failed to fetch profile of issuer ${issuerNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async getKeyRotations(): Promise<SignedKeyRotation[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const rotationsJson = await this.state.peerNode.get_key_rotations();
+      return JSON.parse(rotationsJson) as SignedKeyRotation[];
+    } catch (error) {
+      log.error('Failed to get key rotations', error);
+      throw error;
+    }
+  }
+
   async addTrustedIssuer(nodeId: string): Promise<void> {
     if (!this.state) {
       throw new Error('Peer node not initialized. Call spawn() first.');
@@ -390,6 +678,38 @@ export class PeerNodeAPI implements API {
     }
   }
 
+  async exportTrustStore(): Promise<OfflineTrustStore> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const trustStoreJson = await this.state.peerNode.export_trust_store();
+      const trustStore = JSON.parse(trustStoreJson) as OfflineTrustStore;
+      log.info(`Exported trust store with ${trustStore.trustedIssuers.length} trusted issuers`);
+      return trustStore;
+    } catch (error) {
+      log.error('Failed to export trust store', error);
+      throw error;
+    }
+  }
+
+  async exportRevocations(): Promise<OfflineRevocations> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const revocationsJson = await this.state.peerNode.export_revocations();
+      const revocations = JSON.parse(revocationsJson) as OfflineRevocations;
+      log.info('Exported status lists and revocation notices');
+      return revocations;
+    } catch (error) {
+      log.error('Failed to export revocations', error);
+      throw error;
+    }
+  }
+
   async getVerifiedCredentials(): Promise<VerifiedCredentialRecord[]> {
     if (!this.state) {
       throw new Error('Peer node not initialized. Call spawn() first.');
@@ -423,8 +743,133 @@ export class PeerNodeAPI implements API {
     }
   }
 
+  async getVerifiedPresentation(presentationId: string): Promise<VerifiedCredentialRecord[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const recordsJson = await this.state.peerNode.get_verified_presentation(presentationId);
+    return JSON.parse(recordsJson) as VerifiedCredentialRecord[];
+  }
+
+  async getVerifiedDisclosures(): Promise<VerifiedDisclosureRecord[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const recordsJson = await this.state.peerNode.get_verified_disclosures();
+    return JSON.parse(recordsJson) as VerifiedDisclosureRecord[];
+  }
+
+  async getVerifiedThresholdProofs(): Promise<VerifiedThresholdRecord[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const recordsJson = await this.state.peerNode.get_verified_threshold_proofs();
+    return JSON.parse(recordsJson) as VerifiedThresholdRecord[];
+  }
+
+  async requestPresentation(
+    employeeNodeId: string,
+    constraints: PresentationConstraints,
+    expiresInMinutes?: number
+  ): Promise<SentPresentationRequest> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const requestJson = await this.state.peerNode.request_presentation(
+        employeeNodeId,
+        JSON.stringify(constraints),
+        expiresInMinutes ?? null
+      );
+      const request = JSON.parse(requestJson) as SentPresentationRequest;
+      log.info(`Presentation request ${request.requestId} sent to ${employeeNodeId}`);
+      return request;
+    } catch (error) {
+      log.error(`Failed to send presentation request to ${employeeNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async getSentPresentationRequests(): Promise<SentPresentationRequest[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const requestsJson = await this.state.peerNode.get_sent_presentation_requests();
+    return JSON.parse(requestsJson) as SentPresentationRequest[];
+  }
+
   // Employee methods
 
+  async getPresentationRequests(): Promise<ReceivedPresentationRequest[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const requestsJson = await this.state.peerNode.get_presentation_requests();
+    return JSON.parse(requestsJson) as ReceivedPresentationRequest[];
+  }
+
+  async getMatchingCredentials(requestId: string): Promise<SignedIncomeCredential[]> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const credentialsJson = await this.state.peerNode.get_matching_credentials(requestId);
+    return JSON.parse(credentialsJson) as SignedIncomeCredential[];
+  }
+
+  async approvePresentationRequest(
+    requestId: string,
+    credentialIds: string[]
+  ): Promise<ReceivedPresentationRequest> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const requestJson = await this.state.peerNode.approve_presentation_request(requestId, credentialIds);
+      log.info(`Presented ${credentialIds.length} credentials for request ${requestId}`);
+      return JSON.parse(requestJson) as ReceivedPresentationRequest;
+    } catch (error) {
+      log.error(`Failed to approve presentation request ${requestId}`, error);
+      throw error;
+    }
+  }
+
+  async provePresentationRequest(requestId: string, credentialId: string): Promise<ReceivedPresentationRequest> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const requestJson = await this.state.peerNode.prove_presentation_request(requestId, credentialId);
+      log.info(`Proved net salary of credential ${credentialId} for request ${requestId}`);
+      return JSON.parse(requestJson) as ReceivedPresentationRequest;
+    } catch (error) {
+      log.error(`Failed to prove presentation request ${requestId}`, error);
+      throw error;
+    }
+  }
+
+  async rejectPresentationRequest(requestId: string, reason?: string): Promise<void> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      await this.state.peerNode.reject_presentation_request(requestId, reason ?? null);
+      log.info(`Declined presentation request ${requestId}`);
+    } catch (error) {
+      log.error(`Failed to decline presentation request ${requestId}`, error);
+      throw error;
+    }
+  }
+
   async getReceivedCredentials(): Promise<ReceivedCredentialResponse[]> {
     if (!this.state) {
       throw new Error('Peer node not initialized. Call spawn() first.');
@@ -459,6 +904,161 @@ export class PeerNodeAPI implements API {
       throw error;
     }
   }
+
+  async getWalletStatus(): Promise<WalletStatus> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const statusJson = await this.state.peerNode.wallet_status();
+    return JSON.parse(statusJson) as WalletStatus;
+  }
+
+  async lockWallet(): Promise<void> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    await this.state.peerNode.lock_wallet();
+    log.info('Wallet locked');
+  }
+
+  async unlockWallet(passphrase?: string): Promise<void> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      await this.state.peerNode.unlock_wallet(passphrase ?? null);
+      log.info('Wallet unlocked');
+    } catch (error) {
+      log.error('Failed to unlock wallet', error);
+      throw error;
+    }
+  }
+
+  async requestChallenge(verifierNodeId: string): Promise<PresentationChallenge> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const challengeJson = await this.state.peerNode.request_challenge(verifierNodeId);
+      return JSON.parse(challengeJson) as PresentationChallenge;
+    } catch (error) {
+      log.error(`Failed to get a challenge from verifier ${verifierNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async signPresentation(
+    verifierNodeId: string,
+    nonce: string,
+    credentials: SignedIncomeCredential[]
+  ): Promise<PresentationEnvelope> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    const envelopeJson = this.state.peerNode.sign_presentation(
+      verifierNodeId,
+      nonce,
+      JSON.stringify(credentials)
+    );
+    return JSON.parse(envelopeJson) as PresentationEnvelope;
+  }
+
+  async presentCredentials(
+    verifierNodeId: string,
+    credentials: SignedIncomeCredential[]
+  ): Promise<PresentationVerdict> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const verdictJson = await this.state.peerNode.present_credentials(
+        verifierNodeId,
+        JSON.stringify(credentials)
+      );
+      const verdict = JSON.parse(verdictJson) as PresentationVerdict;
+      log.info(`Presented ${credentials.length} credentials to ${verifierNodeId}: ${verdict.message}`);
+      return verdict;
+    } catch (error) {
+      log.error(`Failed to present credentials to ${verifierNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async presentDisclosure(
+    verifierNodeId: string,
+    credentialId: string,
+    fields: DisclosableField[]
+  ): Promise<CredentialMessage> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const resultJson = await this.state.peerNode.present_disclosure(verifierNodeId, credentialId, fields);
+      const result = JSON.parse(resultJson) as CredentialMessage;
+      log.info(`Disclosed ${fields.join(', ') || 'no fields'} of credential ${credentialId} to ${verifierNodeId}`);
+      return result;
+    } catch (error) {
+      log.error(`Failed to present disclosure to ${verifierNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async presentThresholdProof(
+    verifierNodeId: string,
+    credentialId: string,
+    threshold: string
+  ): Promise<CredentialMessage> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const resultJson = await this.state.peerNode.present_threshold_proof(verifierNodeId, credentialId, threshold);
+      const result = JSON.parse(resultJson) as CredentialMessage;
+      log.info(`Proved net salary of credential ${credentialId} reaches ${threshold} to ${verifierNodeId}`);
+      return result;
+    } catch (error) {
+      log.error(`Failed to present threshold proof to ${verifierNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async presentJws(verifierNodeId: string, jws: string): Promise<CredentialMessage> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      const resultJson = await this.state.peerNode.present_jws(verifierNodeId, jws);
+      const result = JSON.parse(resultJson) as CredentialMessage;
+      log.info(`Presented JWT-VC to ${verifierNodeId}`);
+      return result;
+    } catch (error) {
+      log.error(`Failed to present JWT-VC to ${verifierNodeId}`, error);
+      throw error;
+    }
+  }
+
+  async setWalletPassphrase(passphrase?: string): Promise<void> {
+    if (!this.state) {
+      throw new Error('Peer node not initialized. Call spawn() first.');
+    }
+
+    try {
+      await this.state.peerNode.set_wallet_passphrase(passphrase ?? null);
+      log.info(passphrase ? 'Wallet passphrase set' : 'Wallet passphrase removed');
+    } catch (error) {
+      log.error('Failed to set wallet passphrase', error);
+      throw error;
+    }
+  }
 }
 
 // Helper function to generate a new secret key
@@ -471,3 +1071,37 @@ export async function generateSecretKey(): Promise<string> {
     throw error;
   }
 }
+
+// Encode a credential as base45 text for a QR code
+export function credentialToQr(credential: SignedIncomeCredential): string {
+  return credential_to_qr(JSON.stringify(credential));
+}
+
+// Check the signatures of a scanned credential offline; issuer trust and
+// revocation are not checked
+export function verifyQrCredential(text: string): SignedIncomeCredential {
+  return JSON.parse(verify_qr_credential(text)) as SignedIncomeCredential;
+}
+
+// Verify a credential file (W3C VC JSON, JWT-VC or compact text) without a
+// node, against data exported by a verifier node, at `now` or the current time
+export async function verifyCredentialFile(
+  contents: string,
+  trustStore: OfflineTrustStore,
+  revocations?: OfflineRevocations,
+  now?: string,
+  maxAgeDays?: number,
+  acceptLegacySignatures?: boolean,
+  acceptUnavailableStatus?: boolean
+): Promise<CredentialCheck> {
+  const checkJson = await verify_credential_file(
+    contents,
+    JSON.stringify(trustStore),
+    revocations ? JSON.stringify(revocations) : null,
+    now ?? null,
+    maxAgeDays ?? null,
+    acceptLegacySignatures ?? null,
+    acceptUnavailableStatus ?? null
+  );
+  return JSON.parse(checkJson) as CredentialCheck;
+}
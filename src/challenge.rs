@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+use iroh::EndpointId;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// How long a verifier accepts an envelope over a nonce it issued
+const CHALLENGE_TTL_MINUTES: i64 = 5;
+
+/// Outstanding challenges one peer may hold; further requests are refused until some are used or expire
+pub const MAX_CHALLENGES_PER_PEER: usize = 16;
+
+/// Outstanding challenges across all peers; beyond it the oldest is evicted, so peers
+/// flooding the registry under fresh IDs cannot lock out everyone else
+pub const MAX_CHALLENGES: usize = 4096;
+
+/// Nonce a verifier hands out for a holder to sign into a presentation envelope
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationChallenge {
+    pub nonce: String,
+    /// The nonce is refused after this time (RFC3339 format)
+    pub expires_at: String,
+}
+
+/// Random hex nonce for challenges and presentation requests
+pub fn generate_nonce() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Single-use nonces issued by this verifier.
+///
+/// Kept in memory only: a restart invalidates outstanding challenges, which
+/// holders recover from by asking for a new one. Anyone can ask for a
+/// challenge, so the number outstanding is capped per peer, and overall by
+/// evicting the oldest.
+#[derive(Debug, Clone, Default)]
+pub struct ChallengeRegistry {
+    inner: Arc<Mutex<Challenges>>,
+}
+
+#[derive(Debug, Default)]
+struct Challenges {
+    /// Nonce → holder it was issued to, expiry and issue sequence number
+    nonces: HashMap<String, (EndpointId, DateTime<Utc>, u64)>,
+    /// Outstanding nonces by issue sequence number, oldest first
+    by_age: BTreeMap<u64, String>,
+    /// Sequence number of the next nonce issued
+    next_seq: u64,
+    /// Outstanding nonces per holder
+    per_peer: HashMap<EndpointId, usize>,
+}
+
+impl Challenges {
+    fn is_full(&self, peer: &EndpointId) -> bool {
+        self.per_peer.get(peer).is_some_and(|held| *held >= MAX_CHALLENGES_PER_PEER)
+    }
+
+    fn insert(&mut self, nonce: String, peer: EndpointId, expires_at: DateTime<Utc>) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.by_age.insert(seq, nonce.clone());
+        self.nonces.insert(nonce, (peer, expires_at, seq));
+        *self.per_peer.entry(peer).or_default() += 1;
+    }
+
+    fn remove(&mut self, nonce: &str) -> Option<DateTime<Utc>> {
+        let (peer, expires_at, seq) = self.nonces.remove(nonce)?;
+        self.by_age.remove(&seq);
+        if let Some(held) = self.per_peer.get_mut(&peer) {
+            *held -= 1;
+            if *held == 0 {
+                self.per_peer.remove(&peer);
+            }
+        }
+        Some(expires_at)
+    }
+
+    /// The oldest outstanding nonce
+    fn oldest(&self) -> Option<String> {
+        self.by_age.first_key_value().map(|(_, nonce)| nonce.clone())
+    }
+
+    /// Removes expired nonces; all share one TTL, so they are the oldest ones
+    fn prune(&mut self, now: DateTime<Utc>) {
+        while let Some(nonce) = self.oldest()
+            && self.nonces.get(&nonce).is_some_and(|(_, expires_at, _)| *expires_at <= now)
+        {
+            self.remove(&nonce);
+        }
+    }
+}
+
+impl ChallengeRegistry {
+    /// Issues a fresh nonce to `peer`, evicting the oldest outstanding one if all
+    /// peers together hold the maximum; `None` if `peer` already holds its maximum
+    pub async fn issue(&self, peer: EndpointId) -> Option<PresentationChallenge> {
+        let now = Utc::now();
+        let mut challenges = self.inner.lock().await;
+        // Expired nonces only count against the caps until the next time one is reached
+        if challenges.is_full(&peer) || challenges.nonces.len() >= MAX_CHALLENGES {
+            challenges.prune(now);
+            if challenges.is_full(&peer) {
+                return None;
+            }
+            while challenges.nonces.len() >= MAX_CHALLENGES
+                && let Some(oldest) = challenges.oldest()
+            {
+                challenges.remove(&oldest);
+            }
+        }
+
+        let nonce = generate_nonce();
+        let expires_at = now + chrono::Duration::minutes(CHALLENGE_TTL_MINUTES);
+        challenges.insert(nonce.clone(), peer, expires_at);
+        Some(PresentationChallenge {
+            nonce,
+            expires_at: expires_at.to_rfc3339(),
+        })
+    }
+
+    /// Consumes `nonce`; false if it was never issued, already used, or expired
+    pub async fn consume(&self, nonce: &str) -> bool {
+        let expiry = self.inner.lock().await.remove(nonce);
+        expiry.is_some_and(|expiry| expiry > Utc::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::SecretKey;
+    use n0_future::future::block_on;
+
+    fn peer() -> EndpointId {
+        SecretKey::generate(&mut rand::rng()).public()
+    }
+
+    #[test]
+    fn test_challenges_are_single_use() {
+        block_on(async {
+            let registry = ChallengeRegistry::default();
+            let challenge = registry.issue(peer()).await.unwrap();
+
+            assert!(!registry.consume("unknown").await);
+            assert!(registry.consume(&challenge.nonce).await);
+            assert!(!registry.consume(&challenge.nonce).await);
+        });
+    }
+
+    #[test]
+    fn test_outstanding_challenges_are_capped() {
+        block_on(async {
+            let registry = ChallengeRegistry::default();
+            let flooding_peer = peer();
+            let mut challenges = Vec::new();
+            for _ in 0..MAX_CHALLENGES_PER_PEER {
+                challenges.push(registry.issue(flooding_peer).await.unwrap());
+            }
+            assert!(registry.issue(flooding_peer).await.is_none());
+            // Other peers are unaffected, and using a challenge frees its slot
+            assert!(registry.issue(peer()).await.is_some());
+            assert!(registry.consume(&challenges[0].nonce).await);
+            assert!(registry.issue(flooding_peer).await.is_some());
+
+            // Many peers together cannot exceed the global cap either: the oldest
+            // outstanding challenge makes way, and a genuine peer still gets one
+            let mut outstanding = MAX_CHALLENGES_PER_PEER + 1;
+            while outstanding < MAX_CHALLENGES {
+                let other_peer = peer();
+                while outstanding < MAX_CHALLENGES && registry.issue(other_peer).await.is_some() {
+                    outstanding += 1;
+                }
+            }
+            let genuine = registry.issue(peer()).await.unwrap();
+            assert_eq!(registry.inner.lock().await.nonces.len(), MAX_CHALLENGES);
+            assert!(!registry.consume(&challenges[1].nonce).await);
+            assert!(registry.consume(&challenges[2].nonce).await);
+            assert!(registry.consume(&genuine.nonce).await);
+        });
+    }
+}
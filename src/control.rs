@@ -0,0 +1,363 @@
+//! Local control API for a long-running node.
+//!
+//! Newline-delimited JSON over a Unix socket: every line a client writes is a
+//! [`ControlRequest`] answered by one [`ControlResponse`] line. After a
+//! `subscribe` request the connection instead streams one [`AcceptEvent`]
+//! per line until the client hangs up.
+
+use anyhow::{Context, Result};
+use iroh::{PublicKey, SecretKey};
+use n0_future::{StreamExt, boxed::BoxStream, stream, task::AbortOnDropHandle};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::net::{UnixListener, UnixStream, unix::{OwnedReadHalf, OwnedWriteHalf}};
+use tracing::info;
+
+use crate::credentials::income_credential::ValidityWindow;
+use crate::credentials::issuer_profile::IssuerProfile;
+use crate::credentials::officer_approval::ApprovalPolicy;
+use crate::peer_node::{AcceptEvent, PeerNode, PendingCredentialRequest};
+
+/// File name of the control socket inside a node's data directory
+pub const CONTROL_SOCKET: &str = "control.sock";
+
+/// A command sent to the control socket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ControlRequest {
+    /// The node's ID
+    Status,
+    /// All credential requests, whatever their status
+    ListPending,
+    GetPending {
+        request_id: String,
+    },
+    /// Answered with the request, still pending if it awaits further officer approvals
+    Approve {
+        request_id: String,
+        #[serde(default)]
+        validity: ValidityWindow,
+    },
+    Reject {
+        request_id: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    ListIssued,
+    Revoke {
+        credential_id: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    /// Answered with the signed rotation; the daemon must then be restarted with the new key
+    RotateKey {
+        new_secret_key: SecretKey,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    /// Resend approvals and rejections the employees have not acknowledged
+    RetryDeliveries,
+    GetProfile,
+    SetProfile {
+        profile: IssuerProfile,
+    },
+    /// The binding of the credential signing key, `null` if the node key signs credentials
+    GetKeyBinding,
+    /// Answered with the published unbinding of the signing key
+    UnbindSigningKey {
+        signing_key: PublicKey,
+    },
+    GetDidDocument,
+    /// Answered with the verified document of a `did:iroh` or `did:key`
+    ResolveDid {
+        did: String,
+    },
+    GetApprovalPolicy,
+    SetApprovalPolicy {
+        policy: ApprovalPolicy,
+    },
+    /// Turn the connection into a stream of [`AcceptEvent`]s
+    Subscribe,
+}
+
+/// Reply to a [`ControlRequest`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    pub(crate) fn from_result(result: Result<serde_json::Value>) -> Self {
+        match result {
+            Ok(value) => Self {
+                ok: true,
+                result: Some(value),
+                error: None,
+            },
+            Err(e) => Self {
+                ok: false,
+                result: None,
+                error: Some(format!("{e:#}")),
+            },
+        }
+    }
+
+    /// The result, or the daemon's error
+    pub(crate) fn into_result(self) -> Result<serde_json::Value> {
+        match self.error {
+            Some(error) if !self.ok => Err(anyhow::anyhow!(error)),
+            _ => Ok(self.result.unwrap_or_default()),
+        }
+    }
+}
+
+/// Serves the control API for `node` until dropped
+#[derive(Debug)]
+pub struct ControlServer {
+    path: PathBuf,
+    _task: AbortOnDropHandle<()>,
+}
+
+impl ControlServer {
+    /// Listens on `path`, replacing a stale socket left by a crashed daemon
+    pub async fn bind(path: impl AsRef<Path>, node: Arc<PeerNode>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // Anyone who can connect can approve credentials
+        let listener = bind_private_socket(&path).await?;
+        info!("control API listening on {}", path.display());
+
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let node = node.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_connection(stream, node).await {
+                                tracing::warn!("control connection failed: {e:#}");
+                            }
+                        });
+                    }
+                    Err(e) => tracing::error!("failed to accept control connection: {e}"),
+                }
+            }
+        });
+        Ok(Self {
+            path,
+            _task: AbortOnDropHandle::new(task),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Binds a Unix socket only its owner may connect to, replacing a stale one left
+/// by a crashed daemon
+pub(crate) async fn bind_private_socket(path: &Path) -> Result<UnixListener> {
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            anyhow::bail!("another daemon is already listening on {}", path.display());
+        }
+        std::fs::remove_file(path)?;
+    }
+
+    let listener = UnixListener::bind(path).with_context(|| format!("failed to bind socket {}", path.display()))?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(listener)
+}
+
+async fn serve_connection(stream: UnixStream, node: Arc<PeerNode>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let response = ControlResponse::from_result(Err(anyhow::anyhow!("invalid request: {e}")));
+                write_line(&mut writer, &response).await?;
+                continue;
+            }
+        };
+
+        if let ControlRequest::Subscribe = request {
+            // Subscribe before acknowledging so no event slips through
+            let mut events = node.accept_events();
+            write_line(&mut writer, &ControlResponse::from_result(Ok(serde_json::Value::Null))).await?;
+            while let Some(event) = events.next().await {
+                write_line(&mut writer, &event).await?;
+            }
+            return Ok(());
+        }
+
+        let response = ControlResponse::from_result(handle(&node, request).await);
+        write_line(&mut writer, &response).await?;
+    }
+    Ok(())
+}
+
+async fn handle(node: &PeerNode, request: ControlRequest) -> Result<serde_json::Value> {
+    let value = match request {
+        ControlRequest::Status => serde_json::json!({ "nodeId": node.endpoint().id() }),
+        ControlRequest::ListPending => serde_json::to_value(node.get_pending_requests().await)?,
+        ControlRequest::GetPending { request_id } => serde_json::to_value(get_pending(node, &request_id).await?)?,
+        ControlRequest::Approve { request_id, validity } => {
+            node.approve_request(request_id.clone(), validity).await?;
+            serde_json::to_value(get_pending(node, &request_id).await?)?
+        }
+        ControlRequest::Reject { request_id, reason } => {
+            node.reject_request(request_id, reason).await?;
+            serde_json::Value::Null
+        }
+        ControlRequest::ListIssued => serde_json::to_value(node.get_issued_credentials().await)?,
+        ControlRequest::Revoke { credential_id, reason } => {
+            node.revoke_credential(credential_id, reason).await?;
+            serde_json::Value::Null
+        }
+        ControlRequest::RotateKey { new_secret_key, reason } => {
+            serde_json::to_value(node.rotate_key(&new_secret_key, reason).await?)?
+        }
+        ControlRequest::RetryDeliveries => serde_json::json!({ "resent": node.retry_deliveries().await }),
+        ControlRequest::GetProfile => serde_json::to_value(node.get_issuer_profile().await)?,
+        ControlRequest::SetProfile { profile } => {
+            node.set_issuer_profile(profile).await?;
+            serde_json::Value::Null
+        }
+        ControlRequest::GetKeyBinding => serde_json::to_value(node.key_binding().await)?,
+        ControlRequest::UnbindSigningKey { signing_key } => {
+            serde_json::to_value(node.unbind_signing_key(signing_key).await?)?
+        }
+        ControlRequest::GetDidDocument => serde_json::to_value(node.did_document().await)?,
+        ControlRequest::ResolveDid { did } => serde_json::to_value(node.resolve_did(did).await?)?,
+        ControlRequest::GetApprovalPolicy => serde_json::to_value(node.get_approval_policy().await)?,
+        ControlRequest::SetApprovalPolicy { policy } => serde_json::to_value(node.set_approval_policy(policy).await?)?,
+        ControlRequest::Subscribe => unreachable!("handled by serve_connection"),
+    };
+    Ok(value)
+}
+
+async fn get_pending(node: &PeerNode, request_id: &str) -> Result<PendingCredentialRequest> {
+    node.get_pending_requests()
+        .await
+        .into_iter()
+        .find(|request| request.request_id == request_id)
+        .with_context(|| format!("Request {request_id} not found"))
+}
+
+pub(crate) async fn write_line(writer: &mut (impl AsyncWrite + Unpin), value: &impl Serialize) -> Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+/// Client side of the control socket
+#[derive(Debug)]
+pub struct ControlClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+}
+
+impl ControlClient {
+    pub async fn connect(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let stream = UnixStream::connect(path)
+            .await
+            .with_context(|| format!("failed to connect to control socket {}", path.display()))?;
+        let (reader, writer) = stream.into_split();
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+        })
+    }
+
+    /// Sends `request` and returns its result, or the daemon's error
+    pub async fn request(&mut self, request: &ControlRequest) -> Result<serde_json::Value> {
+        write_line(&mut self.writer, request).await?;
+        let line = self.lines.next_line().await?.context("control socket closed")?;
+        serde_json::from_str::<ControlResponse>(&line)?.into_result()
+    }
+
+    /// Turns this connection into a stream of the daemon's [`AcceptEvent`]s
+    pub async fn subscribe(mut self) -> Result<BoxStream<AcceptEvent>> {
+        self.request(&ControlRequest::Subscribe).await?;
+        let events = stream::unfold(self, |mut client| async move {
+            loop {
+                let line = client.lines.next_line().await.ok()??;
+                match serde_json::from_str(&line) {
+                    Ok(event) => return Some((event, client)),
+                    Err(e) => tracing::warn!("ignoring unexpected control event {line}: {e}"),
+                }
+            }
+        });
+        Ok(Box::pin(events))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peer_node::Role;
+    use crate::storage::MemoryStore;
+
+    #[tokio::test]
+    async fn test_control_round_trip() {
+        let dir = std::env::temp_dir().join(format!("trust-triangle-control-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONTROL_SOCKET);
+
+        let node = Arc::new(PeerNode::spawn(None, Role::Issuer, Arc::new(MemoryStore::default()), None, None).await.unwrap());
+        let server = ControlServer::bind(&path, node.clone()).await.unwrap();
+        assert!(ControlServer::bind(&path, node.clone()).await.is_err());
+
+        let mut client = ControlClient::connect(&path).await.unwrap();
+        let status = client.request(&ControlRequest::Status).await.unwrap();
+        assert_eq!(status["nodeId"], node.endpoint().id().to_string());
+        assert_eq!(client.request(&ControlRequest::ListPending).await.unwrap(), serde_json::json!([]));
+
+        let approve = ControlRequest::Approve {
+            request_id: "missing".to_string(),
+            validity: ValidityWindow::default(),
+        };
+        let error = client.request(&approve).await.unwrap_err();
+        assert!(error.to_string().contains("not found"));
+
+        let unreachable_threshold = ControlRequest::SetApprovalPolicy {
+            policy: ApprovalPolicy {
+                officers: vec![node.endpoint().id()],
+                threshold: 2,
+            },
+        };
+        assert!(client.request(&unreachable_threshold).await.is_err());
+
+        // A malformed line gets an error reply and leaves the connection usable
+        client.writer.write_all(b"{\"command\":\"nope\"}\n").await.unwrap();
+        let line = client.lines.next_line().await.unwrap().unwrap();
+        assert!(!serde_json::from_str::<ControlResponse>(&line).unwrap().ok);
+        assert!(client.request(&ControlRequest::ListIssued).await.is_ok());
+
+        drop(server);
+        assert!(!path.exists());
+        node.shutdown().await.unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
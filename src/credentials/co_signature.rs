@@ -0,0 +1,314 @@
+use iroh::{EndpointId, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use super::income_credential::{CredentialError, SignatureFormat, SignedIncomeCredential};
+use super::selective_disclosure::CommittedIncomeCredential;
+use super::threshold_proof::SignedAmountCommitment;
+
+/// Domain separation tag prefixed to every co-signature signing payload
+const CO_SIGNATURE_SIGNING_DOMAIN: &str = "trust-triangle/credential-co-signature";
+
+/// Version of the co-signature signing payload layout
+const CO_SIGNATURE_PAYLOAD_VERSION: u8 = 1;
+
+/// Capacity in which a party signs a credential
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerRole {
+    /// The employer, whose signature is the credential's own
+    Issuer,
+    /// The payroll processor named on the credential, attesting the payment
+    PayrollProcessor,
+}
+
+impl SignerRole {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SignerRole::Issuer => "issuer",
+            SignerRole::PayrollProcessor => "payroll processor",
+        }
+    }
+}
+
+/// Signature of a party other than the issuer over a credential's canonical payload
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CoSignature {
+    pub signer_node_id: EndpointId,
+    pub role: SignerRole,
+    /// Timestamp of the signature (RFC3339 format)
+    pub signed_at: String,
+    pub signature: Vec<u8>,
+}
+
+/// Whether one party declared on a credential has signed it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerStatus {
+    pub node_id: EndpointId,
+    pub role: SignerRole,
+    pub signed: bool,
+}
+
+#[derive(Serialize)]
+struct CanonicalCoSignature<'a> {
+    domain: &'a str,
+    version: u8,
+    signer_node_id: &'a [u8; 32],
+    role: SignerRole,
+    signed_at: &'a str,
+    /// Canonical payload of the credential, as signed by the issuer
+    credential: &'a [u8],
+}
+
+impl CoSignature {
+    fn signing_payload(
+        credential: &(impl CoSigned + ?Sized),
+        signer_node_id: &EndpointId,
+        role: SignerRole,
+        signed_at: &str,
+    ) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalCoSignature {
+            domain: CO_SIGNATURE_SIGNING_DOMAIN,
+            version: CO_SIGNATURE_PAYLOAD_VERSION,
+            signer_node_id: signer_node_id.as_bytes(),
+            role,
+            signed_at,
+            credential: &credential.co_signed_payload()?,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs `credential` in the capacity of `role`
+    pub fn sign(
+        credential: &(impl CoSigned + ?Sized),
+        role: SignerRole,
+        secret_key: &SecretKey,
+    ) -> Result<Self, CredentialError> {
+        let signer_node_id = secret_key.public();
+        let signed_at = chrono::Utc::now().to_rfc3339();
+        let payload = Self::signing_payload(credential, &signer_node_id, role, &signed_at)?;
+        Ok(Self {
+            signer_node_id,
+            role,
+            signed_at,
+            signature: secret_key.sign(&payload).to_bytes().to_vec(),
+        })
+    }
+
+    /// Verifies the co-signature over `credential`
+    pub fn verify(&self, credential: &(impl CoSigned + ?Sized)) -> Result<bool, CredentialError> {
+        let payload = Self::signing_payload(credential, &self.signer_node_id, self.role, &self.signed_at)?;
+        super::verify_signature(&self.signer_node_id, &payload, &self.signature)
+    }
+}
+
+/// A credential form that declares parties besides the issuer, who co-sign the
+/// same canonical payload the issuer signed.
+///
+/// The full credential, its selective-disclosure commitment and its net salary
+/// commitment each carry their own co-signatures, so every form a holder can
+/// present shows the processor's attestation.
+pub trait CoSigned {
+    /// Parties other than the issuer the credential declares, who must co-sign it
+    fn declared_co_signers(&self) -> Vec<(EndpointId, SignerRole)>;
+    /// Co-signatures attached to the credential
+    fn co_signatures(&self) -> &[CoSignature];
+    fn co_signatures_mut(&mut self) -> &mut Vec<CoSignature>;
+    /// Canonical payload of the credential, as signed by the issuer
+    fn co_signed_payload(&self) -> Result<Vec<u8>, CredentialError>;
+
+    /// Checks a co-signature from a declared party and attaches it
+    fn add_co_signature(&mut self, co_signature: CoSignature) -> Result<(), CredentialError> {
+        let declared = (co_signature.signer_node_id, co_signature.role);
+        if !self.declared_co_signers().contains(&declared) {
+            return Err(CredentialError::InvalidCoSignature(format!(
+                "{} is not the credential's {}",
+                co_signature.signer_node_id,
+                co_signature.role.as_str()
+            )));
+        }
+        if !co_signature.verify(self)? {
+            return Err(CredentialError::InvalidCoSignature(format!(
+                "signature of {} does not verify",
+                co_signature.signer_node_id
+            )));
+        }
+        let co_signatures = self.co_signatures_mut();
+        co_signatures.retain(|existing| existing.signer_node_id != co_signature.signer_node_id);
+        co_signatures.push(co_signature);
+        Ok(())
+    }
+
+    /// Whether each declared co-signer has a valid co-signature on the credential
+    fn co_signer_statuses(&self) -> Result<Vec<SignerStatus>, CredentialError> {
+        // Forms without a canonical payload (legacy statements) cannot be co-signed
+        let co_signable = self.co_signed_payload().is_ok();
+        self.declared_co_signers()
+            .into_iter()
+            .map(|(node_id, role)| {
+                let mut signed = false;
+                for co_signature in self.co_signatures() {
+                    if co_signature.signer_node_id == node_id
+                        && co_signature.role == role
+                        && co_signable
+                        && co_signature.verify(self)?
+                    {
+                        signed = true;
+                        break;
+                    }
+                }
+                Ok(SignerStatus { node_id, role, signed })
+            })
+            .collect()
+    }
+}
+
+impl CoSigned for SignedIncomeCredential {
+    fn declared_co_signers(&self) -> Vec<(EndpointId, SignerRole)> {
+        declared_processor(self.credential.payroll_processor_node_id)
+    }
+
+    fn co_signatures(&self) -> &[CoSignature] {
+        &self.co_signatures
+    }
+
+    fn co_signatures_mut(&mut self) -> &mut Vec<CoSignature> {
+        &mut self.co_signatures
+    }
+
+    fn co_signed_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        if self.format != SignatureFormat::CanonicalV1 {
+            return Err(CredentialError::InvalidCoSignature(
+                "legacy statement credentials cannot be co-signed".to_string(),
+            ));
+        }
+        self.credential.signing_payload()
+    }
+}
+
+impl CoSigned for CommittedIncomeCredential {
+    fn declared_co_signers(&self) -> Vec<(EndpointId, SignerRole)> {
+        declared_processor(self.payroll_processor_node_id)
+    }
+
+    fn co_signatures(&self) -> &[CoSignature] {
+        &self.co_signatures
+    }
+
+    fn co_signatures_mut(&mut self) -> &mut Vec<CoSignature> {
+        &mut self.co_signatures
+    }
+
+    fn co_signed_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        self.signing_payload()
+    }
+}
+
+impl CoSigned for SignedAmountCommitment {
+    fn declared_co_signers(&self) -> Vec<(EndpointId, SignerRole)> {
+        declared_processor(self.payroll_processor_node_id)
+    }
+
+    fn co_signatures(&self) -> &[CoSignature] {
+        &self.co_signatures
+    }
+
+    fn co_signatures_mut(&mut self) -> &mut Vec<CoSignature> {
+        &mut self.co_signatures
+    }
+
+    fn co_signed_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        self.signing_payload()
+    }
+}
+
+fn declared_processor(node_id: Option<EndpointId>) -> Vec<(EndpointId, SignerRole)> {
+    node_id
+        .map(|node_id| (node_id, SignerRole::PayrollProcessor))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::income_credential::{IncomeCredential, PaymentMode};
+
+    fn issue(issuer_key: &SecretKey, processor: EndpointId) -> SignedIncomeCredential {
+        IncomeCredential::new_with_processor(
+            SecretKey::generate(&mut rand::rng()).public(),
+            "Alice".to_string(),
+            issuer_key.public(),
+            "Acme Corp".to_string(),
+            processor,
+            "PayCo".to_string(),
+            "9000.00".to_string(),
+            "7200.00".to_string(),
+            "USD".to_string(),
+            "2025-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+        .sign(issuer_key)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_processor_co_signature() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let processor_key = SecretKey::generate(&mut rand::rng());
+        let mut credential = issue(&issuer_key, processor_key.public());
+
+        let unsigned = credential.co_signer_statuses().unwrap();
+        assert_eq!(unsigned.len(), 1);
+        assert!(!unsigned[0].signed);
+
+        let co_signature = CoSignature::sign(&credential, SignerRole::PayrollProcessor, &processor_key).unwrap();
+        credential.add_co_signature(co_signature).unwrap();
+        let statuses = credential.co_signer_statuses().unwrap();
+        assert_eq!(statuses[0].node_id, processor_key.public());
+        assert!(statuses[0].signed);
+
+        // The co-signature covers the credential's content
+        let mut tampered = credential.clone();
+        tampered.credential.net_salary = "9000.00".to_string();
+        assert!(!tampered.co_signer_statuses().unwrap()[0].signed);
+    }
+
+    #[test]
+    fn test_undeclared_co_signer_is_rejected() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let processor_key = SecretKey::generate(&mut rand::rng());
+        let other_key = SecretKey::generate(&mut rand::rng());
+        let mut credential = issue(&issuer_key, processor_key.public());
+
+        let co_signature = CoSignature::sign(&credential, SignerRole::PayrollProcessor, &other_key).unwrap();
+        assert!(credential.add_co_signature(co_signature.clone()).is_err());
+
+        // Attached anyway, it does not count for the declared processor
+        credential.co_signatures.push(co_signature);
+        assert!(!credential.co_signer_statuses().unwrap()[0].signed);
+    }
+
+    #[test]
+    fn test_commitments_are_co_signed_separately() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let processor_key = SecretKey::generate(&mut rand::rng());
+        let credential = issue(&issuer_key, processor_key.public());
+        let mut selective = credential.credential.sign_selective(&issuer_key).unwrap().credential;
+        let mut threshold = credential.credential.sign_amount_commitment(&issuer_key).unwrap().credential;
+        assert_eq!(selective.declared_co_signers(), credential.declared_co_signers());
+        assert!(!threshold.co_signer_statuses().unwrap()[0].signed);
+
+        // A co-signature over one form does not count for another
+        let full = CoSignature::sign(&credential, SignerRole::PayrollProcessor, &processor_key).unwrap();
+        assert!(selective.add_co_signature(full.clone()).is_err());
+        threshold.co_signatures.push(full);
+        assert!(!threshold.co_signer_statuses().unwrap()[0].signed);
+
+        selective
+            .add_co_signature(CoSignature::sign(&selective, SignerRole::PayrollProcessor, &processor_key).unwrap())
+            .unwrap();
+        assert!(selective.co_signer_statuses().unwrap()[0].signed);
+    }
+}
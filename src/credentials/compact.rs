@@ -0,0 +1,221 @@
+//! Compact binary form of income credentials, small enough for a QR code.
+//!
+//! The bytes are a version byte followed by the DEFLATE-compressed postcard
+//! encoding of the [`SignedIncomeCredential`]. As text they are base45
+//! (RFC 9285) behind [`QR_PREFIX`], which fits the QR alphanumeric mode, or
+//! unpadded base64url for links and other channels that carry any text.
+
+use std::io::{Read, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
+
+use super::income_credential::{CredentialError, SignedIncomeCredential};
+
+/// Version of the compact layout; postcard has no field names, so any change
+/// to [`SignedIncomeCredential`] needs a new version
+pub const COMPACT_VERSION: u8 = 2;
+
+/// Prefix of the base45 text form
+pub const QR_PREFIX: &str = "TT1:";
+
+/// Upper bound on a decompressed credential, so a crafted code cannot exhaust memory
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024;
+
+const BASE45_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+impl SignedIncomeCredential {
+    /// Encodes the credential in the compact binary form
+    pub fn to_compact(&self) -> Result<Vec<u8>, CredentialError> {
+        let mut encoder = DeflateEncoder::new(vec![COMPACT_VERSION], Compression::best());
+        encoder
+            .write_all(&postcard::to_allocvec(self)?)
+            .and_then(|_| encoder.finish())
+            .map_err(|e| CredentialError::InvalidCompact(format!("failed to compress credential: {e}")))
+    }
+
+    /// Inverse of [`Self::to_compact`]; does not check any signature
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, CredentialError> {
+        let invalid = |reason: String| CredentialError::InvalidCompact(reason);
+        let (&version, compressed) = bytes.split_first().ok_or_else(|| invalid("no data".into()))?;
+        if version != COMPACT_VERSION {
+            return Err(invalid(format!("unsupported version {version}")));
+        }
+        let mut encoded = Vec::new();
+        DeflateDecoder::new(compressed)
+            .take(MAX_DECOMPRESSED_SIZE)
+            .read_to_end(&mut encoded)
+            .map_err(|e| invalid(format!("failed to decompress credential: {e}")))?;
+        let (signed, rest) =
+            postcard::take_from_bytes(&encoded).map_err(|e| invalid(format!("malformed credential: {e}")))?;
+        if !rest.is_empty() {
+            return Err(invalid("trailing bytes after the credential".into()));
+        }
+        Ok(signed)
+    }
+
+    /// Base45 text form for QR codes, starting with [`QR_PREFIX`]
+    pub fn to_qr_text(&self) -> Result<String, CredentialError> {
+        Ok(format!("{QR_PREFIX}{}", base45_encode(&self.to_compact()?)))
+    }
+
+    /// Base64url text form for links
+    pub fn to_compact_base64url(&self) -> Result<String, CredentialError> {
+        Ok(URL_SAFE_NO_PAD.encode(self.to_compact()?))
+    }
+
+    /// Decodes either text form; does not check any signature
+    pub fn from_compact_text(text: &str) -> Result<Self, CredentialError> {
+        let text = text.trim();
+        let bytes = match text.strip_prefix(QR_PREFIX) {
+            Some(base45) => base45_decode(base45)?,
+            None => URL_SAFE_NO_PAD
+                .decode(text)
+                .map_err(|_| CredentialError::InvalidCompact("neither base45 nor base64url".into()))?,
+        };
+        Self::from_compact(&bytes)
+    }
+}
+
+/// Decodes a scanned credential and checks its signature and co-signatures
+/// without any network access.
+///
+/// Only the credential's own signatures are checked: whether the issuer is
+/// trusted, its signing key is bound to it, or the credential is revoked or
+/// expired is up to the caller.
+pub fn verify_compact(text: &str) -> Result<SignedIncomeCredential, CredentialError> {
+    let signed = SignedIncomeCredential::from_compact_text(text)?;
+    if !signed.verify()? {
+        return Err(CredentialError::InvalidSignature);
+    }
+    // Declared co-signers that have not signed are reported by the caller;
+    // a co-signature that is present but invalid fails the credential
+    for co_signature in &signed.co_signatures {
+        if !co_signature.verify(&signed)? {
+            return Err(CredentialError::InvalidCoSignature(format!(
+                "signature of {} does not verify",
+                co_signature.signer_node_id
+            )));
+        }
+    }
+    Ok(signed)
+}
+
+/// Encodes every two bytes as three base45 characters, least significant first
+fn base45_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(2) * 3);
+    for chunk in bytes.chunks(2) {
+        let (mut value, digits) = match *chunk {
+            [high, low] => ((high as usize) << 8 | low as usize, 3),
+            [byte] => (byte as usize, 2),
+            _ => unreachable!("chunks of at most two bytes"),
+        };
+        for _ in 0..digits {
+            text.push(BASE45_ALPHABET[value % 45] as char);
+            value /= 45;
+        }
+    }
+    text
+}
+
+fn base45_decode(text: &str) -> Result<Vec<u8>, CredentialError> {
+    let invalid = |reason: &str| CredentialError::InvalidCompact(format!("invalid base45: {reason}"));
+    let digits = text
+        .bytes()
+        .map(|c| BASE45_ALPHABET.iter().position(|&a| a == c).ok_or_else(|| invalid("unknown character")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut bytes = Vec::with_capacity(digits.len() / 3 * 2 + 1);
+    for chunk in digits.chunks(3) {
+        let value = chunk.iter().rev().fold(0, |value, digit| value * 45 + digit);
+        match chunk.len() {
+            3 if value <= 0xffff => bytes.extend_from_slice(&(value as u16).to_be_bytes()),
+            2 if value <= 0xff => bytes.push(value as u8),
+            1 => return Err(invalid("dangling character")),
+            _ => return Err(invalid("group out of range")),
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::co_signature::{CoSignature, CoSigned, SignerRole};
+    use crate::credentials::income_credential::{IncomeCredential, PaymentMode};
+    use iroh::SecretKey;
+
+    #[test]
+    fn test_base45_rfc_vectors() {
+        for (bytes, text) in [
+            (&b"AB"[..], "BB8"),
+            (b"Hello!!", "%69 VD92EX0"),
+            (b"base-45", "UJCLQE7W581"),
+            (b"ietf!", "QED8WEX0"),
+            (b"", ""),
+        ] {
+            assert_eq!(base45_encode(bytes), text);
+            assert_eq!(base45_decode(text).unwrap(), bytes);
+        }
+        // 3 characters above 0xffff, a dangling character and lowercase are rejected
+        assert!(base45_decode("GGW").is_err());
+        assert!(base45_decode("BB8A").is_err());
+        assert!(base45_decode("bb8").is_err());
+    }
+
+    #[test]
+    fn test_compact_round_trip_and_offline_verify() {
+        let employer_key = SecretKey::generate(&mut rand::rng());
+        let processor_key = SecretKey::generate(&mut rand::rng());
+        let mut signed = IncomeCredential::new_with_processor(
+            SecretKey::generate(&mut rand::rng()).public(),
+            "Jane Doe".to_string(),
+            employer_key.public(),
+            "Acme Corporation".to_string(),
+            processor_key.public(),
+            "PayCo".to_string(),
+            "10000.00".to_string(),
+            "8000.00".to_string(),
+            "USD".to_string(),
+            "2024-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+        .with_status_index(3)
+        .sign(&employer_key)
+        .unwrap();
+        signed
+            .add_co_signature(CoSignature::sign(&signed, SignerRole::PayrollProcessor, &processor_key).unwrap())
+            .unwrap();
+
+        let qr = signed.to_qr_text().unwrap();
+        assert!(qr.starts_with(QR_PREFIX));
+        assert!(qr.bytes().all(|c| BASE45_ALPHABET.contains(&c)));
+        // Fits a version 25 QR code at the medium error correction level
+        assert!(qr.len() < 1_000, "{} characters", qr.len());
+        let link = signed.to_compact_base64url().unwrap();
+
+        for text in [&qr, &link] {
+            let decoded = verify_compact(text).unwrap();
+            assert_eq!(
+                decoded.credential.signing_payload().unwrap(),
+                signed.credential.signing_payload().unwrap()
+            );
+            assert_eq!(decoded.signature, signed.signature);
+            assert_eq!(decoded.co_signatures.len(), 1);
+        }
+
+        // Changing the salary breaks the issuer's signature
+        let mut forged = signed.clone();
+        forged.credential.net_salary = "9000.00".to_string();
+        assert!(matches!(
+            verify_compact(&forged.to_qr_text().unwrap()),
+            Err(CredentialError::InvalidSignature)
+        ));
+
+        let mut bytes = signed.to_compact().unwrap();
+        bytes[0] = COMPACT_VERSION + 1;
+        assert!(SignedIncomeCredential::from_compact(&bytes).is_err());
+        assert!(verify_compact("TT1:not base45").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! DIDs of node identities.
+//!
+//! `did:key` encodes an Ed25519 key directly and resolves offline. `did:iroh`
+//! names a node by its `EndpointId`; its DID document, served by the node,
+//! lists the node's addressing info and the keys bound to sign its credentials,
+//! so the signing keys can change while the DID stays the same.
+
+use iroh::{EndpointAddr, EndpointId, PublicKey};
+use n0_future::boxed::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::income_credential::CredentialError;
+use super::key_binding::{SignedKeyBinding, SignedKeyUnbinding, is_unbound};
+
+/// Multicodec prefix of an Ed25519 public key (`ed25519-pub`, varint 0xed)
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// Base context of every DID document
+pub const DID_CONTEXT_V1: &str = "https://www.w3.org/ns/did/v1";
+
+const MULTIKEY_CONTEXT: &str = "https://w3id.org/security/multikey/v1";
+const MULTIKEY: &str = "Multikey";
+
+/// Service type of the iroh addressing info in a `did:iroh` document
+pub const IROH_ENDPOINT_SERVICE: &str = "IrohEndpoint";
+
+/// Encodes `key` as a `did:key` identifier
+pub fn did_key(key: &PublicKey) -> String {
+    format!("did:key:{}", multibase_key(key))
+}
+
+/// Verification method of a `did:key`: the DID with its key as fragment
+pub fn did_key_verification_method(key: &PublicKey) -> String {
+    let multibase = multibase_key(key);
+    format!("did:key:{multibase}#{multibase}")
+}
+
+/// Parses an Ed25519 `did:key`, or one of its verification methods
+pub fn parse_did_key(did: &str) -> Result<PublicKey, CredentialError> {
+    let multibase = did
+        .strip_prefix("did:key:")
+        .ok_or_else(|| CredentialError::InvalidDid(format!("{did}: not a did:key")))?;
+    let (multibase, fragment) = multibase.split_once('#').unwrap_or((multibase, multibase));
+    if fragment != multibase {
+        return Err(CredentialError::InvalidDid(format!("{did}: fragment does not name the key")));
+    }
+    parse_multikey(multibase).map_err(|reason| CredentialError::InvalidDid(format!("{did}: {reason}")))
+}
+
+/// Encodes `node_id` as a `did:iroh` identifier
+pub fn did_iroh(node_id: &EndpointId) -> String {
+    format!("did:iroh:{node_id}")
+}
+
+/// Parses a `did:iroh` into the node ID it names
+pub fn parse_did_iroh(did: &str) -> Result<EndpointId, CredentialError> {
+    did.strip_prefix("did:iroh:")
+        .ok_or_else(|| CredentialError::InvalidDid(format!("{did}: not a did:iroh")))?
+        .parse()
+        .map_err(|e| CredentialError::InvalidDid(format!("{did}: {e}")))
+}
+
+/// DID Core document of a `did:key` or `did:iroh`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    pub verification_method: Vec<VerificationMethod>,
+    /// Keys that authenticate as the DID subject
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authentication: Vec<String>,
+    /// Keys that sign credentials on behalf of the DID subject
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_method: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub service: Vec<DidService>,
+}
+
+/// Ed25519 key of a DID document, as a `Multikey`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub method_type: String,
+    pub controller: String,
+    pub public_key_multibase: String,
+}
+
+/// Where to reach the DID subject over iroh
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DidService {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub service_type: String,
+    pub service_endpoint: IrohServiceEndpoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IrohServiceEndpoint {
+    /// Node currently answering for the DID; differs from the DID's node after a key rotation
+    pub endpoint_id: EndpointId,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relay_urls: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub direct_addresses: Vec<String>,
+}
+
+impl DidDocument {
+    /// Document of a `did:key`: the key alone
+    pub fn for_did_key(key: &PublicKey) -> Self {
+        let method = did_key_verification_method(key);
+        Self {
+            context: vec![DID_CONTEXT_V1.to_string(), MULTIKEY_CONTEXT.to_string()],
+            id: did_key(key),
+            verification_method: vec![VerificationMethod::new(method.clone(), did_key(key), key)],
+            authentication: vec![method.clone()],
+            assertion_method: vec![method],
+            service: Vec::new(),
+        }
+    }
+
+    /// Document of a `did:iroh`: the node key authenticates the node and, with
+    /// the signing keys bound to it, signs its credentials. `addr` is where the
+    /// node answering for the DID can be reached.
+    pub fn for_iroh_node(node_id: &EndpointId, signing_keys: &[PublicKey], addr: Option<&EndpointAddr>) -> Self {
+        let did = did_iroh(node_id);
+        let method_id = |key: &PublicKey| format!("{did}#{}", multibase_key(key));
+        let mut verification_method = vec![VerificationMethod::new(method_id(node_id), did.clone(), node_id)];
+        verification_method.extend(
+            signing_keys
+                .iter()
+                .filter(|key| *key != node_id)
+                .map(|key| VerificationMethod::new(method_id(key), did.clone(), key)),
+        );
+        let service = addr
+            .map(|addr| DidService {
+                id: format!("{did}#iroh"),
+                service_type: IROH_ENDPOINT_SERVICE.to_string(),
+                service_endpoint: IrohServiceEndpoint {
+                    endpoint_id: addr.id,
+                    relay_urls: addr.relay_urls().map(|url| url.to_string()).collect(),
+                    direct_addresses: addr.ip_addrs().map(|addr| addr.to_string()).collect(),
+                },
+            })
+            .into_iter()
+            .collect();
+        Self {
+            context: vec![DID_CONTEXT_V1.to_string(), MULTIKEY_CONTEXT.to_string()],
+            authentication: vec![method_id(node_id)],
+            assertion_method: verification_method.iter().map(|method| method.id.clone()).collect(),
+            verification_method,
+            id: did,
+            service,
+        }
+    }
+
+    /// Keys listed as assertion methods, which verify the subject's credentials
+    pub fn assertion_keys(&self) -> Result<Vec<PublicKey>, CredentialError> {
+        self.assertion_method
+            .iter()
+            .map(|reference| {
+                // References may be relative to the document
+                let method = self
+                    .verification_method
+                    .iter()
+                    .find(|method| method.id == *reference || method.id == format!("{}{reference}", self.id))
+                    .ok_or_else(|| CredentialError::InvalidDid(format!("{}: no verification method {reference}", self.id)))?;
+                method.public_key()
+            })
+            .collect()
+    }
+
+    /// Checks this is the `did:iroh` document of `node_id`, and that every key
+    /// besides the node key is bound to the node by one of `bindings` and not
+    /// retired by one of `unbindings`
+    pub fn check_iroh(
+        &self,
+        node_id: &EndpointId,
+        bindings: &[SignedKeyBinding],
+        unbindings: &[SignedKeyUnbinding],
+    ) -> Result<(), CredentialError> {
+        let invalid = |reason: String| CredentialError::InvalidDid(format!("{}: {reason}", self.id));
+        if self.id != did_iroh(node_id) {
+            return Err(invalid(format!("not the document of {}", did_iroh(node_id))));
+        }
+        for method in &self.verification_method {
+            let key = method.public_key()?;
+            let bound = key == *node_id
+                || bindings
+                    .iter()
+                    .any(|binding| binding.binds(*node_id, key).unwrap_or(false));
+            if !bound {
+                return Err(invalid(format!("key {key} is not bound to the node")));
+            }
+            if is_unbound(unbindings, *node_id, key) {
+                return Err(invalid(format!("key {key} was unbound from the node")));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VerificationMethod {
+    fn new(id: String, controller: String, key: &PublicKey) -> Self {
+        Self {
+            id,
+            method_type: MULTIKEY.to_string(),
+            controller,
+            public_key_multibase: multibase_key(key),
+        }
+    }
+
+    pub fn public_key(&self) -> Result<PublicKey, CredentialError> {
+        if self.method_type != MULTIKEY {
+            return Err(CredentialError::InvalidDid(format!("{}: not a Multikey", self.id)));
+        }
+        parse_multikey(&self.public_key_multibase)
+            .map_err(|reason| CredentialError::InvalidDid(format!("{}: {reason}", self.id)))
+    }
+}
+
+/// Resolves DIDs to their documents.
+///
+/// Implementations return only documents they have authenticated, e.g. by
+/// checking that the DID subject bound every key listed.
+pub trait DidResolver: fmt::Debug + Send + Sync {
+    fn resolve(&self, did: &str) -> BoxFuture<Result<DidDocument, CredentialError>>;
+}
+
+/// Resolves `did:key`, which needs no lookup
+#[derive(Debug, Clone, Default)]
+pub struct KeyDidResolver;
+
+impl DidResolver for KeyDidResolver {
+    fn resolve(&self, did: &str) -> BoxFuture<Result<DidDocument, CredentialError>> {
+        let document = parse_did_key(did).map(|key| DidDocument::for_did_key(&key));
+        Box::pin(async move { document })
+    }
+}
+
+/// `z`-prefixed base58btc multibase of `bytes`
+pub(crate) fn multibase_base58btc(bytes: &[u8]) -> String {
+    format!("z{}", bs58::encode(bytes).into_string())
+}
+
+fn multibase_key(key: &PublicKey) -> String {
+    let mut bytes = ED25519_MULTICODEC.to_vec();
+    bytes.extend_from_slice(key.as_bytes());
+    multibase_base58btc(&bytes)
+}
+
+fn parse_multikey(multibase: &str) -> Result<PublicKey, String> {
+    let encoded = multibase.strip_prefix('z').ok_or("key is not base58btc")?;
+    let bytes = bs58::decode(encoded).into_vec().map_err(|e| e.to_string())?;
+    let key = bytes
+        .strip_prefix(&ED25519_MULTICODEC)
+        .ok_or("not an Ed25519 key")?;
+    let key: &[u8; 32] = key.try_into().map_err(|_| "Ed25519 key is not 32 bytes")?;
+    PublicKey::from_bytes(key).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::key_binding::{KeyBinding, KeyUnbinding};
+    use crate::credentials::signer::{CredentialSigner, MemorySigner};
+    use iroh::SecretKey;
+    use n0_future::future::block_on;
+
+    #[test]
+    fn test_did_key_vector() {
+        // Key pair from the W3C Data Integrity EdDSA Cryptosuites examples
+        let seed = bs58::decode("3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq").into_vec().unwrap();
+        let seed: [u8; 32] = seed[2..].try_into().unwrap();
+        let key = SecretKey::from_bytes(&seed).public();
+        assert_eq!(did_key(&key), "did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2");
+        assert_eq!(parse_did_key(&did_key(&key)).unwrap(), key);
+        assert_eq!(parse_did_key(&did_key_verification_method(&key)).unwrap(), key);
+
+        assert!(parse_did_key("did:web:example.com").is_err());
+        assert!(parse_did_key("did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2#other").is_err());
+    }
+
+    #[test]
+    fn test_did_iroh_document() {
+        let node_key = SecretKey::generate(&mut rand::rng());
+        let node_id = node_key.public();
+        let signer = MemorySigner::new(SecretKey::generate(&mut rand::rng()));
+        let binding = block_on(KeyBinding::new(node_id, signer.public_key()).sign(&signer, &node_key)).unwrap();
+
+        let did = did_iroh(&node_id);
+        assert_eq!(parse_did_iroh(&did).unwrap(), node_id);
+        assert!(parse_did_iroh(&did_key(&node_id)).is_err());
+
+        let addr = EndpointAddr::new(node_id).with_ip_addr("127.0.0.1:4433".parse().unwrap());
+        let document = DidDocument::for_iroh_node(&node_id, &[signer.public_key()], Some(&addr));
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(json["id"], did);
+        assert_eq!(json["service"][0]["serviceEndpoint"]["directAddresses"][0], "127.0.0.1:4433");
+        assert_eq!(document.assertion_keys().unwrap(), vec![node_id, signer.public_key()]);
+        document.check_iroh(&node_id, std::slice::from_ref(&binding), &[]).unwrap();
+
+        // Keys the node did not bind, or unbound since, are refused
+        assert!(document.check_iroh(&node_id, &[], &[]).is_err());
+        assert!(document.check_iroh(&signer.public_key(), &[], &[]).is_err());
+        let unbinding = KeyUnbinding::new(node_id, signer.public_key()).sign(&node_key).unwrap();
+        assert!(document.check_iroh(&node_id, &[binding], &[unbinding]).is_err());
+
+        let resolved = block_on(KeyDidResolver.resolve(&did_key(&node_id))).unwrap();
+        assert_eq!(resolved.assertion_keys().unwrap(), vec![node_id]);
+        assert!(block_on(KeyDidResolver.resolve(&did)).is_err());
+    }
+}
@@ -1,8 +1,14 @@
-use iroh::{EndpointId, SecretKey};
+use chrono::{DateTime, Utc};
+use iroh::{EndpointId, PublicKey, SecretKey};
 use iroh_base::Signature;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use super::co_signature::CoSignature;
+use super::key_binding::distinct_signing_key;
+use super::officer_approval::CredentialApproval;
+use super::signer::CredentialSigner;
+
 #[derive(Error, Debug)]
 pub enum CredentialError {
     #[error("failed to sign credential: {0}")]
@@ -16,8 +22,56 @@ pub enum CredentialError {
     
     #[error("serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+
+    #[error("encoding error: {0}")]
+    EncodingError(#[from] postcard::Error),
+
+    #[error("invalid validity window: {0}")]
+    InvalidValidity(String),
+
+    #[error("status list error: {0}")]
+    StatusListError(String),
+
+    #[error("invalid issuer profile: {0}")]
+    InvalidProfile(String),
+
+    #[error("invalid presentation constraints: {0}")]
+    InvalidConstraints(String),
+
+    #[error("invalid disclosure: {0}")]
+    InvalidDisclosure(String),
+
+    #[error("invalid threshold proof: {0}")]
+    InvalidProof(String),
+
+    #[error("invalid co-signature: {0}")]
+    InvalidCoSignature(String),
+
+    #[error("invalid officer approval: {0}")]
+    InvalidApproval(String),
+
+    #[error("invalid key rotation: {0}")]
+    InvalidRotation(String),
+
+    #[error("invalid DID: {0}")]
+    InvalidDid(String),
+
+    #[error("invalid verifiable credential: {0}")]
+    InvalidVerifiableCredential(String),
+
+    #[error("invalid JWS: {0}")]
+    InvalidJws(String),
+
+    #[error("invalid compact credential: {0}")]
+    InvalidCompact(String),
 }
 
+/// Domain separation tag prefixed to every canonical signing payload
+const CREDENTIAL_SIGNING_DOMAIN: &str = "trust-triangle/income-credential";
+
+/// Version of the canonical signing payload layout
+pub const CANONICAL_PAYLOAD_VERSION: u8 = 1;
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum PaymentMode {
@@ -40,17 +94,127 @@ impl PaymentMode {
     }
 }
 
+/// How the signature on a [`SignedIncomeCredential`] was produced
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureFormat {
+    /// Signature over the human-readable statement (credentials issued before canonical signing)
+    #[default]
+    LegacyStatement,
+    /// Signature over the versioned, domain-separated postcard encoding of every field
+    CanonicalV1,
+}
+
+/// Optional period during which a credential may be relied upon (RFC3339 timestamps)
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidityWindow {
+    /// The credential must not be accepted before this time
+    pub valid_from: Option<String>,
+    /// The credential must not be accepted after this time
+    pub expires_at: Option<String>,
+}
+
+impl ValidityWindow {
+    /// Checks that both bounds are RFC3339 timestamps and that they are ordered
+    pub fn validate(&self) -> Result<(), CredentialError> {
+        let valid_from = self.valid_from.as_deref().map(parse_timestamp).transpose()?;
+        let expires_at = self.expires_at.as_deref().map(parse_timestamp).transpose()?;
+        if let (Some(from), Some(until)) = (valid_from, expires_at)
+            && from >= until
+        {
+            return Err(CredentialError::InvalidValidity(
+                "validFrom must be earlier than expiresAt".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of checking a credential's validity window and age at a point in time
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidityStatus {
+    /// Within its validity window and not older than the verifier's max age
+    #[default]
+    Valid,
+    /// `valid_from` is in the future
+    NotYetValid,
+    /// `expires_at` has passed
+    Expired,
+    /// Issued longer ago than the verifier's max-age policy allows
+    Stale,
+    /// A timestamp on the credential could not be parsed
+    InvalidTimestamp,
+    /// The issuer's signature does not cover the credential's timestamps (legacy
+    /// statement signature), so they cannot be relied on
+    Unknown,
+}
+
+impl ValidityStatus {
+    pub fn is_valid(&self) -> bool {
+        *self == ValidityStatus::Valid
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ValidityStatus::Valid => "valid",
+            ValidityStatus::NotYetValid => "not yet valid",
+            ValidityStatus::Expired => "expired",
+            ValidityStatus::Stale => "stale",
+            ValidityStatus::InvalidTimestamp => "invalid timestamp",
+            ValidityStatus::Unknown => "unknown, the timestamps are not signed",
+        }
+    }
+}
+
+pub(crate) fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, CredentialError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|e| CredentialError::InvalidValidity(format!("{value:?} is not an RFC3339 timestamp: {e}")))
+}
+
+/// Checks a validity window and, if `max_age` is set, the age of a credential issued at `issued_at`
+pub(crate) fn validity_at(
+    issued_at: &str,
+    valid_from: Option<&str>,
+    expires_at: Option<&str>,
+    now: DateTime<Utc>,
+    max_age: Option<chrono::Duration>,
+) -> ValidityStatus {
+    let check = || -> Result<ValidityStatus, CredentialError> {
+        if let Some(valid_from) = valid_from
+            && now < parse_timestamp(valid_from)?
+        {
+            return Ok(ValidityStatus::NotYetValid);
+        }
+        if let Some(expires_at) = expires_at
+            && now >= parse_timestamp(expires_at)?
+        {
+            return Ok(ValidityStatus::Expired);
+        }
+        let issued_at = parse_timestamp(issued_at)?;
+        if let Some(max_age) = max_age
+            && now - issued_at > max_age
+        {
+            return Ok(ValidityStatus::Stale);
+        }
+        Ok(ValidityStatus::Valid)
+    };
+    check().unwrap_or(ValidityStatus::InvalidTimestamp)
+}
+
 /// Core income credential data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IncomeCredential {
     /// Unique credential identifier
     pub id: String,
-    /// Employee's node ID (DID)
+    /// Employee's node ID, the subject of `did:iroh:<node ID>`
     pub employee_node_id: EndpointId,
     /// Employee's name
     pub employee_name: String,
-    /// Employer's node ID (DID) - the issuer
+    /// Employer's node ID - the issuer, the subject of `did:iroh:<node ID>`
     pub employer_node_id: EndpointId,
     /// Employer's organization name
     pub employer_name: String,
@@ -70,6 +234,15 @@ pub struct IncomeCredential {
     pub payment_mode: PaymentMode,
     /// Timestamp when credential was issued (RFC3339 format)
     pub issued_at: String,
+    /// Optional start of the validity window (RFC3339 format)
+    #[serde(default)]
+    pub valid_from: Option<String>,
+    /// Optional end of the validity window (RFC3339 format)
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    /// Position of this credential in the issuer's revocation status list
+    #[serde(default)]
+    pub status_index: Option<u32>,
 }
 
 /// Signed income credential with cryptographic signature
@@ -80,10 +253,52 @@ pub struct SignedIncomeCredential {
     pub credential: IncomeCredential,
     /// Cryptographic signature from the issuer (employer)
     pub signature: Vec<u8>,
+    /// Payload the signature covers; missing on credentials issued before canonical signing
+    #[serde(default)]
+    pub format: SignatureFormat,
+    /// Signatures of the other parties the credential declares, e.g. the payroll processor
+    #[serde(default)]
+    pub co_signatures: Vec<CoSignature>,
+    /// Key that signed the credential, when it is not the issuer's node key; see
+    /// [`super::key_binding::SignedKeyBinding`]
+    #[serde(default)]
+    pub signing_key: Option<PublicKey>,
+    /// Approvals of the issuer's officers (organizational issuers); see
+    /// [`super::officer_approval::CredentialApproval`]
+    #[serde(default)]
+    pub approvals: Vec<CredentialApproval>,
+}
+
+/// Field-by-field view of a credential used to build the canonical signing payload.
+///
+/// Postcard length-prefixes every string and tags every option, so two distinct
+/// credentials can never encode to the same bytes. Field order (and the order of
+/// `PaymentMode` variants) is part of the signing format.
+#[derive(Serialize)]
+struct CanonicalPayload<'a> {
+    domain: &'a str,
+    version: u8,
+    id: &'a str,
+    employee_node_id: &'a [u8; 32],
+    employee_name: &'a str,
+    employer_node_id: &'a [u8; 32],
+    employer_name: &'a str,
+    payroll_processor_node_id: Option<&'a [u8; 32]>,
+    payroll_processor_name: Option<&'a str>,
+    gross_salary: &'a str,
+    net_salary: &'a str,
+    currency: &'a str,
+    pay_period: &'a str,
+    payment_mode: &'a PaymentMode,
+    issued_at: &'a str,
+    valid_from: Option<&'a str>,
+    expires_at: Option<&'a str>,
+    status_index: Option<u32>,
 }
 
 impl IncomeCredential {
     /// Creates a new income credential
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         employee_node_id: EndpointId,
         employee_name: String,
@@ -109,10 +324,14 @@ impl IncomeCredential {
             pay_period,
             payment_mode,
             issued_at: chrono::Utc::now().to_rfc3339(),
+            valid_from: None,
+            expires_at: None,
+            status_index: None,
         }
     }
 
     /// Creates a credential with payroll processor information
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_processor(
         employee_node_id: EndpointId,
         employee_name: String,
@@ -140,10 +359,46 @@ impl IncomeCredential {
             pay_period,
             payment_mode,
             issued_at: chrono::Utc::now().to_rfc3339(),
+            valid_from: None,
+            expires_at: None,
+            status_index: None,
         }
     }
 
-    /// Generates a human-readable statement from the credential
+    /// Restricts the credential to a validity window
+    pub fn with_validity(mut self, validity: ValidityWindow) -> Self {
+        self.valid_from = validity.valid_from;
+        self.expires_at = validity.expires_at;
+        self
+    }
+
+    /// Names the payroll processor that paid the salary
+    pub fn with_payroll_processor(mut self, node_id: EndpointId, name: String) -> Self {
+        self.payroll_processor_node_id = Some(node_id);
+        self.payroll_processor_name = Some(name);
+        self
+    }
+
+    /// Assigns the credential a position in the issuer's status list
+    pub fn with_status_index(mut self, status_index: u32) -> Self {
+        self.status_index = Some(status_index);
+        self
+    }
+
+    /// Checks the validity window and, if `max_age` is set, the credential's age at `now`
+    pub fn validity_at(&self, now: DateTime<Utc>, max_age: Option<chrono::Duration>) -> ValidityStatus {
+        validity_at(
+            &self.issued_at,
+            self.valid_from.as_deref(),
+            self.expires_at.as_deref(),
+            now,
+            max_age,
+        )
+    }
+
+    /// Generates a human-readable statement from the credential.
+    ///
+    /// This is for display only; signatures cover [`IncomeCredential::signing_payload`].
     pub fn generate_statement(&self) -> String {
         let payroll_info = if let (Some(pp_did), Some(pp_name)) = 
             (&self.payroll_processor_node_id, &self.payroll_processor_name) {
@@ -168,49 +423,86 @@ impl IncomeCredential {
         )
     }
 
-    /// Signs the credential with the issuer's secret key
+    /// Canonical bytes covered by the issuer's signature.
+    ///
+    /// Encodes every credential field with postcard, prefixed by a domain
+    /// separation tag and [`CANONICAL_PAYLOAD_VERSION`].
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalPayload {
+            domain: CREDENTIAL_SIGNING_DOMAIN,
+            version: CANONICAL_PAYLOAD_VERSION,
+            id: &self.id,
+            employee_node_id: self.employee_node_id.as_bytes(),
+            employee_name: &self.employee_name,
+            employer_node_id: self.employer_node_id.as_bytes(),
+            employer_name: &self.employer_name,
+            payroll_processor_node_id: self.payroll_processor_node_id.as_ref().map(|id| id.as_bytes()),
+            payroll_processor_name: self.payroll_processor_name.as_deref(),
+            gross_salary: &self.gross_salary,
+            net_salary: &self.net_salary,
+            currency: &self.currency,
+            pay_period: &self.pay_period,
+            payment_mode: &self.payment_mode,
+            issued_at: &self.issued_at,
+            valid_from: self.valid_from.as_deref(),
+            expires_at: self.expires_at.as_deref(),
+            status_index: self.status_index,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs the credential with the issuer's node key
     pub fn sign(self, secret_key: &SecretKey) -> Result<SignedIncomeCredential, CredentialError> {
-        // Generate the statement to be signed
-        let statement = self.generate_statement();
-        
-        // Sign the statement bytes
-        let signature = secret_key.sign(statement.as_bytes());
-        
-        Ok(SignedIncomeCredential {
+        let payload = self.signing_payload()?;
+        let signature = secret_key.sign(&payload);
+        Ok(self.into_signed(signature, None))
+    }
+
+    /// Signs the credential through `signer`, naming its key if it is not the
+    /// issuer's node key
+    pub async fn sign_with(self, signer: &dyn CredentialSigner) -> Result<SignedIncomeCredential, CredentialError> {
+        let payload = self.signing_payload()?;
+        let signature = signer.sign(payload).await?;
+        let signing_key = distinct_signing_key(signer.public_key(), self.employer_node_id);
+        Ok(self.into_signed(signature, signing_key))
+    }
+
+    fn into_signed(self, signature: Signature, signing_key: Option<PublicKey>) -> SignedIncomeCredential {
+        SignedIncomeCredential {
             credential: self,
             signature: signature.to_bytes().to_vec(),
-        })
+            format: SignatureFormat::CanonicalV1,
+            co_signatures: Vec::new(),
+            signing_key,
+            approvals: Vec::new(),
+        }
     }
 }
 
 impl SignedIncomeCredential {
-    /// Verifies the credential's signature against the issuer's public key
+    /// Verifies the credential's signature against the issuer's public key.
+    ///
+    /// Co-signatures are checked separately, see [`super::co_signature::CoSigned::co_signer_statuses`].
     pub fn verify(&self) -> Result<bool, CredentialError> {
-        // The employer_node_id IS the public key (NodeId = PublicKey)
-        let public_key = self.credential.employer_node_id;
+        // The employer_node_id IS the public key (NodeId = PublicKey), unless a
+        // separate signing key was used
+        let public_key = self.signing_key.unwrap_or(self.credential.employer_node_id);
         
-        // Generate the statement that was signed
-        let statement = self.credential.generate_statement();
-        
-        // Parse the signature bytes (Signature is 64 bytes)
-        if self.signature.len() != 64 {
-            return Err(CredentialError::VerificationError(
-                format!("Invalid signature length: expected 64, got {}", self.signature.len())
-            ));
-        }
-        
-        let mut sig_bytes = [0u8; 64];
-        sig_bytes.copy_from_slice(&self.signature);
-        let signature = Signature::from_bytes(&sig_bytes);
-        
-        // Verify the signature
-        match public_key.verify(statement.as_bytes(), &signature) {
-            Ok(_) => Ok(true),
-            Err(e) => {
-                tracing::warn!("Signature verification failed: {}", e);
-                Ok(false)
+        // Rebuild the bytes that were signed
+        let message = match self.format {
+            SignatureFormat::CanonicalV1 => self.credential.signing_payload()?,
+            SignatureFormat::LegacyStatement => {
+                tracing::debug!("Verifying credential {} with legacy statement signature", self.credential.id);
+                self.credential.generate_statement().into_bytes()
             }
-        }
+        };
+        
+        super::verify_signature(&public_key, &message, &self.signature)
+    }
+
+    /// Returns true if the signature covers only the legacy human-readable statement
+    pub fn is_legacy(&self) -> bool {
+        self.format == SignatureFormat::LegacyStatement
     }
 
     /// Returns the credential ID
@@ -237,6 +529,8 @@ impl SignedIncomeCredential {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::credentials::signer::MemorySigner;
+    use n0_future::future::block_on;
 
     fn test_secret_key() -> SecretKey {
         SecretKey::generate(&mut rand::rng())
@@ -405,4 +699,179 @@ mod tests {
         let is_valid = signed.verify().unwrap();
         assert!(!is_valid, "Signature should be invalid when signed with wrong key");
     }
+
+    fn sample_credential(employee_node_id: EndpointId, employer_node_id: EndpointId) -> IncomeCredential {
+        IncomeCredential::new(
+            employee_node_id,
+            "John Doe".to_string(),
+            employer_node_id,
+            "Acme Corporation".to_string(),
+            "10000.00".to_string(),
+            "8000.00".to_string(),
+            "USD".to_string(),
+            "2024-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+    }
+
+    #[test]
+    fn test_signature_covers_every_field() {
+        let employer_key = test_secret_key();
+        let employee_node_id = test_node_id(&test_secret_key());
+        let credential = sample_credential(employee_node_id, test_node_id(&employer_key));
+
+        let signed = credential.sign(&employer_key).unwrap();
+        assert_eq!(signed.format, SignatureFormat::CanonicalV1);
+        assert!(!signed.is_legacy());
+
+        // Fields that never appeared in the statement must now be covered
+        let mut tampered = signed.clone();
+        tampered.credential.id = uuid::Uuid::new_v4().to_string();
+        assert!(!tampered.verify().unwrap());
+
+        let mut tampered = signed.clone();
+        tampered.credential.issued_at = "2020-01-01T00:00:00+00:00".to_string();
+        assert!(!tampered.verify().unwrap());
+
+        let mut tampered = signed;
+        tampered.credential.payroll_processor_node_id = Some(employee_node_id);
+        assert!(!tampered.verify().unwrap());
+    }
+
+    #[test]
+    fn test_colliding_statements_have_distinct_payloads() {
+        let employee_node_id = test_node_id(&test_secret_key());
+        let employer_node_id = test_node_id(&test_secret_key());
+
+        let mut first = sample_credential(employee_node_id, employer_node_id);
+        first.gross_salary = "100 USD and net salary of 1".to_string();
+        first.net_salary = "2".to_string();
+
+        let mut second = first.clone();
+        second.gross_salary = "100".to_string();
+        second.net_salary = "1 USD and net salary of 2".to_string();
+
+        assert_eq!(first.generate_statement(), second.generate_statement());
+        assert_ne!(first.signing_payload().unwrap(), second.signing_payload().unwrap());
+    }
+
+    #[test]
+    fn test_legacy_statement_signature_still_verifies() {
+        let employer_key = test_secret_key();
+        let credential = sample_credential(
+            test_node_id(&test_secret_key()),
+            test_node_id(&employer_key),
+        );
+
+        // Credentials issued before canonical signing carry no `format` field
+        let signature = employer_key.sign(credential.generate_statement().as_bytes());
+        let mut json = serde_json::to_value(SignedIncomeCredential {
+            credential,
+            signature: signature.to_bytes().to_vec(),
+            format: SignatureFormat::LegacyStatement,
+            co_signatures: Vec::new(),
+            signing_key: None,
+            approvals: Vec::new(),
+        })
+        .unwrap();
+        json.as_object_mut().unwrap().remove("format");
+
+        let signed: SignedIncomeCredential = serde_json::from_value(json).unwrap();
+        assert!(signed.is_legacy());
+        assert!(signed.verify().unwrap());
+
+        // A legacy signature must not pass as a canonical one
+        let mut upgraded = signed;
+        upgraded.format = SignatureFormat::CanonicalV1;
+        assert!(!upgraded.verify().unwrap());
+    }
+
+    #[test]
+    fn test_validity_window_is_signed() {
+        let employer_key = test_secret_key();
+        let credential = sample_credential(
+            test_node_id(&test_secret_key()),
+            test_node_id(&employer_key),
+        )
+        .with_validity(ValidityWindow {
+            valid_from: None,
+            expires_at: Some("2030-01-01T00:00:00Z".to_string()),
+        });
+
+        let mut signed = credential.sign(&employer_key).unwrap();
+        assert!(signed.verify().unwrap());
+
+        signed.credential.expires_at = Some("2040-01-01T00:00:00Z".to_string());
+        assert!(!signed.verify().unwrap());
+    }
+
+    #[test]
+    fn test_separate_signing_key() {
+        let node_key = test_secret_key();
+        let signing_key = test_secret_key();
+        let credential = sample_credential(test_node_id(&test_secret_key()), test_node_id(&node_key));
+
+        // Checked against the node key unless the signing key is named
+        let mut signed = credential.clone().sign(&signing_key).unwrap();
+        assert!(!signed.verify().unwrap());
+        signed.signing_key = Some(signing_key.public());
+        assert!(signed.verify().unwrap());
+
+        let signer = MemorySigner::new(signing_key.clone());
+        let signed = block_on(credential.clone().sign_with(&signer)).unwrap();
+        assert_eq!(signed.signing_key, Some(signing_key.public()));
+        assert!(signed.verify().unwrap());
+
+        // Signing with the node key through a signer names no separate key
+        let signed = block_on(credential.sign_with(&MemorySigner::new(node_key))).unwrap();
+        assert_eq!(signed.signing_key, None);
+        assert!(signed.verify().unwrap());
+    }
+
+    #[test]
+    fn test_validity_status() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let mut credential = sample_credential(
+            test_node_id(&test_secret_key()),
+            test_node_id(&test_secret_key()),
+        );
+        credential.issued_at = "2024-05-01T00:00:00Z".to_string();
+        assert_eq!(credential.validity_at(now, None), ValidityStatus::Valid);
+
+        // Max-age policy
+        assert_eq!(credential.validity_at(now, Some(chrono::Duration::days(90))), ValidityStatus::Valid);
+        assert_eq!(credential.validity_at(now, Some(chrono::Duration::days(14))), ValidityStatus::Stale);
+
+        let not_yet = credential.clone().with_validity(ValidityWindow {
+            valid_from: Some("2024-07-01T00:00:00Z".to_string()),
+            expires_at: None,
+        });
+        assert_eq!(not_yet.validity_at(now, None), ValidityStatus::NotYetValid);
+
+        let expired = credential.clone().with_validity(ValidityWindow {
+            valid_from: None,
+            expires_at: Some("2024-05-31T23:59:59Z".to_string()),
+        });
+        assert_eq!(expired.validity_at(now, None), ValidityStatus::Expired);
+
+        credential.issued_at = "last tuesday".to_string();
+        assert_eq!(credential.validity_at(now, None), ValidityStatus::InvalidTimestamp);
+    }
+
+    #[test]
+    fn test_validity_window_validation() {
+        let window = ValidityWindow {
+            valid_from: Some("2024-07-01T00:00:00Z".to_string()),
+            expires_at: Some("2024-06-01T00:00:00Z".to_string()),
+        };
+        assert!(window.validate().is_err());
+
+        let window = ValidityWindow {
+            valid_from: None,
+            expires_at: Some("next year".to_string()),
+        };
+        assert!(window.validate().is_err());
+
+        assert!(ValidityWindow::default().validate().is_ok());
+    }
 }
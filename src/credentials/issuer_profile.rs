@@ -0,0 +1,168 @@
+use iroh::{EndpointId, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use super::income_credential::CredentialError;
+
+/// Domain separation tag prefixed to every issuer profile signing payload
+const PROFILE_SIGNING_DOMAIN: &str = "trust-triangle/issuer-profile";
+
+/// Version of the issuer profile signing payload layout
+const PROFILE_PAYLOAD_VERSION: u8 = 1;
+
+/// Organization name used until an issuer configures its profile
+pub const DEFAULT_ORGANIZATION_NAME: &str = "AscentHR Organization";
+
+/// Payroll processor named on every credential an issuer signs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PayrollProcessor {
+    pub node_id: EndpointId,
+    pub name: String,
+}
+
+/// Organization behind an issuer node
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuerProfile {
+    /// Used as `employer_name` on issued credentials
+    pub organization_name: String,
+    /// Registration number, e.g. an LEI or company number
+    #[serde(default)]
+    pub legal_identifier: String,
+    /// Country or region of registration, e.g. "US-DE" or "GB"
+    #[serde(default)]
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub default_payroll_processor: Option<PayrollProcessor>,
+}
+
+impl Default for IssuerProfile {
+    fn default() -> Self {
+        Self {
+            organization_name: DEFAULT_ORGANIZATION_NAME.to_string(),
+            legal_identifier: String::new(),
+            jurisdiction: String::new(),
+            default_payroll_processor: None,
+        }
+    }
+}
+
+/// Issuer profile signed by the issuer node, as served to verifiers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedIssuerProfile {
+    pub profile: IssuerProfile,
+    pub issuer_node_id: EndpointId,
+    /// Timestamp of the signature (RFC3339 format)
+    pub signed_at: String,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalProfile<'a> {
+    domain: &'a str,
+    version: u8,
+    issuer_node_id: &'a [u8; 32],
+    organization_name: &'a str,
+    legal_identifier: &'a str,
+    jurisdiction: &'a str,
+    payroll_processor_node_id: Option<&'a [u8; 32]>,
+    payroll_processor_name: Option<&'a str>,
+    signed_at: &'a str,
+}
+
+impl IssuerProfile {
+    /// Checks the profile can be used to sign credentials
+    pub fn validate(&self) -> Result<(), CredentialError> {
+        if self.organization_name.trim().is_empty() {
+            return Err(CredentialError::InvalidProfile("organization name is empty".to_string()));
+        }
+        if let Some(processor) = &self.default_payroll_processor
+            && processor.name.trim().is_empty()
+        {
+            return Err(CredentialError::InvalidProfile("payroll processor name is empty".to_string()));
+        }
+        Ok(())
+    }
+
+    fn signing_payload(&self, issuer_node_id: &EndpointId, signed_at: &str) -> Result<Vec<u8>, CredentialError> {
+        let processor = self.default_payroll_processor.as_ref();
+        let payload = CanonicalProfile {
+            domain: PROFILE_SIGNING_DOMAIN,
+            version: PROFILE_PAYLOAD_VERSION,
+            issuer_node_id: issuer_node_id.as_bytes(),
+            organization_name: &self.organization_name,
+            legal_identifier: &self.legal_identifier,
+            jurisdiction: &self.jurisdiction,
+            payroll_processor_node_id: processor.map(|p| p.node_id.as_bytes()),
+            payroll_processor_name: processor.map(|p| p.name.as_str()),
+            signed_at,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs the profile with the issuer's secret key
+    pub fn sign(self, secret_key: &SecretKey) -> Result<SignedIssuerProfile, CredentialError> {
+        let issuer_node_id = secret_key.public();
+        let signed_at = chrono::Utc::now().to_rfc3339();
+        let signature = secret_key.sign(&self.signing_payload(&issuer_node_id, &signed_at)?);
+        Ok(SignedIssuerProfile {
+            profile: self,
+            issuer_node_id,
+            signed_at,
+            signature: signature.to_bytes().to_vec(),
+        })
+    }
+}
+
+impl SignedIssuerProfile {
+    /// Verifies the profile was signed by the issuer it names
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let payload = self.profile.signing_payload(&self.issuer_node_id, &self.signed_at)?;
+        super::verify_signature(&self.issuer_node_id, &payload, &self.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issuer_profile_signing_and_verification() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let processor_key = SecretKey::generate(&mut rand::rng());
+        let profile = IssuerProfile {
+            organization_name: "Acme Corp".to_string(),
+            legal_identifier: "5493001KJTIIGC8Y1R12".to_string(),
+            jurisdiction: "US-DE".to_string(),
+            default_payroll_processor: Some(PayrollProcessor {
+                node_id: processor_key.public(),
+                name: "PayCo".to_string(),
+            }),
+        };
+
+        let signed = profile.sign(&issuer_key).unwrap();
+        assert_eq!(signed.issuer_node_id, issuer_key.public());
+        assert!(signed.verify().unwrap());
+
+        let mut tampered = signed.clone();
+        tampered.profile.jurisdiction = "GB".to_string();
+        assert!(!tampered.verify().unwrap());
+
+        // Claiming another issuer's profile fails verification
+        let mut stolen = signed;
+        stolen.issuer_node_id = processor_key.public();
+        assert!(!stolen.verify().unwrap());
+    }
+
+    #[test]
+    fn test_issuer_profile_validation() {
+        assert!(IssuerProfile::default().validate().is_ok());
+
+        let unnamed = IssuerProfile {
+            organization_name: " ".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(unnamed.validate(), Err(CredentialError::InvalidProfile(_))));
+    }
+}
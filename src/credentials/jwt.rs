@@ -0,0 +1,273 @@
+//! JWT-VC form of income credentials.
+//!
+//! The credential travels as a compact JWS signed with `EdDSA` by the key that
+//! signed the credential. Its `vc` claim is the VC 2.0 document without a
+//! proof, so verifying the token gives back the exact [`SignedIncomeCredential`].
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::did::{did_key, did_key_verification_method, parse_did_key};
+use super::income_credential::{CredentialError, SignedIncomeCredential, parse_timestamp};
+use super::signer::CredentialSigner;
+use super::verify_signature;
+use super::w3c::VerifiableCredential;
+
+/// JOSE name of Ed25519 signatures
+pub const JWS_ALGORITHM: &str = "EdDSA";
+
+const JWT_TYPE: &str = "JWT";
+
+/// Protected header of a JWT-VC
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JwsHeader {
+    pub alg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    /// `did:key` verification method of the signing key
+    pub kid: String,
+}
+
+/// Claims of a JWT-VC; times are seconds since the Unix epoch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JwtClaims {
+    /// `did:key` of the issuer's node
+    pub iss: String,
+    /// `did:key` of the employee's node
+    pub sub: String,
+    /// Credential ID as a `urn:uuid`
+    pub jti: String,
+    pub iat: i64,
+    /// Start of the validity window, or the issuance time
+    pub nbf: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    pub vc: VerifiableCredential,
+}
+
+impl JwtClaims {
+    fn new(signed: &SignedIncomeCredential) -> Result<Self, CredentialError> {
+        let credential = signed.credential();
+        let seconds = |value: &str| parse_timestamp(value).map(|timestamp| timestamp.timestamp());
+        let vc = signed.to_unsecured_document();
+        Ok(Self {
+            iss: did_key(&credential.employer_node_id),
+            sub: did_key(&credential.employee_node_id),
+            jti: vc.id.clone(),
+            iat: seconds(&credential.issued_at)?,
+            nbf: seconds(credential.valid_from.as_deref().unwrap_or(&credential.issued_at))?,
+            exp: credential.expires_at.as_deref().map(seconds).transpose()?,
+            vc,
+        })
+    }
+}
+
+impl SignedIncomeCredential {
+    /// Encodes the credential as a compact JWS signed by `signer`, which must
+    /// hold the key that signed the credential
+    pub async fn to_jws(&self, signer: &dyn CredentialSigner) -> Result<String, CredentialError> {
+        let signing_key = self.check_signer(signer)?;
+        let header = JwsHeader {
+            alg: JWS_ALGORITHM.to_string(),
+            typ: Some(JWT_TYPE.to_string()),
+            kid: did_key_verification_method(&signing_key),
+        };
+        let signing_input = format!("{}.{}", encode_json(&header)?, encode_json(&JwtClaims::new(self)?)?);
+        let signature = signer.sign(signing_input.as_bytes().to_vec()).await?;
+        Ok(format!("{signing_input}.{}", URL_SAFE_NO_PAD.encode(signature.to_bytes())))
+    }
+}
+
+/// Verifies a compact JWS made by [`SignedIncomeCredential::to_jws`] and returns
+/// the credential it carries, whose own signature must verify as well.
+///
+/// `nbf` and `exp` must match the credential but are not compared with the
+/// clock: verifiers evaluate the validity window as for any other credential.
+pub fn verify_jws(jws: &str) -> Result<SignedIncomeCredential, CredentialError> {
+    let invalid = |reason: String| CredentialError::InvalidJws(reason);
+    let mut parts = jws.trim().split('.');
+    let (Some(header), Some(claims), Some(signature), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("not a compact JWS".into()));
+    };
+
+    let signing_input = format!("{header}.{claims}");
+    let header: JwsHeader = decode_json(header)?;
+    if header.alg != JWS_ALGORITHM {
+        return Err(invalid(format!("unsupported algorithm {}", header.alg)));
+    }
+    let key = parse_did_key(&header.kid)?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| invalid("signature is not base64url".into()))?;
+    if !verify_signature(&key, signing_input.as_bytes(), &signature)? {
+        return Err(CredentialError::InvalidSignature);
+    }
+
+    let claims: JwtClaims = decode_json(claims)?;
+    let signed = SignedIncomeCredential::from_document(&claims.vc, key)?;
+    if claims != JwtClaims::new(&signed)? {
+        return Err(invalid("claims do not match the credential".into()));
+    }
+    Ok(signed)
+}
+
+fn encode_json(value: &impl Serialize) -> Result<String, CredentialError> {
+    Ok(URL_SAFE_NO_PAD.encode(serde_json::to_vec(value)?))
+}
+
+fn decode_json<T: DeserializeOwned>(part: &str) -> Result<T, CredentialError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| CredentialError::InvalidJws(format!("{part} is not base64url")))?;
+    serde_json::from_slice(&bytes).map_err(|e| CredentialError::InvalidJws(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::income_credential::{IncomeCredential, PaymentMode};
+    use crate::credentials::signer::MemorySigner;
+    use iroh::SecretKey;
+    use n0_future::future::block_on;
+
+    /// `fixed_credential` signed by the employer key and encoded by `to_jws`
+    const FIXED_JWS: &str = "eyJhbGciOiJFZERTQSIsInR5cCI6IkpXVCIsImtpZCI6ImRpZDprZXk6ejZNa29uM05lY2Q2TmtreWZvR29IeGlkMnpuR2M1OU\
+    xVM0s3bXViYVJjRmJMZkxYI3o2TWtvbjNOZWNkNk5ra3lmb0dvSHhpZDJ6bkdjNTlMVTNLN211YmFSY0ZiTGZMWCJ9.eyJpc3M\
+    iOiJkaWQ6a2V5Ono2TWtvbjNOZWNkNk5ra3lmb0dvSHhpZDJ6bkdjNTlMVTNLN211YmFSY0ZiTGZMWCIsInN1YiI6ImRpZDprZ\
+    Xk6ejZNa285aFRnZ013alNURWFKYVBVZkU2dHFjeTJ4dlU2Qm5OcTNlM284cVZCaXlIIiwianRpIjoidXJuOnV1aWQ6OWYwYzJ\
+    kNGUtNmExYi00YzNkLThlNWYtN2E5YjBjMWQyZTNmIiwiaWF0IjoxNzM4NDAwNDAwLCJuYmYiOjE3Mzg0MDA0MDAsImV4cCI6M\
+    Tc2OTkzNjQwMCwidmMiOnsiQGNvbnRleHQiOlsiaHR0cHM6Ly93d3cudzMub3JnL25zL2NyZWRlbnRpYWxzL3YyIix7IkBwcm9\
+    0ZWN0ZWQiOnRydWUsIkluY29tZUNyZWRlbnRpYWwiOiJ1cm46dHJ1c3QtdHJpYW5nbGU6aW5jb21lI0luY29tZUNyZWRlbnRpY\
+    WwiLCJhcHByb3ZhbHMiOiJ1cm46dHJ1c3QtdHJpYW5nbGU6aW5jb21lI2FwcHJvdmFscyIsImFwcHJvdmVkQXQiOiJ1cm46dHJ\
+    1c3QtdHJpYW5nbGU6aW5jb21lI2FwcHJvdmVkQXQiLCJjb1NpZ25hdHVyZXMiOiJ1cm46dHJ1c3QtdHJpYW5nbGU6aW5jb21lI\
+    2NvU2lnbmF0dXJlcyIsImNyZWRlbnRpYWxTaWduYXR1cmUiOiJ1cm46dHJ1c3QtdHJpYW5nbGU6aW5jb21lI2NyZWRlbnRpYWx\
+    TaWduYXR1cmUiLCJjdXJyZW5jeSI6InVybjp0cnVzdC10cmlhbmdsZTppbmNvbWUjY3VycmVuY3kiLCJmb3JtYXQiOiJ1cm46d\
+    HJ1c3QtdHJpYW5nbGU6aW5jb21lI2Zvcm1hdCIsImdyb3NzU2FsYXJ5IjoidXJuOnRydXN0LXRyaWFuZ2xlOmluY29tZSNncm9\
+    zc1NhbGFyeSIsImlzc3VlZEF0IjoidXJuOnRydXN0LXRyaWFuZ2xlOmluY29tZSNpc3N1ZWRBdCIsIm5ldFNhbGFyeSI6InVyb\
+    jp0cnVzdC10cmlhbmdsZTppbmNvbWUjbmV0U2FsYXJ5Iiwib2ZmaWNlciI6InVybjp0cnVzdC10cmlhbmdsZTppbmNvbWUjb2Z\
+    maWNlciIsInBheVBlcmlvZCI6InVybjp0cnVzdC10cmlhbmdsZTppbmNvbWUjcGF5UGVyaW9kIiwicGF5bWVudE1vZGUiOiJ1c\
+    m46dHJ1c3QtdHJpYW5nbGU6aW5jb21lI3BheW1lbnRNb2RlIiwicGF5cm9sbFByb2Nlc3NvciI6InVybjp0cnVzdC10cmlhbmd\
+    sZTppbmNvbWUjcGF5cm9sbFByb2Nlc3NvciIsInBvbGljeVZlcnNpb24iOiJ1cm46dHJ1c3QtdHJpYW5nbGU6aW5jb21lI3Bvb\
+    GljeVZlcnNpb24iLCJyb2xlIjoidXJuOnRydXN0LXRyaWFuZ2xlOmluY29tZSNyb2xlIiwic2lnbmF0dXJlVmFsdWUiOiJ1cm4\
+    6dHJ1c3QtdHJpYW5nbGU6aW5jb21lI3NpZ25hdHVyZVZhbHVlIiwic2lnbmVkQXQiOiJ1cm46dHJ1c3QtdHJpYW5nbGU6aW5jb\
+    21lI3NpZ25lZEF0Iiwic2lnbmVyIjoidXJuOnRydXN0LXRyaWFuZ2xlOmluY29tZSNzaWduZXIiLCJzaWduaW5nS2V5IjoidXJ\
+    uOnRydXN0LXRyaWFuZ2xlOmluY29tZSNzaWduaW5nS2V5In1dLCJpZCI6InVybjp1dWlkOjlmMGMyZDRlLTZhMWItNGMzZC04Z\
+    TVmLTdhOWIwYzFkMmUzZiIsInR5cGUiOlsiVmVyaWZpYWJsZUNyZWRlbnRpYWwiLCJJbmNvbWVDcmVkZW50aWFsIl0sImlzc3V\
+    lciI6eyJpZCI6ImRpZDprZXk6ejZNa29uM05lY2Q2TmtreWZvR29IeGlkMnpuR2M1OUxVM0s3bXViYVJjRmJMZkxYIiwibmFtZ\
+    SI6IkFjbWUgQ29ycG9yYXRpb24ifSwidmFsaWRVbnRpbCI6IjIwMjYtMDItMDFUMDk6MDA6MDBaIiwiY3JlZGVudGlhbFN1Ymp\
+    lY3QiOnsiaWQiOiJkaWQ6a2V5Ono2TWtvOWhUZ2dNd2pTVEVhSmFQVWZFNnRxY3kyeHZVNkJuTnEzZTNvOHFWQml5SCIsIm5hb\
+    WUiOiJKYW5lIERvZSIsImdyb3NzU2FsYXJ5IjoiMTAwMDAuMDAiLCJuZXRTYWxhcnkiOiI4MDAwLjAwIiwiY3VycmVuY3kiOiJ\
+    VU0QiLCJwYXlQZXJpb2QiOiIyMDI1LTAxIiwicGF5bWVudE1vZGUiOiJiYW5rX3RyYW5zZmVyIn0sImlzc3VlZEF0IjoiMjAyN\
+    S0wMi0wMVQwOTowMDowMFoiLCJjcmVkZW50aWFsU3RhdHVzIjp7InR5cGUiOiJCaXRzdHJpbmdTdGF0dXNMaXN0RW50cnkiLCJ\
+    zdGF0dXNQdXJwb3NlIjoicmV2b2NhdGlvbiIsInN0YXR1c0xpc3RJbmRleCI6IjciLCJzdGF0dXNMaXN0Q3JlZGVudGlhbCI6I\
+    mRpZDppcm9oOjhhODhlM2RkNzQwOWYxOTVmZDUyZGIyZDNjYmE1ZDcyY2E2NzA5YmYxZDk0MTIxYmYzNzQ4ODAxYjQwZjZmNWM\
+    jc3RhdHVzLWxpc3QifSwiY3JlZGVudGlhbFNpZ25hdHVyZSI6eyJmb3JtYXQiOiJjYW5vbmljYWxfdjEiLCJzaWduYXR1cmVWY\
+    Wx1ZSI6Ino0UHFyY3ZLRlZYQVFkSzlzUjFEblRpVFNvdW02Q1Z5ZVpTM1piemM3Zm9oTDVVS0ZqVmJCdVJDZVU1MkxmRnlCYlF\
+    MZFNRUXViQVBBZXI2MW9hREJMdWlMIn19fQ.bxVLygVPPU2AFVZlb1mKQWSOKRK8GNa-woFCJijzSg4S-NDFauc-uU70OnfU0t\
+    j1SPY7zgqmuL4TpENVyH-3Dw";
+
+    fn fixed_credential() -> SignedIncomeCredential {
+        IncomeCredential {
+            id: "9f0c2d4e-6a1b-4c3d-8e5f-7a9b0c1d2e3f".to_string(),
+            employee_node_id: SecretKey::from_bytes(&[2; 32]).public(),
+            employee_name: "Jane Doe".to_string(),
+            employer_node_id: SecretKey::from_bytes(&[1; 32]).public(),
+            employer_name: "Acme Corporation".to_string(),
+            payroll_processor_node_id: None,
+            payroll_processor_name: None,
+            gross_salary: "10000.00".to_string(),
+            net_salary: "8000.00".to_string(),
+            currency: "USD".to_string(),
+            pay_period: "2025-01".to_string(),
+            payment_mode: PaymentMode::BankTransfer,
+            issued_at: "2025-02-01T09:00:00Z".to_string(),
+            valid_from: None,
+            expires_at: Some("2026-02-01T09:00:00Z".to_string()),
+            status_index: Some(7),
+        }
+        .sign(&SecretKey::from_bytes(&[1; 32]))
+        .unwrap()
+    }
+
+    fn encode(key: &SecretKey, header: &JwsHeader, claims: &JwtClaims) -> String {
+        let signing_input = format!("{}.{}", encode_json(header).unwrap(), encode_json(claims).unwrap());
+        let signature = key.sign(signing_input.as_bytes());
+        format!("{signing_input}.{}", URL_SAFE_NO_PAD.encode(signature.to_bytes()))
+    }
+
+    #[test]
+    fn test_jws_vector() {
+        let signed = fixed_credential();
+        let signer = MemorySigner::new(SecretKey::from_bytes(&[1; 32]));
+        assert_eq!(block_on(signed.to_jws(&signer)).unwrap(), FIXED_JWS);
+
+        let verified = verify_jws(FIXED_JWS).unwrap();
+        assert_eq!(serde_json::to_value(&verified).unwrap(), serde_json::to_value(&signed).unwrap());
+
+        let (header, rest) = FIXED_JWS.split_once('.').unwrap();
+        let header: JwsHeader = decode_json(header).unwrap();
+        assert_eq!(header.alg, "EdDSA");
+        assert_eq!(header.kid, did_key_verification_method(&signed.issuer_node_id()));
+        let claims: JwtClaims = decode_json(rest.split_once('.').unwrap().0).unwrap();
+        assert_eq!(claims.iss, did_key(&signed.issuer_node_id()));
+        assert_eq!(claims.sub, did_key(&signed.employee_node_id()));
+        assert_eq!(claims.jti, "urn:uuid:9f0c2d4e-6a1b-4c3d-8e5f-7a9b0c1d2e3f");
+        assert_eq!((claims.iat, claims.nbf, claims.exp), (1738400400, 1738400400, Some(1769936400)));
+    }
+
+    #[test]
+    fn test_jws_with_separate_signing_key() {
+        let credential = fixed_credential().credential;
+        let signer = MemorySigner::new(SecretKey::from_bytes(&[3; 32]));
+        let signed = block_on(credential.sign_with(&signer)).unwrap();
+        let jws = block_on(signed.to_jws(&signer)).unwrap();
+        assert_eq!(verify_jws(&jws).unwrap().signing_key, Some(signer.public_key()));
+
+        // Only the key that signed the credential can make its JWS
+        let node_signer = MemorySigner::new(SecretKey::from_bytes(&[1; 32]));
+        assert!(block_on(signed.to_jws(&node_signer)).is_err());
+    }
+
+    #[test]
+    fn test_jws_rejects_tampering() {
+        let employer_key = SecretKey::from_bytes(&[1; 32]);
+        let (header, rest) = FIXED_JWS.split_once('.').unwrap();
+        let header: JwsHeader = decode_json(header).unwrap();
+        let claims: JwtClaims = decode_json(rest.split_once('.').unwrap().0).unwrap();
+        assert!(verify_jws(&encode(&employer_key, &header, &claims)).is_ok());
+
+        assert!(verify_jws("not.a-jws").is_err());
+        let unsigned = format!("{}.", FIXED_JWS.rsplit_once('.').unwrap().0);
+        assert!(verify_jws(&unsigned).is_err());
+
+        let mut raised = claims.clone();
+        raised.vc.credential_subject.net_salary = "9000.00".to_string();
+        let forged = format!("{}.{}", encode_json(&header).unwrap(), encode_json(&raised).unwrap());
+        let signature = FIXED_JWS.rsplit_once('.').unwrap().1;
+        assert!(matches!(verify_jws(&format!("{forged}.{signature}")), Err(CredentialError::InvalidSignature)));
+        // Re-signing does not help: the credential's own signature breaks
+        assert!(verify_jws(&encode(&employer_key, &header, &raised)).is_err());
+
+        // Claims must agree with the credential they wrap
+        let mut extended = claims.clone();
+        extended.exp = Some(claims.exp.unwrap() + 365 * 24 * 3600);
+        assert!(verify_jws(&encode(&employer_key, &header, &extended)).is_err());
+
+        // A token signed by another key does not stand for the issuer
+        let forger = SecretKey::from_bytes(&[4; 32]);
+        let forger_header = JwsHeader {
+            kid: did_key_verification_method(&forger.public()),
+            ..header.clone()
+        };
+        assert!(verify_jws(&encode(&forger, &forger_header, &claims)).is_err());
+
+        let none = JwsHeader {
+            alg: "none".to_string(),
+            ..header
+        };
+        assert!(verify_jws(&encode(&employer_key, &none, &claims)).is_err());
+    }
+}
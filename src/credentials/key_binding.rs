@@ -0,0 +1,255 @@
+use iroh::{EndpointId, PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use super::income_credential::CredentialError;
+use super::signer::CredentialSigner;
+
+/// Domain separation tag prefixed to every key binding signing payload
+const BINDING_SIGNING_DOMAIN: &str = "trust-triangle/key-binding";
+
+/// Version of the key binding signing payload layout
+const BINDING_PAYLOAD_VERSION: u8 = 1;
+
+/// Domain separation tag prefixed to every key unbinding signing payload
+const UNBINDING_SIGNING_DOMAIN: &str = "trust-triangle/key-unbinding";
+
+/// Version of the key unbinding signing payload layout
+const UNBINDING_PAYLOAD_VERSION: u8 = 1;
+
+/// Statement that `signing_key` signs credentials on behalf of issuer node `node_id`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBinding {
+    pub node_id: EndpointId,
+    pub signing_key: PublicKey,
+    /// Timestamp of the binding (RFC3339 format)
+    pub bound_at: String,
+}
+
+/// Key binding signed by both keys it links
+///
+/// The signing key attests the transport identity it issues for, and the node key
+/// accepts the signing key, so neither can claim the other on its own.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedKeyBinding {
+    pub binding: KeyBinding,
+    /// Signature of the signing key
+    pub signature: Vec<u8>,
+    /// Signature of the node key
+    pub node_signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalBinding<'a> {
+    domain: &'a str,
+    version: u8,
+    node_id: &'a [u8; 32],
+    signing_key: &'a [u8; 32],
+    bound_at: &'a str,
+}
+
+impl KeyBinding {
+    /// Binds `signing_key` to `node_id` as of now
+    pub fn new(node_id: EndpointId, signing_key: PublicKey) -> Self {
+        Self {
+            node_id,
+            signing_key,
+            bound_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Canonical bytes covered by both signatures
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalBinding {
+            domain: BINDING_SIGNING_DOMAIN,
+            version: BINDING_PAYLOAD_VERSION,
+            node_id: self.node_id.as_bytes(),
+            signing_key: self.signing_key.as_bytes(),
+            bound_at: &self.bound_at,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs the binding with both keys it links, the signing key through `signer`
+    pub async fn sign(self, signer: &dyn CredentialSigner, node_key: &SecretKey) -> Result<SignedKeyBinding, CredentialError> {
+        if signer.public_key() != self.signing_key || node_key.public() != self.node_id {
+            return Err(CredentialError::SigningError(
+                "a key binding must be signed by the keys it links".to_string(),
+            ));
+        }
+        let payload = self.signing_payload()?;
+        Ok(SignedKeyBinding {
+            signature: signer.sign(payload.clone()).await?.to_bytes().to_vec(),
+            node_signature: node_key.sign(&payload).to_bytes().to_vec(),
+            binding: self,
+        })
+    }
+}
+
+impl SignedKeyBinding {
+    /// Verifies the signatures of both the signing key and the node key
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let payload = self.binding.signing_payload()?;
+        Ok(super::verify_signature(&self.binding.signing_key, &payload, &self.signature)?
+            && super::verify_signature(&self.binding.node_id, &payload, &self.node_signature)?)
+    }
+
+    /// Whether the binding is valid and lets `signing_key` sign for `node_id`
+    pub fn binds(&self, node_id: EndpointId, signing_key: PublicKey) -> Result<bool, CredentialError> {
+        Ok(self.binding.node_id == node_id && self.binding.signing_key == signing_key && self.verify()?)
+    }
+}
+
+/// Statement that `signing_key` no longer signs for issuer node `node_id`, e.g.
+/// because it leaked
+///
+/// Signed by the node key alone, so a lost or stolen signing key can be retired.
+/// Every credential signed by the key stops verifying, whatever its issuance
+/// time: the key's holder can backdate credentials.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUnbinding {
+    pub node_id: EndpointId,
+    pub signing_key: PublicKey,
+    /// Timestamp of the unbinding (RFC3339 format)
+    pub unbound_at: String,
+}
+
+/// Key unbinding signed by the node key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedKeyUnbinding {
+    pub unbinding: KeyUnbinding,
+    pub node_signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalUnbinding<'a> {
+    domain: &'a str,
+    version: u8,
+    node_id: &'a [u8; 32],
+    signing_key: &'a [u8; 32],
+    unbound_at: &'a str,
+}
+
+impl KeyUnbinding {
+    /// Unbinds `signing_key` from `node_id` as of now
+    pub fn new(node_id: EndpointId, signing_key: PublicKey) -> Self {
+        Self {
+            node_id,
+            signing_key,
+            unbound_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Canonical bytes covered by the node key's signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalUnbinding {
+            domain: UNBINDING_SIGNING_DOMAIN,
+            version: UNBINDING_PAYLOAD_VERSION,
+            node_id: self.node_id.as_bytes(),
+            signing_key: self.signing_key.as_bytes(),
+            unbound_at: &self.unbound_at,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs the unbinding with the node key
+    pub fn sign(self, node_key: &SecretKey) -> Result<SignedKeyUnbinding, CredentialError> {
+        if node_key.public() != self.node_id {
+            return Err(CredentialError::SigningError(
+                "a key unbinding must be signed by the node key".to_string(),
+            ));
+        }
+        let payload = self.signing_payload()?;
+        Ok(SignedKeyUnbinding {
+            node_signature: node_key.sign(&payload).to_bytes().to_vec(),
+            unbinding: self,
+        })
+    }
+}
+
+impl SignedKeyUnbinding {
+    /// Verifies the node key's signature
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let payload = self.unbinding.signing_payload()?;
+        super::verify_signature(&self.unbinding.node_id, &payload, &self.node_signature)
+    }
+
+    /// Whether the unbinding is valid and retires `signing_key` for `node_id`
+    pub fn unbinds(&self, node_id: EndpointId, signing_key: PublicKey) -> Result<bool, CredentialError> {
+        Ok(self.unbinding.node_id == node_id && self.unbinding.signing_key == signing_key && self.verify()?)
+    }
+}
+
+/// Whether one of `unbindings` validly retires `signing_key` for `node_id`
+pub fn is_unbound(unbindings: &[SignedKeyUnbinding], node_id: EndpointId, signing_key: PublicKey) -> bool {
+    unbindings
+        .iter()
+        .any(|unbinding| unbinding.unbinds(node_id, signing_key).unwrap_or(false))
+}
+
+/// Key under which a binding or unbinding is stored: a signing key may serve
+/// several node keys of one issuer across rotations. Hashed to keep file store
+/// names short.
+pub(crate) fn binding_key(node_id: EndpointId, signing_key: PublicKey) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(node_id.as_bytes());
+    hasher.update(signing_key.as_bytes());
+    hasher.finalize().to_hex().to_string()
+}
+
+/// `signing_key` if it is not the node key of `node_id`
+pub(crate) fn distinct_signing_key(signing_key: PublicKey, node_id: EndpointId) -> Option<PublicKey> {
+    Some(signing_key).filter(|key| *key != node_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::signer::MemorySigner;
+    use n0_future::future::block_on;
+
+    #[test]
+    fn test_key_binding_needs_both_keys() {
+        let node_key = SecretKey::generate(&mut rand::rng());
+        let signing_key = SecretKey::generate(&mut rand::rng());
+        let signer = MemorySigner::new(signing_key.clone());
+        let binding = block_on(KeyBinding::new(node_key.public(), signing_key.public()).sign(&signer, &node_key)).unwrap();
+        assert!(binding.binds(node_key.public(), signing_key.public()).unwrap());
+
+        // A signing key cannot claim an issuer without the issuer's consent
+        let attacker_key = SecretKey::generate(&mut rand::rng());
+        let attacker = MemorySigner::new(attacker_key.clone());
+        let claimed = KeyBinding::new(node_key.public(), attacker_key.public()).sign(&attacker, &attacker_key);
+        assert!(block_on(claimed).is_err());
+        let forged = KeyBinding::new(attacker_key.public(), attacker_key.public()).sign(&attacker, &attacker_key);
+        let mut forged = block_on(forged).unwrap();
+        forged.binding.node_id = node_key.public();
+        assert!(!forged.verify().unwrap());
+
+        // Nor can the binding be moved to another signing key
+        let mut moved = binding.clone();
+        moved.binding.signing_key = attacker_key.public();
+        assert!(!moved.verify().unwrap());
+        assert!(!binding.binds(node_key.public(), attacker_key.public()).unwrap());
+    }
+
+    #[test]
+    fn test_only_the_node_key_unbinds() {
+        let node_key = SecretKey::generate(&mut rand::rng());
+        let signing_key = SecretKey::generate(&mut rand::rng()).public();
+        let unbinding = KeyUnbinding::new(node_key.public(), signing_key).sign(&node_key).unwrap();
+        let unbindings = std::slice::from_ref(&unbinding);
+        assert!(is_unbound(unbindings, node_key.public(), signing_key));
+        assert!(!is_unbound(unbindings, node_key.public(), node_key.public()));
+
+        // The signing key cannot sign it, nor can it be moved to another key
+        let other_key = SecretKey::generate(&mut rand::rng());
+        assert!(KeyUnbinding::new(node_key.public(), signing_key).sign(&other_key).is_err());
+        let mut moved = unbinding;
+        moved.unbinding.signing_key = other_key.public();
+        assert!(!is_unbound(&[moved], node_key.public(), other_key.public()));
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use iroh::{EndpointId, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use super::income_credential::CredentialError;
+
+/// Domain separation tag prefixed to every key rotation signing payload
+const ROTATION_SIGNING_DOMAIN: &str = "trust-triangle/key-rotation";
+
+/// Version of the key rotation signing payload layout
+const ROTATION_PAYLOAD_VERSION: u8 = 1;
+
+/// Statement by an issuer key that it has been replaced by `new_node_id`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotation {
+    pub previous_node_id: EndpointId,
+    pub new_node_id: EndpointId,
+    /// Credentials the previous key signs from this time on are refused (RFC3339 format)
+    pub rotated_at: String,
+    /// Optional human-readable reason, e.g. "key compromised"
+    pub reason: Option<String>,
+}
+
+/// Key rotation signed by the previous key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedKeyRotation {
+    pub rotation: KeyRotation,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalRotation<'a> {
+    domain: &'a str,
+    version: u8,
+    previous_node_id: &'a [u8; 32],
+    new_node_id: &'a [u8; 32],
+    rotated_at: &'a str,
+    reason: Option<&'a str>,
+}
+
+impl KeyRotation {
+    /// Creates a rotation from `previous_node_id` to `new_node_id` taking effect now
+    pub fn new(previous_node_id: EndpointId, new_node_id: EndpointId, reason: Option<String>) -> Self {
+        Self {
+            previous_node_id,
+            new_node_id,
+            rotated_at: Utc::now().to_rfc3339(),
+            reason,
+        }
+    }
+
+    /// Canonical bytes covered by the previous key's signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalRotation {
+            domain: ROTATION_SIGNING_DOMAIN,
+            version: ROTATION_PAYLOAD_VERSION,
+            previous_node_id: self.previous_node_id.as_bytes(),
+            new_node_id: self.new_node_id.as_bytes(),
+            rotated_at: &self.rotated_at,
+            reason: self.reason.as_deref(),
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs the rotation with the previous key
+    pub fn sign(self, secret_key: &SecretKey) -> Result<SignedKeyRotation, CredentialError> {
+        if secret_key.public() != self.previous_node_id {
+            return Err(CredentialError::InvalidRotation(
+                "a rotation must be signed by the key it replaces".to_string(),
+            ));
+        }
+        let signature = secret_key.sign(&self.signing_payload()?);
+        Ok(SignedKeyRotation {
+            rotation: self,
+            signature: signature.to_bytes().to_vec(),
+        })
+    }
+
+    /// Whether a credential issued at `issued_at` was signed after the rotation
+    ///
+    /// An unparsable issuance time counts as after the rotation. The issuance time is
+    /// chosen by the signer, so this does not stop a leaked retired key from backdating.
+    pub fn retires(&self, issued_at: &str) -> bool {
+        match (parse(issued_at), parse(&self.rotated_at)) {
+            (Some(issued_at), Some(rotated_at)) => issued_at >= rotated_at,
+            _ => true,
+        }
+    }
+}
+
+fn parse(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp).ok().map(|t| t.with_timezone(&Utc))
+}
+
+impl SignedKeyRotation {
+    /// Verifies the rotation was signed by the key it replaces
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let payload = self.rotation.signing_payload()?;
+        super::verify_signature(&self.rotation.previous_node_id, &payload, &self.signature)
+    }
+}
+
+/// Verified key rotations, indexed for following chains in both directions
+#[derive(Debug, Clone, Default)]
+pub struct RotationLog {
+    /// Keyed by the previous key
+    by_previous: HashMap<EndpointId, SignedKeyRotation>,
+    /// New key → previous key
+    by_new: HashMap<EndpointId, EndpointId>,
+}
+
+impl RotationLog {
+    /// Keeps the rotations consistent with each other, the earliest of conflicting ones winning
+    pub fn new(rotations: impl IntoIterator<Item = SignedKeyRotation>) -> Self {
+        let mut rotations: Vec<_> = rotations.into_iter().collect();
+        rotations.sort_by_key(|rotation| parse(&rotation.rotation.rotated_at));
+        let mut log = Self::default();
+        for rotation in rotations {
+            let _ = log.insert(rotation);
+        }
+        log
+    }
+
+    /// Adds `rotation` if it passes [`Self::check`]
+    pub fn insert(&mut self, rotation: SignedKeyRotation) -> Result<(), CredentialError> {
+        self.check(&rotation)?;
+        self.by_new.insert(rotation.rotation.new_node_id, rotation.rotation.previous_node_id);
+        self.by_previous.insert(rotation.rotation.previous_node_id, rotation);
+        Ok(())
+    }
+
+    /// The rotation retiring `node_id`, if it has been rotated
+    pub fn rotation_of(&self, node_id: &EndpointId) -> Option<&SignedKeyRotation> {
+        self.by_previous.get(node_id)
+    }
+
+    /// `node_id` followed by every key it replaced, most recent first
+    pub fn predecessors(&self, node_id: EndpointId) -> Vec<EndpointId> {
+        let mut chain = vec![node_id];
+        while let Some(previous) = self.by_new.get(chain.last().expect("chain is never empty")) {
+            if chain.contains(previous) {
+                break;
+            }
+            chain.push(*previous);
+        }
+        chain
+    }
+
+    /// `node_id` followed by every key that replaced it, oldest first
+    pub fn successors(&self, node_id: EndpointId) -> Vec<EndpointId> {
+        let mut chain = vec![node_id];
+        while let Some(rotation) = self.by_previous.get(chain.last().expect("chain is never empty")) {
+            if chain.contains(&rotation.rotation.new_node_id) {
+                break;
+            }
+            chain.push(rotation.rotation.new_node_id);
+        }
+        chain
+    }
+
+    /// The rotations leading up to `node_id`, oldest first
+    pub fn history(&self, node_id: EndpointId) -> Vec<SignedKeyRotation> {
+        let mut history: Vec<_> = self
+            .predecessors(node_id)
+            .iter()
+            .skip(1)
+            .filter_map(|previous| self.by_previous.get(previous).cloned())
+            .collect();
+        history.reverse();
+        history
+    }
+
+    /// Checks `rotation` is signed by its previous key and consistent with the log
+    pub fn check(&self, rotation: &SignedKeyRotation) -> Result<(), CredentialError> {
+        let KeyRotation { previous_node_id, new_node_id, .. } = &rotation.rotation;
+        if previous_node_id == new_node_id {
+            return Err(CredentialError::InvalidRotation("a key cannot be rotated to itself".to_string()));
+        }
+        if !rotation.verify()? {
+            return Err(CredentialError::InvalidRotation(format!(
+                "not signed by the previous key {}",
+                previous_node_id
+            )));
+        }
+        if let Some(existing) = self.by_previous.get(previous_node_id)
+            && existing.rotation.new_node_id != *new_node_id
+        {
+            return Err(CredentialError::InvalidRotation(format!(
+                "key {} was already rotated to {}",
+                previous_node_id, existing.rotation.new_node_id
+            )));
+        }
+        if let Some(existing) = self.by_new.get(new_node_id)
+            && existing != previous_node_id
+        {
+            return Err(CredentialError::InvalidRotation(format!(
+                "key {} already replaced {}",
+                new_node_id, existing
+            )));
+        }
+        if self.predecessors(*previous_node_id).contains(new_node_id) {
+            return Err(CredentialError::InvalidRotation(format!(
+                "key {} already preceded {}",
+                new_node_id, previous_node_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotate(from: &SecretKey, to: &SecretKey) -> SignedKeyRotation {
+        KeyRotation::new(from.public(), to.public(), None).sign(from).unwrap()
+    }
+
+    #[test]
+    fn test_key_rotation_signing_and_verification() {
+        let old_key = SecretKey::generate(&mut rand::rng());
+        let new_key = SecretKey::generate(&mut rand::rng());
+        let rotation = rotate(&old_key, &new_key);
+        assert!(rotation.verify().unwrap());
+
+        // Only the key being replaced can sign its rotation
+        let attacker_key = SecretKey::generate(&mut rand::rng());
+        assert!(KeyRotation::new(old_key.public(), attacker_key.public(), None).sign(&attacker_key).is_err());
+
+        let mut redirected = rotation.clone();
+        redirected.rotation.new_node_id = attacker_key.public();
+        assert!(!redirected.verify().unwrap());
+        assert!(RotationLog::default().check(&redirected).is_err());
+    }
+
+    #[test]
+    fn test_rotation_log_follows_chains() {
+        let keys: Vec<SecretKey> = (0..3).map(|_| SecretKey::generate(&mut rand::rng())).collect();
+        let ids: Vec<EndpointId> = keys.iter().map(|key| key.public()).collect();
+        let log = RotationLog::new([rotate(&keys[0], &keys[1]), rotate(&keys[1], &keys[2])]);
+
+        assert_eq!(log.predecessors(ids[2]), vec![ids[2], ids[1], ids[0]]);
+        assert_eq!(log.successors(ids[0]), ids);
+        assert_eq!(log.history(ids[2]).len(), 2);
+        assert_eq!(log.history(ids[2])[0].rotation.previous_node_id, ids[0]);
+        assert!(log.rotation_of(&ids[2]).is_none());
+
+        // A rotated key cannot be rotated again to a different key, nor back into its chain
+        let other_key = SecretKey::generate(&mut rand::rng());
+        assert!(log.check(&rotate(&keys[0], &other_key)).is_err());
+        assert!(log.check(&rotate(&keys[2], &keys[0])).is_err());
+        assert!(log.check(&rotate(&keys[2], &other_key)).is_ok());
+    }
+
+    #[test]
+    fn test_rotation_to_a_taken_key_is_refused() {
+        let previous_key = SecretKey::generate(&mut rand::rng());
+        let trusted_key = SecretKey::generate(&mut rand::rng());
+        let attacker_key = SecretKey::generate(&mut rand::rng());
+        let genuine = rotate(&previous_key, &trusted_key);
+        let hijack = rotate(&attacker_key, &trusted_key);
+
+        // An attacker's key claiming to have been replaced by the trusted key cannot
+        // take the place of its real predecessor
+        let mut log = RotationLog::new([genuine.clone()]);
+        assert!(log.check(&hijack).is_err());
+        assert!(log.insert(hijack.clone()).is_err());
+        assert_eq!(log.predecessors(trusted_key.public()), vec![trusted_key.public(), previous_key.public()]);
+
+        let log = RotationLog::new([genuine, hijack]);
+        assert_eq!(log.predecessors(trusted_key.public()), vec![trusted_key.public(), previous_key.public()]);
+        assert!(log.rotation_of(&attacker_key.public()).is_none());
+    }
+
+    #[test]
+    fn test_rotation_retires_later_credentials() {
+        let rotation = KeyRotation {
+            previous_node_id: SecretKey::generate(&mut rand::rng()).public(),
+            new_node_id: SecretKey::generate(&mut rand::rng()).public(),
+            rotated_at: "2025-06-01T00:00:00+00:00".to_string(),
+            reason: None,
+        };
+        assert!(!rotation.retires("2025-05-31T23:59:59Z"));
+        assert!(rotation.retires("2025-06-01T00:00:00Z"));
+        assert!(rotation.retires("2025-07-01T00:00:00Z"));
+        assert!(rotation.retires("not a timestamp"));
+    }
+}
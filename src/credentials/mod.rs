@@ -1 +1,46 @@
-pub mod income_credential;
\ No newline at end of file
+pub mod co_signature;
+pub mod compact;
+pub mod did;
+pub mod income_credential;
+pub mod issuer_profile;
+pub mod jwt;
+pub mod key_binding;
+pub mod key_rotation;
+pub mod officer_approval;
+pub mod presentation;
+pub mod revocation;
+pub mod selective_disclosure;
+pub mod signer;
+pub mod status_list;
+pub mod threshold_proof;
+pub mod verification;
+pub mod w3c;
+
+use iroh::EndpointId;
+use iroh_base::Signature;
+
+use income_credential::CredentialError;
+
+/// Verifies a raw 64-byte Ed25519 signature over `message`
+pub(crate) fn verify_signature(
+    public_key: &EndpointId,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<bool, CredentialError> {
+    // Parse the signature bytes (Signature is 64 bytes)
+    let sig_bytes: [u8; 64] = signature.try_into().map_err(|_| {
+        CredentialError::VerificationError(format!(
+            "Invalid signature length: expected 64, got {}",
+            signature.len()
+        ))
+    })?;
+    let signature = Signature::from_bytes(&sig_bytes);
+
+    match public_key.verify(message, &signature) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            tracing::warn!("Signature verification failed: {}", e);
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,725 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use iroh::{EndpointId, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use super::income_credential::{CredentialError, IncomeCredential, PaymentMode, SignedIncomeCredential};
+use super::presentation::PresentableCredential;
+use super::selective_disclosure::SelectiveCredential;
+use super::threshold_proof::ThresholdCredential;
+
+/// Domain separation tag prefixed to every officer approval signing payload
+const APPROVAL_SIGNING_DOMAIN: &str = "trust-triangle/officer-approval";
+
+/// Version of the officer approval signing payload layout
+const APPROVAL_PAYLOAD_VERSION: u8 = 2;
+
+/// Domain separation tag prefixed to every approval policy signing payload
+const POLICY_SIGNING_DOMAIN: &str = "trust-triangle/approval-policy";
+
+/// Version of the approval policy signing payload layout
+const POLICY_PAYLOAD_VERSION: u8 = 1;
+
+/// Officers of an organizational issuer, `threshold` of whom must approve each credential
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPolicy {
+    pub officers: Vec<EndpointId>,
+    pub threshold: u32,
+}
+
+impl ApprovalPolicy {
+    pub fn is_officer(&self, node_id: &EndpointId) -> bool {
+        self.officers.contains(node_id)
+    }
+
+    /// Checks the threshold is at least 1 and can be met by the listed officers
+    pub fn validate(&self) -> Result<(), CredentialError> {
+        if self.threshold == 0 {
+            return Err(CredentialError::InvalidApproval("threshold must be at least 1".to_string()));
+        }
+        if self.threshold as usize > self.officers.len() {
+            return Err(CredentialError::InvalidApproval(format!(
+                "threshold {} exceeds the {} listed officers",
+                self.threshold,
+                self.officers.len()
+            )));
+        }
+        for (i, officer) in self.officers.iter().enumerate() {
+            if self.officers[..i].contains(officer) {
+                return Err(CredentialError::InvalidApproval(format!("officer {} is listed twice", officer)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One version of an issuer's approval policy, as published to verifiers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedApprovalPolicy {
+    /// Key the issuer published the first version under; kept across key rotations
+    pub issuer_node_id: EndpointId,
+    /// 1 for the first version, one more for every change
+    pub version: u32,
+    pub policy: ApprovalPolicy,
+    /// Timestamp of publication (RFC3339 format)
+    pub published_at: String,
+}
+
+/// Signature authorizing one version of an approval policy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicySignature {
+    pub signer_node_id: EndpointId,
+    pub signature: Vec<u8>,
+}
+
+/// An approval policy version with the signatures authorizing it: the issuer's
+/// for the first version, `threshold` of the previous version's officers' for
+/// every later one, so the issuer key alone cannot weaken a published policy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedApprovalPolicy {
+    pub policy: PublishedApprovalPolicy,
+    pub signatures: Vec<PolicySignature>,
+}
+
+#[derive(Serialize)]
+struct CanonicalPolicy<'a> {
+    domain: &'a str,
+    payload_version: u8,
+    issuer_node_id: &'a [u8; 32],
+    version: u32,
+    officers: Vec<&'a [u8; 32]>,
+    threshold: u32,
+    published_at: &'a str,
+}
+
+impl PublishedApprovalPolicy {
+    /// First version of the policy of `issuer_node_id`
+    pub fn first(issuer_node_id: EndpointId, policy: ApprovalPolicy) -> Result<Self, CredentialError> {
+        policy.validate()?;
+        Ok(Self {
+            issuer_node_id,
+            version: 1,
+            policy,
+            published_at: Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// Version replacing this one with `policy`
+    pub fn next(&self, policy: ApprovalPolicy) -> Result<Self, CredentialError> {
+        policy.validate()?;
+        Ok(Self {
+            issuer_node_id: self.issuer_node_id,
+            version: self.version + 1,
+            policy,
+            published_at: Utc::now().to_rfc3339(),
+        })
+    }
+
+    fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalPolicy {
+            domain: POLICY_SIGNING_DOMAIN,
+            payload_version: POLICY_PAYLOAD_VERSION,
+            issuer_node_id: self.issuer_node_id.as_bytes(),
+            version: self.version,
+            officers: self.policy.officers.iter().map(|officer| officer.as_bytes()).collect(),
+            threshold: self.policy.threshold,
+            published_at: &self.published_at,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs this version with `secret_key`
+    pub fn sign(&self, secret_key: &SecretKey) -> Result<PolicySignature, CredentialError> {
+        Ok(PolicySignature {
+            signer_node_id: secret_key.public(),
+            signature: secret_key.sign(&self.signing_payload()?).to_bytes().to_vec(),
+        })
+    }
+}
+
+impl SignedApprovalPolicy {
+    /// Keys allowed to authorize this version, and how many of them must
+    fn authorizers(&self, previous: Option<&PublishedApprovalPolicy>) -> (Vec<EndpointId>, u32) {
+        match previous {
+            Some(previous) => (previous.policy.officers.clone(), previous.policy.threshold),
+            None => (vec![self.policy.issuer_node_id], 1),
+        }
+    }
+
+    /// Checks `signature` is from an authorizer of this version and attaches it
+    pub fn add_signature(
+        &mut self,
+        signature: PolicySignature,
+        previous: Option<&PublishedApprovalPolicy>,
+    ) -> Result<(), CredentialError> {
+        let (authorizers, _) = self.authorizers(previous);
+        if !authorizers.contains(&signature.signer_node_id) {
+            return Err(CredentialError::InvalidApproval(format!(
+                "{} cannot authorize version {} of the approval policy",
+                signature.signer_node_id, self.policy.version
+            )));
+        }
+        if !super::verify_signature(&signature.signer_node_id, &self.policy.signing_payload()?, &signature.signature)? {
+            return Err(CredentialError::InvalidApproval(format!(
+                "signature of {} does not verify",
+                signature.signer_node_id
+            )));
+        }
+        self.signatures.retain(|existing| existing.signer_node_id != signature.signer_node_id);
+        self.signatures.push(signature);
+        Ok(())
+    }
+
+    /// Authorizers of this version with a valid signature on it
+    pub fn signed_by(&self, previous: Option<&PublishedApprovalPolicy>) -> Result<Vec<EndpointId>, CredentialError> {
+        let (authorizers, _) = self.authorizers(previous);
+        let payload = self.policy.signing_payload()?;
+        let mut signed_by = Vec::new();
+        for signature in &self.signatures {
+            if authorizers.contains(&signature.signer_node_id)
+                && !signed_by.contains(&signature.signer_node_id)
+                && super::verify_signature(&signature.signer_node_id, &payload, &signature.signature)?
+            {
+                signed_by.push(signature.signer_node_id);
+            }
+        }
+        Ok(signed_by)
+    }
+
+    /// Whether this version is authorized as the successor of `previous`, or as
+    /// the first version without one
+    pub fn is_authorized(&self, previous: Option<&PublishedApprovalPolicy>) -> Result<bool, CredentialError> {
+        let expected_version = previous.map_or(1, |previous| previous.version + 1);
+        if self.policy.version != expected_version
+            || previous.is_some_and(|previous| previous.issuer_node_id != self.policy.issuer_node_id)
+            || self.policy.policy.validate().is_err()
+        {
+            return Ok(false);
+        }
+        let (_, required) = self.authorizers(previous);
+        Ok(self.signed_by(previous)?.len() as u32 >= required)
+    }
+}
+
+/// Verified approval policy histories, keyed by the key each issuer published its
+/// first version under
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalPolicyLog {
+    /// Every version from 1 on, oldest first
+    by_issuer: HashMap<EndpointId, Vec<SignedApprovalPolicy>>,
+}
+
+impl ApprovalPolicyLog {
+    /// Keeps, for every issuer, the versions that form an authorized chain from version 1
+    pub fn new(policies: impl IntoIterator<Item = SignedApprovalPolicy>) -> Self {
+        let mut policies: Vec<_> = policies.into_iter().collect();
+        policies.sort_by_key(|policy| policy.policy.version);
+        let mut log = Self::default();
+        for policy in policies {
+            let _ = log.insert(policy);
+        }
+        log
+    }
+
+    /// Checks `policy` is authorized as the next version of its issuer's policy
+    pub fn check(&self, policy: &SignedApprovalPolicy) -> Result<(), CredentialError> {
+        let previous = self.current(&policy.policy.issuer_node_id);
+        let expected_version = previous.map_or(1, |previous| previous.version + 1);
+        if policy.policy.version != expected_version {
+            return Err(CredentialError::InvalidApproval(format!(
+                "expected version {} of the approval policy of {}, got {}",
+                expected_version, policy.policy.issuer_node_id, policy.policy.version
+            )));
+        }
+        if !policy.is_authorized(previous)? {
+            return Err(CredentialError::InvalidApproval(match previous {
+                Some(previous) => format!(
+                    "version {} needs the signatures of {} of the {} officers of version {}",
+                    policy.policy.version,
+                    previous.policy.threshold,
+                    previous.policy.officers.len(),
+                    previous.version
+                ),
+                None => format!("version 1 is not signed by issuer {}", policy.policy.issuer_node_id),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Appends `policy` after [`Self::check`]ing it
+    pub fn insert(&mut self, policy: SignedApprovalPolicy) -> Result<(), CredentialError> {
+        self.check(&policy)?;
+        self.by_issuer.entry(policy.policy.issuer_node_id).or_default().push(policy);
+        Ok(())
+    }
+
+    /// Every version of the policy published under `issuer_node_id`, oldest first
+    pub fn history(&self, issuer_node_id: &EndpointId) -> &[SignedApprovalPolicy] {
+        self.by_issuer.get(issuer_node_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// The history of the last of `keys` that published a policy, for an issuer key
+    /// followed by the keys it replaced: the chain its oldest key started, so a key
+    /// rotated to cannot start a fresh one
+    pub fn history_of(&self, keys: &[EndpointId]) -> &[SignedApprovalPolicy] {
+        keys.iter()
+            .rev()
+            .map(|key| self.history(key))
+            .find(|history| !history.is_empty())
+            .unwrap_or(&[])
+    }
+
+    /// The newest version of the policy published under `issuer_node_id`
+    pub fn current(&self, issuer_node_id: &EndpointId) -> Option<&PublishedApprovalPolicy> {
+        self.history(issuer_node_id).last().map(|policy| &policy.policy)
+    }
+
+    /// Every recorded version of every issuer
+    pub fn policies(&self) -> impl Iterator<Item = &SignedApprovalPolicy> {
+        self.by_issuer.values().flatten()
+    }
+}
+
+/// What an officer approves: the employee's request as queued by the issuer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestTerms {
+    pub employee_node_id: EndpointId,
+    pub employee_name: String,
+    pub gross_salary: String,
+    pub net_salary: String,
+    pub currency: String,
+    pub pay_period: String,
+    pub payment_mode: PaymentMode,
+}
+
+impl RequestTerms {
+    /// Whether `credential` states these terms
+    pub fn matches(&self, credential: &IncomeCredential) -> bool {
+        self.employee_node_id == credential.employee_node_id
+            && self.employee_name == credential.employee_name
+            && self.gross_salary == credential.gross_salary
+            && self.net_salary == credential.net_salary
+            && self.currency == credential.currency
+            && self.pay_period == credential.pay_period
+            && self.payment_mode == credential.payment_mode
+    }
+}
+
+/// An officer's signature over one form of a credential, as its issuer signed it
+///
+/// Carried by the credential, so verifiers can count the officers who approved it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialApproval {
+    pub officer_node_id: EndpointId,
+    /// Version of the issuer's approval policy the officer approved under
+    pub policy_version: u32,
+    /// Timestamp of the approval (RFC3339 format)
+    pub approved_at: String,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalApproval<'a> {
+    domain: &'a str,
+    version: u8,
+    officer_node_id: &'a [u8; 32],
+    policy_version: u32,
+    approved_at: &'a str,
+    /// Canonical payload of the credential form, as signed by the issuer
+    credential: &'a [u8],
+}
+
+impl CredentialApproval {
+    fn signing_payload(
+        credential: &[u8],
+        officer_node_id: &EndpointId,
+        policy_version: u32,
+        approved_at: &str,
+    ) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalApproval {
+            domain: APPROVAL_SIGNING_DOMAIN,
+            version: APPROVAL_PAYLOAD_VERSION,
+            officer_node_id: officer_node_id.as_bytes(),
+            policy_version,
+            approved_at,
+            credential,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Approves the credential form whose issuer-signed payload is `credential`
+    pub fn sign(credential: &[u8], policy_version: u32, secret_key: &SecretKey) -> Result<Self, CredentialError> {
+        let officer_node_id = secret_key.public();
+        let approved_at = Utc::now().to_rfc3339();
+        let payload = Self::signing_payload(credential, &officer_node_id, policy_version, &approved_at)?;
+        Ok(Self {
+            officer_node_id,
+            policy_version,
+            approved_at,
+            signature: secret_key.sign(&payload).to_bytes().to_vec(),
+        })
+    }
+
+    /// Verifies the approval over the issuer-signed payload `credential`
+    pub fn verify(&self, credential: &[u8]) -> Result<bool, CredentialError> {
+        let payload = Self::signing_payload(credential, &self.officer_node_id, self.policy_version, &self.approved_at)?;
+        super::verify_signature(&self.officer_node_id, &payload, &self.signature)
+    }
+}
+
+/// A credential the issuer signed for a request, in every form it delivers, held
+/// back until enough officers approve it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialDraft {
+    pub credential: SignedIncomeCredential,
+    pub selective_credential: SelectiveCredential,
+    #[serde(default)]
+    pub threshold_credential: Option<ThresholdCredential>,
+}
+
+impl CredentialDraft {
+    /// Checks every form is signed by `issuer_node_id` and states `terms`, and that
+    /// the selective and threshold forms commit to the same credential
+    pub fn check(&self, issuer_node_id: EndpointId, terms: &RequestTerms) -> Result<(), CredentialError> {
+        let invalid = |message: &str| Err(CredentialError::InvalidApproval(message.to_string()));
+        let credential = &self.credential.credential;
+        if credential.employer_node_id != issuer_node_id || !self.credential.verify()? {
+            return invalid("the credential is not signed by the issuer");
+        }
+        if !terms.matches(credential) {
+            return invalid("the credential does not state the requested terms");
+        }
+
+        if !self.selective_credential.commits_to(credential)? {
+            return invalid("the selective disclosure form is not the same credential");
+        }
+
+        if let Some(threshold) = &self.threshold_credential
+            && !threshold.commits_to(credential)?
+        {
+            return invalid("the net salary commitment is not the same credential");
+        }
+        Ok(())
+    }
+
+    /// Attaches the approvals of `approval` to the forms they sign
+    pub fn attach(&mut self, approval: &OfficerApproval) {
+        let officer = approval.officer_node_id;
+        let forms = [
+            (&mut self.credential.approvals, Some(&approval.credential)),
+            (&mut self.selective_credential.credential.approvals, Some(&approval.selective_credential)),
+        ];
+        for (approvals, signed) in forms {
+            approvals.retain(|existing| existing.officer_node_id != officer);
+            approvals.extend(signed.cloned());
+        }
+        if let Some(threshold) = &mut self.threshold_credential {
+            threshold.credential.approvals.retain(|existing| existing.officer_node_id != officer);
+            threshold.credential.approvals.extend(approval.threshold_credential.clone());
+        }
+    }
+}
+
+/// An officer's approval of the credential an issuer drafted for one request,
+/// signing each of its forms
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OfficerApproval {
+    pub request_id: String,
+    pub issuer_node_id: EndpointId,
+    pub officer_node_id: EndpointId,
+    pub credential: CredentialApproval,
+    pub selective_credential: CredentialApproval,
+    #[serde(default)]
+    pub threshold_credential: Option<CredentialApproval>,
+}
+
+impl OfficerApproval {
+    /// Approves `draft` under version `policy_version` of the issuer's policy
+    pub fn sign(
+        secret_key: &SecretKey,
+        request_id: String,
+        draft: &CredentialDraft,
+        policy_version: u32,
+    ) -> Result<Self, CredentialError> {
+        let threshold_credential = match &draft.threshold_credential {
+            Some(threshold) => Some(CredentialApproval::sign(
+                &threshold.credential.signing_payload()?,
+                policy_version,
+                secret_key,
+            )?),
+            None => None,
+        };
+        Ok(Self {
+            request_id,
+            issuer_node_id: draft.credential.issuer_node_id(),
+            officer_node_id: secret_key.public(),
+            credential: CredentialApproval::sign(&draft.credential.signed_payload()?, policy_version, secret_key)?,
+            selective_credential: CredentialApproval::sign(
+                &draft.selective_credential.signed_payload()?,
+                policy_version,
+                secret_key,
+            )?,
+            threshold_credential,
+        })
+    }
+
+    /// Verifies the officer signed every form of `draft` under one policy version
+    pub fn verify(&self, draft: &CredentialDraft) -> Result<bool, CredentialError> {
+        let version = self.credential.policy_version;
+        let mut forms = vec![
+            (&self.credential, draft.credential.signed_payload()?),
+            (&self.selective_credential, draft.selective_credential.signed_payload()?),
+        ];
+        match (&self.threshold_credential, &draft.threshold_credential) {
+            (Some(approval), Some(threshold)) => forms.push((approval, threshold.credential.signing_payload()?)),
+            (None, None) => {}
+            _ => return Ok(false),
+        }
+        for (approval, payload) in forms {
+            if approval.officer_node_id != self.officer_node_id
+                || approval.policy_version != version
+                || !approval.verify(&payload)?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Version of the issuer's policy the officer approved under
+    pub fn policy_version(&self) -> u32 {
+        self.credential.policy_version
+    }
+}
+
+/// Outcome of checking a credential's officer approvals against the policy its
+/// issuer published
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalCheck {
+    pub policy_version: u32,
+    pub threshold: u32,
+    /// Officers of that policy version with a valid approval on the credential
+    pub approved_by: Vec<EndpointId>,
+    /// A newer version was published before the credential's issuance time
+    #[serde(default)]
+    pub superseded: bool,
+}
+
+impl ApprovalCheck {
+    /// Checks the approvals on `credential` against `history`, the verified
+    /// policy versions of its issuer; `None` if the issuer has published none
+    pub fn new(
+        credential: &(impl PresentableCredential + ?Sized),
+        history: &[SignedApprovalPolicy],
+    ) -> Result<Option<Self>, CredentialError> {
+        let Some(latest) = history.last() else {
+            return Ok(None);
+        };
+        let approvals = credential.officer_approvals();
+        // Credentials name the version they were approved under; older ones stay
+        // valid if they were issued before the next version was published
+        let named = approvals.iter().map(|approval| approval.policy_version).max();
+        let position = match named {
+            Some(named) => history.iter().position(|policy| policy.policy.version == named),
+            None => Some(history.len() - 1),
+        };
+        let Some(position) = position else {
+            // Approved under a version the verifier has not seen
+            return Ok(Some(Self {
+                policy_version: named.unwrap_or_default(),
+                threshold: latest.policy.policy.threshold,
+                approved_by: Vec::new(),
+                superseded: false,
+            }));
+        };
+        let policy = &history[position].policy;
+        let superseded = history.get(position + 1).is_some_and(|next| {
+            match (parse(credential.issued_at()), parse(&next.policy.published_at)) {
+                (Some(issued_at), Some(published_at)) => issued_at >= published_at,
+                _ => true,
+            }
+        });
+
+        let payload = credential.signed_payload()?;
+        let mut approved_by = Vec::new();
+        for approval in approvals {
+            if approval.policy_version == policy.version
+                && policy.policy.is_officer(&approval.officer_node_id)
+                && !approved_by.contains(&approval.officer_node_id)
+                && approval.verify(&payload)?
+            {
+                approved_by.push(approval.officer_node_id);
+            }
+        }
+        Ok(Some(Self {
+            policy_version: policy.version,
+            threshold: policy.policy.threshold,
+            approved_by,
+            superseded,
+        }))
+    }
+
+    /// Enough officers of a current policy version approved the credential
+    pub fn is_met(&self) -> bool {
+        !self.superseded && self.approved_by.len() as u32 >= self.threshold
+    }
+}
+
+fn parse(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp).ok().map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::income_credential::ValidityWindow;
+
+    fn keys(count: usize) -> Vec<SecretKey> {
+        (0..count).map(|_| SecretKey::generate(&mut rand::rng())).collect()
+    }
+
+    fn policy(officers: &[SecretKey], threshold: u32) -> ApprovalPolicy {
+        ApprovalPolicy {
+            officers: officers.iter().map(SecretKey::public).collect(),
+            threshold,
+        }
+    }
+
+    fn draft(issuer_key: &SecretKey, terms: &RequestTerms) -> CredentialDraft {
+        let credential = IncomeCredential::new(
+            terms.employee_node_id,
+            terms.employee_name.clone(),
+            issuer_key.public(),
+            "Acme Corp".to_string(),
+            terms.gross_salary.clone(),
+            terms.net_salary.clone(),
+            terms.currency.clone(),
+            terms.pay_period.clone(),
+            terms.payment_mode.clone(),
+        )
+        .with_validity(ValidityWindow::default())
+        .with_status_index(3);
+        CredentialDraft {
+            selective_credential: credential.sign_selective(issuer_key).unwrap(),
+            threshold_credential: Some(credential.sign_amount_commitment(issuer_key).unwrap()),
+            credential: credential.sign(issuer_key).unwrap(),
+        }
+    }
+
+    fn terms() -> RequestTerms {
+        RequestTerms {
+            employee_node_id: SecretKey::generate(&mut rand::rng()).public(),
+            employee_name: "Alice".to_string(),
+            gross_salary: "9000.00".to_string(),
+            net_salary: "7200.00".to_string(),
+            currency: "USD".to_string(),
+            pay_period: "2025-01".to_string(),
+            payment_mode: PaymentMode::BankTransfer,
+        }
+    }
+
+    #[test]
+    fn test_approval_policy_validation() {
+        let officers = keys(3);
+        assert!(ApprovalPolicy::default().validate().is_err());
+        assert!(policy(&officers, 2).validate().is_ok());
+        assert!(policy(&officers, 4).validate().is_err());
+        assert!(policy(&officers, 0).validate().is_err());
+
+        let duplicated = ApprovalPolicy {
+            officers: vec![officers[0].public(), officers[1].public(), officers[0].public()],
+            threshold: 2,
+        };
+        assert!(duplicated.validate().is_err());
+    }
+
+    #[test]
+    fn test_policy_changes_need_the_previous_officers() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let officers = keys(3);
+        let first = PublishedApprovalPolicy::first(issuer_key.public(), policy(&officers, 2)).unwrap();
+        let mut log = ApprovalPolicyLog::default();
+
+        // The first version is the issuer's to publish
+        let unsigned = SignedApprovalPolicy { policy: first.clone(), signatures: Vec::new() };
+        assert!(log.check(&unsigned).is_err());
+        let signed = SignedApprovalPolicy {
+            signatures: vec![first.sign(&issuer_key).unwrap()],
+            policy: first.clone(),
+        };
+        log.insert(signed.clone()).unwrap();
+
+        // The issuer key alone cannot lower the threshold afterwards
+        let lowered = first.next(policy(&officers, 1)).unwrap();
+        let mut change = SignedApprovalPolicy {
+            signatures: vec![lowered.sign(&issuer_key).unwrap()],
+            policy: lowered.clone(),
+        };
+        assert!(log.check(&change).is_err());
+        assert!(change.add_signature(lowered.sign(&issuer_key).unwrap(), Some(&first)).is_err());
+        change.add_signature(lowered.sign(&officers[0]).unwrap(), Some(&first)).unwrap();
+        assert!(log.check(&change).is_err());
+        change.add_signature(lowered.sign(&officers[2]).unwrap(), Some(&first)).unwrap();
+        log.insert(change.clone()).unwrap();
+        assert_eq!(log.current(&issuer_key.public()).unwrap().policy.threshold, 1);
+
+        // Versions cannot be skipped or replayed, and the log rebuilds from any order
+        assert!(log.check(&change).is_err());
+        let rebuilt = ApprovalPolicyLog::new([change, signed]);
+        assert_eq!(rebuilt.history(&issuer_key.public()).len(), 2);
+        assert!(PublishedApprovalPolicy::first(issuer_key.public(), policy(&officers, 0)).is_err());
+    }
+
+    #[test]
+    fn test_officer_approval_covers_every_form() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let officer_key = SecretKey::generate(&mut rand::rng());
+        let terms = terms();
+        let mut draft = draft(&issuer_key, &terms);
+        draft.check(issuer_key.public(), &terms).unwrap();
+
+        let approval = OfficerApproval::sign(&officer_key, "request-1".to_string(), &draft, 1).unwrap();
+        assert_eq!(approval.officer_node_id, officer_key.public());
+        assert!(approval.verify(&draft).unwrap());
+
+        // A draft that differs from the request, in any form, is refused
+        let mut raised = terms.clone();
+        raised.net_salary = "9000.00".to_string();
+        assert!(draft.check(issuer_key.public(), &raised).is_err());
+        let mut mixed = draft.clone();
+        mixed.threshold_credential = self::draft(&issuer_key, &terms).threshold_credential;
+        assert!(mixed.check(issuer_key.public(), &terms).is_err());
+        assert!(!approval.verify(&mixed).unwrap());
+
+        // Attached approvals verify on each form on their own
+        draft.attach(&approval);
+        let history = vec![SignedApprovalPolicy {
+            policy: PublishedApprovalPolicy::first(issuer_key.public(), ApprovalPolicy {
+                officers: vec![officer_key.public()],
+                threshold: 1,
+            })
+            .unwrap(),
+            signatures: Vec::new(),
+        }];
+        assert!(ApprovalCheck::new(&draft.credential, &history).unwrap().unwrap().is_met());
+        assert!(ApprovalCheck::new(&draft.selective_credential, &history).unwrap().unwrap().is_met());
+        let proof = draft.threshold_credential.as_ref().unwrap().prove("5000").unwrap();
+        assert!(ApprovalCheck::new(&proof, &history).unwrap().unwrap().is_met());
+
+        // Approvals do not carry over to another credential
+        let mut other = self::draft(&issuer_key, &terms).credential;
+        other.approvals = draft.credential.approvals.clone();
+        assert!(!ApprovalCheck::new(&other, &history).unwrap().unwrap().is_met());
+        assert_eq!(ApprovalCheck::new(&other, &[]).unwrap(), None);
+    }
+}
@@ -0,0 +1,671 @@
+use chrono::{DateTime, Utc};
+use iroh::{EndpointId, PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use super::co_signature::{CoSigned, SignerStatus};
+use super::income_credential::{CredentialError, IncomeCredential, SignedIncomeCredential, ValidityStatus};
+use super::officer_approval::CredentialApproval;
+use super::selective_disclosure::{DisclosableField, DisclosedClaims, SelectiveCredential};
+use super::threshold_proof::ThresholdProof;
+
+/// Domain separation tag prefixed to every presentation envelope signing payload
+const PRESENTATION_SIGNING_DOMAIN: &str = "trust-triangle/presentation-envelope";
+
+/// Version of the presentation envelope signing payload layout
+const PRESENTATION_PAYLOAD_VERSION: u8 = 1;
+
+/// A credential a holder can sign into a [`PresentationEnvelope`] and a verifier can check
+pub trait PresentableCredential {
+    fn id(&self) -> &str;
+    /// The employee the credential was issued to
+    fn holder_node_id(&self) -> EndpointId;
+    fn issuer_node_id(&self) -> EndpointId;
+    /// Key that signed for the issuer, when it is not the issuer's node key
+    fn signing_key(&self) -> Option<PublicKey>;
+    /// Bound into holder envelopes, so an envelope cannot be moved to another credential
+    fn issuer_signature(&self) -> &[u8];
+    fn status_index(&self) -> Option<u32>;
+    /// When the issuer signed the credential (RFC3339 format)
+    fn issued_at(&self) -> &str;
+    fn validity_at(&self, now: DateTime<Utc>, max_age: Option<chrono::Duration>) -> ValidityStatus;
+    /// Claims the verifier can read; hidden fields are `None`
+    fn disclosed_claims(&self) -> DisclosedClaims;
+    /// Checks the issuer's signature and, for selective credentials and threshold
+    /// proofs, every disclosure or range proof
+    fn verify_issuer(&self) -> Result<bool, CredentialError>;
+    /// The signature covers only the legacy human-readable statement
+    fn is_legacy(&self) -> bool {
+        false
+    }
+    /// Parties besides the issuer the credential declares, and whether each has signed
+    fn co_signers(&self) -> Result<Vec<SignerStatus>, CredentialError> {
+        Ok(Vec::new())
+    }
+    /// Canonical bytes the issuer signed, which officer approvals also sign
+    fn signed_payload(&self) -> Result<Vec<u8>, CredentialError>;
+    /// Approvals of the issuer's officers the credential carries
+    fn officer_approvals(&self) -> &[CredentialApproval];
+}
+
+impl PresentableCredential for SignedIncomeCredential {
+    fn id(&self) -> &str {
+        &self.credential.id
+    }
+
+    fn holder_node_id(&self) -> EndpointId {
+        self.credential.employee_node_id
+    }
+
+    fn issuer_node_id(&self) -> EndpointId {
+        self.credential.employer_node_id
+    }
+
+    fn signing_key(&self) -> Option<PublicKey> {
+        self.signing_key
+    }
+
+    fn issuer_signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    fn status_index(&self) -> Option<u32> {
+        self.credential.status_index
+    }
+
+    fn issued_at(&self) -> &str {
+        &self.credential.issued_at
+    }
+
+    fn validity_at(&self, now: DateTime<Utc>, max_age: Option<chrono::Duration>) -> ValidityStatus {
+        // The legacy statement does not cover the timestamps, so they prove nothing
+        if self.is_legacy() {
+            return ValidityStatus::Unknown;
+        }
+        self.credential.validity_at(now, max_age)
+    }
+
+    fn disclosed_claims(&self) -> DisclosedClaims {
+        DisclosedClaims::from(&self.credential)
+    }
+
+    fn verify_issuer(&self) -> Result<bool, CredentialError> {
+        self.verify()
+    }
+
+    fn is_legacy(&self) -> bool {
+        SignedIncomeCredential::is_legacy(self)
+    }
+
+    fn co_signers(&self) -> Result<Vec<SignerStatus>, CredentialError> {
+        self.co_signer_statuses()
+    }
+
+    fn signed_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        self.credential.signing_payload()
+    }
+
+    fn officer_approvals(&self) -> &[CredentialApproval] {
+        &self.approvals
+    }
+}
+
+impl PresentableCredential for SelectiveCredential {
+    fn id(&self) -> &str {
+        &self.credential.id
+    }
+
+    fn holder_node_id(&self) -> EndpointId {
+        self.credential.employee_node_id
+    }
+
+    fn issuer_node_id(&self) -> EndpointId {
+        self.credential.employer_node_id
+    }
+
+    fn signing_key(&self) -> Option<PublicKey> {
+        self.credential.signing_key
+    }
+
+    fn issuer_signature(&self) -> &[u8] {
+        &self.credential.signature
+    }
+
+    fn status_index(&self) -> Option<u32> {
+        self.credential.status_index
+    }
+
+    fn issued_at(&self) -> &str {
+        &self.credential.issued_at
+    }
+
+    fn validity_at(&self, now: DateTime<Utc>, max_age: Option<chrono::Duration>) -> ValidityStatus {
+        self.credential.validity_at(now, max_age)
+    }
+
+    fn disclosed_claims(&self) -> DisclosedClaims {
+        self.claims().unwrap_or_default()
+    }
+
+    fn verify_issuer(&self) -> Result<bool, CredentialError> {
+        Ok(self.verify()?.is_some())
+    }
+
+    fn co_signers(&self) -> Result<Vec<SignerStatus>, CredentialError> {
+        self.credential.co_signer_statuses()
+    }
+
+    fn signed_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        self.credential.signing_payload()
+    }
+
+    fn officer_approvals(&self) -> &[CredentialApproval] {
+        &self.credential.approvals
+    }
+}
+
+impl PresentableCredential for ThresholdProof {
+    fn id(&self) -> &str {
+        &self.credential.credential_id
+    }
+
+    fn holder_node_id(&self) -> EndpointId {
+        self.credential.employee_node_id
+    }
+
+    fn issuer_node_id(&self) -> EndpointId {
+        self.credential.employer_node_id
+    }
+
+    fn signing_key(&self) -> Option<PublicKey> {
+        self.credential.signing_key
+    }
+
+    fn issuer_signature(&self) -> &[u8] {
+        &self.credential.signature
+    }
+
+    fn status_index(&self) -> Option<u32> {
+        self.credential.status_index
+    }
+
+    fn issued_at(&self) -> &str {
+        &self.credential.issued_at
+    }
+
+    fn validity_at(&self, now: DateTime<Utc>, max_age: Option<chrono::Duration>) -> ValidityStatus {
+        self.credential.validity_at(now, max_age)
+    }
+
+    fn disclosed_claims(&self) -> DisclosedClaims {
+        DisclosedClaims {
+            payroll_processor_node_id: self.credential.payroll_processor_node_id,
+            currency: Some(self.credential.currency.clone()),
+            pay_period: Some(self.credential.pay_period.clone()),
+            net_salary_at_least: Some(self.threshold.clone()),
+            ..Default::default()
+        }
+    }
+
+    fn verify_issuer(&self) -> Result<bool, CredentialError> {
+        self.verify()
+    }
+
+    fn co_signers(&self) -> Result<Vec<SignerStatus>, CredentialError> {
+        self.credential.co_signer_statuses()
+    }
+
+    fn signed_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        self.credential.signing_payload()
+    }
+
+    fn officer_approvals(&self) -> &[CredentialApproval] {
+        &self.credential.approvals
+    }
+}
+
+/// Holder's signature binding presented credentials to one verifier and one
+/// verifier-issued nonce, so a copied credential cannot be replayed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationEnvelope {
+    /// Must match the `employee_node_id` of every presented credential
+    pub holder_node_id: EndpointId,
+    /// Audience: the only verifier that may accept the envelope
+    pub verifier_node_id: EndpointId,
+    /// Single-use challenge issued by the verifier
+    pub nonce: String,
+    /// Timestamp of the signature (RFC3339 format)
+    pub created_at: String,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalEnvelope<'a> {
+    domain: &'a str,
+    version: u8,
+    holder_node_id: &'a [u8; 32],
+    verifier_node_id: &'a [u8; 32],
+    nonce: &'a str,
+    created_at: &'a str,
+    /// ID and issuer signature of each credential, in presentation order
+    credentials: Vec<(&'a str, &'a [u8])>,
+}
+
+/// How the verifier established that the presenter holds the credential
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HolderBinding {
+    /// Recorded before holder binding was checked
+    #[default]
+    NotChecked,
+    /// Presented over a connection authenticated as the credential's employee
+    Connection,
+    /// The employee signed an envelope over a nonce issued by this verifier
+    Envelope,
+    /// Presented by another node without an envelope
+    Unbound,
+    /// The envelope's signature, audience, holder or nonce did not check out
+    InvalidEnvelope,
+}
+
+impl HolderBinding {
+    /// The presenter proved possession of the credential
+    pub fn is_bound(&self) -> bool {
+        matches!(self, HolderBinding::Connection | HolderBinding::Envelope)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HolderBinding::NotChecked => "not checked",
+            HolderBinding::Connection => "presented by holder",
+            HolderBinding::Envelope => "holder-signed envelope",
+            HolderBinding::Unbound => "not presented by holder",
+            HolderBinding::InvalidEnvelope => "invalid holder envelope",
+        }
+    }
+}
+
+/// What a verifier asks for in a presentation request; unset fields match anything
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationConstraints {
+    /// Acceptable issuers; empty accepts any issuer
+    #[serde(default)]
+    pub issuers: Vec<EndpointId>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Earliest acceptable pay period, e.g. "2025-01"
+    #[serde(default)]
+    pub pay_period_from: Option<String>,
+    /// Latest acceptable pay period, inclusive
+    #[serde(default)]
+    pub pay_period_to: Option<String>,
+    /// Decimal amount the net salary must reach, e.g. "3000.00"
+    #[serde(default)]
+    pub min_net_salary: Option<String>,
+}
+
+impl PresentationConstraints {
+    /// Checks the constraints are well-formed
+    pub fn validate(&self) -> Result<(), CredentialError> {
+        if let Some(min) = &self.min_net_salary
+            && parse_decimal(min).is_none()
+        {
+            return Err(CredentialError::InvalidConstraints(format!("{min:?} is not a decimal amount")));
+        }
+        if let (Some(from), Some(to)) = (&self.pay_period_from, &self.pay_period_to)
+            && from > to
+        {
+            return Err(CredentialError::InvalidConstraints(format!("pay period {from} is after {to}")));
+        }
+        Ok(())
+    }
+
+    /// Returns the first constraint `credential` fails, if any
+    pub fn mismatch(&self, credential: &IncomeCredential) -> Option<String> {
+        self.mismatch_claims(credential.employer_node_id, &DisclosedClaims::from(credential))
+    }
+
+    /// Returns the first constraint the claims of a credential from `issuer`
+    /// fail, if any; a constrained field that was not disclosed fails
+    pub fn mismatch_claims(&self, issuer: EndpointId, claims: &DisclosedClaims) -> Option<String> {
+        if !self.issuers.is_empty() && !self.issuers.contains(&issuer) {
+            return Some(format!("issuer {} is not accepted", issuer));
+        }
+        let disclosed = |value: &Option<String>, field: DisclosableField| match value {
+            Some(value) => Ok(value.clone()),
+            None => Err(format!("{} is not disclosed", field.as_str())),
+        };
+        let check = || -> Result<(), String> {
+            if let Some(currency) = &self.currency {
+                let actual = disclosed(&claims.currency, DisclosableField::Currency)?;
+                if !currency.eq_ignore_ascii_case(&actual) {
+                    return Err(format!("currency {} is not {}", actual, currency));
+                }
+            }
+            // Pay periods are "YYYY-MM", so they order as strings
+            if self.pay_period_from.is_some() || self.pay_period_to.is_some() {
+                let pay_period = disclosed(&claims.pay_period, DisclosableField::PayPeriod)?;
+                if let Some(from) = &self.pay_period_from
+                    && pay_period < *from
+                {
+                    return Err(format!("pay period {} is before {}", pay_period, from));
+                }
+                if let Some(to) = &self.pay_period_to
+                    && pay_period > *to
+                {
+                    return Err(format!("pay period {} is after {}", pay_period, to));
+                }
+            }
+            if let Some(min) = &self.min_net_salary {
+                match (&claims.net_salary, &claims.net_salary_at_least) {
+                    (None, Some(bound)) => {
+                        if compare_decimal(bound, min).is_none_or(|order| order == Ordering::Less) {
+                            return Err(format!("net salary is only proven to reach {}, not {}", bound, min));
+                        }
+                    }
+                    (net_salary, _) => {
+                        let net_salary = disclosed(net_salary, DisclosableField::NetSalary)?;
+                        if compare_decimal(&net_salary, min).is_none_or(|order| order == Ordering::Less) {
+                            return Err(format!("net salary {} is below {}", net_salary, min));
+                        }
+                    }
+                }
+            }
+            Ok(())
+        };
+        check().err()
+    }
+
+    pub fn matches(&self, credential: &IncomeCredential) -> bool {
+        self.mismatch(credential).is_none()
+    }
+}
+
+/// Splits a non-negative decimal like "1,234.50" into integer and fraction digits
+pub(super) fn parse_decimal(value: &str) -> Option<(String, String)> {
+    let value = value.trim().replace(',', "");
+    let (int, frac) = value.split_once('.').unwrap_or((&value, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int.is_empty() && frac.is_empty() || !all_digits(int) || !all_digits(frac) {
+        return None;
+    }
+    let int = int.trim_start_matches('0').to_string();
+    let frac = frac.trim_end_matches('0').to_string();
+    Some((int, frac))
+}
+
+/// Compares decimal amounts exactly, without going through floats
+fn compare_decimal(a: &str, b: &str) -> Option<Ordering> {
+    let (a_int, a_frac) = parse_decimal(a)?;
+    let (b_int, b_frac) = parse_decimal(b)?;
+    Some(
+        a_int
+            .len()
+            .cmp(&b_int.len())
+            .then_with(|| a_int.cmp(&b_int))
+            .then_with(|| a_frac.cmp(&b_frac)),
+    )
+}
+
+fn signing_payload<C: PresentableCredential>(
+    holder_node_id: &EndpointId,
+    verifier_node_id: &EndpointId,
+    nonce: &str,
+    created_at: &str,
+    credentials: &[&C],
+) -> Result<Vec<u8>, CredentialError> {
+    let payload = CanonicalEnvelope {
+        domain: PRESENTATION_SIGNING_DOMAIN,
+        version: PRESENTATION_PAYLOAD_VERSION,
+        holder_node_id: holder_node_id.as_bytes(),
+        verifier_node_id: verifier_node_id.as_bytes(),
+        nonce,
+        created_at,
+        credentials: credentials
+            .iter()
+            .map(|credential| (credential.id(), credential.issuer_signature()))
+            .collect(),
+    };
+    Ok(postcard::to_allocvec(&payload)?)
+}
+
+impl PresentationEnvelope {
+    /// Signs `credentials` for `verifier_node_id` with the holder's secret key
+    pub fn sign<C: PresentableCredential>(
+        secret_key: &SecretKey,
+        verifier_node_id: EndpointId,
+        nonce: String,
+        credentials: &[&C],
+    ) -> Result<Self, CredentialError> {
+        let holder_node_id = secret_key.public();
+        let created_at = chrono::Utc::now().to_rfc3339();
+        let payload = signing_payload(&holder_node_id, &verifier_node_id, &nonce, &created_at, credentials)?;
+        Ok(Self {
+            holder_node_id,
+            verifier_node_id,
+            nonce,
+            created_at,
+            signature: secret_key.sign(&payload).to_bytes().to_vec(),
+        })
+    }
+
+    /// Verifies the holder signed exactly `credentials` and holds all of them.
+    ///
+    /// The caller still has to check the audience and consume the nonce.
+    pub fn verify<C: PresentableCredential>(&self, credentials: &[&C]) -> Result<bool, CredentialError> {
+        if credentials
+            .iter()
+            .any(|credential| credential.holder_node_id() != self.holder_node_id)
+        {
+            return Ok(false);
+        }
+        let payload = signing_payload(
+            &self.holder_node_id,
+            &self.verifier_node_id,
+            &self.nonce,
+            &self.created_at,
+            credentials,
+        )?;
+        super::verify_signature(&self.holder_node_id, &payload, &self.signature)
+    }
+}
+
+/// Several credentials presented together under one holder signature, e.g.
+/// a few months of payslips for a lender
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiablePresentation {
+    pub credentials: Vec<SignedIncomeCredential>,
+    /// Holder signature over every credential, naming the audience and nonce
+    pub envelope: PresentationEnvelope,
+}
+
+impl VerifiablePresentation {
+    /// Bundles `credentials` for `verifier_node_id`, signed with the holder's secret key
+    pub fn sign(
+        secret_key: &SecretKey,
+        verifier_node_id: EndpointId,
+        nonce: String,
+        credentials: Vec<SignedIncomeCredential>,
+    ) -> Result<Self, CredentialError> {
+        let refs: Vec<_> = credentials.iter().collect();
+        let envelope = PresentationEnvelope::sign(secret_key, verifier_node_id, nonce, &refs)?;
+        Ok(Self { credentials, envelope })
+    }
+
+    /// Verifies the bundle is non-empty, free of duplicates and signed by the
+    /// holder of every credential.
+    ///
+    /// Like [`PresentationEnvelope::verify`], this leaves the audience and
+    /// nonce to the caller.
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let mut ids: Vec<_> = self.credentials.iter().map(|credential| credential.id()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() || ids.len() != self.credentials.len() {
+            return Ok(false);
+        }
+        let refs: Vec<_> = self.credentials.iter().collect();
+        self.envelope.verify(&refs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::income_credential::{IncomeCredential, PaymentMode};
+
+    fn issue(issuer_key: &SecretKey, holder: EndpointId) -> SignedIncomeCredential {
+        IncomeCredential::new(
+            holder,
+            "Alice".to_string(),
+            issuer_key.public(),
+            "Acme Corp".to_string(),
+            "5000".to_string(),
+            "4000".to_string(),
+            "USD".to_string(),
+            "2025-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+        .sign(issuer_key)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_presentation_envelope_binds_holder_and_credentials() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let holder_key = SecretKey::generate(&mut rand::rng());
+        let verifier = SecretKey::generate(&mut rand::rng()).public();
+        let credential = issue(&issuer_key, holder_key.public());
+        let other = issue(&issuer_key, holder_key.public());
+
+        let envelope = PresentationEnvelope::sign(&holder_key, verifier, "nonce-1".to_string(), &[&credential]).unwrap();
+        assert!(envelope.verify(&[&credential]).unwrap());
+
+        // The envelope does not carry over to another credential or nonce
+        assert!(!envelope.verify(&[&other]).unwrap());
+        let mut replayed = envelope.clone();
+        replayed.nonce = "nonce-2".to_string();
+        assert!(!replayed.verify(&[&credential]).unwrap());
+    }
+
+    #[test]
+    fn test_verifiable_presentation_bundles_credentials() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let holder_key = SecretKey::generate(&mut rand::rng());
+        let verifier = SecretKey::generate(&mut rand::rng()).public();
+        let payslips: Vec<_> = (0..3).map(|_| issue(&issuer_key, holder_key.public())).collect();
+
+        let presentation =
+            VerifiablePresentation::sign(&holder_key, verifier, "nonce".to_string(), payslips.clone()).unwrap();
+        assert!(presentation.verify().unwrap());
+
+        // Dropping, reordering or repeating a credential breaks the bundle
+        let mut dropped = presentation.clone();
+        dropped.credentials.pop();
+        assert!(!dropped.verify().unwrap());
+        let mut reordered = presentation.clone();
+        reordered.credentials.swap(0, 1);
+        assert!(!reordered.verify().unwrap());
+        let repeated = VerifiablePresentation::sign(
+            &holder_key,
+            verifier,
+            "nonce".to_string(),
+            vec![payslips[0].clone(), payslips[0].clone()],
+        )
+        .unwrap();
+        assert!(!repeated.verify().unwrap());
+
+        let empty = VerifiablePresentation::sign(&holder_key, verifier, "nonce".to_string(), Vec::new()).unwrap();
+        assert!(!empty.verify().unwrap());
+    }
+
+    #[test]
+    fn test_presentation_constraints() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let holder = SecretKey::generate(&mut rand::rng()).public();
+        let credential = issue(&issuer_key, holder);
+        let credential = credential.credential();
+
+        assert!(PresentationConstraints::default().matches(credential));
+
+        let constraints = PresentationConstraints {
+            issuers: vec![issuer_key.public()],
+            currency: Some("usd".to_string()),
+            pay_period_from: Some("2024-10".to_string()),
+            pay_period_to: Some("2025-01".to_string()),
+            min_net_salary: Some("4,000.00".to_string()),
+        };
+        assert!(constraints.validate().is_ok());
+        assert!(constraints.matches(credential));
+
+        let too_high = PresentationConstraints {
+            min_net_salary: Some("4000.01".to_string()),
+            ..Default::default()
+        };
+        assert!(too_high.mismatch(credential).unwrap().contains("below"));
+
+        let too_late = PresentationConstraints {
+            pay_period_from: Some("2025-02".to_string()),
+            ..Default::default()
+        };
+        assert!(!too_late.matches(credential));
+
+        let other_issuer = PresentationConstraints {
+            issuers: vec![holder],
+            ..Default::default()
+        };
+        assert!(!other_issuer.matches(credential));
+
+        let malformed = PresentationConstraints {
+            min_net_salary: Some("4k".to_string()),
+            ..Default::default()
+        };
+        assert!(malformed.validate().is_err());
+
+        // Constraints on hidden fields are not met
+        let claims = DisclosedClaims {
+            net_salary: Some("4000".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(too_high.mismatch_claims(issuer_key.public(), &claims).unwrap(), "net salary 4000 is below 4000.01");
+        assert_eq!(constraints.mismatch_claims(issuer_key.public(), &claims).unwrap(), "currency is not disclosed");
+
+        // A proven lower bound stands in for the hidden net salary
+        let proven = DisclosedClaims {
+            net_salary_at_least: Some("4000.01".to_string()),
+            ..Default::default()
+        };
+        assert!(too_high.mismatch_claims(issuer_key.public(), &proven).is_none());
+        let below = PresentationConstraints {
+            min_net_salary: Some("4500".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            below.mismatch_claims(issuer_key.public(), &proven).unwrap(),
+            "net salary is only proven to reach 4000.01, not 4500"
+        );
+    }
+
+    #[test]
+    fn test_compare_decimal() {
+        assert_eq!(compare_decimal("100", "99.99"), Some(Ordering::Greater));
+        assert_eq!(compare_decimal("0100.50", "100.5"), Some(Ordering::Equal));
+        assert_eq!(compare_decimal("0.1", ".2"), Some(Ordering::Less));
+        assert_eq!(compare_decimal("abc", "1"), None);
+    }
+
+    #[test]
+    fn test_presentation_envelope_from_another_node_is_rejected() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let holder_key = SecretKey::generate(&mut rand::rng());
+        let thief_key = SecretKey::generate(&mut rand::rng());
+        let verifier = SecretKey::generate(&mut rand::rng()).public();
+        let credential = issue(&issuer_key, holder_key.public());
+
+        let envelope = PresentationEnvelope::sign(&thief_key, verifier, "nonce".to_string(), &[&credential]).unwrap();
+        assert!(!envelope.verify(&[&credential]).unwrap());
+    }
+}
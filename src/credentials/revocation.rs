@@ -0,0 +1,117 @@
+use iroh::{EndpointId, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use super::income_credential::CredentialError;
+
+/// Domain separation tag prefixed to every revocation notice signing payload
+const REVOCATION_SIGNING_DOMAIN: &str = "trust-triangle/revocation-notice";
+
+/// Version of the revocation notice signing payload layout
+const REVOCATION_PAYLOAD_VERSION: u8 = 1;
+
+/// Statement by an issuer that one of its credentials must no longer be accepted
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationNotice {
+    /// ID of the revoked credential
+    pub credential_id: String,
+    /// Issuer that revoked the credential; must match the credential's issuer
+    pub issuer_node_id: EndpointId,
+    /// Optional human-readable reason
+    pub reason: Option<String>,
+    /// Timestamp of the revocation (RFC3339 format)
+    pub revoked_at: String,
+}
+
+/// Revocation notice signed by the issuer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedRevocationNotice {
+    pub notice: RevocationNotice,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalNotice<'a> {
+    domain: &'a str,
+    version: u8,
+    credential_id: &'a str,
+    issuer_node_id: &'a [u8; 32],
+    reason: Option<&'a str>,
+    revoked_at: &'a str,
+}
+
+impl RevocationNotice {
+    /// Creates a notice revoking `credential_id` now
+    pub fn new(credential_id: String, issuer_node_id: EndpointId, reason: Option<String>) -> Self {
+        Self {
+            credential_id,
+            issuer_node_id,
+            reason,
+            revoked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Canonical bytes covered by the issuer's signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalNotice {
+            domain: REVOCATION_SIGNING_DOMAIN,
+            version: REVOCATION_PAYLOAD_VERSION,
+            credential_id: &self.credential_id,
+            issuer_node_id: self.issuer_node_id.as_bytes(),
+            reason: self.reason.as_deref(),
+            revoked_at: &self.revoked_at,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs the notice with the issuer's secret key
+    pub fn sign(self, secret_key: &SecretKey) -> Result<SignedRevocationNotice, CredentialError> {
+        let signature = secret_key.sign(&self.signing_payload()?);
+        Ok(SignedRevocationNotice {
+            notice: self,
+            signature: signature.to_bytes().to_vec(),
+        })
+    }
+}
+
+impl SignedRevocationNotice {
+    /// Verifies the notice was signed by the issuer it names
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let payload = self.notice.signing_payload()?;
+        super::verify_signature(&self.notice.issuer_node_id, &payload, &self.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revocation_notice_signing_and_verification() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let notice = RevocationNotice::new(
+            "credential-1".to_string(),
+            issuer_key.public(),
+            Some("Payslip reissued".to_string()),
+        );
+
+        let signed = notice.sign(&issuer_key).unwrap();
+        assert!(signed.verify().unwrap());
+
+        let mut tampered = signed.clone();
+        tampered.notice.credential_id = "credential-2".to_string();
+        assert!(!tampered.verify().unwrap());
+    }
+
+    #[test]
+    fn test_revocation_notice_for_another_issuer_is_rejected() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let attacker_key = SecretKey::generate(&mut rand::rng());
+
+        // An attacker cannot revoke credentials in another issuer's name
+        let notice = RevocationNotice::new("credential-1".to_string(), issuer_key.public(), None);
+        let forged = notice.sign(&attacker_key).unwrap();
+        assert!(!forged.verify().unwrap());
+    }
+}
@@ -0,0 +1,503 @@
+use iroh::{EndpointId, PublicKey, SecretKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::co_signature::CoSignature;
+use super::income_credential::{
+    CredentialError, IncomeCredential, PaymentMode, ValidityStatus, validity_at,
+};
+use super::key_binding::distinct_signing_key;
+use super::officer_approval::CredentialApproval;
+use super::signer::CredentialSigner;
+
+/// Domain separation tag prefixed to every commitment signing payload
+const COMMITMENT_SIGNING_DOMAIN: &str = "trust-triangle/income-commitment";
+
+/// Domain separation tag hashed into every disclosure digest
+const DISCLOSURE_DIGEST_DOMAIN: &str = "trust-triangle/income-disclosure";
+
+/// Version of the commitment signing payload and disclosure digest layout
+const COMMITMENT_PAYLOAD_VERSION: u8 = 2;
+
+/// Credential fields a holder may keep hidden from a verifier.
+///
+/// IDs, node IDs, timestamps and the status index are always disclosed: the
+/// verifier needs them to check the signature, holder, validity and revocation,
+/// and the payroll processor's node ID to check its co-signature.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DisclosableField {
+    EmployeeName,
+    EmployerName,
+    PayrollProcessorName,
+    GrossSalary,
+    NetSalary,
+    Currency,
+    PayPeriod,
+    PaymentMode,
+}
+
+impl DisclosableField {
+    pub const ALL: [DisclosableField; 8] = [
+        DisclosableField::EmployeeName,
+        DisclosableField::EmployerName,
+        DisclosableField::PayrollProcessorName,
+        DisclosableField::GrossSalary,
+        DisclosableField::NetSalary,
+        DisclosableField::Currency,
+        DisclosableField::PayPeriod,
+        DisclosableField::PaymentMode,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisclosableField::EmployeeName => "employee name",
+            DisclosableField::EmployerName => "employer name",
+            DisclosableField::PayrollProcessorName => "payroll processor name",
+            DisclosableField::GrossSalary => "gross salary",
+            DisclosableField::NetSalary => "net salary",
+            DisclosableField::Currency => "currency",
+            DisclosableField::PayPeriod => "pay period",
+            DisclosableField::PaymentMode => "payment mode",
+        }
+    }
+}
+
+/// One field value with the salt that hides it in the issuer's commitment
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Disclosure {
+    pub field: DisclosableField,
+    /// Field value as a string; payment modes use their snake_case name
+    pub value: String,
+    /// Random hex salt, so hidden values cannot be guessed from their digest
+    pub salt: String,
+}
+
+#[derive(Serialize)]
+struct CanonicalDisclosure<'a> {
+    domain: &'a str,
+    version: u8,
+    salt: &'a str,
+    field: DisclosableField,
+    value: &'a str,
+}
+
+impl Disclosure {
+    fn new(field: DisclosableField, value: String) -> Self {
+        Self {
+            field,
+            value,
+            salt: random_hex(),
+        }
+    }
+
+    /// Hex BLAKE3 digest the issuer commits to
+    pub fn digest(&self) -> Result<String, CredentialError> {
+        let payload = CanonicalDisclosure {
+            domain: DISCLOSURE_DIGEST_DOMAIN,
+            version: COMMITMENT_PAYLOAD_VERSION,
+            salt: &self.salt,
+            field: self.field,
+            value: &self.value,
+        };
+        Ok(blake3::hash(&postcard::to_allocvec(&payload)?).to_hex().to_string())
+    }
+}
+
+fn random_hex() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Issuer-signed commitment to an income credential: the public claims in
+/// the clear and every other field as a salted digest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommittedIncomeCredential {
+    /// Same ID as the [`IncomeCredential`] it was derived from
+    pub id: String,
+    pub employee_node_id: EndpointId,
+    pub employer_node_id: EndpointId,
+    /// Payroll processor that must co-sign the commitment
+    #[serde(default)]
+    pub payroll_processor_node_id: Option<EndpointId>,
+    pub issued_at: String,
+    #[serde(default)]
+    pub valid_from: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub status_index: Option<u32>,
+    /// One digest per [`DisclosableField`], sorted; fields the credential lacks
+    /// get a random decoy so the count and order reveal nothing
+    pub digests: Vec<String>,
+    pub signature: Vec<u8>,
+    /// Signatures of the other parties the commitment declares, over the same payload
+    #[serde(default)]
+    pub co_signatures: Vec<CoSignature>,
+    /// Key that signed the commitment, when it is not the issuer's node key
+    #[serde(default)]
+    pub signing_key: Option<PublicKey>,
+    /// Approvals of the issuer's officers (organizational issuers)
+    #[serde(default)]
+    pub approvals: Vec<CredentialApproval>,
+}
+
+#[derive(Serialize)]
+struct CanonicalCommitment<'a> {
+    domain: &'a str,
+    version: u8,
+    id: &'a str,
+    employee_node_id: &'a [u8; 32],
+    employer_node_id: &'a [u8; 32],
+    payroll_processor_node_id: Option<&'a [u8; 32]>,
+    issued_at: &'a str,
+    valid_from: Option<&'a str>,
+    expires_at: Option<&'a str>,
+    status_index: Option<u32>,
+    digests: &'a [String],
+}
+
+impl CommittedIncomeCredential {
+    /// Canonical bytes covered by the issuer's signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalCommitment {
+            domain: COMMITMENT_SIGNING_DOMAIN,
+            version: COMMITMENT_PAYLOAD_VERSION,
+            id: &self.id,
+            employee_node_id: self.employee_node_id.as_bytes(),
+            employer_node_id: self.employer_node_id.as_bytes(),
+            payroll_processor_node_id: self.payroll_processor_node_id.as_ref().map(|id| id.as_bytes()),
+            issued_at: &self.issued_at,
+            valid_from: self.valid_from.as_deref(),
+            expires_at: self.expires_at.as_deref(),
+            status_index: self.status_index,
+            digests: &self.digests,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Verifies the issuer's signature over the public claims and digests
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let signer = self.signing_key.unwrap_or(self.employer_node_id);
+        super::verify_signature(&signer, &self.signing_payload()?, &self.signature)
+    }
+
+    /// Checks the validity window and, if `max_age` is set, the credential's age at `now`
+    pub fn validity_at(&self, now: chrono::DateTime<chrono::Utc>, max_age: Option<chrono::Duration>) -> ValidityStatus {
+        validity_at(
+            &self.issued_at,
+            self.valid_from.as_deref(),
+            self.expires_at.as_deref(),
+            now,
+            max_age,
+        )
+    }
+}
+
+/// A committed credential with the disclosures for some or all of its fields.
+///
+/// The holder keeps every disclosure and derives presentations with
+/// [`SelectiveCredential::disclose`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectiveCredential {
+    pub credential: CommittedIncomeCredential,
+    pub disclosures: Vec<Disclosure>,
+}
+
+/// Field values revealed by a [`SelectiveCredential`]; hidden fields are `None`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DisclosedClaims {
+    pub employee_name: Option<String>,
+    pub employer_name: Option<String>,
+    pub payroll_processor_node_id: Option<EndpointId>,
+    pub payroll_processor_name: Option<String>,
+    pub gross_salary: Option<String>,
+    pub net_salary: Option<String>,
+    pub currency: Option<String>,
+    pub pay_period: Option<String>,
+    pub payment_mode: Option<PaymentMode>,
+    /// Lower bound on the net salary proven without revealing it
+    #[serde(default)]
+    pub net_salary_at_least: Option<String>,
+}
+
+impl From<&IncomeCredential> for DisclosedClaims {
+    fn from(credential: &IncomeCredential) -> Self {
+        Self {
+            employee_name: Some(credential.employee_name.clone()),
+            employer_name: Some(credential.employer_name.clone()),
+            payroll_processor_node_id: credential.payroll_processor_node_id,
+            payroll_processor_name: credential.payroll_processor_name.clone(),
+            gross_salary: Some(credential.gross_salary.clone()),
+            net_salary: Some(credential.net_salary.clone()),
+            currency: Some(credential.currency.clone()),
+            pay_period: Some(credential.pay_period.clone()),
+            payment_mode: Some(credential.payment_mode.clone()),
+            net_salary_at_least: None,
+        }
+    }
+}
+
+impl DisclosedClaims {
+    /// Fields that were disclosed
+    pub fn fields(&self) -> Vec<DisclosableField> {
+        DisclosableField::ALL
+            .into_iter()
+            .filter(|field| match field {
+                DisclosableField::EmployeeName => self.employee_name.is_some(),
+                DisclosableField::EmployerName => self.employer_name.is_some(),
+                DisclosableField::PayrollProcessorName => self.payroll_processor_name.is_some(),
+                DisclosableField::GrossSalary => self.gross_salary.is_some(),
+                DisclosableField::NetSalary => self.net_salary.is_some(),
+                DisclosableField::Currency => self.currency.is_some(),
+                DisclosableField::PayPeriod => self.pay_period.is_some(),
+                DisclosableField::PaymentMode => self.payment_mode.is_some(),
+            })
+            .collect()
+    }
+
+    fn set(&mut self, disclosure: &Disclosure) -> Result<(), CredentialError> {
+        let value = disclosure.value.clone();
+        match disclosure.field {
+            DisclosableField::EmployeeName => self.employee_name = Some(value),
+            DisclosableField::EmployerName => self.employer_name = Some(value),
+            DisclosableField::PayrollProcessorName => self.payroll_processor_name = Some(value),
+            DisclosableField::GrossSalary => self.gross_salary = Some(value),
+            DisclosableField::NetSalary => self.net_salary = Some(value),
+            DisclosableField::Currency => self.currency = Some(value),
+            DisclosableField::PayPeriod => self.pay_period = Some(value),
+            DisclosableField::PaymentMode => {
+                let mode = serde_json::from_value(serde_json::Value::String(value))?;
+                self.payment_mode = Some(mode);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl IncomeCredential {
+    /// Signs a salted-digest commitment to the credential, returning it with
+    /// the disclosure of every field the credential has
+    pub fn sign_selective(&self, secret_key: &SecretKey) -> Result<SelectiveCredential, CredentialError> {
+        let mut selective = self.commit_fields()?;
+        let payload = selective.credential.signing_payload()?;
+        selective.credential.signature = secret_key.sign(&payload).to_bytes().to_vec();
+        Ok(selective)
+    }
+
+    /// Like [`Self::sign_selective`], signing through `signer`
+    pub async fn sign_selective_with(&self, signer: &dyn CredentialSigner) -> Result<SelectiveCredential, CredentialError> {
+        let mut selective = self.commit_fields()?;
+        let payload = selective.credential.signing_payload()?;
+        selective.credential.signature = signer.sign(payload).await?.to_bytes().to_vec();
+        selective.credential.signing_key = distinct_signing_key(signer.public_key(), self.employer_node_id);
+        Ok(selective)
+    }
+
+    /// Salted-digest commitment to the credential, not yet signed
+    fn commit_fields(&self) -> Result<SelectiveCredential, CredentialError> {
+        let mode = match serde_json::to_value(&self.payment_mode)? {
+            serde_json::Value::String(mode) => mode,
+            other => return Err(CredentialError::SigningError(format!("unexpected payment mode encoding {other}"))),
+        };
+        let values = [
+            (DisclosableField::EmployeeName, Some(self.employee_name.clone())),
+            (DisclosableField::EmployerName, Some(self.employer_name.clone())),
+            (DisclosableField::PayrollProcessorName, self.payroll_processor_name.clone()),
+            (DisclosableField::GrossSalary, Some(self.gross_salary.clone())),
+            (DisclosableField::NetSalary, Some(self.net_salary.clone())),
+            (DisclosableField::Currency, Some(self.currency.clone())),
+            (DisclosableField::PayPeriod, Some(self.pay_period.clone())),
+            (DisclosableField::PaymentMode, Some(mode)),
+        ];
+        let disclosures: Vec<_> = values
+            .into_iter()
+            .filter_map(|(field, value)| Some(Disclosure::new(field, value?)))
+            .collect();
+
+        let mut digests = disclosures.iter().map(Disclosure::digest).collect::<Result<Vec<_>, _>>()?;
+        while digests.len() < DisclosableField::ALL.len() {
+            digests.push(blake3::hash(random_hex().as_bytes()).to_hex().to_string());
+        }
+        digests.sort_unstable();
+
+        let credential = CommittedIncomeCredential {
+            id: self.id.clone(),
+            employee_node_id: self.employee_node_id,
+            employer_node_id: self.employer_node_id,
+            payroll_processor_node_id: self.payroll_processor_node_id,
+            issued_at: self.issued_at.clone(),
+            valid_from: self.valid_from.clone(),
+            expires_at: self.expires_at.clone(),
+            status_index: self.status_index,
+            digests,
+            signature: Vec::new(),
+            co_signatures: Vec::new(),
+            signing_key: None,
+            approvals: Vec::new(),
+        };
+        Ok(SelectiveCredential { credential, disclosures })
+    }
+}
+
+impl SelectiveCredential {
+    /// Keeps only the disclosures for `fields`; fields the credential lacks are skipped
+    pub fn disclose(&self, fields: &[DisclosableField]) -> Self {
+        Self {
+            credential: self.credential.clone(),
+            disclosures: self
+                .disclosures
+                .iter()
+                .filter(|disclosure| fields.contains(&disclosure.field))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Verifies the issuer's signature and that every disclosure matches a
+    /// committed digest, returning the disclosed values.
+    ///
+    /// `Ok(None)` means the signature or a disclosure did not check out.
+    pub fn verify(&self) -> Result<Option<DisclosedClaims>, CredentialError> {
+        if !self.credential.verify()? {
+            return Ok(None);
+        }
+        let mut seen = Vec::with_capacity(self.disclosures.len());
+        for disclosure in &self.disclosures {
+            if seen.contains(&disclosure.field) || !self.credential.digests.contains(&disclosure.digest()?) {
+                return Ok(None);
+            }
+            seen.push(disclosure.field);
+        }
+        Ok(Some(self.claims()?))
+    }
+
+    /// Whether this is a signed commitment to `credential`, with the same public
+    /// claims and a disclosure matching every field it has
+    pub fn commits_to(&self, credential: &IncomeCredential) -> Result<bool, CredentialError> {
+        let committed = &self.credential;
+        let same_claims = committed.id == credential.id
+            && committed.employee_node_id == credential.employee_node_id
+            && committed.employer_node_id == credential.employer_node_id
+            && committed.payroll_processor_node_id == credential.payroll_processor_node_id
+            && committed.issued_at == credential.issued_at
+            && committed.valid_from == credential.valid_from
+            && committed.expires_at == credential.expires_at
+            && committed.status_index == credential.status_index;
+        Ok(same_claims && self.verify()? == Some(DisclosedClaims::from(credential)))
+    }
+
+    /// Decodes the disclosed values without checking them; see [`SelectiveCredential::verify`]
+    pub fn claims(&self) -> Result<DisclosedClaims, CredentialError> {
+        let mut claims = DisclosedClaims {
+            payroll_processor_node_id: self.credential.payroll_processor_node_id,
+            ..Default::default()
+        };
+        for disclosure in &self.disclosures {
+            claims.set(disclosure)?;
+        }
+        Ok(claims)
+    }
+
+    /// Returns the credential ID
+    pub fn id(&self) -> &str {
+        &self.credential.id
+    }
+
+    /// Returns the employee's node ID
+    pub fn employee_node_id(&self) -> EndpointId {
+        self.credential.employee_node_id
+    }
+
+    /// Returns the employer's node ID (issuer)
+    pub fn issuer_node_id(&self) -> EndpointId {
+        self.credential.employer_node_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credential(issuer_key: &SecretKey) -> IncomeCredential {
+        IncomeCredential::new(
+            SecretKey::generate(&mut rand::rng()).public(),
+            "Alice".to_string(),
+            issuer_key.public(),
+            "Acme Corp".to_string(),
+            "5000.00".to_string(),
+            "4000.00".to_string(),
+            "USD".to_string(),
+            "2025-01".to_string(),
+            PaymentMode::Crypto,
+        )
+    }
+
+    #[test]
+    fn test_selective_disclosure_reveals_only_chosen_fields() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let full = credential(&issuer_key).sign_selective(&issuer_key).unwrap();
+        assert_eq!(full.disclosures.len(), 7);
+        assert_eq!(full.credential.digests.len(), DisclosableField::ALL.len());
+
+        let claims = full.verify().unwrap().unwrap();
+        assert_eq!(claims.payment_mode, Some(PaymentMode::Crypto));
+        assert_eq!(claims.payroll_processor_name, None);
+
+        let partial = full.disclose(&[DisclosableField::NetSalary, DisclosableField::PayPeriod]);
+        let claims = partial.verify().unwrap().unwrap();
+        assert_eq!(claims.fields(), vec![DisclosableField::NetSalary, DisclosableField::PayPeriod]);
+        assert_eq!(claims.net_salary.as_deref(), Some("4000.00"));
+        assert_eq!(claims.gross_salary, None);
+        assert_eq!(claims.employer_name, None);
+    }
+
+    #[test]
+    fn test_selective_disclosure_rejects_tampering() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let full = credential(&issuer_key).sign_selective(&issuer_key).unwrap();
+
+        let mut altered_value = full.disclose(&[DisclosableField::NetSalary]);
+        altered_value.disclosures[0].value = "9000.00".to_string();
+        assert_eq!(altered_value.verify().unwrap(), None);
+
+        let mut duplicated = full.disclose(&[DisclosableField::NetSalary]);
+        duplicated.disclosures.push(duplicated.disclosures[0].clone());
+        assert_eq!(duplicated.verify().unwrap(), None);
+
+        let mut extended = full.clone();
+        extended.credential.expires_at = Some("2099-01-01T00:00:00Z".to_string());
+        assert_eq!(extended.verify().unwrap(), None);
+
+        // Re-signing with another key does not make the issuer vouch for it
+        let forger = SecretKey::generate(&mut rand::rng());
+        let forged = credential(&issuer_key).sign_selective(&forger).unwrap();
+        assert_eq!(forged.verify().unwrap(), None);
+    }
+
+    #[test]
+    fn test_payroll_processor_is_a_signed_public_claim() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let processor = SecretKey::generate(&mut rand::rng()).public();
+        let income = credential(&issuer_key).with_payroll_processor(processor, "PayCo".to_string());
+        let full = income.sign_selective(&issuer_key).unwrap();
+        assert!(full.commits_to(&income).unwrap());
+
+        // Disclosed even when every field is hidden, and covered by the signature
+        let hidden = full.disclose(&[]);
+        assert_eq!(hidden.verify().unwrap().unwrap().payroll_processor_node_id, Some(processor));
+        let mut dropped = hidden;
+        dropped.credential.payroll_processor_node_id = None;
+        assert_eq!(dropped.verify().unwrap(), None);
+
+        // Nor does a commitment to other values pass for the credential
+        let mut other = income.clone();
+        other.net_salary = "9000.00".to_string();
+        assert!(!full.commits_to(&other).unwrap());
+    }
+}
@@ -0,0 +1,49 @@
+use iroh::{PublicKey, SecretKey};
+use iroh_base::Signature;
+use n0_future::boxed::BoxFuture;
+use std::fmt;
+
+use super::income_credential::CredentialError;
+
+/// Signs credential payloads on behalf of an issuer.
+///
+/// Implementations may keep the key outside the app process, e.g. in an HSM, a
+/// KMS or a local signing daemon, so signing is asynchronous.
+pub trait CredentialSigner: fmt::Debug + Send + Sync {
+    /// Key the signatures verify against
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs `payload` with Ed25519
+    fn sign(&self, payload: Vec<u8>) -> BoxFuture<Result<Signature, CredentialError>>;
+}
+
+/// Signer holding its key in memory
+#[derive(Clone)]
+pub struct MemorySigner {
+    secret_key: SecretKey,
+}
+
+impl MemorySigner {
+    pub fn new(secret_key: SecretKey) -> Self {
+        Self { secret_key }
+    }
+}
+
+impl fmt::Debug for MemorySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemorySigner")
+            .field("public_key", &self.secret_key.public())
+            .finish()
+    }
+}
+
+impl CredentialSigner for MemorySigner {
+    fn public_key(&self) -> PublicKey {
+        self.secret_key.public()
+    }
+
+    fn sign(&self, payload: Vec<u8>) -> BoxFuture<Result<Signature, CredentialError>> {
+        let signature = self.secret_key.sign(&payload);
+        Box::pin(async move { Ok(signature) })
+    }
+}
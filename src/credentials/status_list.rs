@@ -0,0 +1,293 @@
+use std::io::{Read, Write};
+
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
+use iroh::{EndpointId, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use super::income_credential::CredentialError;
+
+/// Domain separation tag prefixed to every status list signing payload
+const STATUS_LIST_SIGNING_DOMAIN: &str = "trust-triangle/status-list";
+
+/// Version of the status list signing payload layout
+const STATUS_LIST_PAYLOAD_VERSION: u8 = 1;
+
+/// Minimum list length in bits (16 KiB), so a fetched list hides which of
+/// many credentials a verifier is interested in
+pub const MIN_STATUS_LIST_BITS: usize = 131_072;
+
+/// Uncompressed revocation bitstring; bit `i` is set when the credential with
+/// `status_index == i` has been revoked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusBits(Vec<u8>);
+
+impl Default for StatusBits {
+    fn default() -> Self {
+        Self(vec![0; MIN_STATUS_LIST_BITS / 8])
+    }
+}
+
+impl StatusBits {
+    /// Number of indices the list can hold
+    pub fn len(&self) -> usize {
+        self.0.len() * 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether bit `index` is set; indices past the end are unset
+    pub fn get(&self, index: u32) -> bool {
+        let index = index as usize;
+        self.0
+            .get(index / 8)
+            .is_some_and(|byte| byte & (0x80 >> (index % 8)) != 0)
+    }
+
+    /// Sets bit `index`, growing the list in 16 KiB steps if needed
+    pub fn set(&mut self, index: u32) {
+        let index = index as usize;
+        while index >= self.len() {
+            self.0.resize(self.0.len() + MIN_STATUS_LIST_BITS / 8, 0);
+        }
+        self.0[index / 8] |= 0x80 >> (index % 8);
+    }
+
+    /// DEFLATE-compresses the bitstring for transport
+    pub fn compress(&self) -> Result<Vec<u8>, CredentialError> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder
+            .write_all(&self.0)
+            .and_then(|_| encoder.finish())
+            .map_err(|e| CredentialError::StatusListError(format!("failed to compress status list: {e}")))
+    }
+
+    /// Inverse of [`StatusBits::compress`]
+    pub fn decompress(encoded: &[u8]) -> Result<Self, CredentialError> {
+        let mut bits = Vec::new();
+        DeflateDecoder::new(encoded)
+            // Bound the output so a malicious list cannot exhaust memory
+            .take(64 * 1024 * 1024)
+            .read_to_end(&mut bits)
+            .map_err(|e| CredentialError::StatusListError(format!("failed to decompress status list: {e}")))?;
+        Ok(Self(bits))
+    }
+}
+
+/// Outcome of checking a credential against its issuer's status list
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusCheck {
+    /// The credential has no status index
+    #[default]
+    NotApplicable,
+    /// The credential's bit is clear in a fresh list
+    Active,
+    /// The credential's bit is set
+    Revoked,
+    /// No list of the issuer was available
+    Unavailable,
+    /// The issuer's signature does not cover the status index (legacy statement
+    /// signature), so the list cannot be consulted
+    Unknown,
+}
+
+/// Issuer-side bookkeeping for its status list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusListState {
+    /// Next unallocated status index
+    pub next_index: u32,
+    /// Indices allocated to credentials that were never issued, handed out again first
+    #[serde(default)]
+    pub released: Vec<u32>,
+    /// Bumped every time a bit changes
+    pub version: u64,
+    pub bits: StatusBits,
+}
+
+impl StatusListState {
+    /// Allocates a status index for a newly issued credential
+    pub fn allocate(&mut self) -> u32 {
+        if let Some(index) = self.released.pop() {
+            return index;
+        }
+        let index = self.next_index;
+        self.next_index += 1;
+        index
+    }
+
+    /// Returns `index` for reuse after issuing the credential it was allocated to failed
+    pub fn release(&mut self, index: u32) {
+        if index < self.next_index && !self.released.contains(&index) && !self.bits.get(index) {
+            self.released.push(index);
+        }
+    }
+
+    /// Marks `index` as revoked and bumps the list version
+    pub fn revoke(&mut self, index: u32) {
+        if !self.bits.get(index) {
+            self.bits.set(index);
+            self.version += 1;
+        }
+    }
+}
+
+/// Snapshot of an issuer's status list as served to verifiers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusList {
+    pub issuer_node_id: EndpointId,
+    /// Monotonically increasing list version; verifiers refuse older versions
+    pub version: u64,
+    /// DEFLATE-compressed bitstring
+    pub encoded_list: Vec<u8>,
+    /// When this snapshot was signed (RFC3339 format)
+    pub issued_at: String,
+    /// Verifiers should refetch after this time (RFC3339 format)
+    pub next_update: String,
+}
+
+/// Status list signed by the issuer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedStatusList {
+    pub list: StatusList,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct CanonicalStatusList<'a> {
+    domain: &'a str,
+    payload_version: u8,
+    issuer_node_id: &'a [u8; 32],
+    version: u64,
+    encoded_list: &'a [u8],
+    issued_at: &'a str,
+    next_update: &'a str,
+}
+
+impl StatusList {
+    /// Snapshots `state`, valid for `ttl` from now
+    pub fn new(
+        issuer_node_id: EndpointId,
+        state: &StatusListState,
+        ttl: chrono::Duration,
+    ) -> Result<Self, CredentialError> {
+        let now = chrono::Utc::now();
+        Ok(Self {
+            issuer_node_id,
+            version: state.version,
+            encoded_list: state.bits.compress()?,
+            issued_at: now.to_rfc3339(),
+            next_update: (now + ttl).to_rfc3339(),
+        })
+    }
+
+    /// Canonical bytes covered by the issuer's signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalStatusList {
+            domain: STATUS_LIST_SIGNING_DOMAIN,
+            payload_version: STATUS_LIST_PAYLOAD_VERSION,
+            issuer_node_id: self.issuer_node_id.as_bytes(),
+            version: self.version,
+            encoded_list: &self.encoded_list,
+            issued_at: &self.issued_at,
+            next_update: &self.next_update,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Signs the list with the issuer's secret key
+    pub fn sign(self, secret_key: &SecretKey) -> Result<SignedStatusList, CredentialError> {
+        let signature = secret_key.sign(&self.signing_payload()?);
+        Ok(SignedStatusList {
+            list: self,
+            signature: signature.to_bytes().to_vec(),
+        })
+    }
+
+    /// Returns whether the credential at `index` is revoked
+    pub fn is_revoked(&self, index: u32) -> Result<bool, CredentialError> {
+        Ok(StatusBits::decompress(&self.encoded_list)?.get(index))
+    }
+}
+
+impl SignedStatusList {
+    /// Verifies the list was signed by the issuer it names
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let payload = self.list.signing_payload()?;
+        super::verify_signature(&self.list.issuer_node_id, &payload, &self.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_bits() {
+        let mut bits = StatusBits::default();
+        assert_eq!(bits.len(), MIN_STATUS_LIST_BITS);
+        assert!(!bits.get(7));
+
+        bits.set(7);
+        assert!(bits.get(7));
+        assert!(!bits.get(6));
+        assert!(!bits.get(8));
+
+        // Growing past the minimum size
+        bits.set(MIN_STATUS_LIST_BITS as u32 + 3);
+        assert_eq!(bits.len(), 2 * MIN_STATUS_LIST_BITS);
+        assert!(bits.get(MIN_STATUS_LIST_BITS as u32 + 3));
+
+        let compressed = bits.compress().unwrap();
+        assert!(compressed.len() < 1024, "sparse list should compress well");
+        assert_eq!(StatusBits::decompress(&compressed).unwrap(), bits);
+    }
+
+    #[test]
+    fn test_status_list_signing_and_lookup() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let mut state = StatusListState::default();
+        let first = state.allocate();
+        let second = state.allocate();
+        state.revoke(second);
+        assert_eq!(state.version, 1);
+
+        // Revoking twice does not bump the version
+        state.revoke(second);
+        assert_eq!(state.version, 1);
+
+        let signed = StatusList::new(issuer_key.public(), &state, chrono::Duration::hours(1))
+            .unwrap()
+            .sign(&issuer_key)
+            .unwrap();
+        assert!(signed.verify().unwrap());
+        assert!(!signed.list.is_revoked(first).unwrap());
+        assert!(signed.list.is_revoked(second).unwrap());
+
+        let mut rolled_back = signed;
+        rolled_back.list.version = 0;
+        assert!(!rolled_back.verify().unwrap());
+    }
+
+    #[test]
+    fn test_released_status_indices_are_reused() {
+        let mut state = StatusListState::default();
+        let first = state.allocate();
+        let second = state.allocate();
+        state.release(second);
+        // Releasing twice, or an index never allocated, has no effect
+        state.release(second);
+        state.release(7);
+        assert_eq!(state.allocate(), second);
+        assert_eq!(state.allocate(), 2);
+
+        // Revoked indices stay used
+        state.revoke(first);
+        state.release(first);
+        assert_eq!(state.allocate(), 3);
+    }
+}
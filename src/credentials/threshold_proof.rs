@@ -0,0 +1,395 @@
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use chrono::{DateTime, Utc};
+use curve25519_dalek::ristretto::CompressedRistretto;
+use curve25519_dalek::scalar::Scalar;
+use iroh::{EndpointId, PublicKey, SecretKey};
+use merlin::Transcript;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use super::co_signature::CoSignature;
+use super::income_credential::{CredentialError, IncomeCredential, ValidityStatus, validity_at};
+use super::key_binding::distinct_signing_key;
+use super::officer_approval::CredentialApproval;
+use super::presentation::parse_decimal;
+use super::signer::CredentialSigner;
+
+/// Domain separation tag prefixed to every amount commitment signing payload
+const AMOUNT_COMMITMENT_SIGNING_DOMAIN: &str = "trust-triangle/amount-commitment";
+
+/// Version of the amount commitment signing payload layout
+const AMOUNT_COMMITMENT_PAYLOAD_VERSION: u8 = 2;
+
+/// Merlin transcript label binding a range proof to this protocol
+const THRESHOLD_TRANSCRIPT_LABEL: &[u8] = b"trust-triangle/net-salary-threshold";
+
+/// Committed amounts are in hundredths of the currency unit
+const AMOUNT_DECIMALS: usize = 2;
+
+/// Bits of the range proof: the net salary minus the threshold is proven to fit in a `u64`
+const RANGE_BITS: usize = 64;
+
+/// Converts a decimal amount like "7,200.50" to hundredths, if it has at most two decimals
+pub fn to_minor_units(amount: &str) -> Option<u64> {
+    let (int, frac) = parse_decimal(amount)?;
+    if frac.len() > AMOUNT_DECIMALS {
+        return None;
+    }
+    let int: u64 = if int.is_empty() { 0 } else { int.parse().ok()? };
+    let frac: u64 = format!("{frac:0<width$}", width = AMOUNT_DECIMALS).parse().ok()?;
+    int.checked_mul(10u64.pow(AMOUNT_DECIMALS as u32))?.checked_add(frac)
+}
+
+/// Formats hundredths as a decimal amount, e.g. 500000 as "5000.00"
+pub fn from_minor_units(amount: u64) -> String {
+    let scale = 10u64.pow(AMOUNT_DECIMALS as u32);
+    format!("{}.{:0width$}", amount / scale, amount % scale, width = AMOUNT_DECIMALS)
+}
+
+/// Issuer-signed Pedersen commitment to the net salary of a credential.
+///
+/// Currency and pay period stay in the clear so verifiers know what a proven
+/// threshold means, and so does the payroll processor that co-signs it; the
+/// amount itself is hidden.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAmountCommitment {
+    /// Same ID as the [`IncomeCredential`] it was derived from
+    pub credential_id: String,
+    pub employee_node_id: EndpointId,
+    pub employer_node_id: EndpointId,
+    /// Payroll processor that must co-sign the commitment
+    #[serde(default)]
+    pub payroll_processor_node_id: Option<EndpointId>,
+    pub currency: String,
+    pub pay_period: String,
+    pub issued_at: String,
+    #[serde(default)]
+    pub valid_from: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub status_index: Option<u32>,
+    /// Compressed Ristretto point committing to the net salary in hundredths
+    pub commitment: Vec<u8>,
+    pub signature: Vec<u8>,
+    /// Signatures of the other parties the commitment declares, over the same payload
+    #[serde(default)]
+    pub co_signatures: Vec<CoSignature>,
+    /// Key that signed the commitment, when it is not the issuer's node key
+    #[serde(default)]
+    pub signing_key: Option<PublicKey>,
+    /// Approvals of the issuer's officers (organizational issuers)
+    #[serde(default)]
+    pub approvals: Vec<CredentialApproval>,
+}
+
+#[derive(Serialize)]
+struct CanonicalAmountCommitment<'a> {
+    domain: &'a str,
+    version: u8,
+    credential_id: &'a str,
+    employee_node_id: &'a [u8; 32],
+    employer_node_id: &'a [u8; 32],
+    payroll_processor_node_id: Option<&'a [u8; 32]>,
+    currency: &'a str,
+    pay_period: &'a str,
+    issued_at: &'a str,
+    valid_from: Option<&'a str>,
+    expires_at: Option<&'a str>,
+    status_index: Option<u32>,
+    commitment: &'a [u8],
+}
+
+impl SignedAmountCommitment {
+    /// Canonical bytes covered by the issuer's signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>, CredentialError> {
+        let payload = CanonicalAmountCommitment {
+            domain: AMOUNT_COMMITMENT_SIGNING_DOMAIN,
+            version: AMOUNT_COMMITMENT_PAYLOAD_VERSION,
+            credential_id: &self.credential_id,
+            employee_node_id: self.employee_node_id.as_bytes(),
+            employer_node_id: self.employer_node_id.as_bytes(),
+            payroll_processor_node_id: self.payroll_processor_node_id.as_ref().map(|id| id.as_bytes()),
+            currency: &self.currency,
+            pay_period: &self.pay_period,
+            issued_at: &self.issued_at,
+            valid_from: self.valid_from.as_deref(),
+            expires_at: self.expires_at.as_deref(),
+            status_index: self.status_index,
+            commitment: &self.commitment,
+        };
+        Ok(postcard::to_allocvec(&payload)?)
+    }
+
+    /// Verifies the issuer's signature over the commitment and public claims
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        let signer = self.signing_key.unwrap_or(self.employer_node_id);
+        super::verify_signature(&signer, &self.signing_payload()?, &self.signature)
+    }
+
+    /// Checks the validity window and, if `max_age` is set, the credential's age at `now`
+    pub fn validity_at(&self, now: DateTime<Utc>, max_age: Option<chrono::Duration>) -> ValidityStatus {
+        validity_at(
+            &self.issued_at,
+            self.valid_from.as_deref(),
+            self.expires_at.as_deref(),
+            now,
+            max_age,
+        )
+    }
+
+    /// Transcript shared by prover and verifier, binding the proof to this
+    /// commitment and threshold
+    fn transcript(&self, threshold: u64) -> Transcript {
+        let mut transcript = Transcript::new(THRESHOLD_TRANSCRIPT_LABEL);
+        transcript.append_message(b"credential", self.credential_id.as_bytes());
+        transcript.append_message(b"commitment", &self.commitment);
+        transcript.append_u64(b"threshold", threshold);
+        transcript
+    }
+}
+
+/// An amount commitment with its opening, kept by the holder to prove
+/// thresholds without revealing the net salary
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdCredential {
+    pub credential: SignedAmountCommitment,
+    /// Committed net salary in hundredths
+    pub net_salary: u64,
+    /// Blinding scalar of the commitment
+    pub blinding: Vec<u8>,
+}
+
+/// Range proof that a committed net salary reaches `threshold`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdProof {
+    pub credential: SignedAmountCommitment,
+    /// Decimal amount the net salary is proven to reach, e.g. "5000.00"
+    pub threshold: String,
+    /// Bulletproof that the net salary minus the threshold is non-negative
+    pub proof: Vec<u8>,
+}
+
+fn rng() -> ChaCha20Rng {
+    ChaCha20Rng::from_seed(rand::random())
+}
+
+fn proof_error(e: impl std::fmt::Debug) -> CredentialError {
+    CredentialError::InvalidProof(format!("{e:?}"))
+}
+
+impl IncomeCredential {
+    /// Signs a Pedersen commitment to the net salary, returning it with its opening.
+    ///
+    /// Fails if the net salary has more than two decimals or does not fit in a `u64` of hundredths.
+    pub fn sign_amount_commitment(&self, secret_key: &SecretKey) -> Result<ThresholdCredential, CredentialError> {
+        let mut threshold = self.commit_amount()?;
+        let payload = threshold.credential.signing_payload()?;
+        threshold.credential.signature = secret_key.sign(&payload).to_bytes().to_vec();
+        Ok(threshold)
+    }
+
+    /// Like [`Self::sign_amount_commitment`], signing through `signer`
+    pub async fn sign_amount_commitment_with(
+        &self,
+        signer: &dyn CredentialSigner,
+    ) -> Result<ThresholdCredential, CredentialError> {
+        let mut threshold = self.commit_amount()?;
+        let payload = threshold.credential.signing_payload()?;
+        threshold.credential.signature = signer.sign(payload).await?.to_bytes().to_vec();
+        threshold.credential.signing_key = distinct_signing_key(signer.public_key(), self.employer_node_id);
+        Ok(threshold)
+    }
+
+    /// Pedersen commitment to the net salary with its opening, not yet signed
+    fn commit_amount(&self) -> Result<ThresholdCredential, CredentialError> {
+        let net_salary = to_minor_units(&self.net_salary).ok_or_else(|| {
+            CredentialError::InvalidProof(format!("net salary {:?} cannot be committed to", self.net_salary))
+        })?;
+        let mut wide = [0u8; 64];
+        rand::Rng::fill(&mut rand::rng(), &mut wide[..]);
+        let blinding = Scalar::from_bytes_mod_order_wide(&wide);
+        let commitment = PedersenGens::default().commit(Scalar::from(net_salary), blinding);
+
+        let credential = SignedAmountCommitment {
+            credential_id: self.id.clone(),
+            employee_node_id: self.employee_node_id,
+            employer_node_id: self.employer_node_id,
+            payroll_processor_node_id: self.payroll_processor_node_id,
+            currency: self.currency.clone(),
+            pay_period: self.pay_period.clone(),
+            issued_at: self.issued_at.clone(),
+            valid_from: self.valid_from.clone(),
+            expires_at: self.expires_at.clone(),
+            status_index: self.status_index,
+            commitment: commitment.compress().to_bytes().to_vec(),
+            signature: Vec::new(),
+            co_signatures: Vec::new(),
+            signing_key: None,
+            approvals: Vec::new(),
+        };
+        Ok(ThresholdCredential {
+            credential,
+            net_salary,
+            blinding: blinding.to_bytes().to_vec(),
+        })
+    }
+}
+
+impl ThresholdCredential {
+    /// Whether this is a signed commitment to the net salary of `credential`,
+    /// with the same public claims
+    pub fn commits_to(&self, credential: &IncomeCredential) -> Result<bool, CredentialError> {
+        let committed = &self.credential;
+        let same_claims = committed.credential_id == credential.id
+            && committed.employee_node_id == credential.employee_node_id
+            && committed.employer_node_id == credential.employer_node_id
+            && committed.payroll_processor_node_id == credential.payroll_processor_node_id
+            && committed.currency == credential.currency
+            && committed.pay_period == credential.pay_period
+            && committed.issued_at == credential.issued_at
+            && committed.valid_from == credential.valid_from
+            && committed.expires_at == credential.expires_at
+            && committed.status_index == credential.status_index
+            && to_minor_units(&credential.net_salary) == Some(self.net_salary);
+        let Ok(blinding) = <[u8; 32]>::try_from(self.blinding.as_slice()) else {
+            return Ok(false);
+        };
+        let Some(blinding) = Option::<Scalar>::from(Scalar::from_canonical_bytes(blinding)) else {
+            return Ok(false);
+        };
+        let opening = PedersenGens::default().commit(Scalar::from(self.net_salary), blinding);
+        Ok(same_claims && opening.compress().as_bytes()[..] == committed.commitment[..] && committed.verify()?)
+    }
+
+    /// Proves the committed net salary reaches `threshold` (a decimal amount)
+    pub fn prove(&self, threshold: &str) -> Result<ThresholdProof, CredentialError> {
+        let threshold_units = to_minor_units(threshold)
+            .ok_or_else(|| CredentialError::InvalidProof(format!("{threshold:?} is not an amount in hundredths")))?;
+        let excess = self.net_salary.checked_sub(threshold_units).ok_or_else(|| {
+            CredentialError::InvalidProof(format!("net salary is below {}", from_minor_units(threshold_units)))
+        })?;
+        let blinding: [u8; 32] = self.blinding.as_slice().try_into().map_err(proof_error)?;
+        let blinding = Option::<Scalar>::from(Scalar::from_canonical_bytes(blinding))
+            .ok_or_else(|| CredentialError::InvalidProof("blinding is not a canonical scalar".to_string()))?;
+
+        // The commitment minus threshold·B commits to the excess with the same blinding
+        let (proof, _) = RangeProof::prove_single_with_rng(
+            &BulletproofGens::new(RANGE_BITS, 1),
+            &PedersenGens::default(),
+            &mut self.credential.transcript(threshold_units),
+            excess,
+            &blinding,
+            RANGE_BITS,
+            &mut rng(),
+        )
+        .map_err(proof_error)?;
+        Ok(ThresholdProof {
+            credential: self.credential.clone(),
+            threshold: from_minor_units(threshold_units),
+            proof: proof.to_bytes(),
+        })
+    }
+}
+
+impl ThresholdProof {
+    /// Verifies the issuer's signature and the range proof against the signed commitment
+    pub fn verify(&self) -> Result<bool, CredentialError> {
+        if !self.credential.verify()? {
+            return Ok(false);
+        }
+        let Some(threshold) = to_minor_units(&self.threshold) else {
+            return Ok(false);
+        };
+        let Some(commitment) = CompressedRistretto::from_slice(&self.credential.commitment)
+            .ok()
+            .and_then(|point| point.decompress())
+        else {
+            return Ok(false);
+        };
+        let Ok(proof) = RangeProof::from_bytes(&self.proof) else {
+            return Ok(false);
+        };
+        let pc_gens = PedersenGens::default();
+        let excess = commitment - pc_gens.B * Scalar::from(threshold);
+        let verified = proof.verify_single_with_rng(
+            &BulletproofGens::new(RANGE_BITS, 1),
+            &pc_gens,
+            &mut self.credential.transcript(threshold),
+            &excess.compress(),
+            RANGE_BITS,
+            &mut rng(),
+        );
+        Ok(verified.is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::income_credential::PaymentMode;
+
+    fn threshold_credential(issuer_key: &SecretKey, net_salary: &str) -> ThresholdCredential {
+        IncomeCredential::new(
+            SecretKey::generate(&mut rand::rng()).public(),
+            "Alice".to_string(),
+            issuer_key.public(),
+            "Acme Corp".to_string(),
+            "9000.00".to_string(),
+            net_salary.to_string(),
+            "USD".to_string(),
+            "2025-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+        .sign_amount_commitment(issuer_key)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_minor_units() {
+        assert_eq!(to_minor_units("7,200.5"), Some(720050));
+        assert_eq!(to_minor_units("0.07"), Some(7));
+        assert_eq!(to_minor_units("1.005"), None);
+        assert_eq!(to_minor_units("abc"), None);
+        assert_eq!(from_minor_units(500000), "5000.00");
+    }
+
+    #[test]
+    fn test_threshold_proof() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let credential = threshold_credential(&issuer_key, "7200.50");
+
+        let proof = credential.prove("5000").unwrap();
+        assert_eq!(proof.threshold, "5000.00");
+        assert!(proof.verify().unwrap());
+
+        // The exact amount is the highest provable threshold
+        assert!(credential.prove("7200.50").unwrap().verify().unwrap());
+        assert!(credential.prove("7200.51").is_err());
+
+        // A proof does not carry over to a higher threshold
+        let mut raised = proof.clone();
+        raised.threshold = "7500".to_string();
+        assert!(!raised.verify().unwrap());
+
+        // Nor to another commitment
+        let mut moved = proof;
+        moved.credential = threshold_credential(&issuer_key, "9000").credential;
+        assert!(!moved.verify().unwrap());
+    }
+
+    #[test]
+    fn test_amount_commitment_signature() {
+        let issuer_key = SecretKey::generate(&mut rand::rng());
+        let credential = threshold_credential(&issuer_key, "7200");
+        assert!(credential.credential.verify().unwrap());
+
+        let mut tampered = credential.credential;
+        tampered.currency = "EUR".to_string();
+        assert!(!tampered.verify().unwrap());
+    }
+}
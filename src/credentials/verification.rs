@@ -0,0 +1,710 @@
+//! Verification of credentials without a running node.
+//!
+//! [`CredentialVerifier`] checks a credential against a [`TrustStore`], a
+//! [`RevocationSource`] and a [`Clock`]. Verifier nodes back these with their
+//! stores and the network; [`OfflineTrustStore`] and [`OfflineRevocations`]
+//! back them with data exported from such a node, so backends and browsers can
+//! check uploaded files directly.
+
+use chrono::{DateTime, Utc};
+use iroh::{EndpointId, PublicKey};
+use n0_future::boxed::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use super::co_signature::SignerRole;
+use super::compact::QR_PREFIX;
+use super::income_credential::{CredentialError, SignedIncomeCredential, ValidityStatus};
+use super::jwt::verify_jws;
+use super::key_binding::{SignedKeyBinding, SignedKeyUnbinding, is_unbound};
+use super::key_rotation::{RotationLog, SignedKeyRotation};
+use super::officer_approval::{ApprovalCheck, ApprovalPolicyLog, SignedApprovalPolicy};
+use super::presentation::{HolderBinding, PresentableCredential};
+use super::revocation::SignedRevocationNotice;
+use super::status_list::{SignedStatusList, StatusCheck};
+use super::w3c::VerifiableCredential;
+
+/// Outcome of checking one credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialCheck {
+    pub credential_id: String,
+    pub issuer_node_id: EndpointId,
+    /// Signature, validity window, revocation, holder binding and any
+    /// presentation request all check out
+    pub is_valid: bool,
+    pub is_trusted: bool,
+    pub legacy_signature: bool,
+    pub validity: ValidityStatus,
+    pub is_revoked: bool,
+    pub revocation_reason: Option<String>,
+    pub status_check: StatusCheck,
+    pub status_list_version: Option<u64>,
+    /// The issuer and every co-signer the credential declares
+    #[serde(default)]
+    pub signers: Vec<SignerCheck>,
+    /// Officer approvals, checked when the issuer published an approval policy
+    #[serde(default)]
+    pub approvals: Option<ApprovalCheck>,
+    pub message: String,
+}
+
+/// Outcome of checking one party that signed, or had to sign, a credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerCheck {
+    pub node_id: EndpointId,
+    pub role: SignerRole,
+    /// A missing or invalid signature from any declared party makes the credential invalid
+    pub signed: bool,
+    /// The signer is in the verifier's trusted list
+    pub is_trusted: bool,
+}
+
+/// The issuers a verifier trusts and what it knows about their keys
+pub trait TrustStore: Send + Sync {
+    /// The trusted key `node_id` is, or descends from through key rotations
+    fn trusted_root(&self, node_id: EndpointId) -> BoxFuture<Option<EndpointId>>;
+    /// Like [`Self::trusted_root`] for the issuer of a checked credential;
+    /// stores that can reach the issuer may ask it for its key history
+    fn issuer_root(&self, node_id: EndpointId) -> BoxFuture<Option<EndpointId>> {
+        self.trusted_root(node_id)
+    }
+    /// Known key rotations, verified
+    fn rotation_log(&self) -> BoxFuture<RotationLog>;
+    /// Whether `signing_key` signs credentials for issuer node `node_id`
+    fn is_assertion_key(&self, node_id: EndpointId, signing_key: PublicKey) -> BoxFuture<bool>;
+    /// Verified versions of the approval policy of issuer `node_id` or a key it
+    /// replaced, oldest first; stores that can reach the issuer ask it when they
+    /// know no version, or none up to `min_version`
+    fn approval_policies(&self, node_id: EndpointId, min_version: u32) -> BoxFuture<Vec<SignedApprovalPolicy>>;
+}
+
+/// Where a verifier learns which credentials their issuers revoked
+pub trait RevocationSource: Send + Sync {
+    /// Looks up `status_index` in the status list of issuer key `issuer`, with
+    /// the version of the list used
+    fn status(&self, issuer: EndpointId, status_index: u32) -> BoxFuture<(StatusCheck, Option<u64>)>;
+    /// The notice issuer key `issuer` published revoking `credential_id`, if any
+    fn revocation(&self, issuer: EndpointId, credential_id: String) -> BoxFuture<Option<SignedRevocationNotice>>;
+}
+
+/// The time validity windows are checked against
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A fixed time, e.g. to check a credential as of an earlier date
+impl Clock for DateTime<Utc> {
+    fn now(&self) -> DateTime<Utc> {
+        *self
+    }
+}
+
+/// Checks credentials against a trust store, a revocation source and a clock
+pub struct CredentialVerifier<'a> {
+    trust_store: &'a dyn TrustStore,
+    revocations: &'a dyn RevocationSource,
+    clock: &'a dyn Clock,
+    max_age: Option<chrono::Duration>,
+    accept_legacy_signatures: bool,
+    accept_unavailable_status: bool,
+}
+
+impl<'a> CredentialVerifier<'a> {
+    pub fn new(trust_store: &'a dyn TrustStore, revocations: &'a dyn RevocationSource, clock: &'a dyn Clock) -> Self {
+        Self {
+            trust_store,
+            revocations,
+            clock,
+            max_age: None,
+            accept_legacy_signatures: false,
+            accept_unavailable_status: false,
+        }
+    }
+
+    /// Rejects credentials issued longer than `max_age` ago as stale
+    pub fn with_max_age(mut self, max_age: Option<chrono::Duration>) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Accepts credentials signed over the legacy human-readable statement, which
+    /// are rejected by default. Their ID, timestamps and status index are not
+    /// signed, so their validity and revocation status are reported as unknown.
+    pub fn with_legacy_signatures(mut self, accept: bool) -> Self {
+        self.accept_legacy_signatures = accept;
+        self
+    }
+
+    /// Accepts credentials whose issuer status list cannot be obtained, e.g. while
+    /// the issuer is offline. By default a credential with a status index is
+    /// rejected then, since whoever blocks the list could otherwise revive it.
+    pub fn with_unavailable_status(mut self, accept: bool) -> Self {
+        self.accept_unavailable_status = accept;
+        self
+    }
+
+    /// Checks a credential on its own, e.g. an uploaded file, where there is no
+    /// presenter to bind to the holder
+    pub async fn check(&self, credential: &(impl PresentableCredential + Sync)) -> CredentialCheck {
+        self.check_inner(credential, None, None).await
+    }
+
+    /// Checks a credential presented with `holder_binding`, answering a
+    /// presentation request it fails with `request_mismatch` if any
+    pub async fn check_presented(
+        &self,
+        credential: &(impl PresentableCredential + Sync),
+        holder_binding: HolderBinding,
+        request_mismatch: Option<&str>,
+    ) -> CredentialCheck {
+        self.check_inner(credential, Some(holder_binding), request_mismatch).await
+    }
+
+    async fn check_inner(
+        &self,
+        credential: &(impl PresentableCredential + Sync),
+        holder_binding: Option<HolderBinding>,
+        request_mismatch: Option<&str>,
+    ) -> CredentialCheck {
+        let issuer_node_id = credential.issuer_node_id();
+
+        // Check if issuer is trusted, directly or through the keys it replaced
+        let is_trusted = self.trust_store.issuer_root(issuer_node_id).await.is_some();
+        let rotations = self.trust_store.rotation_log().await;
+        // A legacy signature leaves the ID, timestamps and status index unsigned,
+        // so none of them is relied on: a rotated key retires all its legacy credentials
+        let legacy_signature = credential.is_legacy();
+        let retired_by = rotations
+            .rotation_of(&issuer_node_id)
+            .filter(|rotation| legacy_signature || rotation.rotation.retires(credential.issued_at()))
+            .map(|rotation| rotation.rotation.clone());
+        // Revocations and the status list come from whichever key the issuer uses now
+        let issuer_keys = rotations.successors(issuer_node_id);
+        let current_key = *issuer_keys.last().expect("successors include the key itself");
+
+        let validity = credential.validity_at(self.clock.now(), self.max_age);
+        // Unsigned timestamps can never satisfy a max-age policy
+        let fresh = validity.is_valid() || (validity == ValidityStatus::Unknown && self.max_age.is_none());
+
+        // Check the issuer's status list, then any revocation notice
+        let (status_check, status_list_version) = match credential.status_index() {
+            _ if legacy_signature => (StatusCheck::Unknown, None),
+            Some(index) => self.revocations.status(current_key, index).await,
+            None => (StatusCheck::NotApplicable, None),
+        };
+        let mut revocation = None;
+        for key in issuer_keys {
+            revocation = revocation.or(self.revocations.revocation(key, credential.id().to_string()).await);
+        }
+        let is_revoked = revocation.is_some() || status_check == StatusCheck::Revoked;
+        let revocation_reason = match revocation {
+            Some(notice) => notice.notice.reason,
+            None if is_revoked => Some(format!(
+                "Revoked in issuer status list (version {})",
+                status_list_version.unwrap_or_default()
+            )),
+            None => None,
+        };
+
+        let mut check = CredentialCheck {
+            credential_id: credential.id().to_string(),
+            issuer_node_id,
+            is_valid: false,
+            is_trusted,
+            legacy_signature,
+            validity,
+            is_revoked,
+            revocation_reason,
+            status_check,
+            status_list_version,
+            signers: Vec::new(),
+            approvals: None,
+            message: String::new(),
+        };
+
+        // Verify the issuer's signature, those of every declared co-signer and, for
+        // organizational issuers, those of their officers
+        let named_policy = credential.officer_approvals().iter().map(|approval| approval.policy_version).max();
+        let policies = self.trust_store.approval_policies(issuer_node_id, named_policy.unwrap_or_default()).await;
+        let signatures = credential
+            .verify_issuer()
+            .and_then(|valid| Ok((valid, credential.co_signers()?, ApprovalCheck::new(credential, &policies)?)));
+        let (signature_valid, co_signers) =
+            match signatures {
+                Ok((valid, co_signers, approvals)) => {
+                    check.approvals = approvals;
+                    (valid, co_signers)
+                }
+                Err(e) => {
+                    tracing::error!("Verification error: {}", e);
+                    check.is_trusted = false;
+                    check.message = format!("Verification error: {}", e);
+                    return check;
+                }
+            };
+        // A separate signing key counts only if the issuer node bound it
+        let unbound_key = match credential.signing_key() {
+            Some(signing_key)
+                if signature_valid && !self.trust_store.is_assertion_key(issuer_node_id, signing_key).await =>
+            {
+                Some(signing_key)
+            }
+            _ => None,
+        };
+        check.signers.push(SignerCheck {
+            node_id: issuer_node_id,
+            role: SignerRole::Issuer,
+            signed: signature_valid && unbound_key.is_none(),
+            is_trusted,
+        });
+        for co_signer in co_signers {
+            check.signers.push(SignerCheck {
+                node_id: co_signer.node_id,
+                role: co_signer.role,
+                signed: co_signer.signed,
+                is_trusted: self.trust_store.trusted_root(co_signer.node_id).await.is_some(),
+            });
+        }
+        let refused_legacy = legacy_signature && !self.accept_legacy_signatures;
+        let missing_signer = check.signers.iter().find(|signer| !signer.signed).cloned();
+        let unapproved = check.approvals.clone().filter(|approvals| !approvals.is_met());
+        let unbound_holder = holder_binding.filter(|binding| !binding.is_bound());
+        let unknown_status = status_check == StatusCheck::Unavailable && !self.accept_unavailable_status;
+
+        check.is_valid = signature_valid
+            && !refused_legacy
+            && unbound_key.is_none()
+            && missing_signer.is_none()
+            && unapproved.is_none()
+            && retired_by.is_none()
+            && fresh
+            && !is_revoked
+            && !unknown_status
+            && unbound_holder.is_none()
+            && request_mismatch.is_none();
+        check.message = if !signature_valid {
+            "Invalid credential signature".to_string()
+        } else if refused_legacy {
+            "Credential rejected: its legacy statement signature does not cover its id, issuance time, validity window, status index and processor, and the verification policy does not accept legacy signatures".to_string()
+        } else if let Some(signing_key) = unbound_key {
+            format!("Credential rejected: signing key {} is not bound to issuer {}", signing_key, issuer_node_id)
+        } else if let Some(signer) = missing_signer {
+            format!("Credential rejected: {} {} has not co-signed", signer.role.as_str(), signer.node_id)
+        } else if let Some(approvals) = unapproved {
+            if approvals.superseded {
+                format!(
+                    "Credential rejected: approved under version {} of the issuer's approval policy, which was replaced before the credential was signed",
+                    approvals.policy_version
+                )
+            } else {
+                format!(
+                    "Credential rejected: approved by {} officers, version {} of the issuer's approval policy requires {}",
+                    approvals.approved_by.len(), approvals.policy_version, approvals.threshold
+                )
+            }
+        } else if let Some(rotation) = retired_by.as_ref().filter(|_| legacy_signature) {
+            format!(
+                "Credential rejected: issuer key {} was rotated to {}, and the legacy statement signature does not cover the credential's issuance time",
+                issuer_node_id, rotation.new_node_id
+            )
+        } else if let Some(rotation) = retired_by {
+            format!(
+                "Credential rejected: issuer key {} was rotated to {} at {}, before the credential was signed",
+                issuer_node_id, rotation.new_node_id, rotation.rotated_at
+            )
+        } else if let Some(holder_binding) = unbound_holder {
+            format!("Credential rejected: {}", holder_binding.as_str())
+        } else if let Some(reason) = request_mismatch {
+            format!("Credential does not satisfy the presentation request: {}", reason)
+        } else if is_revoked {
+            format!(
+                "Credential revoked by issuer: {}",
+                check.revocation_reason.as_deref().unwrap_or("no reason given")
+            )
+        } else if unknown_status {
+            "Credential rejected: the issuer's status list is unavailable, so the credential may have been revoked".to_string()
+        } else if !fresh && validity == ValidityStatus::Unknown {
+            "Credential rejected: the verification policy limits credential age, and the legacy statement signature does not cover the issuance time".to_string()
+        } else if !fresh {
+            format!("Credential rejected: {}", validity.as_str())
+        } else if is_trusted {
+            format!("Credential verified successfully. Issued by trusted issuer: {}", issuer_node_id)
+        } else {
+            format!("Credential signature is valid but issuer {} is not in trusted list", issuer_node_id)
+        };
+        for signer in check.signers.iter().filter(|signer| signer.role != SignerRole::Issuer && !signer.is_trusted) {
+            check.message.push_str(&format!(" ({} {} is not in trusted list)", signer.role.as_str(), signer.node_id));
+        }
+        if signature_valid && legacy_signature && !refused_legacy {
+            check.message.push_str(
+                " (legacy statement signature: id, issuance time, validity window, status index and processor are not covered, so validity and revocation status are unknown)",
+            );
+        }
+        if status_check == StatusCheck::Unavailable && !unknown_status {
+            check.message.push_str(" (issuer status list unavailable)");
+        }
+        check
+    }
+}
+
+/// Trusted issuers and their key history, as exported by a verifier node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineTrustStore {
+    pub trusted_issuers: Vec<EndpointId>,
+    /// Rotations that do not verify are ignored
+    #[serde(default)]
+    pub rotations: Vec<SignedKeyRotation>,
+    /// Bindings of issuers' signing keys; those that do not verify are ignored
+    #[serde(default)]
+    pub key_bindings: Vec<SignedKeyBinding>,
+    /// Issuers' approval policy versions; those not authorized by the previous version are ignored
+    #[serde(default)]
+    pub approval_policies: Vec<SignedApprovalPolicy>,
+    /// Retired signing keys; those that do not verify are ignored
+    #[serde(default)]
+    pub key_unbindings: Vec<SignedKeyUnbinding>,
+}
+
+impl OfflineTrustStore {
+    fn verified_rotations(&self) -> RotationLog {
+        RotationLog::new(
+            self.rotations
+                .iter()
+                .filter(|rotation| rotation.verify().unwrap_or(false))
+                .cloned(),
+        )
+    }
+}
+
+impl TrustStore for OfflineTrustStore {
+    fn trusted_root(&self, node_id: EndpointId) -> BoxFuture<Option<EndpointId>> {
+        let root = self
+            .verified_rotations()
+            .predecessors(node_id)
+            .into_iter()
+            .find(|key| self.trusted_issuers.contains(key));
+        Box::pin(async move { root })
+    }
+
+    fn rotation_log(&self) -> BoxFuture<RotationLog> {
+        let log = self.verified_rotations();
+        Box::pin(async move { log })
+    }
+
+    fn is_assertion_key(&self, node_id: EndpointId, signing_key: PublicKey) -> BoxFuture<bool> {
+        let bound = self
+            .key_bindings
+            .iter()
+            .any(|binding| binding.binds(node_id, signing_key).unwrap_or(false))
+            && !is_unbound(&self.key_unbindings, node_id, signing_key);
+        Box::pin(async move { bound })
+    }
+
+    fn approval_policies(&self, node_id: EndpointId, _min_version: u32) -> BoxFuture<Vec<SignedApprovalPolicy>> {
+        let history = ApprovalPolicyLog::new(self.approval_policies.iter().cloned())
+            .history_of(&self.verified_rotations().predecessors(node_id))
+            .to_vec();
+        Box::pin(async move { history })
+    }
+}
+
+/// Issuer status lists and revocation notices, as cached by a verifier node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineRevocations {
+    /// The newest valid list of each issuer is used
+    #[serde(default)]
+    pub status_lists: Vec<SignedStatusList>,
+    #[serde(default)]
+    pub notices: Vec<SignedRevocationNotice>,
+}
+
+impl RevocationSource for OfflineRevocations {
+    fn status(&self, issuer: EndpointId, status_index: u32) -> BoxFuture<(StatusCheck, Option<u64>)> {
+        let newest = self
+            .status_lists
+            .iter()
+            .filter(|list| list.list.issuer_node_id == issuer && list.verify().unwrap_or(false))
+            .max_by_key(|list| list.list.version);
+        let status = match newest.map(|list| (list.list.is_revoked(status_index), list.list.version)) {
+            Some((Ok(true), version)) => (StatusCheck::Revoked, Some(version)),
+            Some((Ok(false), version)) => (StatusCheck::Active, Some(version)),
+            Some((Err(e), _)) => {
+                tracing::error!("Status list of {} is corrupt: {}", issuer, e);
+                (StatusCheck::Unavailable, None)
+            }
+            None => (StatusCheck::Unavailable, None),
+        };
+        Box::pin(async move { status })
+    }
+
+    fn revocation(&self, issuer: EndpointId, credential_id: String) -> BoxFuture<Option<SignedRevocationNotice>> {
+        let notice = self
+            .notices
+            .iter()
+            .find(|notice| {
+                notice.notice.issuer_node_id == issuer
+                    && notice.notice.credential_id == credential_id
+                    && notice.verify().unwrap_or(false)
+            })
+            .cloned();
+        Box::pin(async move { notice })
+    }
+}
+
+/// Reads a credential in any form this crate writes: a W3C VC document, a
+/// JWT-VC or compact text. The document proof or JWS signature is checked; the
+/// credential's own signatures are left to [`CredentialVerifier`].
+pub fn decode_credential(contents: &str) -> Result<SignedIncomeCredential, CredentialError> {
+    let contents = contents.trim();
+    if contents.starts_with('{') {
+        let document: VerifiableCredential = serde_json::from_str(contents)?;
+        SignedIncomeCredential::from_verifiable_credential(&document)
+    } else if contents.contains('.') && !contents.starts_with(QR_PREFIX) {
+        verify_jws(contents)
+    } else {
+        SignedIncomeCredential::from_compact_text(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::income_credential::{IncomeCredential, PaymentMode, ValidityWindow};
+    use crate::credentials::key_binding::{KeyBinding, KeyUnbinding};
+    use crate::credentials::revocation::RevocationNotice;
+    use crate::credentials::signer::MemorySigner;
+    use crate::credentials::status_list::{StatusList, StatusListState};
+    use iroh::SecretKey;
+    use n0_future::future::block_on;
+
+    fn credential(employer_key: &SecretKey) -> IncomeCredential {
+        IncomeCredential::new(
+            SecretKey::generate(&mut rand::rng()).public(),
+            "Jane Doe".to_string(),
+            employer_key.public(),
+            "Acme Corporation".to_string(),
+            "10000.00".to_string(),
+            "8000.00".to_string(),
+            "USD".to_string(),
+            "2024-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+    }
+
+    #[test]
+    fn test_offline_verification() {
+        block_on(async {
+            let employer_key = SecretKey::generate(&mut rand::rng());
+            let validity = ValidityWindow {
+                valid_from: None,
+                expires_at: Some("2030-01-01T00:00:00Z".to_string()),
+            };
+            let signed = credential(&employer_key)
+                .with_validity(validity)
+                .with_status_index(5)
+                .sign(&employer_key)
+                .unwrap();
+            let now: DateTime<Utc> = "2026-01-01T00:00:00Z".parse().unwrap();
+
+            // Valid signature, but nothing is known about the issuer: without its
+            // status list the credential passes only if the policy allows it
+            let mut trust_store = OfflineTrustStore::default();
+            let mut revocations = OfflineRevocations::default();
+            let check = CredentialVerifier::new(&trust_store, &revocations, &now).check(&signed).await;
+            assert!(!check.is_valid && !check.is_revoked);
+            assert_eq!(check.status_check, StatusCheck::Unavailable);
+            assert!(check.message.contains("status list is unavailable"), "{}", check.message);
+            let check = CredentialVerifier::new(&trust_store, &revocations, &now)
+                .with_unavailable_status(true)
+                .check(&signed)
+                .await;
+            assert!(check.is_valid && !check.is_trusted);
+            assert!(check.message.contains("not in trusted list"));
+            assert!(check.message.contains("(issuer status list unavailable)"));
+
+            trust_store.trusted_issuers.push(employer_key.public());
+            let mut state = StatusListState::default();
+            let list = |state: &StatusListState| {
+                StatusList::new(employer_key.public(), state, chrono::Duration::hours(1))
+                    .unwrap()
+                    .sign(&employer_key)
+                    .unwrap()
+            };
+            revocations.status_lists.push(list(&state));
+            let verifier = CredentialVerifier::new(&trust_store, &revocations, &now);
+            let check = verifier.check(&signed).await;
+            assert!(check.is_valid && check.is_trusted, "{}", check.message);
+            assert_eq!(check.status_check, StatusCheck::Active);
+            assert_eq!(check.signers.len(), 1);
+
+            // The clock decides the validity window
+            let later: DateTime<Utc> = "2031-01-01T00:00:00Z".parse().unwrap();
+            let check = CredentialVerifier::new(&trust_store, &revocations, &later).check(&signed).await;
+            assert!(!check.is_valid);
+            assert_eq!(check.validity, ValidityStatus::Expired);
+
+            // The newest status list wins
+            state.revoke(5);
+            revocations.status_lists.push(list(&state));
+            let check = CredentialVerifier::new(&trust_store, &revocations, &now).check(&signed).await;
+            assert!(!check.is_valid && check.is_revoked);
+            assert_eq!(check.status_list_version, Some(1));
+
+            // So does a signed notice, with its reason
+            let notice = RevocationNotice::new(signed.id().to_string(), employer_key.public(), Some("error".into()))
+                .sign(&employer_key)
+                .unwrap();
+            let revocations = OfflineRevocations {
+                status_lists: Vec::new(),
+                notices: vec![notice],
+            };
+            let check = CredentialVerifier::new(&trust_store, &revocations, &now).check(&signed).await;
+            assert!(check.is_revoked);
+            assert_eq!(check.revocation_reason.as_deref(), Some("error"));
+        });
+    }
+
+    #[test]
+    fn test_offline_signing_key_and_decoding() {
+        block_on(async {
+            let employer_key = SecretKey::generate(&mut rand::rng());
+            let signing_key = SecretKey::generate(&mut rand::rng());
+            let signer = MemorySigner::new(signing_key.clone());
+            let signed = credential(&employer_key).sign_with(&signer).await.unwrap();
+            let mut trust_store = OfflineTrustStore {
+                trusted_issuers: vec![employer_key.public()],
+                ..Default::default()
+            };
+            let revocations = OfflineRevocations::default();
+
+            // Uploaded as compact text, a JWT-VC or a VC document
+            let jws = signed.to_jws(&signer).await.unwrap();
+            let document = signed.to_verifiable_credential(&signer).await.unwrap();
+            for contents in [
+                signed.to_qr_text().unwrap(),
+                jws,
+                serde_json::to_string(&document).unwrap(),
+            ] {
+                let decoded = decode_credential(&contents).unwrap();
+                let check = CredentialVerifier::new(&trust_store, &revocations, &SystemClock).check(&decoded).await;
+                assert!(!check.is_valid);
+                assert!(check.message.contains("is not bound"), "{}", check.message);
+            }
+
+            trust_store.key_bindings.push(
+                KeyBinding::new(employer_key.public(), signing_key.public())
+                    .sign(&signer, &employer_key)
+                    .await
+                    .unwrap(),
+            );
+            let check = CredentialVerifier::new(&trust_store, &revocations, &SystemClock).check(&signed).await;
+            assert!(check.is_valid && check.is_trusted, "{}", check.message);
+
+            // Until the node unbinds the signing key
+            let mut unbound = trust_store.clone();
+            unbound.key_unbindings.push(KeyUnbinding::new(employer_key.public(), signing_key.public()).sign(&employer_key).unwrap());
+            let check = CredentialVerifier::new(&unbound, &revocations, &SystemClock).check(&signed).await;
+            assert!(!check.is_valid);
+            assert!(check.message.contains("is not bound"), "{}", check.message);
+
+            // A presentation that is not bound to its holder fails
+            let check = CredentialVerifier::new(&trust_store, &revocations, &SystemClock)
+                .check_presented(&signed, HolderBinding::Unbound, None)
+                .await;
+            assert!(!check.is_valid);
+
+            let mut forged = signed.clone();
+            forged.credential.net_salary = "9000.00".to_string();
+            let decoded = decode_credential(&forged.to_compact_base64url().unwrap()).unwrap();
+            let check = CredentialVerifier::new(&trust_store, &revocations, &SystemClock).check(&decoded).await;
+            assert_eq!(check.message, "Invalid credential signature");
+        });
+    }
+
+    #[test]
+    fn test_legacy_signatures_need_opt_in() {
+        block_on(async {
+            let employer_key = SecretKey::generate(&mut rand::rng());
+            let credential = credential(&employer_key).with_status_index(5);
+            let signature = employer_key.sign(credential.generate_statement().as_bytes());
+            let legacy = SignedIncomeCredential {
+                credential,
+                signature: signature.to_bytes().to_vec(),
+                format: crate::credentials::income_credential::SignatureFormat::LegacyStatement,
+                co_signatures: Vec::new(),
+                signing_key: None,
+                approvals: Vec::new(),
+            };
+            let trust_store = OfflineTrustStore {
+                trusted_issuers: vec![employer_key.public()],
+                ..Default::default()
+            };
+            let revocations = OfflineRevocations::default();
+
+            let check = CredentialVerifier::new(&trust_store, &revocations, &SystemClock).check(&legacy).await;
+            assert!(check.signers[0].signed && !check.is_valid);
+            assert!(check.message.contains("does not accept legacy signatures"), "{}", check.message);
+
+            // Accepted on request, but nothing the statement leaves unsigned is relied on
+            let check = CredentialVerifier::new(&trust_store, &revocations, &SystemClock)
+                .with_legacy_signatures(true)
+                .check(&legacy)
+                .await;
+            assert!(check.is_valid && check.legacy_signature, "{}", check.message);
+            assert_eq!(check.validity, ValidityStatus::Unknown);
+            assert_eq!(check.status_check, StatusCheck::Unknown);
+            assert!(check.message.contains("revocation status are unknown"));
+        });
+    }
+
+    #[test]
+    fn test_legacy_issuance_time_is_not_trusted() {
+        block_on(async {
+            let employer_key = SecretKey::generate(&mut rand::rng());
+            let mut credential = credential(&employer_key);
+            credential.issued_at = "2020-01-01T00:00:00Z".to_string();
+            let signature = employer_key.sign(credential.generate_statement().as_bytes());
+            let mut legacy = SignedIncomeCredential {
+                credential,
+                signature: signature.to_bytes().to_vec(),
+                format: crate::credentials::income_credential::SignatureFormat::LegacyStatement,
+                co_signatures: Vec::new(),
+                signing_key: None,
+                approvals: Vec::new(),
+            };
+            let trust_store = OfflineTrustStore {
+                trusted_issuers: vec![employer_key.public()],
+                ..Default::default()
+            };
+            let revocations = OfflineRevocations::default();
+            let now: DateTime<Utc> = "2026-01-01T00:00:00Z".parse().unwrap();
+
+            // The holder freshens the issuance time; the statement signature still verifies
+            legacy.credential.issued_at = "2025-12-31T00:00:00Z".to_string();
+            assert!(legacy.verify().unwrap());
+
+            let verifier = CredentialVerifier::new(&trust_store, &revocations, &now).with_legacy_signatures(true);
+            let check = verifier.check(&legacy).await;
+            assert_eq!(check.validity, ValidityStatus::Unknown);
+
+            // A max-age policy cannot be met by an unsigned timestamp
+            let check = verifier.with_max_age(Some(chrono::Duration::days(30))).check(&legacy).await;
+            assert!(!check.is_valid);
+            assert_eq!(check.validity, ValidityStatus::Unknown);
+            assert!(check.message.contains("does not cover the issuance time"), "{}", check.message);
+        });
+    }
+}
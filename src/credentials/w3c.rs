@@ -0,0 +1,524 @@
+//! W3C Verifiable Credentials Data Model 2.0 form of income credentials.
+//!
+//! The document carries the credential's own signature, so importing it gives
+//! back the exact [`SignedIncomeCredential`], and an `eddsa-jcs-2022` Data
+//! Integrity proof by the same key, so standard verifiers can check it too.
+//! Terms outside VC 2.0 are declared in an embedded `@context`, and the status
+//! list index is a `BitstringStatusListEntry`.
+
+use iroh::PublicKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::co_signature::{CoSignature, SignerRole};
+use super::did::{did_iroh, did_key, did_key_verification_method, multibase_base58btc, parse_did_key};
+use super::income_credential::{CredentialError, IncomeCredential, PaymentMode, SignatureFormat, SignedIncomeCredential};
+use super::officer_approval::CredentialApproval;
+use super::signer::CredentialSigner;
+
+/// Base context of every VC 2.0 document
+pub const VC_CONTEXT_V2: &str = "https://www.w3.org/ns/credentials/v2";
+
+/// Type naming an income credential, next to `VerifiableCredential`
+pub const INCOME_CREDENTIAL_TYPE: &str = "IncomeCredential";
+
+/// Vocabulary of the terms declared by [`income_context`]
+pub const INCOME_VOCABULARY: &str = "urn:trust-triangle:income#";
+
+/// Terms of income credentials that VC 2.0 does not define
+const INCOME_TERMS: [&str; 20] = [
+    INCOME_CREDENTIAL_TYPE,
+    "payrollProcessor",
+    "grossSalary",
+    "netSalary",
+    "currency",
+    "payPeriod",
+    "paymentMode",
+    "issuedAt",
+    "credentialSignature",
+    "format",
+    "signatureValue",
+    "signingKey",
+    "coSignatures",
+    "signer",
+    "role",
+    "signedAt",
+    "approvals",
+    "officer",
+    "policyVersion",
+    "approvedAt",
+];
+
+const BITSTRING_STATUS_LIST_ENTRY: &str = "BitstringStatusListEntry";
+const REVOCATION_PURPOSE: &str = "revocation";
+
+const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+const EDDSA_JCS_2022: &str = "eddsa-jcs-2022";
+const ASSERTION_METHOD: &str = "assertionMethod";
+
+/// Income credential as a W3C VC 2.0 JSON-LD document
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiableCredential {
+    /// [`VC_CONTEXT_V2`] followed by [`income_context`]
+    #[serde(rename = "@context")]
+    pub context: Vec<Context>,
+    /// `urn:uuid:` of the credential ID
+    pub id: String,
+    #[serde(rename = "type")]
+    pub types: Vec<String>,
+    pub issuer: Party,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
+    pub credential_subject: IncomeSubject,
+    pub issued_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_status: Option<CredentialStatus>,
+    pub credential_signature: CredentialSignature,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<DataIntegrityProof>,
+}
+
+/// Entry of `@context`: a context URL, or term definitions embedded in place
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Context {
+    Url(String),
+    Embedded(serde_json::Map<String, serde_json::Value>),
+}
+
+/// Embedded context mapping the income credential terms into [`INCOME_VOCABULARY`]
+pub fn income_context() -> Context {
+    let mut terms = serde_json::Map::new();
+    terms.insert("@protected".to_string(), true.into());
+    for term in INCOME_TERMS {
+        terms.insert(term.to_string(), format!("{INCOME_VOCABULARY}{term}").into());
+    }
+    Context::Embedded(terms)
+}
+
+/// Position of the credential in its issuer's revocation status list, which the
+/// issuer node serves to verifiers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialStatus {
+    #[serde(rename = "type")]
+    pub status_type: String,
+    pub status_purpose: String,
+    /// The index, as a decimal string
+    pub status_list_index: String,
+    /// `did:iroh` URL of the issuer's status list
+    pub status_list_credential: String,
+}
+
+/// Issuer or payroll processor, identified by `did:key`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Party {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// The employee and their income
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeSubject {
+    /// `did:key` of the employee
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payroll_processor: Option<Party>,
+    pub gross_salary: String,
+    pub net_salary: String,
+    pub currency: String,
+    pub pay_period: String,
+    pub payment_mode: PaymentMode,
+}
+
+/// The credential's own signatures, in multibase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialSignature {
+    pub format: SignatureFormat,
+    pub signature_value: String,
+    /// `did:key` of the signing key, when it is not the issuer's node key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_signatures: Vec<VcCoSignature>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approvals: Vec<VcApproval>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VcCoSignature {
+    /// `did:key` of the co-signer
+    pub signer: String,
+    pub role: SignerRole,
+    pub signed_at: String,
+    pub signature_value: String,
+}
+
+/// Officer approval of an organizational issuer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VcApproval {
+    /// `did:key` of the officer
+    pub officer: String,
+    pub policy_version: u32,
+    pub approved_at: String,
+    pub signature_value: String,
+}
+
+/// Ed25519 Data Integrity proof
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataIntegrityProof {
+    #[serde(rename = "type")]
+    pub proof_type: String,
+    pub cryptosuite: String,
+    pub created: String,
+    pub verification_method: String,
+    pub proof_purpose: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_value: Option<String>,
+}
+
+impl SignedIncomeCredential {
+    /// Exports the credential as a VC 2.0 document with an `eddsa-jcs-2022`
+    /// proof by `signer`, which must hold the key that signed the credential
+    pub async fn to_verifiable_credential(
+        &self,
+        signer: &dyn CredentialSigner,
+    ) -> Result<VerifiableCredential, CredentialError> {
+        let signing_key = self.check_signer(signer)?;
+        let mut document = self.to_unsecured_document();
+        let mut proof = DataIntegrityProof {
+            proof_type: DATA_INTEGRITY_PROOF.to_string(),
+            cryptosuite: EDDSA_JCS_2022.to_string(),
+            created: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            verification_method: did_key_verification_method(&signing_key),
+            proof_purpose: ASSERTION_METHOD.to_string(),
+            proof_value: None,
+        };
+        let signature = signer.sign(document.hash_data(&proof)?).await?;
+        proof.proof_value = Some(multibase_base58btc(&signature.to_bytes()));
+        document.proof = Some(proof);
+        Ok(document)
+    }
+
+    /// Returns the key that signed the credential, which `signer` must hold
+    pub(crate) fn check_signer(&self, signer: &dyn CredentialSigner) -> Result<PublicKey, CredentialError> {
+        let signing_key = self.signing_key.unwrap_or(self.credential().employer_node_id);
+        if signer.public_key() != signing_key {
+            return Err(CredentialError::SigningError(format!(
+                "the proof must be made by {signing_key}, the key that signed the credential"
+            )));
+        }
+        Ok(signing_key)
+    }
+
+    /// VC 2.0 document of the credential, without a proof
+    pub(crate) fn to_unsecured_document(&self) -> VerifiableCredential {
+        let credential = self.credential();
+        let payroll_processor = (credential.payroll_processor_node_id.is_some()
+            || credential.payroll_processor_name.is_some())
+        .then(|| Party {
+            id: credential.payroll_processor_node_id.as_ref().map(did_key),
+            name: credential.payroll_processor_name.clone(),
+        });
+        let co_signatures = self
+            .co_signatures
+            .iter()
+            .map(|co_signature| VcCoSignature {
+                signer: did_key(&co_signature.signer_node_id),
+                role: co_signature.role,
+                signed_at: co_signature.signed_at.clone(),
+                signature_value: multibase_base58btc(&co_signature.signature),
+            })
+            .collect();
+        let approvals = self
+            .approvals
+            .iter()
+            .map(|approval| VcApproval {
+                officer: did_key(&approval.officer_node_id),
+                policy_version: approval.policy_version,
+                approved_at: approval.approved_at.clone(),
+                signature_value: multibase_base58btc(&approval.signature),
+            })
+            .collect();
+
+        VerifiableCredential {
+            context: vec![Context::Url(VC_CONTEXT_V2.to_string()), income_context()],
+            id: format!("urn:uuid:{}", credential.id),
+            types: vec!["VerifiableCredential".to_string(), INCOME_CREDENTIAL_TYPE.to_string()],
+            issuer: Party {
+                id: Some(did_key(&credential.employer_node_id)),
+                name: Some(credential.employer_name.clone()),
+            },
+            valid_from: credential.valid_from.clone(),
+            valid_until: credential.expires_at.clone(),
+            credential_subject: IncomeSubject {
+                id: did_key(&credential.employee_node_id),
+                name: credential.employee_name.clone(),
+                payroll_processor,
+                gross_salary: credential.gross_salary.clone(),
+                net_salary: credential.net_salary.clone(),
+                currency: credential.currency.clone(),
+                pay_period: credential.pay_period.clone(),
+                payment_mode: credential.payment_mode.clone(),
+            },
+            issued_at: credential.issued_at.clone(),
+            credential_status: credential.status_index.map(|index| CredentialStatus {
+                status_type: BITSTRING_STATUS_LIST_ENTRY.to_string(),
+                status_purpose: REVOCATION_PURPOSE.to_string(),
+                status_list_index: index.to_string(),
+                status_list_credential: format!("{}#status-list", did_iroh(&credential.employer_node_id)),
+            }),
+            credential_signature: CredentialSignature {
+                format: self.format,
+                signature_value: multibase_base58btc(&self.signature),
+                signing_key: self.signing_key.as_ref().map(did_key),
+                co_signatures,
+                approvals,
+            },
+            proof: None,
+        }
+    }
+
+    /// Checks the proof of a VC 2.0 income credential and rebuilds the signed
+    /// credential it carries; its own signature must verify as well
+    pub fn from_verifiable_credential(document: &VerifiableCredential) -> Result<Self, CredentialError> {
+        let proof_key = document.verify_proof()?;
+        Self::from_document(document, proof_key)
+    }
+
+    /// Rebuilds the credential in `document`, whose proof by `proof_key` the
+    /// caller has checked; the key must have signed the credential too
+    pub(crate) fn from_document(document: &VerifiableCredential, proof_key: PublicKey) -> Result<Self, CredentialError> {
+        let invalid = |reason: String| CredentialError::InvalidVerifiableCredential(reason);
+        if !document.types.iter().any(|t| t == INCOME_CREDENTIAL_TYPE) {
+            return Err(invalid(format!("not an {INCOME_CREDENTIAL_TYPE}")));
+        }
+        if document.context != [Context::Url(VC_CONTEXT_V2.to_string()), income_context()] {
+            return Err(invalid("@context does not declare the income credential terms".into()));
+        }
+        let status_index = document
+            .credential_status
+            .as_ref()
+            .map(|status| {
+                if status.status_type != BITSTRING_STATUS_LIST_ENTRY || status.status_purpose != REVOCATION_PURPOSE {
+                    return Err(invalid(format!("credentialStatus is not a {BITSTRING_STATUS_LIST_ENTRY} for revocation")));
+                }
+                status
+                    .status_list_index
+                    .parse()
+                    .map_err(|_| invalid(format!("status list index {} is not a number", status.status_list_index)))
+            })
+            .transpose()?;
+
+        let issuer = &document.issuer;
+        let employer_node_id = parse_did_key(issuer.id.as_deref().ok_or_else(|| invalid("issuer has no id".into()))?)?;
+        let signature = &document.credential_signature;
+        let signing_key = signature.signing_key.as_deref().map(parse_did_key).transpose()?;
+        if proof_key != signing_key.unwrap_or(employer_node_id) {
+            return Err(invalid(format!("proof by {proof_key} is not from the credential's signer")));
+        }
+
+        let subject = &document.credential_subject;
+        let processor = subject.payroll_processor.as_ref();
+        let credential = IncomeCredential {
+            id: document
+                .id
+                .strip_prefix("urn:uuid:")
+                .ok_or_else(|| invalid(format!("id {} is not a urn:uuid", document.id)))?
+                .to_string(),
+            employee_node_id: parse_did_key(&subject.id)?,
+            employee_name: subject.name.clone(),
+            employer_node_id,
+            employer_name: issuer.name.clone().ok_or_else(|| invalid("issuer has no name".into()))?,
+            payroll_processor_node_id: processor.and_then(|p| p.id.as_deref()).map(parse_did_key).transpose()?,
+            payroll_processor_name: processor.and_then(|p| p.name.clone()),
+            gross_salary: subject.gross_salary.clone(),
+            net_salary: subject.net_salary.clone(),
+            currency: subject.currency.clone(),
+            pay_period: subject.pay_period.clone(),
+            payment_mode: subject.payment_mode.clone(),
+            issued_at: document.issued_at.clone(),
+            valid_from: document.valid_from.clone(),
+            expires_at: document.valid_until.clone(),
+            status_index,
+        };
+        let co_signatures = signature
+            .co_signatures
+            .iter()
+            .map(|co_signature| {
+                Ok(CoSignature {
+                    signer_node_id: parse_did_key(&co_signature.signer)?,
+                    role: co_signature.role,
+                    signed_at: co_signature.signed_at.clone(),
+                    signature: decode_multibase(&co_signature.signature_value)?,
+                })
+            })
+            .collect::<Result<_, CredentialError>>()?;
+        let approvals = signature
+            .approvals
+            .iter()
+            .map(|approval| {
+                Ok(CredentialApproval {
+                    officer_node_id: parse_did_key(&approval.officer)?,
+                    policy_version: approval.policy_version,
+                    approved_at: approval.approved_at.clone(),
+                    signature: decode_multibase(&approval.signature_value)?,
+                })
+            })
+            .collect::<Result<_, CredentialError>>()?;
+
+        let signed = SignedIncomeCredential {
+            credential,
+            signature: decode_multibase(&signature.signature_value)?,
+            format: signature.format,
+            co_signatures,
+            signing_key,
+            approvals,
+        };
+        if !signed.verify()? {
+            return Err(invalid("the credential signature does not verify".into()));
+        }
+        Ok(signed)
+    }
+}
+
+impl VerifiableCredential {
+    /// Verifies the `eddsa-jcs-2022` proof, returning the key that made it
+    pub fn verify_proof(&self) -> Result<PublicKey, CredentialError> {
+        let invalid = |reason: &str| CredentialError::InvalidVerifiableCredential(reason.to_string());
+        let proof = self.proof.as_ref().ok_or_else(|| invalid("no proof"))?;
+        if proof.proof_type != DATA_INTEGRITY_PROOF || proof.cryptosuite != EDDSA_JCS_2022 {
+            return Err(invalid("proof is not an eddsa-jcs-2022 Data Integrity proof"));
+        }
+        if proof.proof_purpose != ASSERTION_METHOD {
+            return Err(invalid("proof purpose is not assertionMethod"));
+        }
+        let key = parse_did_key(&proof.verification_method)?;
+        let signature = decode_multibase(proof.proof_value.as_deref().ok_or_else(|| invalid("proof has no value"))?)?;
+
+        let options = DataIntegrityProof {
+            proof_value: None,
+            ..proof.clone()
+        };
+        let unsecured = Self {
+            proof: None,
+            ..self.clone()
+        };
+        if !super::verify_signature(&key, &unsecured.hash_data(&options)?, &signature)? {
+            return Err(CredentialError::InvalidSignature);
+        }
+        Ok(key)
+    }
+
+    /// Bytes an `eddsa-jcs-2022` proof signs: the SHA-256 of the JCS proof
+    /// configuration followed by that of the JCS document without its proof
+    fn hash_data(&self, options: &DataIntegrityProof) -> Result<Vec<u8>, CredentialError> {
+        let mut config = serde_json::to_value(options)?;
+        config["@context"] = serde_json::to_value(&self.context)?;
+        let mut hash_data = Sha256::digest(serde_jcs::to_vec(&config)?).to_vec();
+        hash_data.extend_from_slice(&Sha256::digest(serde_jcs::to_vec(self)?));
+        Ok(hash_data)
+    }
+}
+
+fn decode_multibase(value: &str) -> Result<Vec<u8>, CredentialError> {
+    let invalid = || CredentialError::InvalidVerifiableCredential(format!("{value} is not base58btc multibase"));
+    let encoded = value.strip_prefix('z').ok_or_else(invalid)?;
+    bs58::decode(encoded).into_vec().map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::signer::MemorySigner;
+    use iroh::SecretKey;
+    use n0_future::future::block_on;
+
+    fn signed_credential(employer_key: &SecretKey) -> SignedIncomeCredential {
+        IncomeCredential::new(
+            SecretKey::generate(&mut rand::rng()).public(),
+            "Jane Doe".to_string(),
+            employer_key.public(),
+            "Acme Corporation".to_string(),
+            "10000.00".to_string(),
+            "8000.00".to_string(),
+            "USD".to_string(),
+            "2024-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+        .with_payroll_processor(SecretKey::generate(&mut rand::rng()).public(), "PayCo".to_string())
+        .with_status_index(7)
+        .sign(employer_key)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verifiable_credential_round_trip() {
+        let employer_key = SecretKey::generate(&mut rand::rng());
+        let signed = signed_credential(&employer_key);
+        let signer = MemorySigner::new(employer_key.clone());
+        let document = block_on(signed.to_verifiable_credential(&signer)).unwrap();
+
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(json["@context"][0], VC_CONTEXT_V2);
+        assert_eq!(json["@context"][1]["netSalary"], format!("{INCOME_VOCABULARY}netSalary"));
+        assert_eq!(json["credentialStatus"]["type"], BITSTRING_STATUS_LIST_ENTRY);
+        assert_eq!(json["credentialStatus"]["statusListIndex"], "7");
+        assert_eq!(json["issuer"]["id"], did_key(&employer_key.public()));
+        assert_eq!(json["credentialSubject"]["netSalary"], "8000.00");
+        assert_eq!(json["proof"]["cryptosuite"], EDDSA_JCS_2022);
+        assert!(json["proof"]["proofValue"].as_str().unwrap().starts_with('z'));
+
+        let document: VerifiableCredential = serde_json::from_value(json).unwrap();
+        let imported = SignedIncomeCredential::from_verifiable_credential(&document).unwrap();
+        assert_eq!(serde_json::to_value(&imported).unwrap(), serde_json::to_value(&signed).unwrap());
+        assert!(imported.verify().unwrap());
+    }
+
+    #[test]
+    fn test_verifiable_credential_rejects_tampering() {
+        let employer_key = SecretKey::generate(&mut rand::rng());
+        let signed = signed_credential(&employer_key);
+        let document = block_on(signed.to_verifiable_credential(&MemorySigner::new(employer_key.clone()))).unwrap();
+
+        let mut raised = document.clone();
+        raised.credential_subject.net_salary = "9000.00".to_string();
+        assert!(SignedIncomeCredential::from_verifiable_credential(&raised).is_err());
+
+        // A valid proof by another key does not stand for the issuer
+        let forger = SecretKey::generate(&mut rand::rng());
+        assert!(block_on(signed.to_verifiable_credential(&MemorySigner::new(forger.clone()))).is_err());
+        let mut reproved = document.clone();
+        reproved.proof = None;
+        let mut proof = document.proof.clone().unwrap();
+        proof.verification_method = did_key_verification_method(&forger.public());
+        proof.proof_value = None;
+        let signature = forger.sign(&reproved.hash_data(&proof).unwrap());
+        proof.proof_value = Some(multibase_base58btc(&signature.to_bytes()));
+        reproved.proof = Some(proof);
+        assert_eq!(reproved.verify_proof().unwrap(), forger.public());
+        assert!(SignedIncomeCredential::from_verifiable_credential(&reproved).is_err());
+
+        // The terms must keep the meaning the issuer declared
+        let mut undeclared = document.clone();
+        undeclared.context.truncate(1);
+        assert!(SignedIncomeCredential::from_document(&undeclared, employer_key.public()).is_err());
+        assert!(SignedIncomeCredential::from_document(&document, employer_key.public()).is_ok());
+
+        let mut unproven = document;
+        unproven.proof = None;
+        assert!(SignedIncomeCredential::from_verifiable_credential(&unproven).is_err());
+    }
+}
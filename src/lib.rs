@@ -1,5 +1,14 @@
+pub mod challenge;
 pub mod peer_node;
 pub mod credentials;
+#[cfg(all(feature = "cli", unix))]
+pub mod control;
+#[cfg(all(feature = "cli", unix))]
+pub mod remote_signer;
+pub mod revocation;
+pub mod status_list;
+pub mod storage;
+pub mod wallet;
 
 #[cfg(all(target_family = "wasm", target_os = "unknown"))]
 pub mod wasm;
\ No newline at end of file
@@ -0,0 +1,1965 @@
+//! Headless `trust-triangle` CLI for scripting issuance and verification.
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use iroh::{EndpointId, SecretKey};
+use n0_future::{StreamExt, boxed::BoxStream};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+#[cfg(unix)]
+use trust_triangle::control::{CONTROL_SOCKET, ControlClient, ControlRequest, ControlServer};
+#[cfg(unix)]
+use trust_triangle::remote_signer::{SIGNER_SOCKET, SignerServer, SocketSigner};
+use trust_triangle::credentials::co_signature::SignerRole;
+use trust_triangle::credentials::did::DidDocument;
+use trust_triangle::credentials::income_credential::{PaymentMode, SignedIncomeCredential, ValidityWindow};
+use trust_triangle::credentials::issuer_profile::{IssuerProfile, PayrollProcessor};
+use trust_triangle::credentials::key_binding::{KeyUnbinding, SignedKeyBinding, SignedKeyUnbinding};
+use trust_triangle::credentials::key_rotation::{KeyRotation, SignedKeyRotation};
+use trust_triangle::credentials::officer_approval::{ApprovalPolicy, PublishedApprovalPolicy, SignedApprovalPolicy};
+use trust_triangle::credentials::presentation::PresentationConstraints;
+use trust_triangle::credentials::selective_disclosure::{DisclosableField, DisclosedClaims};
+use trust_triangle::credentials::signer::{CredentialSigner, MemorySigner};
+use trust_triangle::credentials::verification::{
+    CredentialVerifier, OfflineRevocations, OfflineTrustStore, SystemClock, decode_credential,
+};
+use trust_triangle::peer_node::{
+    AcceptEvent, ApprovalPolicyStatus, ApprovalProgress, ConnectEvent, CredentialCheck, CredentialMessage, PeerNode,
+    PendingCredentialRequest, PresentationVerdict, RequestStatus, Role, SignerCheck,
+};
+use trust_triangle::storage::FileStore;
+
+/// Environment variable holding the wallet passphrase, if one is set
+const WALLET_PASSPHRASE_ENV: &str = "TRUST_TRIANGLE_WALLET_PASSPHRASE";
+
+#[derive(Parser)]
+#[command(name = "trust-triangle", version, about = "Issue, hold and verify income credentials over iroh")]
+struct Cli {
+    /// Directory holding the node key and state
+    #[arg(long, global = true, default_value = "trust-triangle-data")]
+    data_dir: PathBuf,
+
+    /// Print JSON instead of human-readable text
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Sign credentials through the signing daemon listening on this socket
+    /// (see `signer`) instead of a key in the data directory
+    #[cfg(unix)]
+    #[arg(long, global = true)]
+    remote_signer: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print this node's ID
+    Id,
+
+    /// Run a node in the foreground, printing incoming events until Ctrl-C
+    Run {
+        #[arg(long, value_enum)]
+        role: RoleArg,
+    },
+
+    /// Run an issuer that serves the control API on `<data-dir>/control.sock`
+    /// until stopped. Other commands on the same data directory go through it.
+    #[cfg(unix)]
+    Daemon,
+
+    /// Stream the daemon's incoming events
+    #[cfg(unix)]
+    Events,
+
+    /// Run a signing daemon that keeps `<data-dir>/signing_key` out of the issuer
+    /// process and signs over `<data-dir>/signer.sock` until stopped. Issuers use
+    /// it with `--remote-signer`.
+    #[cfg(unix)]
+    Signer,
+
+    /// Ask an issuer for an income credential (Employee)
+    Request {
+        /// Node ID of the issuer
+        #[arg(long)]
+        issuer: EndpointId,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        gross_salary: String,
+        #[arg(long)]
+        net_salary: String,
+        #[arg(long, default_value = "USD")]
+        currency: String,
+        /// Pay period, e.g. "2025-01"
+        #[arg(long)]
+        pay_period: String,
+        #[arg(long, value_enum, default_value = "bank-transfer")]
+        payment_mode: PaymentModeArg,
+        /// Stay online this many seconds waiting for the issuer's decision
+        #[arg(long, default_value_t = 0)]
+        wait: u64,
+    },
+
+    /// Manage the queue of credential requests (Issuer)
+    Pending {
+        #[command(subcommand)]
+        command: PendingCommand,
+    },
+
+    /// Manage the organization profile used on issued credentials (Issuer)
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+
+    /// Manage the officers who must approve credential requests (Issuer)
+    Officers {
+        #[command(subcommand)]
+        command: OfficersCommand,
+    },
+
+    /// Replace this issuer's key with a new one, signing the handover with the
+    /// current key. The node must be restarted to use the new key.
+    RotateKey {
+        /// Why the key is replaced, e.g. "scheduled rotation"
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Manage a credential signing key kept apart from the node key (Issuer)
+    SigningKey {
+        #[command(subcommand)]
+        command: SigningKeyCommand,
+    },
+
+    /// Show this node's DID document or resolve another DID
+    Did {
+        #[command(subcommand)]
+        command: DidCommand,
+    },
+
+    /// Approve an organizational issuer's credential requests as one of its officers
+    Officer {
+        #[command(subcommand)]
+        command: OfficerCommand,
+    },
+
+    /// Fetch and verify an issuer's signed profile
+    FetchProfile {
+        /// Node ID of the issuer
+        issuer: EndpointId,
+    },
+
+    /// List credentials received from issuers (Employee)
+    Credentials,
+
+    /// Print a received credential as a W3C Verifiable Credential 2.0 document,
+    /// a JWT-VC or compact text for QR codes (Employee)
+    Export {
+        /// Request ID the credential was issued for
+        request_id: String,
+        #[arg(long, value_enum, default_value_t = ExportFormat::W3c)]
+        format: ExportFormat,
+    },
+
+    /// Verify a W3C Verifiable Credential, a JWT-VC or a scanned compact
+    /// credential offline and print the income credential it carries
+    Import {
+        /// File holding the JSON document, the compact JWS or the compact text
+        file: PathBuf,
+        /// Trusted issuers as written by `issuers export`; none are trusted without it
+        #[arg(long)]
+        trust_store: Option<PathBuf>,
+        /// Status lists and revocation notices as written by `issuers export --revocations`
+        #[arg(long)]
+        revocations: Option<PathBuf>,
+        /// Accept a signature over the legacy human-readable statement, which leaves the
+        /// validity window and revocation status unknown
+        #[arg(long)]
+        accept_legacy_signatures: bool,
+        /// Accept the credential when `--revocations` holds no status list of its issuer
+        #[arg(long)]
+        accept_unavailable_status: bool,
+    },
+
+    /// Present received credentials to a verifier; several are bundled
+    /// under one holder signature (Employee)
+    Present {
+        /// Node ID of the verifier
+        #[arg(long)]
+        verifier: EndpointId,
+        /// Request IDs the credentials were issued for
+        #[arg(required = true)]
+        request_ids: Vec<String>,
+        /// Sign a single credential over a verifier nonce, as needed when the
+        /// verifier does not see the connection come from this node
+        #[arg(long)]
+        envelope: bool,
+        /// Reveal only these fields of a single credential, e.g.
+        /// `--disclose net-salary,pay-period`; implies `--envelope`
+        #[arg(long, value_enum, value_delimiter = ',')]
+        disclose: Vec<DisclosedFieldArg>,
+        /// Prove the net salary of a single credential reaches this amount
+        /// without revealing it; implies `--envelope`
+        #[arg(long, conflicts_with = "disclose")]
+        at_least: Option<String>,
+        /// Present a single credential as the issuer's JWT-VC; implies `--envelope`
+        #[arg(long, conflicts_with_all = ["disclose", "at_least"])]
+        jws: bool,
+    },
+
+    /// Answer presentation requests from verifiers (Employee)
+    Inbox {
+        #[command(subcommand)]
+        command: InboxCommand,
+    },
+
+    /// Ask an employee for a credential matching the given constraints (Verifier)
+    Ask {
+        /// Node ID of the employee
+        employee: EndpointId,
+        /// Accept credentials from this issuer only; repeat for several
+        #[arg(long = "issuer")]
+        issuers: Vec<EndpointId>,
+        #[arg(long)]
+        currency: Option<String>,
+        /// Earliest pay period, e.g. "2025-01"
+        #[arg(long)]
+        pay_period_from: Option<String>,
+        /// Latest pay period, inclusive
+        #[arg(long)]
+        pay_period_to: Option<String>,
+        #[arg(long)]
+        min_net_salary: Option<String>,
+        /// Minutes the employee has to answer
+        #[arg(long)]
+        expires_in: Option<u32>,
+        /// Stay online this many seconds waiting for the employee's answer
+        #[arg(long, default_value_t = 0)]
+        wait: u64,
+    },
+
+    /// List presentation requests sent to employees (Verifier)
+    Asked,
+
+    /// Manage trusted issuers (Verifier)
+    Issuers {
+        #[command(subcommand)]
+        command: IssuersCommand,
+    },
+
+    /// List verification results (Verifier)
+    Verified {
+        /// List selective disclosures instead of full credentials
+        #[arg(long)]
+        disclosures: bool,
+        /// List net salary threshold proofs instead of full credentials
+        #[arg(long, conflicts_with = "disclosures")]
+        proofs: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PendingCommand {
+    /// List credential requests
+    List,
+    /// Sign a credential and deliver it to the employee
+    Approve {
+        request_id: String,
+        /// Start of the validity window (RFC3339)
+        #[arg(long)]
+        valid_from: Option<String>,
+        /// End of the validity window (RFC3339)
+        #[arg(long)]
+        expires_at: Option<String>,
+        /// Seconds to wait for delivery to the employee
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+    /// Reject a request and notify the employee
+    Reject {
+        request_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+        /// Seconds to wait for delivery to the employee
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum InboxCommand {
+    /// List presentation requests and the credentials that satisfy them
+    List,
+    /// Present credentials in answer to a request
+    Approve {
+        request_id: String,
+        /// IDs of the credentials to present
+        #[arg(required = true)]
+        credential_ids: Vec<String>,
+    },
+    /// Answer a request by proving the net salary reaches its minimum, without revealing it
+    Prove {
+        request_id: String,
+        /// ID of the credential whose net salary to prove
+        credential_id: String,
+    },
+    /// Decline a request and tell the verifier
+    Decline {
+        request_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// Print the profile
+    Show,
+    /// Update the given fields of the profile
+    Set {
+        #[arg(long)]
+        organization_name: Option<String>,
+        #[arg(long)]
+        legal_identifier: Option<String>,
+        #[arg(long)]
+        jurisdiction: Option<String>,
+        /// Node ID of the default payroll processor
+        #[arg(long, requires = "processor_name")]
+        processor_id: Option<EndpointId>,
+        #[arg(long, requires = "processor_id")]
+        processor_name: Option<String>,
+        /// Stop naming a payroll processor on credentials
+        #[arg(long, conflicts_with = "processor_id")]
+        no_processor: bool,
+    },
+}
+
+impl ProfileCommand {
+    /// Applies a `set` command to `profile`
+    fn apply(&self, mut profile: IssuerProfile) -> IssuerProfile {
+        if let ProfileCommand::Set {
+            organization_name,
+            legal_identifier,
+            jurisdiction,
+            processor_id,
+            processor_name,
+            no_processor,
+        } = self
+        {
+            if let Some(name) = organization_name {
+                profile.organization_name = name.clone();
+            }
+            if let Some(legal_identifier) = legal_identifier {
+                profile.legal_identifier = legal_identifier.clone();
+            }
+            if let Some(jurisdiction) = jurisdiction {
+                profile.jurisdiction = jurisdiction.clone();
+            }
+            if let (Some(node_id), Some(name)) = (processor_id, processor_name) {
+                profile.default_payroll_processor = Some(PayrollProcessor {
+                    node_id: *node_id,
+                    name: name.clone(),
+                });
+            }
+            if *no_processor {
+                profile.default_payroll_processor = None;
+            }
+        }
+        profile
+    }
+}
+
+#[derive(Subcommand)]
+enum OfficersCommand {
+    /// Print the officers and the approval threshold
+    Show,
+    /// Require `threshold` of the given officers to approve each request. The first
+    /// policy is published at once; later ones once enough current officers sign them
+    Set {
+        #[arg(long)]
+        threshold: u32,
+        /// Node IDs of the officers
+        #[arg(required = true)]
+        officers: Vec<EndpointId>,
+    },
+}
+
+impl OfficersCommand {
+    /// The policy a `set` command proposes
+    fn policy(&self) -> Option<ApprovalPolicy> {
+        match self {
+            OfficersCommand::Show => None,
+            OfficersCommand::Set { threshold, officers } => Some(ApprovalPolicy {
+                officers: officers.clone(),
+                threshold: *threshold,
+            }),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum OfficerCommand {
+    /// List the issuer's requests awaiting officer approval
+    Queue {
+        /// Node ID of the issuer
+        #[arg(long)]
+        issuer: EndpointId,
+    },
+    /// Check the credential the issuer signed for one of its requests and approve it
+    Approve {
+        /// Node ID of the issuer
+        #[arg(long)]
+        issuer: EndpointId,
+        request_id: String,
+    },
+    /// Sign the change of the issuer's approval policy shown by `queue`
+    ApprovePolicy {
+        /// Node ID of the issuer
+        #[arg(long)]
+        issuer: EndpointId,
+        /// Version of the proposed policy
+        version: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum DidCommand {
+    /// Print this node's `did:iroh` document: its addresses and credential signing keys
+    Show,
+    /// Fetch and verify the document of a `did:iroh`, or expand a `did:key`
+    Resolve { did: String },
+}
+
+#[derive(Subcommand)]
+enum SigningKeyCommand {
+    /// Generate `<data_dir>/signing_key`; credentials are signed with it from the
+    /// next start
+    Generate,
+    /// Show the binding between the signing key and the node key
+    Show,
+    /// Retire a signing key, e.g. after it leaked: announce a node-signed unbinding
+    /// so verifiers refuse every credential it signed
+    Unbind { signing_key: EndpointId },
+}
+
+#[derive(Subcommand)]
+enum IssuersCommand {
+    List,
+    Add { node_id: EndpointId },
+    Remove { node_id: EndpointId },
+    /// List known issuer key rotations
+    Rotations,
+    /// Print the trusted issuers with their key rotations and signing key
+    /// bindings, for verifying credentials with `import` or outside any node
+    Export {
+        /// Print the cached status lists and revocation notices instead
+        #[arg(long)]
+        revocations: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RoleArg {
+    Employee,
+    Issuer,
+    Verifier,
+    /// Payroll processor co-signing credentials for the employers in its trusted list
+    Processor,
+}
+
+impl From<RoleArg> for Role {
+    fn from(role: RoleArg) -> Self {
+        match role {
+            RoleArg::Employee => Role::Employee,
+            RoleArg::Issuer => Role::Issuer,
+            RoleArg::Verifier => Role::Verifier,
+            RoleArg::Processor => Role::Processor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PaymentModeArg {
+    BankTransfer,
+    Crypto,
+    Check,
+    Cash,
+    Other,
+}
+
+impl From<PaymentModeArg> for PaymentMode {
+    fn from(mode: PaymentModeArg) -> Self {
+        match mode {
+            PaymentModeArg::BankTransfer => PaymentMode::BankTransfer,
+            PaymentModeArg::Crypto => PaymentMode::Crypto,
+            PaymentModeArg::Check => PaymentMode::Check,
+            PaymentModeArg::Cash => PaymentMode::Cash,
+            PaymentModeArg::Other => PaymentMode::Other,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// VC 2.0 JSON document with an eddsa-jcs-2022 proof
+    W3c,
+    /// Compact JWS
+    Jwt,
+    /// Base45 text for the alphanumeric mode of a QR code
+    Qr,
+    /// Base64url text for links
+    Compact,
+}
+
+/// A credential read by `import`, with the verdict on it
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportedCredential {
+    credential: SignedIncomeCredential,
+    check: CredentialCheck,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DisclosedFieldArg {
+    EmployeeName,
+    EmployerName,
+    PayrollProcessorName,
+    GrossSalary,
+    NetSalary,
+    Currency,
+    PayPeriod,
+    PaymentMode,
+}
+
+impl From<DisclosedFieldArg> for DisclosableField {
+    fn from(field: DisclosedFieldArg) -> Self {
+        match field {
+            DisclosedFieldArg::EmployeeName => DisclosableField::EmployeeName,
+            DisclosedFieldArg::EmployerName => DisclosableField::EmployerName,
+            DisclosedFieldArg::PayrollProcessorName => DisclosableField::PayrollProcessorName,
+            DisclosedFieldArg::GrossSalary => DisclosableField::GrossSalary,
+            DisclosedFieldArg::NetSalary => DisclosableField::NetSalary,
+            DisclosedFieldArg::Currency => DisclosableField::Currency,
+            DisclosedFieldArg::PayPeriod => DisclosableField::PayPeriod,
+            DisclosedFieldArg::PaymentMode => DisclosableField::PaymentMode,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Logs go to stderr so stdout stays parseable
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")))
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    // The signing daemon has no node key
+    #[cfg(unix)]
+    if let Command::Signer = cli.command {
+        return run_signer(&cli).await;
+    }
+    let secret_key = load_or_create_secret_key(&cli.data_dir)?;
+
+    let role = match &cli.command {
+        Command::Id => {
+            let node_id = secret_key.public();
+            return emit(cli.json, &node_id, || node_id.to_string());
+        }
+        Command::Import {
+            file,
+            trust_store,
+            revocations,
+            accept_legacy_signatures,
+            accept_unavailable_status,
+        } => {
+            let contents = std::fs::read_to_string(file)?;
+            let credential = decode_credential(&contents)
+                .with_context(|| format!("{} does not hold a readable credential", file.display()))?;
+            let trust_store: OfflineTrustStore = read_json_file(trust_store.as_deref())?;
+            let revocations: OfflineRevocations = read_json_file(revocations.as_deref())?;
+            let check = CredentialVerifier::new(&trust_store, &revocations, &SystemClock)
+                .with_legacy_signatures(*accept_legacy_signatures)
+                .with_unavailable_status(*accept_unavailable_status)
+                .check(&credential)
+                .await;
+            let imported = ImportedCredential { credential, check };
+            return emit(cli.json, &imported, || describe_imported(&imported));
+        }
+        Command::SigningKey {
+            command: SigningKeyCommand::Generate,
+        } => {
+            let signing_key = create_signing_key(&cli.data_dir)?;
+            let public_key = signing_key.public();
+            return emit(cli.json, &public_key, || {
+                format!("Signing key {public_key} created; restart the node to sign credentials with it")
+            });
+        }
+        Command::Run { role } => (*role).into(),
+        #[cfg(unix)]
+        Command::Daemon => Role::Issuer,
+        #[cfg(unix)]
+        Command::Events => return stream_daemon_events(&cli).await,
+        #[cfg(unix)]
+        Command::Signer => unreachable!("handled before loading the node key"),
+        Command::Request { .. }
+        | Command::Credentials
+        | Command::Export { .. }
+        | Command::Present { .. }
+        | Command::Inbox { .. } => Role::Employee,
+        Command::Pending { .. }
+        | Command::Profile { .. }
+        | Command::Officers { .. }
+        | Command::Officer { .. }
+        | Command::SigningKey { .. }
+        | Command::RotateKey { .. } => Role::Issuer,
+        Command::Issuers { .. }
+        | Command::Verified { .. }
+        | Command::FetchProfile { .. }
+        | Command::Did { .. }
+        | Command::Ask { .. }
+        | Command::Asked => Role::Verifier,
+    };
+
+    // The daemon owns the data directory while it runs: the commands it serves go
+    // through its socket, the others fail on the directory lock
+    #[cfg(unix)]
+    if matches!(
+        cli.command,
+        Command::Pending { .. }
+            | Command::Profile { .. }
+            | Command::Officers { .. }
+            | Command::RotateKey { .. }
+            | Command::SigningKey { .. }
+            | Command::Did { .. }
+    )
+        && let Ok(client) = ControlClient::connect(cli.data_dir.join(CONTROL_SOCKET)).await
+    {
+        return run_remote(&cli, client).await;
+    }
+
+    let store = Arc::new(FileStore::open(&cli.data_dir)?);
+    let signer = load_signer(&cli).await?;
+    let node = Arc::new(PeerNode::spawn(Some(secret_key), role.clone(), store, None, signer).await?);
+    if matches!(role, Role::Employee) {
+        unlock_wallet_from_env(&node).await?;
+    }
+
+    let result = run(&cli, &node).await;
+    node.shutdown().await?;
+    result
+}
+
+async fn run(cli: &Cli, node: &Arc<PeerNode>) -> Result<()> {
+    let json = cli.json;
+    match &cli.command {
+        Command::Id | Command::Import { .. } => unreachable!("handled before spawning"),
+
+        Command::Run { .. } => {
+            eprintln!("Node {} running, press Ctrl-C to stop", node.endpoint().id());
+            let mut events = node.accept_events();
+            loop {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => break,
+                    Some(event) = events.next() => emit(json, &event, || describe_event(&event))?,
+                }
+            }
+        }
+
+        #[cfg(unix)]
+        Command::Daemon => run_daemon(cli, node).await?,
+        #[cfg(unix)]
+        Command::Events => unreachable!("handled before spawning"),
+        #[cfg(unix)]
+        Command::Signer => unreachable!("handled before spawning"),
+
+        Command::Request {
+            issuer,
+            name,
+            gross_salary,
+            net_salary,
+            currency,
+            pay_period,
+            payment_mode,
+            wait,
+        } => {
+            let request_id = uuid::Uuid::new_v4().to_string();
+            let mut events = node.accept_events();
+            let message = CredentialMessage::IssueRequest {
+                request_id: request_id.clone(),
+                employee_node_id: node.endpoint().id(),
+                employee_name: name.clone(),
+                gross_salary: gross_salary.clone(),
+                net_salary: net_salary.clone(),
+                currency: currency.clone(),
+                pay_period: pay_period.clone(),
+                payment_mode: (*payment_mode).into(),
+            };
+            let reply = send(node, *issuer, message).await?;
+            emit(json, &reply, || describe_message(&reply))?;
+
+            if *wait > 0 {
+                eprintln!("Waiting up to {wait}s for the issuer's decision on request {request_id}");
+                wait_for(&mut events, *wait, |event| match event {
+                    AcceptEvent::ResponseSent { message, .. } => match message {
+                        CredentialMessage::RequestQueued { request_id: id, .. } if id == request_id => Some(Ok(())),
+                        CredentialMessage::Error { request_id: id, message, .. } if id == request_id => {
+                            Some(Err(anyhow::anyhow!(message)))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .await??;
+                let response = node
+                    .get_received_credential(request_id.clone())
+                    .await?
+                    .with_context(|| format!("no response stored for request {request_id}"))?;
+                emit(json, &response, || match (&response.credential, &response.error) {
+                    (Some(credential), _) => format!("Credential {} issued", credential.id()),
+                    (None, error) => format!("Request rejected: {}", error.as_deref().unwrap_or("no reason given")),
+                })?;
+            }
+        }
+
+        Command::Pending { command } => match command {
+            PendingCommand::List => emit_pending(json, &node.get_pending_requests().await)?,
+            PendingCommand::Approve {
+                request_id,
+                valid_from,
+                expires_at,
+                timeout,
+            } => {
+                let mut events = node.accept_events();
+                let validity = ValidityWindow {
+                    valid_from: valid_from.clone(),
+                    expires_at: expires_at.clone(),
+                };
+                node.approve_request(request_id.clone(), validity).await?;
+                let request = node
+                    .get_pending_requests()
+                    .await
+                    .into_iter()
+                    .find(|request| &request.request_id == request_id)
+                    .with_context(|| format!("Request {request_id} not found"))?;
+                if request.status == RequestStatus::Pending {
+                    let policy_version = node.get_approval_policy().await.version();
+                    return emit_progress(json, &request.approval_progress(policy_version));
+                }
+                wait_for_delivery(&mut events, request_id, *timeout, json).await?;
+            }
+            PendingCommand::Reject {
+                request_id,
+                reason,
+                timeout,
+            } => {
+                let mut events = node.accept_events();
+                node.reject_request(request_id.clone(), reason.clone()).await?;
+                wait_for_delivery(&mut events, request_id, *timeout, json).await?;
+            }
+        },
+
+        Command::Profile { command } => {
+            if let ProfileCommand::Set { .. } = command {
+                node.set_issuer_profile(command.apply(node.get_issuer_profile().await)).await?;
+            }
+            emit_profile(json, &node.get_issuer_profile().await)?;
+        }
+
+        Command::Officers { command } => {
+            if let Some(policy) = command.policy() {
+                node.set_approval_policy(policy).await?;
+            }
+            emit_approval_policy(json, &node.get_approval_policy().await)?;
+        }
+
+        Command::RotateKey { reason } => {
+            let new_key = load_or_create_next_secret_key(&cli.data_dir)?;
+            let rotation = node.rotate_key(&new_key, reason.clone()).await?;
+            finish_key_rotation(cli, &rotation)?;
+        }
+
+        Command::SigningKey {
+            command: SigningKeyCommand::Unbind { signing_key },
+        } => {
+            let unbinding = node.unbind_signing_key(*signing_key).await?;
+            emit_key_unbinding(json, &unbinding)?;
+        }
+
+        Command::SigningKey { .. } => {
+            let binding = node.key_binding().await;
+            emit_key_binding(json, &binding)?;
+        }
+
+        Command::Did { command } => {
+            let document = match command {
+                DidCommand::Show => node.did_document().await,
+                DidCommand::Resolve { did } => node.resolve_did(did.clone()).await?,
+            };
+            emit_did_document(json, &document)?;
+        }
+
+        Command::Officer { command } => match command {
+            OfficerCommand::Queue { issuer } => {
+                let requests = node.request_approval_queue(*issuer).await?;
+                let proposal = node.request_policy_proposal(*issuer).await?;
+                emit(json, &requests, || {
+                    let mut lines: Vec<_> = requests.iter().map(describe_progress).collect();
+                    if let Some(proposal) = &proposal {
+                        lines.push(format!("Proposed: {}", describe_policy(proposal)));
+                    }
+                    lines.join("\n")
+                })?;
+            }
+            OfficerCommand::Approve { issuer, request_id } => {
+                let progress = node.approve_as_officer(*issuer, request_id.clone()).await?;
+                emit_progress(json, &progress)?;
+            }
+            OfficerCommand::ApprovePolicy { issuer, version } => {
+                let status = node.approve_policy_as_officer(*issuer, *version).await?;
+                emit_approval_policy(json, &status)?;
+            }
+        },
+
+        Command::FetchProfile { issuer } => {
+            let signed = node.request_issuer_profile(*issuer).await?;
+            emit(json, &signed, || {
+                format!("{}\nsigned by {} at {}", describe_profile(&signed.profile), signed.issuer_node_id, signed.signed_at)
+            })?;
+        }
+
+        Command::Credentials => {
+            let credentials = node.get_received_credentials().await?;
+            emit(json, &credentials, || {
+                credentials
+                    .iter()
+                    .map(|r| match (&r.credential, &r.error) {
+                        (Some(c), _) => format!(
+                            "{}  issued  {} / {}  gross {} / net {} {}  {}",
+                            r.request_id, c.credential().employer_name, r.issuer_node_id,
+                            c.credential().gross_salary, c.credential().net_salary,
+                            c.credential().currency, c.credential().pay_period
+                        ),
+                        (None, error) => format!(
+                            "{}  rejected  {}",
+                            r.request_id,
+                            error.as_deref().unwrap_or("no reason given")
+                        ),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+
+        Command::Export { request_id, format } => {
+            let response = node
+                .get_received_credential(request_id.clone())
+                .await?
+                .with_context(|| format!("no credential received for request {request_id}"))?;
+            match format {
+                ExportFormat::W3c => {
+                    let document = response
+                        .verifiable_credential
+                        .with_context(|| format!("the issuer sent no verifiable credential for request {request_id}"))?;
+                    let pretty = serde_json::to_string_pretty(&document)?;
+                    emit(json, &document, || pretty)?;
+                }
+                ExportFormat::Jwt => {
+                    let jws = response
+                        .jws
+                        .with_context(|| format!("the issuer sent no JWT-VC for request {request_id}"))?;
+                    emit(json, &jws, || jws.clone())?;
+                }
+                ExportFormat::Qr | ExportFormat::Compact => {
+                    let signed = response
+                        .credential
+                        .with_context(|| format!("no credential received for request {request_id}"))?;
+                    let text = if matches!(format, ExportFormat::Qr) {
+                        signed.to_qr_text()?
+                    } else {
+                        signed.to_compact_base64url()?
+                    };
+                    emit(json, &text, || text.clone())?;
+                }
+            }
+        }
+
+        Command::Present {
+            verifier,
+            request_ids,
+            envelope,
+            disclose,
+            at_least,
+            jws,
+        } => {
+            if *jws {
+                let [request_id] = request_ids.as_slice() else {
+                    anyhow::bail!("--jws presents a single credential");
+                };
+                let token = node
+                    .get_received_credential(request_id.clone())
+                    .await?
+                    .and_then(|response| response.jws)
+                    .with_context(|| format!("no JWT-VC received for request {request_id}"))?;
+                let reply = node.present_jws(*verifier, token).await?;
+                emit(json, &reply, || describe_message(&reply))?;
+                return Ok(());
+            }
+            if !disclose.is_empty() || at_least.is_some() {
+                let [request_id] = request_ids.as_slice() else {
+                    anyhow::bail!("--disclose and --at-least present a single credential");
+                };
+                let credential_id = node
+                    .get_received_credential(request_id.clone())
+                    .await?
+                    .and_then(|response| response.credential)
+                    .with_context(|| format!("no issued credential for request {request_id}"))?
+                    .id()
+                    .to_string();
+                let reply = match at_least {
+                    Some(threshold) => node.present_threshold_proof(*verifier, credential_id, threshold.clone()).await?,
+                    None => {
+                        let fields = disclose.iter().map(|&field| field.into()).collect();
+                        node.present_disclosure(*verifier, credential_id, fields).await?
+                    }
+                };
+                emit(json, &reply, || describe_message(&reply))?;
+                return Ok(());
+            }
+            let mut credentials = Vec::new();
+            for request_id in request_ids {
+                let credential = node
+                    .get_received_credential(request_id.clone())
+                    .await?
+                    .and_then(|response| response.credential)
+                    .with_context(|| format!("no issued credential for request {request_id}"))?;
+                credentials.push(credential);
+            }
+            if credentials.len() > 1 {
+                let verdict = node.present_credentials(*verifier, credentials).await?;
+                emit(json, &verdict, || describe_verdict(&verdict))?;
+                return Ok(());
+            }
+            let credential = credentials.remove(0);
+            let envelope = if *envelope {
+                let challenge = node.request_challenge(*verifier).await?;
+                Some(node.sign_presentation(*verifier, challenge.nonce, &[&credential])?)
+            } else {
+                None
+            };
+            let message = CredentialMessage::PresentCredential {
+                presentation_id: uuid::Uuid::new_v4().to_string(),
+                credential,
+                envelope,
+            };
+            let reply = send(node, *verifier, message).await?;
+            emit(json, &reply, || describe_message(&reply))?;
+        }
+
+        Command::Inbox { command } => match command {
+            InboxCommand::List => {
+                let requests = node.get_presentation_requests().await;
+                let mut lines = Vec::new();
+                for r in &requests {
+                    lines.push(format!(
+                        "{}  {:?}  from {}  expires {}  {}",
+                        r.request_id, r.status, r.verifier_node_id, r.expires_at, describe_constraints(&r.constraints)
+                    ));
+                    if r.status == RequestStatus::Pending {
+                        for credential in node.get_matching_credentials(r.request_id.clone()).await? {
+                            lines.push(format!(
+                                "    matches {}  {}  net {} {}  {}",
+                                credential.id(), credential.credential().employer_name,
+                                credential.credential().net_salary, credential.credential().currency,
+                                credential.credential().pay_period
+                            ));
+                        }
+                    } else if let Some(message) = &r.message {
+                        lines.push(format!("    {message}"));
+                    }
+                }
+                emit(json, &requests, || lines.join("\n"))?;
+            }
+            InboxCommand::Approve {
+                request_id,
+                credential_ids,
+            } => {
+                let request = node
+                    .approve_presentation_request(request_id.clone(), credential_ids.clone())
+                    .await?;
+                emit(json, &request, || {
+                    format!(
+                        "{}: {}",
+                        if request.is_valid == Some(true) { "VALID" } else { "INVALID" },
+                        request.message.as_deref().unwrap_or_default()
+                    )
+                })?;
+            }
+            InboxCommand::Prove {
+                request_id,
+                credential_id,
+            } => {
+                let request = node
+                    .prove_presentation_request(request_id.clone(), credential_id.clone())
+                    .await?;
+                emit(json, &request, || {
+                    format!(
+                        "{}: {}",
+                        if request.is_valid == Some(true) { "VALID" } else { "INVALID" },
+                        request.message.as_deref().unwrap_or_default()
+                    )
+                })?;
+            }
+            InboxCommand::Decline { request_id, reason } => {
+                node.reject_presentation_request(request_id.clone(), reason.clone()).await?;
+                emit(json, &serde_json::Value::Null, || format!("Request {request_id} declined"))?;
+            }
+        },
+
+        Command::Ask {
+            employee,
+            issuers,
+            currency,
+            pay_period_from,
+            pay_period_to,
+            min_net_salary,
+            expires_in,
+            wait,
+        } => {
+            let constraints = PresentationConstraints {
+                issuers: issuers.clone(),
+                currency: currency.clone(),
+                pay_period_from: pay_period_from.clone(),
+                pay_period_to: pay_period_to.clone(),
+                min_net_salary: min_net_salary.clone(),
+            };
+            let mut events = node.accept_events();
+            let request = node.request_presentation(*employee, constraints, *expires_in).await?;
+            emit(json, &request, || {
+                format!("Request {} sent to {}, expires {}", request.request_id, employee, request.expires_at)
+            })?;
+
+            if *wait > 0 {
+                eprintln!("Waiting up to {wait}s for the employee's answer to request {}", request.request_id);
+                let answer = wait_for(&mut events, *wait, |event| match event {
+                    AcceptEvent::ResponseSent { message, .. } => match &message {
+                        CredentialMessage::VerificationResult { presentation_id: id, .. }
+                        | CredentialMessage::RequestQueued { request_id: id, .. }
+                            if *id == request.request_id =>
+                        {
+                            Some(message)
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .await?;
+                match answer {
+                    CredentialMessage::VerificationResult { .. } => emit(json, &answer, || describe_message(&answer))?,
+                    _ => {
+                        let declined = node
+                            .get_sent_presentation_requests()
+                            .await
+                            .into_iter()
+                            .find(|sent| sent.request_id == request.request_id)
+                            .with_context(|| format!("request {} not found", request.request_id))?;
+                        emit(json, &declined, || {
+                            format!(
+                                "Request {} declined: {}",
+                                declined.request_id,
+                                declined.decline_reason.as_deref().unwrap_or("no reason given")
+                            )
+                        })?;
+                    }
+                }
+            }
+        }
+
+        Command::Asked => {
+            let requests = node.get_sent_presentation_requests().await;
+            emit(json, &requests, || {
+                requests
+                    .iter()
+                    .map(|r| {
+                        format!(
+                            "{}  {:?}  to {}  expires {}  {}",
+                            r.request_id, r.status, r.employee_node_id, r.expires_at, describe_constraints(&r.constraints)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+
+        Command::Issuers { command } => {
+            match command {
+                IssuersCommand::Export { revocations: false } => {
+                    let trust_store = node.export_trust_store().await;
+                    let pretty = serde_json::to_string_pretty(&trust_store)?;
+                    return emit(json, &trust_store, || pretty);
+                }
+                IssuersCommand::Export { revocations: true } => {
+                    let revocations = node.export_revocations().await;
+                    let pretty = serde_json::to_string_pretty(&revocations)?;
+                    return emit(json, &revocations, || pretty);
+                }
+                IssuersCommand::Rotations => {
+                    let rotations = node.get_key_rotations().await;
+                    return emit(json, &rotations, || {
+                        rotations.iter().map(describe_rotation).collect::<Vec<_>>().join("\n")
+                    });
+                }
+                IssuersCommand::List => {}
+                IssuersCommand::Add { node_id } => node.add_trusted_issuer(*node_id).await?,
+                IssuersCommand::Remove { node_id } => node.remove_trusted_issuer(*node_id).await?,
+            }
+            let issuers = node.get_trusted_issuers().await;
+            emit(json, &issuers, || {
+                issuers.iter().map(|id| id.to_string()).collect::<Vec<_>>().join("\n")
+            })?;
+        }
+
+        Command::Verified { proofs: true, .. } => {
+            let records = node.get_verified_threshold_proofs().await;
+            emit(json, &records, || {
+                records
+                    .iter()
+                    .map(|r| {
+                        format!(
+                            "{}  valid={} trusted={} revoked={} {:?} {:?}  {} ({})  issuer {}",
+                            r.presentation_id, r.is_valid, r.is_trusted, r.is_revoked, r.validity, r.holder_binding,
+                            describe_claims(&r.claims), r.employee_node_id, r.issuer_node_id
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+
+        Command::Verified { disclosures: true, .. } => {
+            let records = node.get_verified_disclosures().await;
+            emit(json, &records, || {
+                records
+                    .iter()
+                    .map(|r| {
+                        format!(
+                            "{}  valid={} trusted={} revoked={} {:?} {:?}  {} ({})  issuer {}",
+                            r.presentation_id, r.is_valid, r.is_trusted, r.is_revoked, r.validity, r.holder_binding,
+                            describe_claims(&r.claims), r.employee_node_id, r.issuer_node_id
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+
+        Command::Verified { .. } => {
+            let records = node.get_verified_credentials().await;
+            emit(json, &records, || {
+                records
+                    .iter()
+                    .map(|r| {
+                        let bundle = match (r.bundle_index, r.bundle_size) {
+                            (Some(index), Some(size)) => format!(" [{}/{}]", index + 1, size),
+                            _ => String::new(),
+                        };
+                        let mut line = format!(
+                            "{}{}  valid={} trusted={} revoked={} {:?} {:?}  {} ({})  issuer {}",
+                            r.presentation_id, bundle, r.is_valid, r.is_trusted, r.is_revoked, r.validity, r.holder_binding,
+                            r.credential.credential().employee_name, r.employee_node_id, r.issuer_node_id
+                        );
+                        for signer in r.signers.iter().filter(|signer| signer.role != SignerRole::Issuer) {
+                            line.push_str(&format!("\n    {}", describe_signer(signer)));
+                        }
+                        line
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Serves the control API until Ctrl-C or SIGTERM
+#[cfg(unix)]
+async fn run_daemon(cli: &Cli, node: &Arc<PeerNode>) -> Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let server = ControlServer::bind(cli.data_dir.join(CONTROL_SOCKET), node.clone()).await?;
+    eprintln!(
+        "Issuer {} running, control API on {}",
+        node.endpoint().id(),
+        server.path().display()
+    );
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut events = node.accept_events();
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+            Some(event) = events.next() => emit(cli.json, &event, || describe_event(&event))?,
+        }
+    }
+    drop(server);
+    Ok(())
+}
+
+/// Serves `<data-dir>/signing_key`, generated on first use, on `<data-dir>/signer.sock`
+#[cfg(unix)]
+async fn run_signer(cli: &Cli) -> Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let signing_key = match load_signing_key(&cli.data_dir)? {
+        Some(signing_key) => signing_key,
+        None => create_signing_key(&cli.data_dir)?,
+    };
+    let server = SignerServer::bind(cli.data_dir.join(SIGNER_SOCKET), signing_key.clone()).await?;
+    eprintln!("Signer for {} listening on {}", signing_key.public(), server.path().display());
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+    drop(server);
+    Ok(())
+}
+
+/// Runs a `pending`, `profile`, `officers`, `rotate-key` or `signing-key` command against the daemon that owns the data directory
+#[cfg(unix)]
+async fn run_remote(cli: &Cli, mut client: ControlClient) -> Result<()> {
+    let json = cli.json;
+    let command = match &cli.command {
+        Command::Pending { command } => command,
+        Command::Officers { command } => {
+            if let Some(policy) = command.policy() {
+                client.request(&ControlRequest::SetApprovalPolicy { policy }).await?;
+            }
+            let policy = serde_json::from_value(client.request(&ControlRequest::GetApprovalPolicy).await?)?;
+            return emit_approval_policy(json, &policy);
+        }
+        Command::Profile { command } => {
+            let mut profile: IssuerProfile = serde_json::from_value(client.request(&ControlRequest::GetProfile).await?)?;
+            if let ProfileCommand::Set { .. } = command {
+                profile = command.apply(profile);
+                client.request(&ControlRequest::SetProfile { profile: profile.clone() }).await?;
+            }
+            return emit_profile(json, &profile);
+        }
+        Command::RotateKey { reason } => {
+            let new_key = load_or_create_next_secret_key(&cli.data_dir)?;
+            let request = ControlRequest::RotateKey {
+                new_secret_key: new_key,
+                reason: reason.clone(),
+            };
+            let rotation = serde_json::from_value(client.request(&request).await?)?;
+            return finish_key_rotation(cli, &rotation);
+        }
+        Command::SigningKey {
+            command: SigningKeyCommand::Unbind { signing_key },
+        } => {
+            let request = ControlRequest::UnbindSigningKey {
+                signing_key: *signing_key,
+            };
+            let unbinding = serde_json::from_value(client.request(&request).await?)?;
+            return emit_key_unbinding(json, &unbinding);
+        }
+        Command::SigningKey { .. } => {
+            let binding = serde_json::from_value(client.request(&ControlRequest::GetKeyBinding).await?)?;
+            return emit_key_binding(json, &binding);
+        }
+        Command::Did { command } => {
+            let request = match command {
+                DidCommand::Show => ControlRequest::GetDidDocument,
+                DidCommand::Resolve { did } => ControlRequest::ResolveDid { did: did.clone() },
+            };
+            let document = serde_json::from_value(client.request(&request).await?)?;
+            return emit_did_document(json, &document);
+        }
+        _ => unreachable!("only pending, profile, officers, rotate-key, signing-key and did commands are forwarded"),
+    };
+    let (request_id, request, timeout) = match command {
+        PendingCommand::List => {
+            let requests = client.request(&ControlRequest::ListPending).await?;
+            let requests: Vec<PendingCredentialRequest> = serde_json::from_value(requests)?;
+            return emit_pending(json, &requests);
+        }
+        PendingCommand::Approve {
+            request_id,
+            valid_from,
+            expires_at,
+            timeout,
+        } => {
+            let validity = ValidityWindow {
+                valid_from: valid_from.clone(),
+                expires_at: expires_at.clone(),
+            };
+            let request = ControlRequest::Approve {
+                request_id: request_id.clone(),
+                validity,
+            };
+            (request_id, request, timeout)
+        }
+        PendingCommand::Reject {
+            request_id,
+            reason,
+            timeout,
+        } => {
+            let request = ControlRequest::Reject {
+                request_id: request_id.clone(),
+                reason: reason.clone(),
+            };
+            (request_id, request, timeout)
+        }
+    };
+
+    // Subscribe first so the delivery event cannot be missed
+    let mut events = ControlClient::connect(cli.data_dir.join(CONTROL_SOCKET))
+        .await?
+        .subscribe()
+        .await?;
+    let result = client.request(&request).await?;
+    if let ControlRequest::Approve { .. } = request {
+        let request: PendingCredentialRequest = serde_json::from_value(result)?;
+        if request.status == RequestStatus::Pending {
+            let policy: ApprovalPolicyStatus =
+                serde_json::from_value(client.request(&ControlRequest::GetApprovalPolicy).await?)?;
+            return emit_progress(json, &request.approval_progress(policy.version()));
+        }
+    }
+    wait_for_delivery(&mut events, request_id, *timeout, json).await
+}
+
+#[cfg(unix)]
+async fn stream_daemon_events(cli: &Cli) -> Result<()> {
+    let client = ControlClient::connect(cli.data_dir.join(CONTROL_SOCKET))
+        .await
+        .context("is the daemon running?")?;
+    let mut events = client.subscribe().await?;
+    while let Some(event) = events.next().await {
+        emit(cli.json, &event, || describe_event(&event))?;
+    }
+    Ok(())
+}
+
+/// Loads the node key from `<data_dir>/secret_key`, generating it on first use
+fn load_or_create_secret_key(data_dir: &Path) -> Result<SecretKey> {
+    let path = data_dir.join("secret_key");
+    match std::fs::read_to_string(&path) {
+        Ok(key) => key.trim().parse().context("failed to parse secret key"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => create_secret_key_file(&path),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Generates a key into the new file `path`, readable by its owner only from the
+/// moment it is created; fails if the file exists
+fn create_secret_key_file(path: &Path) -> Result<SecretKey> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    let secret_key = SecretKey::generate(&mut rand::rng());
+    std::io::Write::write_all(&mut file, hex::encode(secret_key.to_bytes()).as_bytes())?;
+    file.sync_all()?;
+    Ok(secret_key)
+}
+
+/// Reads JSON from `path`, or the default value without one
+fn read_json_file<T: DeserializeOwned + Default>(path: Option<&Path>) -> Result<T> {
+    let Some(path) = path else {
+        return Ok(T::default());
+    };
+    let contents = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Loads the key a rotation hands over to from `<data_dir>/secret_key.next`,
+/// generating it on first use so an interrupted rotation can be retried
+fn load_or_create_next_secret_key(data_dir: &Path) -> Result<SecretKey> {
+    let path = data_dir.join("secret_key.next");
+    if path.exists() {
+        let key = std::fs::read_to_string(&path)?;
+        return key.trim().parse().context("failed to parse next secret key");
+    }
+    create_secret_key_file(&path)
+}
+
+/// Loads the credential signing key from `<data_dir>/signing_key`, if one was generated
+fn load_signing_key(data_dir: &Path) -> Result<Option<SecretKey>> {
+    let path = data_dir.join("signing_key");
+    match std::fs::read_to_string(&path) {
+        Ok(key) => Ok(Some(key.trim().parse().context("failed to parse signing key")?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Signs credentials through the daemon given by `--remote-signer`, or with
+/// `<data_dir>/signing_key` if one was generated; `None` signs with the node key
+async fn load_signer(cli: &Cli) -> Result<Option<Arc<dyn CredentialSigner>>> {
+    let signing_key = load_signing_key(&cli.data_dir)?;
+    #[cfg(unix)]
+    if let Some(path) = &cli.remote_signer {
+        if signing_key.is_some() {
+            anyhow::bail!("{} holds a signing key; remove it to sign through --remote-signer", cli.data_dir.display());
+        }
+        return Ok(Some(Arc::new(SocketSigner::connect(path).await?)));
+    }
+    Ok(signing_key.map(|signing_key| Arc::new(MemorySigner::new(signing_key)) as Arc<dyn CredentialSigner>))
+}
+
+/// Generates `<data_dir>/signing_key`, refusing to replace an existing one
+fn create_signing_key(data_dir: &Path) -> Result<SecretKey> {
+    let path = data_dir.join("signing_key");
+    if path.exists() {
+        anyhow::bail!("{} already exists", path.display());
+    }
+    create_secret_key_file(&path)
+}
+
+/// Retires the rotated key file and moves the new key in its place
+fn finish_key_rotation(cli: &Cli, rotation: &SignedKeyRotation) -> Result<()> {
+    let retired = cli
+        .data_dir
+        .join(format!("secret_key.retired-{}", rotation.rotation.previous_node_id.fmt_short()));
+    std::fs::rename(cli.data_dir.join("secret_key"), &retired)?;
+    std::fs::rename(cli.data_dir.join("secret_key.next"), cli.data_dir.join("secret_key"))?;
+    emit(cli.json, rotation, || {
+        format!(
+            "{}\nOld key kept in {}; restart the node to issue with the new key",
+            describe_rotation(rotation),
+            retired.display()
+        )
+    })
+}
+
+async fn unlock_wallet_from_env(node: &PeerNode) -> Result<()> {
+    if !node.wallet_status().await?.locked {
+        return Ok(());
+    }
+    let passphrase = std::env::var(WALLET_PASSPHRASE_ENV)
+        .with_context(|| format!("wallet is locked; set {WALLET_PASSPHRASE_ENV} to unlock it"))?;
+    node.unlock_wallet(Some(passphrase)).await
+}
+
+/// Sends `message` and returns the peer's reply
+async fn send(node: &PeerNode, node_id: EndpointId, message: CredentialMessage) -> Result<CredentialMessage> {
+    let mut events = node.connect(node_id, message);
+    let mut reply = None;
+    while let Some(event) = events.next().await {
+        match event {
+            ConnectEvent::ResponseReceived { message, .. } => reply = Some(message),
+            ConnectEvent::Closed { error: Some(error) } => anyhow::bail!("connection to {node_id} failed: {error}"),
+            ConnectEvent::Closed { error: None } => break,
+            _ => {}
+        }
+    }
+    reply.with_context(|| format!("no reply from {node_id}"))
+}
+
+async fn wait_for_delivery(
+    events: &mut BoxStream<AcceptEvent>,
+    request_id: &str,
+    timeout: u64,
+    json: bool,
+) -> Result<()> {
+    let event = wait_for(events, timeout, |event| match &event {
+        AcceptEvent::ResponseDelivered { request_id: id, .. } if id == request_id => Some(event),
+        _ => None,
+    })
+    .await
+    .context("response not delivered yet; it is resent whenever the issuer restarts")?;
+    emit(json, &event, || describe_event(&event))?;
+    match event {
+        AcceptEvent::ResponseDelivered { error: Some(error), .. } => anyhow::bail!("delivery failed: {error}"),
+        _ => Ok(()),
+    }
+}
+
+/// Waits up to `timeout` seconds for an event accepted by `filter`
+async fn wait_for<T>(
+    events: &mut BoxStream<AcceptEvent>,
+    timeout: u64,
+    mut filter: impl FnMut(AcceptEvent) -> Option<T>,
+) -> Result<T> {
+    let wait = async {
+        while let Some(event) = events.next().await {
+            if let Some(found) = filter(event) {
+                return Ok(found);
+            }
+        }
+        Err(anyhow::anyhow!("event stream closed"))
+    };
+    tokio::time::timeout(Duration::from_secs(timeout), wait)
+        .await
+        .context("timed out")?
+}
+
+fn emit_pending(json: bool, requests: &[PendingCredentialRequest]) -> Result<()> {
+    emit(json, &requests, || {
+        requests
+            .iter()
+            .map(|r| {
+                let approvals = if r.approvals_required > 0 && r.status == RequestStatus::Pending {
+                    format!(" ({}/{} approvals)", r.approvals.len(), r.approvals_required)
+                } else {
+                    String::new()
+                };
+                format!(
+                    "{}  {:?}{}{}  {} ({})  gross {} / net {} {}  {}",
+                    r.request_id,
+                    r.status,
+                    approvals,
+                    if r.undelivered_response.is_some() { " (undelivered)" } else { "" },
+                    r.employee_name, r.employee_node_id,
+                    r.gross_salary, r.net_salary, r.currency, r.pay_period
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+fn emit_approval_policy(json: bool, status: &ApprovalPolicyStatus) -> Result<()> {
+    emit(json, status, || {
+        let mut lines = vec![match &status.published {
+            Some(policy) => describe_policy(policy),
+            None => "Requests are approved by this node alone".to_string(),
+        }];
+        if let Some(proposal) = &status.proposed {
+            lines.push(format!(
+                "Proposed, signed by {} current officers: {}",
+                proposal.signatures.len(),
+                describe_policy(proposal)
+            ));
+        }
+        lines.join("\n")
+    })
+}
+
+fn describe_policy(signed: &SignedApprovalPolicy) -> String {
+    let PublishedApprovalPolicy { version, policy, published_at, .. } = &signed.policy;
+    let mut lines = vec![format!(
+        "version {version} ({published_at}): {} of {} officers must approve each request:",
+        policy.threshold,
+        policy.officers.len()
+    )];
+    lines.extend(policy.officers.iter().map(|officer| format!("    {officer}")));
+    lines.join("\n")
+}
+
+fn describe_rotation(rotation: &SignedKeyRotation) -> String {
+    let KeyRotation { previous_node_id, new_node_id, rotated_at, reason } = &rotation.rotation;
+    let mut line = format!("{previous_node_id} rotated to {new_node_id} at {rotated_at}");
+    if let Some(reason) = reason {
+        line.push_str(&format!(" ({reason})"));
+    }
+    line
+}
+
+fn emit_key_binding(json: bool, binding: &Option<SignedKeyBinding>) -> Result<()> {
+    emit(json, binding, || match binding {
+        Some(SignedKeyBinding { binding, .. }) => format!(
+            "Credentials are signed with {} on behalf of {} (bound at {})",
+            binding.signing_key, binding.node_id, binding.bound_at
+        ),
+        None => "Credentials are signed with the node key".to_string(),
+    })
+}
+
+fn emit_key_unbinding(json: bool, unbinding: &SignedKeyUnbinding) -> Result<()> {
+    let KeyUnbinding {
+        node_id,
+        signing_key,
+        unbound_at,
+    } = &unbinding.unbinding;
+    emit(json, unbinding, || {
+        format!("Signing key {signing_key} unbound from {node_id} at {unbound_at}; verifiers refuse its credentials")
+    })
+}
+
+fn emit_did_document(json: bool, document: &DidDocument) -> Result<()> {
+    let pretty = serde_json::to_string_pretty(document)?;
+    emit(json, document, || pretty)
+}
+
+fn emit_progress(json: bool, progress: &ApprovalProgress) -> Result<()> {
+    emit(json, progress, || describe_progress(progress))
+}
+
+fn describe_progress(progress: &ApprovalProgress) -> String {
+    let terms = &progress.terms;
+    let mut lines = vec![format!(
+        "{}  {:?}  {}/{} approvals  {} ({})  gross {} / net {} {}  {}",
+        progress.request_id,
+        progress.status,
+        progress.approved_by.len(),
+        progress.approvals_required,
+        terms.employee_name, terms.employee_node_id,
+        terms.gross_salary, terms.net_salary, terms.currency, terms.pay_period
+    )];
+    match &progress.draft {
+        Some(draft) => {
+            let credential = draft.credential.credential();
+            lines.push(format!(
+                "    credential {}: valid from {} to {}",
+                credential.id,
+                credential.valid_from.as_deref().unwrap_or(&credential.issued_at),
+                credential.expires_at.as_deref().unwrap_or("no expiry")
+            ));
+        }
+        None => lines.push("    not signed by the issuer yet".to_string()),
+    }
+    lines.extend(progress.approved_by.iter().map(|officer| format!("    approved by {officer}")));
+    lines.join("\n")
+}
+
+fn emit_profile(json: bool, profile: &IssuerProfile) -> Result<()> {
+    emit(json, profile, || describe_profile(profile))
+}
+
+fn describe_imported(imported: &ImportedCredential) -> String {
+    let credential = imported.credential.credential();
+    let check = &imported.check;
+    let mut lines = vec![format!(
+        "Credential {} from {} ({}) to {} ({})
+gross {} / net {} {}  {}
+{}: {}",
+        credential.id,
+        credential.employer_name,
+        credential.employer_node_id,
+        credential.employee_name,
+        credential.employee_node_id,
+        credential.gross_salary,
+        credential.net_salary,
+        credential.currency,
+        credential.pay_period,
+        if check.is_valid { "VALID" } else { "INVALID" },
+        check.message
+    )];
+    if check.signers.len() > 1 {
+        lines.extend(check.signers.iter().map(|signer| format!("    {}", describe_signer(signer))));
+    }
+    lines.join("\n")
+}
+
+fn describe_profile(profile: &IssuerProfile) -> String {
+    let processor = match &profile.default_payroll_processor {
+        Some(processor) => format!("{} ({})", processor.name, processor.node_id),
+        None => "none".to_string(),
+    };
+    format!(
+        "Organization:      {}\nLegal identifier:  {}\nJurisdiction:      {}\nPayroll processor: {}",
+        profile.organization_name, profile.legal_identifier, profile.jurisdiction, processor
+    )
+}
+
+fn describe_verdict(verdict: &PresentationVerdict) -> String {
+    let mut lines = vec![format!(
+        "{}: {}",
+        if verdict.is_valid { "VALID" } else { "INVALID" },
+        verdict.message
+    )];
+    for check in &verdict.credentials {
+        lines.push(format!(
+            "    {}  {}: {}",
+            check.credential_id,
+            if check.is_valid { "valid" } else { "invalid" },
+            check.message
+        ));
+        if check.signers.len() > 1 {
+            lines.extend(check.signers.iter().map(|signer| format!("        {}", describe_signer(signer))));
+        }
+    }
+    lines.join("\n")
+}
+
+fn describe_signer(signer: &SignerCheck) -> String {
+    format!(
+        "{} {}: {}, {}",
+        signer.role.as_str(),
+        signer.node_id,
+        if signer.signed { "signed" } else { "NOT SIGNED" },
+        if signer.is_trusted { "trusted" } else { "untrusted" }
+    )
+}
+
+fn describe_constraints(constraints: &PresentationConstraints) -> String {
+    let mut parts = Vec::new();
+    if !constraints.issuers.is_empty() {
+        let issuers: Vec<_> = constraints.issuers.iter().map(|id| id.fmt_short().to_string()).collect();
+        parts.push(format!("issuer in [{}]", issuers.join(", ")));
+    }
+    if let Some(currency) = &constraints.currency {
+        parts.push(format!("currency {currency}"));
+    }
+    match (&constraints.pay_period_from, &constraints.pay_period_to) {
+        (Some(from), Some(to)) => parts.push(format!("pay period {from}..{to}")),
+        (Some(from), None) => parts.push(format!("pay period from {from}")),
+        (None, Some(to)) => parts.push(format!("pay period until {to}")),
+        (None, None) => {}
+    }
+    if let Some(min) = &constraints.min_net_salary {
+        parts.push(format!("net salary >= {min}"));
+    }
+    if parts.is_empty() {
+        "any credential".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+fn describe_claims(claims: &DisclosedClaims) -> String {
+    let mut parts = Vec::new();
+    if let Some(name) = &claims.employee_name {
+        parts.push(name.clone());
+    }
+    if let Some(employer) = &claims.employer_name {
+        parts.push(format!("employer {employer}"));
+    }
+    if let Some(processor) = &claims.payroll_processor_name {
+        parts.push(format!("processor {processor}"));
+    }
+    let currency = claims.currency.as_deref().unwrap_or("");
+    if let Some(gross) = &claims.gross_salary {
+        parts.push(format!("gross {gross} {currency}").trim_end().to_string());
+    }
+    if let Some(net) = &claims.net_salary {
+        parts.push(format!("net {net} {currency}").trim_end().to_string());
+    } else if let Some(bound) = &claims.net_salary_at_least {
+        parts.push(format!("net >= {bound} {currency}").trim_end().to_string());
+    }
+    if claims.gross_salary.is_none() && claims.net_salary.is_none() && claims.net_salary_at_least.is_none() && !currency.is_empty() {
+        parts.push(currency.to_string());
+    }
+    if let Some(period) = &claims.pay_period {
+        parts.push(period.clone());
+    }
+    if let Some(mode) = &claims.payment_mode {
+        parts.push(mode.as_str().to_string());
+    }
+    if parts.is_empty() {
+        "no fields disclosed".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+fn emit<T: Serialize>(json: bool, value: &T, text: impl FnOnce() -> String) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(value)?);
+    } else {
+        let text = text();
+        if !text.is_empty() {
+            println!("{text}");
+        }
+    }
+    Ok(())
+}
+
+fn describe_message(message: &CredentialMessage) -> String {
+    match message {
+        CredentialMessage::RequestQueued { request_id, message } => format!("Request {request_id} queued: {message}"),
+        CredentialMessage::IssueResponse { request_id, credential, error, .. } => match credential {
+            Some(credential) => format!("Credential {} issued for request {request_id}", credential.id()),
+            None => format!("Request {request_id} rejected: {}", error.as_deref().unwrap_or("no reason given")),
+        },
+        CredentialMessage::VerificationResult { is_valid, message, signers, .. } => {
+            let mut lines = vec![format!("{}: {message}", if *is_valid { "VALID" } else { "INVALID" })];
+            // The issuer alone is already covered by the message
+            if signers.len() > 1 {
+                lines.extend(signers.iter().map(|signer| format!("    {}", describe_signer(signer))));
+            }
+            lines.join("\n")
+        }
+        CredentialMessage::PresentationResult(verdict) => describe_verdict(verdict),
+        CredentialMessage::PresentThresholdProof { presentation_id, proof, .. } => {
+            format!("Presentation {presentation_id} proving net salary >= {} {}", proof.threshold, proof.credential.currency)
+        }
+        CredentialMessage::PresentDisclosure { presentation_id, credential, .. } => {
+            let fields: Vec<_> = credential.disclosures.iter().map(|d| d.field.as_str()).collect();
+            format!("Presentation {presentation_id} disclosing {}", if fields.is_empty() { "no fields".to_string() } else { fields.join(", ") })
+        }
+        CredentialMessage::PresentJws { presentation_id, .. } => format!("Presentation {presentation_id} of a JWT-VC"),
+        CredentialMessage::PresentCredentials { presentation_id, presentation } => {
+            format!("Presentation {presentation_id} of {} credentials", presentation.credentials.len())
+        }
+        CredentialMessage::PresentationRequest { request_id, constraints, .. } => {
+            format!("Presentation request {request_id}: {}", describe_constraints(constraints))
+        }
+        CredentialMessage::CoSignRequest { request_id, credential, .. } => {
+            format!("Co-signing request for credential {} of request {request_id}", credential.id())
+        }
+        CredentialMessage::CoSignResponse { request_id, co_signature, .. } => {
+            format!("Co-signature by {} for request {request_id}", co_signature.signer_node_id)
+        }
+        CredentialMessage::SubmitApproval { approval } => {
+            format!("Officer approval of request {} by {}", approval.request_id, approval.officer_node_id)
+        }
+        CredentialMessage::ApprovalRecorded(progress) => format!(
+            "Approval of request {} recorded ({}/{}, {:?})",
+            progress.request_id, progress.approved_by.len(), progress.approvals_required, progress.status
+        ),
+        CredentialMessage::PresentationDeclined { request_id, reason } => {
+            format!("Presentation request {request_id} declined: {}", reason.as_deref().unwrap_or("no reason given"))
+        }
+        CredentialMessage::Error { error_code, message, .. } => format!("Error {error_code}: {message}"),
+        other => format!("{other:?}"),
+    }
+}
+
+fn describe_event(event: &AcceptEvent) -> String {
+    match event {
+        AcceptEvent::Accepted { node_id } => format!("Connection from {node_id}"),
+        AcceptEvent::MessageReceived { node_id, message } => {
+            format!("Message from {node_id}: {}", describe_message(message))
+        }
+        AcceptEvent::ResponseSent { node_id, message, .. } => {
+            format!("Replied to {node_id}: {}", describe_message(message))
+        }
+        AcceptEvent::Closed { node_id, error } => match error {
+            Some(error) => format!("Connection with {node_id} failed: {error}"),
+            None => format!("Connection with {node_id} closed"),
+        },
+        AcceptEvent::ResponseDelivered { node_id, request_id, error } => match error {
+            Some(error) => format!("Response for request {request_id} not delivered to {node_id}: {error}"),
+            None => format!("Response for request {request_id} delivered to {node_id}"),
+        },
+        AcceptEvent::RevocationReceived { issuer_node_id, credential_id } => {
+            format!("Issuer {issuer_node_id} revoked credential {credential_id}")
+        }
+        AcceptEvent::KeyRotated { previous_node_id, new_node_id } => {
+            format!("Issuer key {previous_node_id} rotated to {new_node_id}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::discovery::static_provider::StaticProvider;
+    use trust_triangle::credentials::presentation::HolderBinding;
+    use trust_triangle::storage::MemoryStore;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("trust-triangle").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_cli_parsing() {
+        let node_id = SecretKey::generate(&mut rand::rng()).public().to_string();
+
+        let cli = parse(&["--json", "present", "--verifier", &node_id, "a", "b"]).unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.data_dir, PathBuf::from("trust-triangle-data"));
+        let Command::Present { request_ids, envelope, .. } = cli.command else {
+            panic!("not a present command");
+        };
+        assert_eq!(request_ids, vec!["a", "b"]);
+        assert!(!envelope);
+
+        let cli = parse(&["present", "--verifier", &node_id, "a", "--disclose", "net-salary,pay-period"]).unwrap();
+        let Command::Present { disclose, .. } = cli.command else {
+            panic!("not a present command");
+        };
+        let fields: Vec<DisclosableField> = disclose.into_iter().map(Into::into).collect();
+        assert_eq!(fields, vec![DisclosableField::NetSalary, DisclosableField::PayPeriod]);
+
+        // Global flags follow the subcommand too
+        let cli = parse(&["pending", "approve", "r1", "--data-dir", "issuer"]).unwrap();
+        assert_eq!(cli.data_dir, PathBuf::from("issuer"));
+        assert!(matches!(
+            cli.command,
+            Command::Pending { command: PendingCommand::Approve { timeout: 60, .. } }
+        ));
+        let cli = parse(&["signing-key", "unbind", &node_id]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::SigningKey { command: SigningKeyCommand::Unbind { .. } }
+        ));
+
+        // Malformed input is refused before any node starts
+        assert!(parse(&["present", "--verifier", &node_id]).is_err());
+        assert!(parse(&["present", "--verifier", "not-a-node-id", "a"]).is_err());
+        assert!(parse(&["present", "--verifier", &node_id, "a", "--disclose", "net-salary", "--at-least", "1"]).is_err());
+        assert!(parse(&["request", "--issuer", &node_id, "--name", "Alice"]).is_err());
+        assert!(parse(&["verified", "--disclosures", "--proofs"]).is_err());
+    }
+
+    #[test]
+    fn test_secret_key_file_is_created_private_and_kept() {
+        let data_dir = std::env::temp_dir().join(format!("trust-triangle-cli-{}", uuid::Uuid::new_v4()));
+        let secret_key = load_or_create_secret_key(&data_dir).unwrap();
+        assert_eq!(load_or_create_secret_key(&data_dir).unwrap().public(), secret_key.public());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(data_dir.join("secret_key")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert!(create_secret_key_file(&data_dir.join("secret_key")).is_err());
+        std::fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    /// Runs `args` against `node` as the binary would after spawning it
+    async fn run_command(node: &Arc<PeerNode>, args: &[&str]) -> Result<()> {
+        run(&parse(args)?, node).await
+    }
+
+    #[tokio::test]
+    async fn test_request_approve_present() {
+        let discovery = StaticProvider::new();
+        let mut nodes = Vec::new();
+        for role in [Role::Issuer, Role::Employee, Role::Verifier] {
+            let node = PeerNode::spawn(None, role, Arc::new(MemoryStore::default()), None, None).await.unwrap();
+            node.endpoint().discovery().add(discovery.clone());
+            nodes.push(Arc::new(node));
+        }
+        for node in &nodes {
+            discovery.add_endpoint_info(node.endpoint().addr());
+        }
+        let (issuer, employee, verifier) = (&nodes[0], &nodes[1], &nodes[2]);
+        let issuer_id = issuer.endpoint().id().to_string();
+        let verifier_id = verifier.endpoint().id().to_string();
+
+        run_command(verifier, &["issuers", "add", &issuer_id]).await.unwrap();
+        let salary = ["--gross-salary", "9000.00", "--net-salary", "7200.00", "--pay-period", "2025-01"];
+        let request = [&["request", "--issuer", &issuer_id, "--name", "Alice"][..], &salary].concat();
+        run_command(employee, &request).await.unwrap();
+        let pending = issuer.get_pending_requests().await;
+        assert_eq!(pending.len(), 1);
+        let request_id = pending[0].request_id.clone();
+
+        run_command(issuer, &["pending", "approve", &request_id, "--timeout", "10"]).await.unwrap();
+        let received = employee.get_received_credential(request_id.clone()).await.unwrap().unwrap();
+        assert!(received.credential.is_some(), "{:?}", received.error);
+
+        run_command(employee, &["present", "--verifier", &verifier_id, &request_id]).await.unwrap();
+        let verified = verifier.get_verified_credentials().await;
+        assert_eq!(verified.len(), 1);
+        assert!(verified[0].is_valid && verified[0].is_trusted, "{:?}", verified[0]);
+        assert_eq!(verified[0].holder_binding, HolderBinding::Connection);
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+}
@@ -1,20 +1,50 @@
 use anyhow::Result;
 use async_channel::Sender;
 use iroh::{
-    Endpoint, EndpointId, SecretKey,
+    Endpoint, EndpointId, PublicKey, SecretKey,
     endpoint::Connection,
     protocol::{AcceptError, ProtocolHandler, Router},
     discovery::mdns::MdnsDiscovery,
 };
-use n0_future::{Stream, StreamExt, boxed::BoxStream, task};
+use n0_future::{Stream, StreamExt, boxed::{BoxFuture, BoxStream}, task};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::broadcast;
 use tokio_stream::wrappers::BroadcastStream;
+use iroh_gossip::net::{Gossip, GOSSIP_ALPN};
 use tracing::info;
 
-use crate::credentials::income_credential::{SignedIncomeCredential, PaymentMode, IncomeCredential};
+use crate::credentials::income_credential::{
+    CredentialError, SignedIncomeCredential, PaymentMode, IncomeCredential, ValidityStatus, ValidityWindow,
+};
+use crate::challenge::{ChallengeRegistry, PresentationChallenge, generate_nonce};
+use crate::credentials::co_signature::{CoSignature, CoSigned, SignerRole};
+use crate::credentials::did::{DidDocument, DidResolver, KeyDidResolver, did_iroh, parse_did_iroh};
+use crate::credentials::issuer_profile::{IssuerProfile, SignedIssuerProfile};
+use crate::credentials::jwt::verify_jws;
+use crate::credentials::key_binding::{KeyBinding, KeyUnbinding, SignedKeyBinding, SignedKeyUnbinding, binding_key};
+use crate::credentials::signer::{CredentialSigner, MemorySigner};
+use crate::credentials::key_rotation::{KeyRotation, RotationLog, SignedKeyRotation};
+use crate::credentials::officer_approval::{
+    ApprovalCheck, ApprovalPolicy, CredentialDraft, OfficerApproval, PolicySignature,
+    PublishedApprovalPolicy, RequestTerms, SignedApprovalPolicy,
+};
+use crate::credentials::presentation::{
+    HolderBinding, PresentableCredential, PresentationConstraints, PresentationEnvelope, VerifiablePresentation,
+};
+use crate::credentials::revocation::{RevocationNotice, SignedRevocationNotice};
+use crate::credentials::selective_disclosure::{DisclosableField, DisclosedClaims, SelectiveCredential};
+use crate::credentials::status_list::StatusListState;
+use crate::credentials::threshold_proof::{ThresholdCredential, ThresholdProof};
+pub use crate::credentials::verification::{CredentialCheck, SignerCheck};
+use crate::credentials::verification::{
+    CredentialVerifier, OfflineRevocations, OfflineTrustStore, RevocationSource, SystemClock, TrustStore,
+};
+use crate::credentials::w3c::VerifiableCredential;
+use crate::revocation::RevocationRegistry;
+use crate::status_list::{StatusCheck, StatusListCache, StatusListProtocol, STATUS_LIST_ALPN};
+use crate::storage::{Collection, Store, StoredMap, StoredValue};
+use crate::wallet::{Wallet, WalletError, WalletStatus};
 
 /// Status of a pending credential request
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -39,6 +69,84 @@ pub struct PendingCredentialRequest {
     pub payment_mode: PaymentMode,
     pub requested_at: String,
     pub status: RequestStatus,
+    /// Approval or rejection the employee has not acknowledged yet; resent on restart
+    #[serde(default)]
+    pub undelivered_response: Option<CredentialMessage>,
+    /// When the employee acknowledged the approval or rejection
+    #[serde(default)]
+    pub delivered_at: Option<String>,
+    /// Officer approvals collected so far (organizational issuers)
+    #[serde(default)]
+    pub approvals: Vec<OfficerApproval>,
+    /// Officer approvals needed before the credential is issued; 0 when the issuer
+    /// approves on its own
+    #[serde(default)]
+    pub approvals_required: u32,
+    /// Credential signed for the request, delivered once officers approve it
+    /// (organizational issuers)
+    #[serde(default)]
+    pub draft: Option<CredentialDraft>,
+}
+
+impl PendingCredentialRequest {
+    /// The terms officers sign when approving the request
+    pub fn terms(&self) -> RequestTerms {
+        RequestTerms {
+            employee_node_id: self.employee_node_id,
+            employee_name: self.employee_name.clone(),
+            gross_salary: self.gross_salary.clone(),
+            net_salary: self.net_salary.clone(),
+            currency: self.currency.clone(),
+            pay_period: self.pay_period.clone(),
+            payment_mode: self.payment_mode.clone(),
+        }
+    }
+
+    pub fn approval_progress(&self, policy_version: u32) -> ApprovalProgress {
+        ApprovalProgress {
+            request_id: self.request_id.clone(),
+            terms: self.terms(),
+            requested_at: self.requested_at.clone(),
+            draft: self.draft.clone().map(Box::new),
+            policy_version,
+            approved_by: self.approvals.iter().map(|approval| approval.officer_node_id).collect(),
+            approvals_required: self.approvals_required,
+            status: self.status.clone(),
+        }
+    }
+}
+
+/// A request's officer approvals, as shown to officers (Issuer)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalProgress {
+    pub request_id: String,
+    pub terms: RequestTerms,
+    pub requested_at: String,
+    /// The credential officers approve, once the issuer has signed it
+    pub draft: Option<Box<CredentialDraft>>,
+    /// Version of the issuer's approval policy approvals are recorded under
+    pub policy_version: u32,
+    pub approved_by: Vec<EndpointId>,
+    pub approvals_required: u32,
+    pub status: RequestStatus,
+}
+
+/// An issuer's published approval policy and a change awaiting its officers (Issuer)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPolicyStatus {
+    /// Current version; `None` while the issuer approves requests on its own
+    pub published: Option<SignedApprovalPolicy>,
+    /// Next version, adopted once `threshold` of the current officers sign it
+    pub proposed: Option<SignedApprovalPolicy>,
+}
+
+impl ApprovalPolicyStatus {
+    /// Version approvals are recorded under; 0 without a published policy
+    pub fn version(&self) -> u32 {
+        self.published.as_ref().map_or(0, |policy| policy.policy.version)
+    }
 }
 
 /// A received credential response (approved or rejected)
@@ -47,11 +155,41 @@ pub struct PendingCredentialRequest {
 pub struct ReceivedCredentialResponse {
     pub request_id: String,
     pub credential: Option<SignedIncomeCredential>,
+    /// Commitment to the same credential, for disclosing only some fields
+    #[serde(default)]
+    pub selective_credential: Option<SelectiveCredential>,
+    /// Commitment to the net salary, for proving thresholds without revealing it
+    #[serde(default)]
+    pub threshold_credential: Option<ThresholdCredential>,
+    /// W3C VC 2.0 form of the same credential, for verifiers outside this network
+    #[serde(default)]
+    pub verifiable_credential: Option<VerifiableCredential>,
+    /// Compact JWS (JWT-VC) form of the same credential
+    #[serde(default)]
+    pub jws: Option<String>,
     pub error: Option<String>,
     pub received_at: String,
     pub issuer_node_id: EndpointId,
 }
 
+/// A credential this node has issued (Issuer role)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedCredentialRecord {
+    pub credential_id: String,
+    pub request_id: String,
+    pub employee_node_id: EndpointId,
+    pub issued_at: String,
+    /// Position of the credential in this issuer's status list
+    #[serde(default)]
+    pub status_index: Option<u32>,
+    /// Set once the issuer has revoked the credential
+    pub revocation: Option<RevocationNotice>,
+    /// Officer approvals the credential was issued on (organizational issuers)
+    #[serde(default)]
+    pub approvals: Vec<OfficerApproval>,
+}
+
 /// A record of a verified credential (Verifier role)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -62,10 +200,301 @@ pub struct VerifiedCredentialRecord {
     pub issuer_node_id: EndpointId,
     pub is_valid: bool,
     pub is_trusted: bool,
+    /// Signature covers only the legacy human-readable statement
+    #[serde(default)]
+    pub legacy_signature: bool,
+    /// Validity window and max-age outcome at verification time
+    #[serde(default)]
+    pub validity: ValidityStatus,
+    /// The issuer has revoked the credential
+    #[serde(default)]
+    pub is_revoked: bool,
+    #[serde(default)]
+    pub revocation_reason: Option<String>,
+    /// Outcome of the issuer status list check
+    #[serde(default)]
+    pub status_check: StatusCheck,
+    /// Version of the status list the check was based on
+    #[serde(default)]
+    pub status_list_version: Option<u64>,
+    /// How the presenter proved it holds the credential
+    #[serde(default)]
+    pub holder_binding: HolderBinding,
+    /// Whether the credential satisfies the presentation request it answers, if any
+    #[serde(default)]
+    pub request_satisfied: Option<bool>,
+    /// Position of the credential within a bundled presentation
+    #[serde(default)]
+    pub bundle_index: Option<u32>,
+    /// Number of credentials in the bundled presentation
+    #[serde(default)]
+    pub bundle_size: Option<u32>,
+    /// The issuer and every co-signer the credential declares
+    #[serde(default)]
+    pub signers: Vec<SignerCheck>,
+    /// Officer approvals, checked when the issuer published an approval policy
+    #[serde(default)]
+    pub approvals: Option<ApprovalCheck>,
+    pub verified_at: String,
+    pub verifier_node_id: EndpointId,
+}
+
+impl VerifiedCredentialRecord {
+    fn new(
+        presentation_id: String,
+        credential: SignedIncomeCredential,
+        check: &CredentialCheck,
+        holder_binding: HolderBinding,
+        request_satisfied: Option<bool>,
+        verifier_node_id: EndpointId,
+    ) -> Self {
+        Self {
+            presentation_id,
+            employee_node_id: credential.employee_node_id(),
+            issuer_node_id: check.issuer_node_id,
+            credential,
+            is_valid: check.is_valid,
+            is_trusted: check.is_trusted,
+            legacy_signature: check.legacy_signature,
+            validity: check.validity,
+            is_revoked: check.is_revoked,
+            revocation_reason: check.revocation_reason.clone(),
+            status_check: check.status_check,
+            status_list_version: check.status_list_version,
+            holder_binding,
+            request_satisfied,
+            bundle_index: None,
+            bundle_size: None,
+            signers: check.signers.clone(),
+            approvals: check.approvals.clone(),
+            verified_at: chrono::Utc::now().to_rfc3339(),
+            verifier_node_id,
+        }
+    }
+}
+
+/// A record of a verified selective disclosure (Verifier role)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedDisclosureRecord {
+    pub presentation_id: String,
+    /// The commitment and only the disclosures that were presented
+    pub credential: SelectiveCredential,
+    /// Disclosed values; hidden fields are `None`
+    pub claims: DisclosedClaims,
+    pub employee_node_id: EndpointId,
+    pub issuer_node_id: EndpointId,
+    pub is_valid: bool,
+    pub is_trusted: bool,
+    pub validity: ValidityStatus,
+    pub is_revoked: bool,
+    pub revocation_reason: Option<String>,
+    pub status_check: StatusCheck,
+    pub status_list_version: Option<u64>,
+    pub holder_binding: HolderBinding,
+    pub request_satisfied: Option<bool>,
+    pub verified_at: String,
+    pub verifier_node_id: EndpointId,
+}
+
+/// A bundled presentation as checked, stored whole so a presenter reusing its ID
+/// replaces every record at once (Verifier role)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedBundleRecord {
+    pub presentation_id: String,
+    pub presenter_node_id: EndpointId,
+    /// One record per credential, in bundle order
+    pub records: Vec<VerifiedCredentialRecord>,
+}
+
+/// A record of a verified net salary threshold proof (Verifier role)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedThresholdRecord {
+    pub presentation_id: String,
+    pub proof: ThresholdProof,
+    /// Currency, pay period and the proven lower bound of the net salary
+    pub claims: DisclosedClaims,
+    pub employee_node_id: EndpointId,
+    pub issuer_node_id: EndpointId,
+    pub is_valid: bool,
+    pub is_trusted: bool,
+    pub validity: ValidityStatus,
+    pub is_revoked: bool,
+    pub revocation_reason: Option<String>,
+    pub status_check: StatusCheck,
+    pub status_list_version: Option<u64>,
+    pub holder_binding: HolderBinding,
+    pub request_satisfied: Option<bool>,
     pub verified_at: String,
     pub verifier_node_id: EndpointId,
 }
 
+/// Verifier's verdict on a bundled presentation, with a breakdown per credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationVerdict {
+    pub presentation_id: String,
+    /// Every credential is valid
+    pub is_valid: bool,
+    pub holder_binding: HolderBinding,
+    /// Set when answering a presentation request; `false` if any credential misses its constraints
+    pub request_satisfied: Option<bool>,
+    /// In presentation order
+    pub credentials: Vec<CredentialCheck>,
+    pub message: String,
+}
+
+/// A verifier's request for a credential, awaiting the holder's consent (Employee role)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivedPresentationRequest {
+    pub request_id: String,
+    pub verifier_node_id: EndpointId,
+    pub constraints: PresentationConstraints,
+    /// Signed into the envelope of the presentation
+    pub nonce: String,
+    /// The request can no longer be approved after this time (RFC3339 format)
+    pub expires_at: String,
+    pub received_at: String,
+    pub status: RequestStatus,
+    /// Credentials presented on approval
+    #[serde(default)]
+    pub presented_credential_ids: Vec<String>,
+    /// The verifier's verdict on the presented credential
+    #[serde(default)]
+    pub is_valid: Option<bool>,
+    /// The verifier's message, or the reason given for declining
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// A presentation request this node sent to an employee (Verifier role)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentPresentationRequest {
+    pub request_id: String,
+    pub employee_node_id: EndpointId,
+    pub constraints: PresentationConstraints,
+    /// Must be signed into the envelope of the answering presentation
+    pub nonce: String,
+    pub expires_at: String,
+    pub sent_at: String,
+    /// `Approved` once the employee presented a credential, `Rejected` if they declined
+    pub status: RequestStatus,
+    #[serde(default)]
+    pub decline_reason: Option<String>,
+}
+
+impl SentPresentationRequest {
+    /// Returns why a presentation of `credential` does not answer this request, if it doesn't
+    fn mismatch(&self, credential: &impl PresentableCredential, has_envelope: bool) -> Option<String> {
+        if is_expired(&self.expires_at) {
+            return Some(format!("request expired at {}", self.expires_at));
+        }
+        if credential.holder_node_id() != self.employee_node_id {
+            return Some(format!("credential belongs to {}, not the requested employee", credential.holder_node_id()));
+        }
+        if !has_envelope {
+            return Some("no holder envelope over the request nonce".to_string());
+        }
+        self.constraints.mismatch_claims(credential.issuer_node_id(), &credential.disclosed_claims())
+    }
+}
+
+
+/// Resolves `did:iroh` by asking the node now answering for the DID, following
+/// the issuer's key rotations, and `did:key` offline. Bindings from verified
+/// documents are kept with the others.
+#[derive(Debug, Clone)]
+struct IrohDidResolver {
+    endpoint: Endpoint,
+    revocations: RevocationRegistry,
+    key_bindings: StoredMap<SignedKeyBinding>,
+}
+
+impl IrohDidResolver {
+    async fn resolve_iroh(self, did: String) -> Result<DidDocument, CredentialError> {
+        let node_id = parse_did_iroh(&did)?;
+        let unresolved = |reason: String| CredentialError::InvalidDid(format!("{did}: {reason}"));
+        let rotations = self.revocations.rotation_log().await;
+        let current_key = *rotations.successors(node_id).last().expect("successors include the key itself");
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::DidDocumentRequest { request_id: request_id.clone(), did: did.clone() };
+        let (document, bindings, unbindings) = match send_and_receive(&self.endpoint, current_key, message).await {
+            Ok(Some(CredentialMessage::DidDocumentResponse { request_id: id, document, bindings, unbindings }))
+                if id == request_id =>
+            {
+                (document, bindings, unbindings)
+            }
+            Ok(Some(CredentialMessage::Error { message, .. })) => return Err(unresolved(message)),
+            Ok(other) => return Err(unresolved(format!("unexpected reply from {current_key}: {other:?}"))),
+            Err(e) => return Err(unresolved(format!("{e:#}"))),
+        };
+        // Retired keys are recorded even if the document does not check out
+        for unbinding in unbindings.iter().filter(|unbinding| unbinding.unbinding.node_id == node_id) {
+            if let Err(e) = self.revocations.record_unbinding(unbinding.clone()).await {
+                tracing::warn!("Ignoring key unbinding from {}: {:#}", current_key, e);
+            }
+        }
+        document.check_iroh(&node_id, &bindings, &unbindings)?;
+        for binding in bindings {
+            let signing_key = binding.binding.signing_key;
+            if binding.binds(node_id, signing_key).unwrap_or(false)
+                && let Err(e) = self.key_bindings.insert(binding_key(node_id, signing_key), binding).await
+            {
+                tracing::warn!("Failed to store key binding: {:#}", e);
+            }
+        }
+        Ok(document)
+    }
+}
+
+impl DidResolver for IrohDidResolver {
+    fn resolve(&self, did: &str) -> BoxFuture<Result<DidDocument, CredentialError>> {
+        if did.starts_with("did:key:") {
+            return KeyDidResolver.resolve(did);
+        }
+        Box::pin(self.clone().resolve_iroh(did.to_string()))
+    }
+}
+
+/// True if `timestamp` (RFC3339) has passed or cannot be parsed
+fn is_expired(timestamp: &str) -> bool {
+    chrono::DateTime::parse_from_rfc3339(timestamp).map_or(true, |expiry| expiry < chrono::Utc::now())
+}
+
+/// Policy applied by a Verifier to every presented credential
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationPolicy {
+    /// Credentials issued more than this many days ago are rejected as stale
+    pub max_age_days: Option<u32>,
+    /// Refetch cached issuer status lists after this many minutes, even if the
+    /// issuer said they were still current
+    pub status_list_max_age_minutes: Option<u32>,
+    /// Accept credentials signed over the legacy human-readable statement; their
+    /// validity and revocation status are reported as unknown
+    #[serde(default)]
+    pub accept_legacy_signatures: bool,
+    /// Accept credentials whose issuer status list cannot be fetched or is not
+    /// cached; by default they are rejected, as they may have been revoked
+    #[serde(default)]
+    pub accept_unavailable_status: bool,
+}
+
+impl VerificationPolicy {
+    pub fn max_age(&self) -> Option<chrono::Duration> {
+        self.max_age_days.map(|days| chrono::Duration::days(days.into()))
+    }
+
+    pub fn status_list_max_age(&self) -> Option<chrono::Duration> {
+        self.status_list_max_age_minutes.map(|minutes| chrono::Duration::minutes(minutes.into()))
+    }
+}
+
 pub struct PeerNode {
     secret_key: SecretKey,
     router: Router,
@@ -75,15 +504,32 @@ pub struct PeerNode {
 
 const CREDENTIAL_ALPN: &[u8] = b"social-id/credential/v1";
 
+/// Connection attempts made when delivering an issue response to an employee
+const DELIVERY_ATTEMPTS: u32 = 4;
+
+/// How long an employee may take to answer a presentation request by default
+const PRESENTATION_REQUEST_TTL_MINUTES: u32 = 24 * 60;
+
 impl PeerNode {
-    /// Spawns a peer node.
-    pub async fn spawn(secret_key: Option<SecretKey>, role: Role) -> Result<Self, anyhow::Error> {
+    /// Spawns a peer node whose state is loaded from and persisted to `store`.
+    ///
+    /// An `issuer_profile`, if given, replaces the stored one. A `signer`, if given,
+    /// signs credentials instead of the node key, possibly outside this process; the
+    /// binding of its key to the node is served to verifiers.
+    pub async fn spawn(
+        secret_key: Option<SecretKey>,
+        role: Role,
+        store: Arc<dyn Store>,
+        issuer_profile: Option<IssuerProfile>,
+        signer: Option<Arc<dyn CredentialSigner>>,
+    ) -> Result<Self, anyhow::Error> {
         let secret_key = secret_key.unwrap_or_else(|| SecretKey::generate(&mut rand::rng()));
+        let signer = signer.unwrap_or_else(|| Arc::new(MemorySigner::new(secret_key.clone())));
         
         let endpoint = iroh::Endpoint::builder()
             .secret_key(secret_key.clone())
             .discovery(iroh::discovery::pkarr::PkarrPublisher::n0_dns())
-            .alpns(vec![CREDENTIAL_ALPN.to_vec()])
+            .alpns(vec![CREDENTIAL_ALPN.to_vec(), GOSSIP_ALPN.to_vec()])
             .bind()
             .await?;
         info!("endpoint bound with Pkarr discovery");
@@ -102,26 +548,106 @@ impl PeerNode {
 
         let (event_sender, _event_receiver) = broadcast::channel(128);
         
-        let pending_requests = Arc::new(RwLock::new(HashMap::new()));
-        let trusted_issuers = Arc::new(RwLock::new(HashSet::new()));
-        let received_credentials = Arc::new(RwLock::new(HashMap::new()));
-        let verified_credentials = Arc::new(RwLock::new(HashMap::new()));
+        let pending_requests = StoredMap::open(store.clone(), Collection::PendingRequests)?;
+        let trusted_issuers = StoredMap::open(store.clone(), Collection::TrustedIssuers)?;
+        let received_credentials = Wallet::open(store.clone(), &secret_key)?;
+        let verified_credentials = StoredMap::open(store.clone(), Collection::VerifiedCredentials)?;
+        let verified_presentations = StoredMap::open(store.clone(), Collection::VerifiedPresentations)?;
+        let verified_disclosures = StoredMap::open(store.clone(), Collection::VerifiedDisclosures)?;
+        let verified_threshold_proofs = StoredMap::open(store.clone(), Collection::VerifiedThresholdProofs)?;
+        let issued_credentials = StoredMap::open(store.clone(), Collection::IssuedCredentials)?;
+        let status_list = StoredValue::open(store.clone(), Collection::StatusList, "state")?;
+        let status_lists = StatusListCache::new(
+            endpoint.clone(),
+            StoredMap::open(store.clone(), Collection::StatusListCache)?,
+        );
+        let verification_policy = StoredValue::open(store.clone(), Collection::Settings, "verification_policy")?;
+        let stored_profile = StoredValue::open(store.clone(), Collection::Settings, "issuer_profile")?;
+        let policy_proposal = StoredValue::open(store.clone(), Collection::Settings, "approval_policy_proposal")?;
+        let presentation_requests = StoredMap::open(store.clone(), Collection::PresentationRequests)?;
+        let sent_presentation_requests = StoredMap::open(store.clone(), Collection::SentPresentationRequests)?;
+        let key_bindings = StoredMap::open(store.clone(), Collection::KeyBindings)?;
+        let key_unbindings = StoredMap::open(store.clone(), Collection::KeyUnbindings)?;
+        if let Some(profile) = issuer_profile {
+            profile.validate()?;
+            stored_profile.set(profile).await?;
+        }
+        // Bind a separate signing key to this node once, keeping the first binding
+        let signing_key = signer.public_key();
+        let binding_id = binding_key(node_id, signing_key);
+        if signing_key != node_id && key_unbindings.contains_key(&binding_id).await {
+            anyhow::bail!("Signing key {} was unbound from node {}; configure a new one", signing_key, node_id);
+        }
+        if signing_key != node_id && !key_bindings.contains_key(&binding_id).await {
+            let binding = KeyBinding::new(node_id, signing_key).sign(&*signer, &secret_key).await?;
+            key_bindings.insert(binding_id, binding).await?;
+            info!("Bound signing key {} to node {}", signing_key, node_id);
+        }
+        info!("peer state loaded from {:?}", store);
+
+        let gossip = Gossip::builder().spawn(endpoint.clone());
+        let revocations = RevocationRegistry::new(
+            gossip.clone(),
+            node_id,
+            event_sender.clone(),
+            StoredMap::open(store.clone(), Collection::Revocations)?,
+            StoredMap::open(store.clone(), Collection::KeyRotations)?,
+            StoredMap::open(store, Collection::ApprovalPolicies)?,
+            key_unbindings,
+        );
 
         let peer = Peer::new(
             event_sender.clone(),
             role.clone(),
             secret_key.clone(),
+            signer,
             node_id,
             pending_requests,
             trusted_issuers,
             received_credentials,
             verified_credentials,
+            verified_presentations,
+            verified_disclosures,
+            verified_threshold_proofs,
+            issued_credentials,
+            revocations,
+            status_list.clone(),
+            status_lists,
+            verification_policy,
+            stored_profile,
+            policy_proposal,
+            endpoint.clone(),
+            presentation_requests,
+            sent_presentation_requests,
+            key_bindings,
         );
 
-        let router = Router::builder(endpoint)
+        let mut router = Router::builder(endpoint)
             .accept(CREDENTIAL_ALPN, peer.clone())
-            .spawn();
+            .accept(GOSSIP_ALPN, gossip);
+        if matches!(role, Role::Issuer) {
+            router = router.accept(STATUS_LIST_ALPN, StatusListProtocol::new(secret_key.clone(), status_list));
+        }
+        let router = router.spawn();
         info!("router spawned");
+        
+        // Rejoin the revocation topics of the persisted state, including those of
+        // the keys trusted issuers rotated to
+        let rotations = peer.revocations.rotation_log().await;
+        for issuer in peer.get_trusted_issuers().await {
+            for key in rotations.successors(issuer) {
+                peer.revocations.follow_issuer(key).await?;
+            }
+        }
+        // Issuers join their topic up front: a verifier's join is dropped unless the
+        // issuer is already on it, and later revocations or unbindings would not reach it
+        if matches!(role, Role::Issuer) || peer.revocations.has_own_announcements().await {
+            peer.revocations.start_publishing().await?;
+        }
+        // Finish deliveries interrupted by the last shutdown
+        if matches!(role, Role::Issuer) {
+            peer.retry_deliveries(router.endpoint()).await;
+        }
         Ok(Self {
             router,
             secret_key,
@@ -134,6 +660,12 @@ impl PeerNode {
         self.router.endpoint()
     }
 
+    /// Stops accepting connections and closes the endpoint
+    pub async fn shutdown(&self) -> Result<(), anyhow::Error> {
+        self.router.shutdown().await?;
+        Ok(())
+    }
+
     pub fn secret_key(&self) -> &SecretKey {
         &self.secret_key
     }
@@ -169,8 +701,8 @@ impl PeerNode {
     }
 
     /// Approve a pending credential request and send signed credential to employee
-    pub async fn approve_request(&self, request_id: String) -> Result<(), anyhow::Error> {
-        self.peer.approve_request(request_id, &self.secret_key, self.endpoint()).await
+    pub async fn approve_request(&self, request_id: String, validity: ValidityWindow) -> Result<(), anyhow::Error> {
+        self.peer.approve_request(request_id, validity, self.endpoint()).await
     }
 
     /// Reject a pending credential request
@@ -178,6 +710,75 @@ impl PeerNode {
         self.peer.reject_request(request_id, reason, self.endpoint()).await
     }
 
+    /// Resend approvals and rejections the employees have not acknowledged (Issuer only)
+    pub async fn retry_deliveries(&self) -> usize {
+        self.peer.retry_deliveries(self.endpoint()).await
+    }
+
+    /// Get all credentials issued by this node (Issuer only)
+    pub async fn get_issued_credentials(&self) -> Vec<IssuedCredentialRecord> {
+        self.peer.get_issued_credentials().await
+    }
+
+    /// Revoke a credential issued by this node, flipping its status list bit and
+    /// announcing it to verifiers (Issuer only)
+    pub async fn revoke_credential(&self, credential_id: String, reason: Option<String>) -> Result<(), anyhow::Error> {
+        self.peer.revoke_credential(credential_id, reason).await
+    }
+
+    /// Get all revocation notices known to this node
+    pub async fn get_revocations(&self) -> Vec<SignedRevocationNotice> {
+        self.peer.revocations.revocations().await
+    }
+
+    /// Replace this issuer's key by `new_secret_key`, announcing the signed rotation to
+    /// verifiers (Issuer only). Restart the node with the new key afterwards.
+    pub async fn rotate_key(
+        &self,
+        new_secret_key: &SecretKey,
+        reason: Option<String>,
+    ) -> Result<SignedKeyRotation, anyhow::Error> {
+        self.peer.rotate_key(new_secret_key, reason).await
+    }
+
+    /// Get all issuer key rotations known to this node
+    pub async fn get_key_rotations(&self) -> Vec<SignedKeyRotation> {
+        self.peer.get_key_rotations().await
+    }
+
+    /// Get the binding of the credential signing key to this node, if a separate
+    /// signing key is used (Issuer only)
+    pub async fn key_binding(&self) -> Option<SignedKeyBinding> {
+        self.peer.key_binding().await
+    }
+
+    /// Retire a signing key bound to this node, e.g. because it leaked, announcing the
+    /// node-signed unbinding to verifiers (Issuer only). Restart the node with a new
+    /// signing key afterwards.
+    pub async fn unbind_signing_key(&self, signing_key: PublicKey) -> Result<SignedKeyUnbinding, anyhow::Error> {
+        self.peer.unbind_signing_key(signing_key).await
+    }
+
+    /// This node's `did:iroh`
+    pub fn did(&self) -> String {
+        did_iroh(&self.peer.node_id)
+    }
+
+    /// This node's DID document: its addressing info and credential signing keys
+    pub async fn did_document(&self) -> DidDocument {
+        let (document, _) = self
+            .peer
+            .did_document(self.peer.node_id)
+            .await
+            .expect("a node answers for its own DID");
+        document
+    }
+
+    /// Resolve a `did:iroh` or `did:key` to its verified DID document
+    pub async fn resolve_did(&self, did: String) -> Result<DidDocument, anyhow::Error> {
+        Ok(self.peer.did_resolver.resolve(&did).await?)
+    }
+
     /// Add a trusted issuer to the trust list (Verifier only)
     pub async fn add_trusted_issuer(&self, node_id: EndpointId) -> Result<(), anyhow::Error> {
         self.peer.add_trusted_issuer(node_id).await
@@ -198,117 +799,709 @@ impl PeerNode {
         self.peer.get_trusted_issuers().await
     }
 
-    /// Get all verified credentials (Verifier only)
-    pub async fn get_verified_credentials(&self) -> Vec<VerifiedCredentialRecord> {
-        self.peer.get_verified_credentials().await
+    /// Get the policy applied to presented credentials (Verifier only)
+    pub async fn get_verification_policy(&self) -> VerificationPolicy {
+        self.peer.get_verification_policy().await
     }
 
-    /// Get a specific verified credential (Verifier only)
-    pub async fn get_verified_credential(&self, presentation_id: &str) -> Option<VerifiedCredentialRecord> {
-        self.peer.get_verified_credential(presentation_id).await
+    /// Replace the policy applied to presented credentials (Verifier only)
+    pub async fn set_verification_policy(&self, policy: VerificationPolicy) -> Result<(), anyhow::Error> {
+        self.peer.set_verification_policy(policy).await
     }
 
-    /// Get all received credentials (Employee only)
-    pub async fn get_received_credentials(&self) -> Vec<ReceivedCredentialResponse> {
-        self.peer.get_received_credentials().await
+    /// Trusted issuers with their key rotations and signing key bindings, for
+    /// verifying credentials without a node (Verifier only)
+    pub async fn export_trust_store(&self) -> OfflineTrustStore {
+        self.peer.export_trust_store().await
     }
 
-    /// Get a specific received credential by request ID (Employee only)
-    pub async fn get_received_credential(&self, request_id: String) -> Option<ReceivedCredentialResponse> {
-        self.peer.get_received_credential(request_id).await
+    /// Cached issuer status lists and received revocation notices, for verifying
+    /// credentials without a node (Verifier only)
+    pub async fn export_revocations(&self) -> OfflineRevocations {
+        self.peer.export_revocations().await
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "camelCase")]
-pub enum ConnectEvent {
-    Connected,
-    MessageSent {
-        message: CredentialMessage,
-        bytes_sent: u64,
-    },
-    ResponseReceived {
-        message: CredentialMessage,
-        bytes_received: u64,
-    },
-    Closed {
-        error: Option<String>,
-    },
-}
+    /// Get the organization profile used on issued credentials (Issuer only)
+    pub async fn get_issuer_profile(&self) -> IssuerProfile {
+        self.peer.get_issuer_profile().await
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "camelCase")]
-pub enum AcceptEvent {
-    Accepted {
-        node_id: EndpointId,
-    },
-    MessageReceived {
-        node_id: EndpointId,
-        message: CredentialMessage,
-    },
-    ResponseSent {
-        node_id: EndpointId,
-        message: CredentialMessage,
-        bytes_sent: u64,
-    },
-    Closed {
-        node_id: EndpointId,
-        error: Option<String>,
-    },
-}
+    /// Replace the organization profile used on issued credentials (Issuer only)
+    pub async fn set_issuer_profile(&self, profile: IssuerProfile) -> Result<(), anyhow::Error> {
+        self.peer.set_issuer_profile(profile).await
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "camelCase")]
-pub enum Role {
-    Employee,
-    Issuer,
-    Verifier,
-}
+    /// Get the published approval policy and any change awaiting officers (Issuer only)
+    pub async fn get_approval_policy(&self) -> ApprovalPolicyStatus {
+        self.peer.get_approval_policy().await
+    }
 
-/// Structured messages for credential protocol
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
-pub enum CredentialMessage {
-    /// Employee → Issuer: Request to issue an income credential
-    IssueRequest {
-        request_id: String,
-        employee_node_id: EndpointId,
-        employee_name: String,
-        gross_salary: String,
-        net_salary: String,
-        currency: String,
-        pay_period: String,
-        payment_mode: PaymentMode,
-    },
-    
-    /// Issuer → Employee: Acknowledgment that request is queued for approval
-    RequestQueued {
+    /// Change the officers who must approve credential requests (Issuer only). Once a
+    /// policy is published, changes take effect when enough current officers sign them.
+    pub async fn set_approval_policy(&self, policy: ApprovalPolicy) -> Result<ApprovalPolicyStatus, anyhow::Error> {
+        self.peer.set_approval_policy(policy).await
+    }
+
+    /// Fetch the requests awaiting officer approval, and any proposed change of the
+    /// approval policy, from an issuer (Officer)
+    async fn fetch_approval_queue(
+        &self,
+        issuer_node_id: EndpointId,
+    ) -> Result<(Vec<ApprovalProgress>, Option<SignedApprovalPolicy>), anyhow::Error> {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::ApprovalQueueRequest { request_id: request_id.clone() };
+        match send_and_receive(self.endpoint(), issuer_node_id, message).await? {
+            Some(CredentialMessage::ApprovalQueue { request_id: id, requests, policy_proposal }) if id == request_id => {
+                Ok((requests, policy_proposal))
+            }
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Issuer {} refused: {}", issuer_node_id, message),
+            other => anyhow::bail!("Unexpected reply from issuer {}: {:?}", issuer_node_id, other),
+        }
+    }
+
+    /// Fetch the requests awaiting officer approval from an issuer (Officer)
+    pub async fn request_approval_queue(&self, issuer_node_id: EndpointId) -> Result<Vec<ApprovalProgress>, anyhow::Error> {
+        Ok(self.fetch_approval_queue(issuer_node_id).await?.0)
+    }
+
+    /// Fetch the change of an issuer's approval policy awaiting officer signatures (Officer)
+    pub async fn request_policy_proposal(
+        &self,
+        issuer_node_id: EndpointId,
+    ) -> Result<Option<SignedApprovalPolicy>, anyhow::Error> {
+        Ok(self.fetch_approval_queue(issuer_node_id).await?.1)
+    }
+
+    /// Approve the credential an issuer signed for one of its pending requests (Officer)
+    ///
+    /// Checks the credential states the requested terms before signing each of its forms.
+    pub async fn approve_as_officer(
+        &self,
+        issuer_node_id: EndpointId,
         request_id: String,
-        message: String,
+    ) -> Result<ApprovalProgress, anyhow::Error> {
+        let request = self.request_approval_queue(issuer_node_id)
+            .await?
+            .into_iter()
+            .find(|request| request.request_id == request_id)
+            .ok_or_else(|| anyhow::anyhow!("Issuer {} has no pending request {}", issuer_node_id, request_id))?;
+        let draft = request.draft
+            .ok_or_else(|| anyhow::anyhow!("Issuer {} has not signed the credential for request {} yet", issuer_node_id, request_id))?;
+        draft.check(issuer_node_id, &request.terms)?;
+        let approval = OfficerApproval::sign(&self.secret_key, request_id.clone(), &draft, request.policy_version)?;
+        
+        let message = CredentialMessage::SubmitApproval { approval };
+        match send_and_receive(self.endpoint(), issuer_node_id, message).await? {
+            Some(CredentialMessage::ApprovalRecorded(progress)) if progress.request_id == request_id => Ok(progress),
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Issuer {} refused: {}", issuer_node_id, message),
+            other => anyhow::bail!("Unexpected reply from issuer {}: {:?}", issuer_node_id, other),
+        }
+    }
+
+    /// Sign version `version` of an issuer's approval policy, as proposed (Officer)
+    pub async fn approve_policy_as_officer(
+        &self,
+        issuer_node_id: EndpointId,
+        version: u32,
+    ) -> Result<ApprovalPolicyStatus, anyhow::Error> {
+        let proposal = self.request_policy_proposal(issuer_node_id)
+            .await?
+            .filter(|proposal| proposal.policy.version == version)
+            .ok_or_else(|| anyhow::anyhow!("Issuer {} proposes no version {} of its approval policy", issuer_node_id, version))?;
+        let signature = proposal.policy.sign(&self.secret_key)?;
+        
+        let message = CredentialMessage::SubmitPolicyApproval { version, signature };
+        match send_and_receive(self.endpoint(), issuer_node_id, message).await? {
+            Some(CredentialMessage::PolicyApprovalRecorded(status)) => Ok(status),
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Issuer {} refused: {}", issuer_node_id, message),
+            other => anyhow::bail!("Unexpected reply from issuer {}: {:?}", issuer_node_id, other),
+        }
+    }
+
+    /// Fetch an issuer's signed profile and check its signature
+    pub async fn request_issuer_profile(&self, issuer_node_id: EndpointId) -> Result<SignedIssuerProfile, anyhow::Error> {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::ProfileRequest { request_id: request_id.clone() };
+        let profile = match send_and_receive(self.endpoint(), issuer_node_id, message).await? {
+            Some(CredentialMessage::ProfileResponse { request_id: id, profile }) if id == request_id => profile,
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Issuer {} refused: {}", issuer_node_id, message),
+            other => anyhow::bail!("Unexpected reply from issuer {}: {:?}", issuer_node_id, other),
+        };
+        
+        if profile.issuer_node_id != issuer_node_id {
+            anyhow::bail!("Issuer {} sent the profile of {}", issuer_node_id, profile.issuer_node_id);
+        }
+        if !profile.verify()? {
+            anyhow::bail!("Invalid signature on the profile of issuer {}", issuer_node_id);
+        }
+        Ok(profile)
+    }
+
+    /// Ask a verifier for a nonce to sign into a presentation envelope (Employee)
+    pub async fn request_challenge(&self, verifier_node_id: EndpointId) -> Result<PresentationChallenge, anyhow::Error> {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::ChallengeRequest { request_id: request_id.clone() };
+        match send_and_receive(self.endpoint(), verifier_node_id, message).await? {
+            Some(CredentialMessage::Challenge { request_id: id, nonce, expires_at }) if id == request_id => {
+                Ok(PresentationChallenge { nonce, expires_at })
+            }
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Verifier {} refused: {}", verifier_node_id, message),
+            other => anyhow::bail!("Unexpected reply from verifier {}: {:?}", verifier_node_id, other),
+        }
+    }
+
+    /// Sign `credentials` over a verifier's nonce, for presenting them from another node (Employee)
+    pub fn sign_presentation(
+        &self,
+        verifier_node_id: EndpointId,
+        nonce: String,
+        credentials: &[&SignedIncomeCredential],
+    ) -> Result<PresentationEnvelope, anyhow::Error> {
+        Ok(PresentationEnvelope::sign(&self.secret_key, verifier_node_id, nonce, credentials)?)
+    }
+
+    /// Ask an employee for a credential matching `constraints`; the request
+    /// expires after `expires_in_minutes`, one day by default (Verifier only)
+    pub async fn request_presentation(
+        &self,
+        employee_node_id: EndpointId,
+        constraints: PresentationConstraints,
+        expires_in_minutes: Option<u32>,
+    ) -> Result<SentPresentationRequest, anyhow::Error> {
+        self.peer
+            .request_presentation(employee_node_id, constraints, expires_in_minutes, self.endpoint())
+            .await
+    }
+
+    /// Get all presentation requests sent by this node (Verifier only)
+    pub async fn get_sent_presentation_requests(&self) -> Vec<SentPresentationRequest> {
+        self.peer.get_sent_presentation_requests().await
+    }
+
+    /// Get all presentation requests received from verifiers (Employee only)
+    pub async fn get_presentation_requests(&self) -> Vec<ReceivedPresentationRequest> {
+        self.peer.get_presentation_requests().await
+    }
+
+    /// Get the wallet's credentials that satisfy a presentation request (Employee only)
+    pub async fn get_matching_credentials(&self, request_id: String) -> Result<Vec<SignedIncomeCredential>, anyhow::Error> {
+        self.peer.get_matching_credentials(&request_id).await
+    }
+
+    /// Present credentials in answer to a presentation request and record the verifier's verdict (Employee only)
+    pub async fn approve_presentation_request(
+        &self,
+        request_id: String,
+        credential_ids: Vec<String>,
+    ) -> Result<ReceivedPresentationRequest, anyhow::Error> {
+        self.peer.approve_presentation_request(request_id, credential_ids, self.endpoint()).await
+    }
+
+    /// Present several credentials to a verifier under one holder signature,
+    /// over a fresh nonce from the verifier (Employee)
+    pub async fn present_credentials(
+        &self,
+        verifier_node_id: EndpointId,
+        credentials: Vec<SignedIncomeCredential>,
+    ) -> Result<PresentationVerdict, anyhow::Error> {
+        let challenge = self.request_challenge(verifier_node_id).await?;
+        let presentation = VerifiablePresentation::sign(&self.secret_key, verifier_node_id, challenge.nonce, credentials)?;
+        let presentation_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::PresentCredentials {
+            presentation_id: presentation_id.clone(),
+            presentation,
+        };
+        match send_and_receive(self.endpoint(), verifier_node_id, message).await? {
+            Some(CredentialMessage::PresentationResult(verdict)) if verdict.presentation_id == presentation_id => Ok(verdict),
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Verifier {} refused: {}", verifier_node_id, message),
+            other => anyhow::bail!("Unexpected reply from verifier {}: {:?}", verifier_node_id, other),
+        }
+    }
+
+    /// Present a wallet credential revealing only `fields`, over a fresh nonce
+    /// from the verifier; returns the verifier's `VerificationResult` (Employee)
+    pub async fn present_disclosure(
+        &self,
+        verifier_node_id: EndpointId,
+        credential_id: String,
+        fields: Vec<DisclosableField>,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let credential = self.peer.find_selective_credential(&credential_id).await?.disclose(&fields);
+        let challenge = self.request_challenge(verifier_node_id).await?;
+        let envelope = PresentationEnvelope::sign(&self.secret_key, verifier_node_id, challenge.nonce, &[&credential])?;
+        let presentation_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::PresentDisclosure {
+            presentation_id: presentation_id.clone(),
+            credential,
+            envelope: Some(envelope),
+        };
+        match send_and_receive(self.endpoint(), verifier_node_id, message).await? {
+            Some(result @ CredentialMessage::VerificationResult { .. }) => Ok(result),
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Verifier {} refused: {}", verifier_node_id, message),
+            other => anyhow::bail!("Unexpected reply from verifier {}: {:?}", verifier_node_id, other),
+        }
+    }
+
+    /// Present a credential as the issuer's compact JWS, over a fresh nonce from
+    /// the verifier; returns the verifier's `VerificationResult` (Employee)
+    pub async fn present_jws(
+        &self,
+        verifier_node_id: EndpointId,
+        jws: String,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let credential = verify_jws(&jws)?;
+        let challenge = self.request_challenge(verifier_node_id).await?;
+        let envelope = PresentationEnvelope::sign(&self.secret_key, verifier_node_id, challenge.nonce, &[&credential])?;
+        let message = CredentialMessage::PresentJws {
+            presentation_id: uuid::Uuid::new_v4().to_string(),
+            jws,
+            envelope: Some(envelope),
+        };
+        match send_and_receive(self.endpoint(), verifier_node_id, message).await? {
+            Some(result @ CredentialMessage::VerificationResult { .. }) => Ok(result),
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Verifier {} refused: {}", verifier_node_id, message),
+            other => anyhow::bail!("Unexpected reply from verifier {}: {:?}", verifier_node_id, other),
+        }
+    }
+
+    /// Prove a wallet credential's net salary reaches `threshold` without revealing
+    /// it, over a fresh nonce from the verifier; returns the verifier's `VerificationResult` (Employee)
+    pub async fn present_threshold_proof(
+        &self,
+        verifier_node_id: EndpointId,
+        credential_id: String,
+        threshold: String,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let proof = self.peer.find_threshold_credential(&credential_id).await?.prove(&threshold)?;
+        let challenge = self.request_challenge(verifier_node_id).await?;
+        let envelope = PresentationEnvelope::sign(&self.secret_key, verifier_node_id, challenge.nonce, &[&proof])?;
+        let presentation_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::PresentThresholdProof {
+            presentation_id: presentation_id.clone(),
+            proof,
+            envelope: Some(envelope),
+        };
+        match send_and_receive(self.endpoint(), verifier_node_id, message).await? {
+            Some(result @ CredentialMessage::VerificationResult { .. }) => Ok(result),
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Verifier {} refused: {}", verifier_node_id, message),
+            other => anyhow::bail!("Unexpected reply from verifier {}: {:?}", verifier_node_id, other),
+        }
+    }
+
+    /// Answer a presentation request by proving the net salary reaches its
+    /// minimum, and record the verifier's verdict (Employee only)
+    pub async fn prove_presentation_request(
+        &self,
+        request_id: String,
+        credential_id: String,
+    ) -> Result<ReceivedPresentationRequest, anyhow::Error> {
+        self.peer.prove_presentation_request(request_id, credential_id, self.endpoint()).await
+    }
+
+    /// Decline a presentation request and tell the verifier (Employee only)
+    pub async fn reject_presentation_request(&self, request_id: String, reason: Option<String>) -> Result<(), anyhow::Error> {
+        self.peer.reject_presentation_request(request_id, reason, self.endpoint()).await
+    }
+
+    /// Get all verified credentials (Verifier only)
+    pub async fn get_verified_credentials(&self) -> Vec<VerifiedCredentialRecord> {
+        self.peer.get_verified_credentials().await
+    }
+
+    /// Get all verified selective disclosures (Verifier only)
+    pub async fn get_verified_disclosures(&self) -> Vec<VerifiedDisclosureRecord> {
+        self.peer.get_verified_disclosures().await
+    }
+
+    /// Get all verified net salary threshold proofs (Verifier only)
+    pub async fn get_verified_threshold_proofs(&self) -> Vec<VerifiedThresholdRecord> {
+        self.peer.get_verified_threshold_proofs().await
+    }
+
+    /// Get a specific verified credential (Verifier only)
+    pub async fn get_verified_credential(&self, presentation_id: &str) -> Option<VerifiedCredentialRecord> {
+        self.peer.get_verified_credential(presentation_id).await
+    }
+
+    /// Get every credential verified in one presentation, bundled or not (Verifier only)
+    pub async fn get_verified_presentation(&self, presentation_id: &str) -> Vec<VerifiedCredentialRecord> {
+        self.peer.get_verified_presentation(presentation_id).await
+    }
+
+    /// Get all received credentials; fails while the wallet is locked (Employee only)
+    pub async fn get_received_credentials(&self) -> Result<Vec<ReceivedCredentialResponse>, anyhow::Error> {
+        Ok(self.peer.get_received_credentials().await?)
+    }
+
+    /// Get a specific received credential by request ID; fails while the wallet is locked (Employee only)
+    pub async fn get_received_credential(&self, request_id: String) -> Result<Option<ReceivedCredentialResponse>, anyhow::Error> {
+        Ok(self.peer.get_received_credential(request_id).await?)
+    }
+
+    /// Get whether the credential wallet is locked and how it is protected (Employee only)
+    pub async fn wallet_status(&self) -> Result<WalletStatus, anyhow::Error> {
+        Ok(self.peer.wallet_status().await?)
+    }
+
+    /// Lock the credential wallet; received credentials are refused until it is unlocked (Employee only)
+    pub async fn lock_wallet(&self) {
+        self.peer.lock_wallet().await
+    }
+
+    /// Unlock the credential wallet with its passphrase, or the node key if `None` (Employee only)
+    pub async fn unlock_wallet(&self, passphrase: Option<String>) -> Result<(), anyhow::Error> {
+        Ok(self.peer.unlock_wallet(passphrase.as_deref()).await?)
+    }
+
+    /// Protect the credential wallet with a passphrase, or the node key if `None` (Employee only)
+    pub async fn set_wallet_passphrase(&self, passphrase: Option<String>) -> Result<(), anyhow::Error> {
+        Ok(self.peer.set_wallet_passphrase(passphrase.as_deref()).await?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ConnectEvent {
+    Connected,
+    MessageSent {
+        message: CredentialMessage,
+        bytes_sent: u64,
+    },
+    ResponseReceived {
+        message: CredentialMessage,
+        bytes_received: u64,
+    },
+    Closed {
+        error: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AcceptEvent {
+    Accepted {
+        node_id: EndpointId,
+    },
+    MessageReceived {
+        node_id: EndpointId,
+        message: CredentialMessage,
+    },
+    ResponseSent {
+        node_id: EndpointId,
+        message: CredentialMessage,
+        bytes_sent: u64,
+    },
+    Closed {
+        node_id: EndpointId,
+        error: Option<String>,
+    },
+    /// An approval or rejection reached the employee, or failed to (Issuer role)
+    ResponseDelivered {
+        node_id: EndpointId,
+        request_id: String,
+        error: Option<String>,
+    },
+    /// A followed issuer revoked one of its credentials
+    RevocationReceived {
+        issuer_node_id: EndpointId,
+        credential_id: String,
+    },
+    /// An issuer key was replaced, announced over gossip or fetched from the new key
+    KeyRotated {
+        previous_node_id: EndpointId,
+        new_node_id: EndpointId,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Role {
+    Employee,
+    Issuer,
+    Verifier,
+    /// Payroll processor co-signing the credentials that name it
+    Processor,
+}
+
+/// Structured messages for credential protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum CredentialMessage {
+    /// Employee → Issuer: Request to issue an income credential
+    IssueRequest {
+        request_id: String,
+        employee_node_id: EndpointId,
+        employee_name: String,
+        gross_salary: String,
+        net_salary: String,
+        currency: String,
+        pay_period: String,
+        payment_mode: PaymentMode,
+    },
+    
+    /// Issuer → Employee: Acknowledgment that request is queued for approval
+    RequestQueued {
+        request_id: String,
+        message: String,
     },
     
     /// Issuer → Employee: Response with signed credential or error
     IssueResponse {
         request_id: String,
         credential: Option<SignedIncomeCredential>,
+        /// Selective-disclosure form of `credential`; missing from issuers that predate it
+        #[serde(default)]
+        selective_credential: Option<Box<SelectiveCredential>>,
+        /// Net salary commitment of `credential`; missing from issuers that predate it
+        #[serde(default)]
+        threshold_credential: Option<Box<ThresholdCredential>>,
+        /// W3C VC 2.0 form of `credential`; missing from issuers that predate it
+        #[serde(default)]
+        verifiable_credential: Option<Box<VerifiableCredential>>,
+        /// JWT-VC form of `credential`; missing from issuers that predate it
+        #[serde(default)]
+        jws: Option<String>,
         error: Option<String>,
     },
     
+    /// Employee → Verifier: Ask for a nonce to sign into a presentation envelope
+    ChallengeRequest {
+        request_id: String,
+    },
+    
+    /// Verifier → Employee: Single-use nonce for a presentation envelope
+    Challenge {
+        request_id: String,
+        nonce: String,
+        expires_at: String,
+    },
+    
     /// Employee → Verifier: Present a credential for verification
     PresentCredential {
         presentation_id: String,
         credential: SignedIncomeCredential,
+        /// Required unless the connection is authenticated as the credential's employee
+        #[serde(default)]
+        envelope: Option<PresentationEnvelope>,
+    },
+    
+    /// Employee → Verifier: Present a credential as the issuer's compact JWS,
+    /// answered by a `VerificationResult`
+    PresentJws {
+        presentation_id: String,
+        jws: String,
+        /// Required unless the connection is authenticated as the credential's employee
+        #[serde(default)]
+        envelope: Option<PresentationEnvelope>,
+    },
+    
+    /// Employee → Verifier: Present several credentials under one holder signature
+    PresentCredentials {
+        presentation_id: String,
+        presentation: VerifiablePresentation,
+    },
+    
+    /// Employee → Verifier: Present a credential revealing only some of its fields,
+    /// answered by a `VerificationResult`
+    PresentDisclosure {
+        presentation_id: String,
+        credential: SelectiveCredential,
+        /// Required unless the connection is authenticated as the credential's employee
+        #[serde(default)]
+        envelope: Option<PresentationEnvelope>,
     },
     
+    /// Employee → Verifier: Prove the net salary reaches a threshold without
+    /// revealing it, answered by a `VerificationResult`
+    PresentThresholdProof {
+        presentation_id: String,
+        proof: ThresholdProof,
+        /// Required unless the connection is authenticated as the credential's employee
+        #[serde(default)]
+        envelope: Option<PresentationEnvelope>,
+    },
+    
+    /// Verifier → Employee: Result of a bundled presentation, per credential
+    PresentationResult(PresentationVerdict),
+    
     /// Verifier → Employee: Result of credential verification
     VerificationResult {
         presentation_id: String,
         is_valid: bool,
         is_trusted: bool,
+        /// Signature covers only the legacy human-readable statement
+        #[serde(default)]
+        legacy_signature: bool,
+        /// Validity window and max-age outcome; anything but `Valid` makes `is_valid` false,
+        /// except `Unknown` for an accepted legacy credential when no max age is set
+        #[serde(default)]
+        validity: ValidityStatus,
+        /// The issuer has revoked the credential; makes `is_valid` false
+        #[serde(default)]
+        is_revoked: bool,
+        #[serde(default)]
+        revocation_reason: Option<String>,
+        /// Outcome of the issuer status list check; `Revoked` sets `is_revoked`
+        #[serde(default)]
+        status_check: StatusCheck,
+        #[serde(default)]
+        status_list_version: Option<u64>,
+        /// How the presenter proved it holds the credential; unbound makes `is_valid` false
+        #[serde(default)]
+        holder_binding: HolderBinding,
+        /// Set when answering a presentation request; `false` makes `is_valid` false
+        #[serde(default)]
+        request_satisfied: Option<bool>,
+        /// The issuer and every declared co-signer; any unsigned one makes `is_valid` false
+        #[serde(default)]
+        signers: Vec<SignerCheck>,
+        /// Officer approvals; too few under the issuer's policy make `is_valid` false
+        #[serde(default)]
+        approvals: Option<ApprovalCheck>,
         issuer_node_id: EndpointId,
         message: String,
     },
     
+    /// Issuer → Payroll processor: Ask the processor named on a credential to co-sign it,
+    /// and the commitments derived from it, before delivery
+    CoSignRequest {
+        request_id: String,
+        credential: SignedIncomeCredential,
+        /// Selective disclosure form with every disclosure, so the processor can check it
+        #[serde(default)]
+        selective_credential: Option<Box<SelectiveCredential>>,
+        /// Net salary commitment with its opening, so the processor can check it
+        #[serde(default)]
+        threshold_credential: Option<Box<ThresholdCredential>>,
+    },
+    
+    /// Payroll processor → Issuer: Co-signatures over the credential and its commitments
+    CoSignResponse {
+        request_id: String,
+        co_signature: CoSignature,
+        #[serde(default)]
+        selective_co_signature: Option<CoSignature>,
+        #[serde(default)]
+        threshold_co_signature: Option<CoSignature>,
+    },
+    
+    /// Verifier → Employee: Ask for a credential matching `constraints`, answered by a
+    /// `PresentCredential` with `presentation_id` set to `request_id`
+    PresentationRequest {
+        request_id: String,
+        constraints: PresentationConstraints,
+        /// Nonce the holder signs into the presentation envelope
+        nonce: String,
+        expires_at: String,
+    },
+    
+    /// Employee → Verifier: The holder declined a presentation request
+    PresentationDeclined {
+        request_id: String,
+        reason: Option<String>,
+    },
+    
+    /// Officer → Issuer: Ask for the requests awaiting officer approval
+    ApprovalQueueRequest {
+        request_id: String,
+    },
+    
+    /// Issuer → Officer: Pending requests and the approvals they have, with any
+    /// change of the approval policy awaiting officer signatures
+    ApprovalQueue {
+        request_id: String,
+        requests: Vec<ApprovalProgress>,
+        #[serde(default)]
+        policy_proposal: Option<SignedApprovalPolicy>,
+    },
+    
+    /// Officer → Issuer: Signed approval of a pending request, answered by
+    /// `ApprovalRecorded`
+    SubmitApproval {
+        approval: OfficerApproval,
+    },
+    
+    /// Issuer → Officer: The approval was recorded; `status` is `Approved` once it met
+    /// the threshold and the credential was issued
+    ApprovalRecorded(ApprovalProgress),
+    
+    /// Officer → Issuer: Signature on the proposed approval policy, answered by
+    /// `PolicyApprovalRecorded`
+    SubmitPolicyApproval {
+        version: u32,
+        signature: PolicySignature,
+    },
+    
+    /// Issuer → Officer: The signature was recorded; the proposal is published once
+    /// enough officers signed it
+    PolicyApprovalRecorded(ApprovalPolicyStatus),
+    
+    /// Verifier → Issuer: Ask for every version of the issuer's approval policy
+    ApprovalPolicyRequest {
+        request_id: String,
+    },
+    
+    /// Issuer → Verifier: Approval policy versions, oldest first; empty if the
+    /// issuer approves requests on its own
+    ApprovalPolicies {
+        request_id: String,
+        policies: Vec<SignedApprovalPolicy>,
+    },
+    
+    /// Verifier → Issuer: Ask for the rotations leading to the issuer's current key
+    KeyHistoryRequest {
+        request_id: String,
+    },
+    
+    /// Issuer → Verifier: Rotations leading to the issuer's key, oldest first, each
+    /// signed by the key it retires
+    KeyHistory {
+        request_id: String,
+        rotations: Vec<SignedKeyRotation>,
+    },
+    
+    /// Verifier → Issuer: Ask for the binding of a credential signing key to an
+    /// issuer node, this one or a key it rotated from. Still answered for older
+    /// verifiers; current ones resolve the issuer's DID document instead
+    KeyBindingRequest {
+        request_id: String,
+        node_id: EndpointId,
+        signing_key: PublicKey,
+    },
+    
+    /// Issuer → Verifier: The binding, signed by both keys
+    KeyBindingResponse {
+        request_id: String,
+        binding: SignedKeyBinding,
+    },
+    
+    /// Any role → Issuer: Ask for the issuer's signed organization profile
+    ProfileRequest {
+        request_id: String,
+    },
+    
+    /// Issuer → requester: The issuer's current profile, signed just now
+    ProfileResponse {
+        request_id: String,
+        profile: SignedIssuerProfile,
+    },
+    
+    /// Any → node: Ask for the document of a `did:iroh` of the node or of a key it rotated from
+    DidDocumentRequest {
+        request_id: String,
+        did: String,
+    },
+    
+    /// Node → requester: The DID document, with the bindings of the signing keys it lists
+    /// and the unbindings of those it retired
+    DidDocumentResponse {
+        request_id: String,
+        document: DidDocument,
+        bindings: Vec<SignedKeyBinding>,
+        #[serde(default)]
+        unbindings: Vec<SignedKeyUnbinding>,
+    },
+    
     /// Generic error message
     Error {
         request_id: String,
@@ -322,11 +1515,40 @@ pub struct Peer {
     event_sender: broadcast::Sender<AcceptEvent>,
     role: Role,
     secret_key: SecretKey,
+    /// Signs credentials; the node key unless a separate key is bound to the node
+    signer: Arc<dyn CredentialSigner>,
     node_id: EndpointId,
-    pending_requests: Arc<RwLock<HashMap<String, PendingCredentialRequest>>>,
-    trusted_issuers: Arc<RwLock<HashSet<EndpointId>>>,
-    received_credentials: Arc<RwLock<HashMap<String, ReceivedCredentialResponse>>>,
-    verified_credentials: Arc<RwLock<HashMap<String, VerifiedCredentialRecord>>>,
+    pending_requests: StoredMap<PendingCredentialRequest>,
+    /// Keyed by issuer node ID
+    trusted_issuers: StoredMap<EndpointId>,
+    /// Encrypted at rest
+    received_credentials: Wallet,
+    verified_credentials: StoredMap<VerifiedCredentialRecord>,
+    /// Keyed by presenter and presentation ID, apart from single credentials
+    verified_presentations: StoredMap<VerifiedBundleRecord>,
+    verified_disclosures: StoredMap<VerifiedDisclosureRecord>,
+    verified_threshold_proofs: StoredMap<VerifiedThresholdRecord>,
+    issued_credentials: StoredMap<IssuedCredentialRecord>,
+    revocations: RevocationRegistry,
+    status_list: StoredValue<StatusListState>,
+    status_lists: StatusListCache,
+    verification_policy: StoredValue<VerificationPolicy>,
+    issuer_profile: StoredValue<IssuerProfile>,
+    /// Change of this issuer's approval policy awaiting officer signatures (Issuer)
+    policy_proposal: StoredValue<Option<SignedApprovalPolicy>>,
+    /// Used to issue from a connection handler, once officer approvals meet the threshold
+    endpoint: Endpoint,
+    /// Nonces handed out for presentation envelopes (Verifier)
+    challenges: ChallengeRegistry,
+    /// Requests from verifiers awaiting consent (Employee)
+    presentation_requests: StoredMap<ReceivedPresentationRequest>,
+    /// Requests sent to employees (Verifier)
+    sent_presentation_requests: StoredMap<SentPresentationRequest>,
+    /// Own bindings (Issuer) and those fetched from issuers (Verifier), keyed by
+    /// node ID and signing key
+    key_bindings: StoredMap<SignedKeyBinding>,
+    /// Turns issuer DIDs into the keys that sign their credentials (Verifier)
+    did_resolver: Arc<dyn DidResolver>,
 }
 
 // impl std::fmt::Debug for Peer {
@@ -339,25 +1561,63 @@ pub struct Peer {
 // }
 
 impl Peer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         event_sender: broadcast::Sender<AcceptEvent>,
         role: Role,
         secret_key: SecretKey,
+        signer: Arc<dyn CredentialSigner>,
         node_id: EndpointId,
-        pending_requests: Arc<RwLock<HashMap<String, PendingCredentialRequest>>>,
-        trusted_issuers: Arc<RwLock<HashSet<EndpointId>>>,
-        received_credentials: Arc<RwLock<HashMap<String, ReceivedCredentialResponse>>>,
-        verified_credentials: Arc<RwLock<HashMap<String, VerifiedCredentialRecord>>>,
+        pending_requests: StoredMap<PendingCredentialRequest>,
+        trusted_issuers: StoredMap<EndpointId>,
+        received_credentials: Wallet,
+        verified_credentials: StoredMap<VerifiedCredentialRecord>,
+        verified_presentations: StoredMap<VerifiedBundleRecord>,
+        verified_disclosures: StoredMap<VerifiedDisclosureRecord>,
+        verified_threshold_proofs: StoredMap<VerifiedThresholdRecord>,
+        issued_credentials: StoredMap<IssuedCredentialRecord>,
+        revocations: RevocationRegistry,
+        status_list: StoredValue<StatusListState>,
+        status_lists: StatusListCache,
+        verification_policy: StoredValue<VerificationPolicy>,
+        issuer_profile: StoredValue<IssuerProfile>,
+        policy_proposal: StoredValue<Option<SignedApprovalPolicy>>,
+        endpoint: Endpoint,
+        presentation_requests: StoredMap<ReceivedPresentationRequest>,
+        sent_presentation_requests: StoredMap<SentPresentationRequest>,
+        key_bindings: StoredMap<SignedKeyBinding>,
     ) -> Self {
+        let did_resolver = Arc::new(IrohDidResolver {
+            endpoint: endpoint.clone(),
+            revocations: revocations.clone(),
+            key_bindings: key_bindings.clone(),
+        });
         Self {
             event_sender,
             role,
             secret_key,
+            signer,
             node_id,
             pending_requests,
             trusted_issuers,
             received_credentials,
             verified_credentials,
+            verified_presentations,
+            verified_disclosures,
+            verified_threshold_proofs,
+            issued_credentials,
+            revocations,
+            status_list,
+            status_lists,
+            verification_policy,
+            issuer_profile,
+            policy_proposal,
+            did_resolver,
+            endpoint,
+            challenges: ChallengeRegistry::default(),
+            presentation_requests,
+            sent_presentation_requests,
+            key_bindings,
         }
     }
 }
@@ -388,7 +1648,7 @@ impl Peer {
 
         // Read the incoming message (max 1MB)
         let buffer = recv.read_to_end(1024 * 1024).await.map_err(|e| {
-            std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to read message: {}", e))
+            std::io::Error::other(format!("Failed to read message: {}", e))
         })?;
 
         // Parse the message as JSON
@@ -413,15 +1673,106 @@ impl Peer {
                 self.handle_issue_request(message).await
             }
             (Role::Verifier, CredentialMessage::PresentCredential { .. }) => {
-                self.handle_verify_credential(message).await
+                self.handle_verify_credential(message, node_id).await
+            }
+            (Role::Verifier, CredentialMessage::PresentJws { .. }) => {
+                self.handle_verify_jws(message, node_id).await
+            }
+            (Role::Verifier, CredentialMessage::PresentDisclosure { .. }) => {
+                self.handle_verify_disclosure(message, node_id).await
+            }
+            (Role::Verifier, CredentialMessage::PresentThresholdProof { .. }) => {
+                self.handle_verify_threshold_proof(message, node_id).await
+            }
+            (Role::Verifier, CredentialMessage::PresentCredentials { .. }) => {
+                self.handle_verify_presentation(message, node_id).await
+            }
+            (Role::Verifier, CredentialMessage::ChallengeRequest { request_id }) => {
+                match self.challenges.issue(node_id).await {
+                    Some(challenge) => Ok(CredentialMessage::Challenge {
+                        request_id: request_id.clone(),
+                        nonce: challenge.nonce,
+                        expires_at: challenge.expires_at,
+                    }),
+                    None => Ok(CredentialMessage::Error {
+                        request_id: request_id.clone(),
+                        error_code: "TOO_MANY_CHALLENGES".to_string(),
+                        message: "Too many outstanding challenges; present or wait for earlier ones to expire".to_string(),
+                    }),
+                }
             }
             (Role::Employee, CredentialMessage::IssueResponse { .. }) => {
                 self.handle_issue_response(message, node_id).await
             }
-            _ => {
-                let error_msg = format!(
-                    "Role {:?} cannot handle this message type",
-                    self.role
+            (Role::Processor, CredentialMessage::CoSignRequest { .. }) => {
+                self.handle_co_sign_request(message, node_id).await
+            }
+            (Role::Issuer, CredentialMessage::ProfileRequest { request_id }) => {
+                self.handle_profile_request(request_id.clone()).await
+            }
+            (Role::Issuer, CredentialMessage::KeyBindingRequest { request_id, node_id, signing_key }) => {
+                let binding = match self.revocations.is_unbound(*node_id, *signing_key).await {
+                    true => None,
+                    false => self.key_bindings.get(&binding_key(*node_id, *signing_key)).await,
+                };
+                match binding {
+                    Some(binding) => Ok(CredentialMessage::KeyBindingResponse { request_id: request_id.clone(), binding }),
+                    None => Ok(CredentialMessage::Error {
+                        request_id: request_id.clone(),
+                        error_code: "NOT_FOUND".to_string(),
+                        message: format!("Signing key {} is not bound to node {}", signing_key, node_id),
+                    }),
+                }
+            }
+            (_, CredentialMessage::DidDocumentRequest { request_id, did }) => {
+                match parse_did_iroh(did) {
+                    Ok(node_id) => match self.did_document(node_id).await {
+                        Some((document, bindings)) => Ok(CredentialMessage::DidDocumentResponse {
+                            request_id: request_id.clone(),
+                            document,
+                            bindings,
+                            unbindings: self.revocations.unbindings_of(node_id).await,
+                        }),
+                        None => Ok(CredentialMessage::Error {
+                            request_id: request_id.clone(),
+                            error_code: "NOT_FOUND".to_string(),
+                            message: format!("{} is not a DID of node {}", did, self.node_id),
+                        }),
+                    },
+                    Err(e) => Ok(CredentialMessage::Error {
+                        request_id: request_id.clone(),
+                        error_code: "INVALID_DID".to_string(),
+                        message: e.to_string(),
+                    }),
+                }
+            }
+            (_, CredentialMessage::KeyHistoryRequest { request_id }) => {
+                let rotations = self.revocations.rotation_log().await.history(self.node_id);
+                Ok(CredentialMessage::KeyHistory { request_id: request_id.clone(), rotations })
+            }
+            (_, CredentialMessage::ApprovalPolicyRequest { request_id }) => {
+                let policies = self.own_policy_history().await;
+                Ok(CredentialMessage::ApprovalPolicies { request_id: request_id.clone(), policies })
+            }
+            (Role::Issuer, CredentialMessage::ApprovalQueueRequest { request_id }) => {
+                self.handle_approval_queue_request(request_id.clone(), node_id).await
+            }
+            (Role::Issuer, CredentialMessage::SubmitApproval { .. }) => {
+                self.handle_submit_approval(message, node_id).await
+            }
+            (Role::Issuer, CredentialMessage::SubmitPolicyApproval { .. }) => {
+                self.handle_submit_policy_approval(message, node_id).await
+            }
+            (Role::Employee, CredentialMessage::PresentationRequest { .. }) => {
+                self.handle_presentation_request(message, node_id).await
+            }
+            (Role::Verifier, CredentialMessage::PresentationDeclined { .. }) => {
+                self.handle_presentation_declined(message, node_id).await
+            }
+            _ => {
+                let error_msg = format!(
+                    "Role {:?} cannot handle this message type",
+                    self.role
                 );
                 tracing::warn!("{}", error_msg);
                 Ok(CredentialMessage::Error {
@@ -442,14 +1793,14 @@ impl Peer {
 
         // Serialize and send the response
         let response_bytes = serde_json::to_vec(&response).map_err(|e| {
-            std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to serialize response: {}", e))
+            std::io::Error::other(format!("Failed to serialize response: {}", e))
         })?;
 
         send.write_all(&response_bytes).await.map_err(|e| {
-            std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to write response: {}", e))
+            std::io::Error::other(format!("Failed to write response: {}", e))
         })?;
         send.finish().map_err(|e| {
-            std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to finish stream: {}", e))
+            std::io::Error::other(format!("Failed to finish stream: {}", e))
         })?;
 
         info!("Sent response, {} bytes", response_bytes.len());
@@ -499,12 +1850,15 @@ impl Peer {
                 payment_mode,
                 requested_at: chrono::Utc::now().to_rfc3339(),
                 status: RequestStatus::Pending,
+                undelivered_response: None,
+                delivered_at: None,
+                approvals: Vec::new(),
+                approvals_required: self.own_policy().await.map_or(0, |policy| policy.policy.threshold),
+                draft: None,
             };
             
             // Store in pending requests
-            let mut requests = self.pending_requests.write().await;
-            requests.insert(request_id.clone(), pending_request);
-            drop(requests); // Release lock
+            self.pending_requests.insert(request_id.clone(), pending_request).await?;
             
             info!("Request {} queued for approval", request_id);
             
@@ -531,6 +1885,10 @@ impl Peer {
         if let CredentialMessage::IssueResponse {
             request_id,
             credential,
+            selective_credential,
+            threshold_credential,
+            verifiable_credential,
+            jws,
             error,
         } = message
         {
@@ -540,14 +1898,27 @@ impl Peer {
             let response = ReceivedCredentialResponse {
                 request_id: request_id.clone(),
                 credential: credential.clone(),
+                selective_credential: selective_credential.map(|credential| *credential),
+                threshold_credential: threshold_credential.map(|credential| *credential),
+                verifiable_credential: verifiable_credential.map(|credential| *credential),
+                jws,
                 error: error.clone(),
                 received_at: chrono::Utc::now().to_rfc3339(),
                 issuer_node_id,
             };
             
-            let mut credentials = self.received_credentials.write().await;
-            credentials.insert(request_id.clone(), response);
-            drop(credentials);
+            match self.received_credentials.insert(response).await {
+                Ok(()) => {}
+                Err(WalletError::Locked) => {
+                    tracing::warn!("Wallet is locked, refusing credential response for request {}", request_id);
+                    return Ok(CredentialMessage::Error {
+                        request_id,
+                        error_code: "WALLET_LOCKED".to_string(),
+                        message: "The employee's wallet is locked; resend once it is unlocked".to_string(),
+                    });
+                }
+                Err(e) => return Err(e.into()),
+            }
             
             if credential.is_some() {
                 info!("Credential approved and stored for request {}", request_id);
@@ -570,131 +1941,684 @@ impl Peer {
     async fn handle_verify_credential(
         &self,
         message: CredentialMessage,
+        presenter_node_id: EndpointId,
     ) -> Result<CredentialMessage, anyhow::Error> {
         if let CredentialMessage::PresentCredential {
             presentation_id,
             credential,
+            envelope,
         } = message
         {
             let issuer_node_id = credential.issuer_node_id();
             info!("Verifying credential from {} issued by {}", 
                 credential.employee_node_id(), issuer_node_id);
             
-            // Check if issuer is trusted
-            let trusted_issuers = self.trusted_issuers.read().await;
-            let is_trusted = trusted_issuers.contains(&issuer_node_id);
-            drop(trusted_issuers); // Release lock
+            let presentation_request = match self.open_presentation_request(&presentation_id).await {
+                Ok(request) => request,
+                Err(refusal) => return Ok(refusal),
+            };
+            
+            // Check the presenter holds the credential
+            let expected_nonce = presentation_request.as_ref().map(|request| request.nonce.as_str());
+            let holder_binding = match &envelope {
+                Some(envelope) => self.check_envelope(envelope, &[&credential], expected_nonce).await,
+                None if presenter_node_id == credential.employee_node_id() => HolderBinding::Connection,
+                None => HolderBinding::Unbound,
+            };
             
-            // Verify the signature
-            match credential.verify() {
-                Ok(is_valid) => {
-                    let message_text = if is_valid && is_trusted {
-                        format!(
-                            "Credential verified successfully. Issued by trusted issuer: {}",
-                            issuer_node_id
-                        )
-                    } else if is_valid && !is_trusted {
-                        format!(
-                            "Credential signature is valid but issuer {} is not in trusted list",
-                            issuer_node_id
-                        )
-                    } else {
-                        "Invalid credential signature".to_string()
-                    };
-                    
-                    info!("Verification result: valid={}, trusted={}", is_valid, is_trusted);
-                    
-                    // Store the verification record
-                    let record = VerifiedCredentialRecord {
-                        presentation_id: presentation_id.clone(),
-                        credential: credential.clone(),
-                        employee_node_id: credential.employee_node_id(),
-                        issuer_node_id,
-                        is_valid,
-                        is_trusted,
-                        verified_at: chrono::Utc::now().to_rfc3339(),
-                        verifier_node_id: self.node_id,
-                    };
-                    
-                    let mut verified = self.verified_credentials.write().await;
-                    verified.insert(presentation_id.clone(), record);
-                    drop(verified);
-                    
-                    Ok(CredentialMessage::VerificationResult {
-                        presentation_id,
-                        is_valid,
-                        is_trusted,
-                        issuer_node_id,
-                        message: message_text,
-                    })
-                }
-                Err(e) => {
-                    tracing::error!("Verification error: {}", e);
-                    Ok(CredentialMessage::VerificationResult {
-                        presentation_id,
-                        is_valid: false,
-                        is_trusted: false,
-                        issuer_node_id,
-                        message: format!("Verification error: {}", e),
-                    })
-                }
+            let request_mismatch = presentation_request
+                .as_ref()
+                .and_then(|request| request.mismatch(&credential, envelope.is_some()));
+            let request_satisfied = presentation_request.as_ref().map(|_| request_mismatch.is_none());
+            
+            let check = self.check_credential(&credential, holder_binding, request_mismatch.as_deref()).await;
+            info!("Verification result: valid={}, trusted={}, legacy={}, validity={:?}, revoked={}, holder={:?}",
+                check.is_valid, check.is_trusted, check.legacy_signature, check.validity, check.is_revoked, holder_binding);
+            
+            // Store the verification record
+            let record = VerifiedCredentialRecord::new(
+                presentation_id.clone(),
+                credential,
+                &check,
+                holder_binding,
+                request_satisfied,
+                self.node_id,
+            );
+            self.verified_credentials.insert(presentation_id.clone(), record).await?;
+            if presentation_request.is_some() {
+                self.answer_presentation_request(&presentation_id, presenter_node_id).await?;
             }
+            
+            Ok(CredentialMessage::VerificationResult {
+                presentation_id,
+                is_valid: check.is_valid,
+                is_trusted: check.is_trusted,
+                legacy_signature: check.legacy_signature,
+                validity: check.validity,
+                is_revoked: check.is_revoked,
+                revocation_reason: check.revocation_reason,
+                status_check: check.status_check,
+                status_list_version: check.status_list_version,
+                holder_binding,
+                request_satisfied,
+                signers: check.signers,
+                approvals: check.approvals,
+                issuer_node_id,
+                message: check.message,
+            })
         } else {
             Err(anyhow::anyhow!("Invalid message type for handle_verify_credential"))
         }
     }
 
+    /// Handle a credential presented as a compact JWS (Verifier role)
+    /// Checks the token, then the credential it carries like `PresentCredential`
+    async fn handle_verify_jws(
+        &self,
+        message: CredentialMessage,
+        presenter_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::PresentJws { presentation_id, jws, envelope } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_verify_jws"));
+        };
+        let credential = match verify_jws(&jws) {
+            Ok(credential) => credential,
+            Err(e) => {
+                return Ok(CredentialMessage::Error {
+                    request_id: presentation_id,
+                    error_code: "INVALID_JWS".to_string(),
+                    message: format!("Credential JWS does not verify: {e}"),
+                });
+            }
+        };
+        let message = CredentialMessage::PresentCredential {
+            presentation_id,
+            credential,
+            envelope,
+        };
+        self.handle_verify_credential(message, presenter_node_id).await
+    }
+
+    /// Handle a selective disclosure (Verifier role)
+    /// Checks the disclosed fields against the issuer's signed commitment
+    async fn handle_verify_disclosure(
+        &self,
+        message: CredentialMessage,
+        presenter_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::PresentDisclosure { presentation_id, credential, envelope } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_verify_disclosure"));
+        };
+        let issuer_node_id = credential.issuer_node_id();
+        info!("Verifying disclosure of {} fields from {} issued by {}",
+            credential.disclosures.len(), credential.employee_node_id(), issuer_node_id);
+        
+        let presentation_request = match self.open_presentation_request(&presentation_id).await {
+            Ok(request) => request,
+            Err(refusal) => return Ok(refusal),
+        };
+        
+        let expected_nonce = presentation_request.as_ref().map(|request| request.nonce.as_str());
+        let holder_binding = match &envelope {
+            Some(envelope) => self.check_envelope(envelope, &[&credential], expected_nonce).await,
+            None if presenter_node_id == credential.employee_node_id() => HolderBinding::Connection,
+            None => HolderBinding::Unbound,
+        };
+        
+        let request_mismatch = presentation_request
+            .as_ref()
+            .and_then(|request| request.mismatch(&credential, envelope.is_some()));
+        let request_satisfied = presentation_request.as_ref().map(|_| request_mismatch.is_none());
+        
+        let check = self.check_credential(&credential, holder_binding, request_mismatch.as_deref()).await;
+        info!("Disclosure result: valid={}, trusted={}, validity={:?}, revoked={}, holder={:?}",
+            check.is_valid, check.is_trusted, check.validity, check.is_revoked, holder_binding);
+        
+        let record = VerifiedDisclosureRecord {
+            presentation_id: presentation_id.clone(),
+            claims: credential.disclosed_claims(),
+            employee_node_id: credential.employee_node_id(),
+            issuer_node_id,
+            credential,
+            is_valid: check.is_valid,
+            is_trusted: check.is_trusted,
+            validity: check.validity,
+            is_revoked: check.is_revoked,
+            revocation_reason: check.revocation_reason.clone(),
+            status_check: check.status_check,
+            status_list_version: check.status_list_version,
+            holder_binding,
+            request_satisfied,
+            verified_at: chrono::Utc::now().to_rfc3339(),
+            verifier_node_id: self.node_id,
+        };
+        self.verified_disclosures.insert(presentation_id.clone(), record).await?;
+        if presentation_request.is_some() {
+            self.answer_presentation_request(&presentation_id, presenter_node_id).await?;
+        }
+        
+        Ok(CredentialMessage::VerificationResult {
+            presentation_id,
+            is_valid: check.is_valid,
+            is_trusted: check.is_trusted,
+            legacy_signature: false,
+            validity: check.validity,
+            is_revoked: check.is_revoked,
+            revocation_reason: check.revocation_reason,
+            status_check: check.status_check,
+            status_list_version: check.status_list_version,
+            holder_binding,
+            request_satisfied,
+            signers: check.signers,
+            approvals: check.approvals,
+            issuer_node_id,
+            message: check.message,
+        })
+    }
+
+    /// Handle a net salary threshold proof (Verifier role)
+    /// Checks the range proof against the issuer's signed amount commitment
+    async fn handle_verify_threshold_proof(
+        &self,
+        message: CredentialMessage,
+        presenter_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::PresentThresholdProof { presentation_id, proof, envelope } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_verify_threshold_proof"));
+        };
+        let issuer_node_id = proof.issuer_node_id();
+        info!("Verifying net salary of at least {} {} from {} issued by {}",
+            proof.threshold, proof.credential.currency, proof.holder_node_id(), issuer_node_id);
+        
+        let presentation_request = match self.open_presentation_request(&presentation_id).await {
+            Ok(request) => request,
+            Err(refusal) => return Ok(refusal),
+        };
+        
+        let expected_nonce = presentation_request.as_ref().map(|request| request.nonce.as_str());
+        let holder_binding = match &envelope {
+            Some(envelope) => self.check_envelope(envelope, &[&proof], expected_nonce).await,
+            None if presenter_node_id == proof.holder_node_id() => HolderBinding::Connection,
+            None => HolderBinding::Unbound,
+        };
+        
+        let request_mismatch = presentation_request
+            .as_ref()
+            .and_then(|request| request.mismatch(&proof, envelope.is_some()));
+        let request_satisfied = presentation_request.as_ref().map(|_| request_mismatch.is_none());
+        
+        let check = self.check_credential(&proof, holder_binding, request_mismatch.as_deref()).await;
+        info!("Threshold proof result: valid={}, trusted={}, validity={:?}, revoked={}, holder={:?}",
+            check.is_valid, check.is_trusted, check.validity, check.is_revoked, holder_binding);
+        
+        let record = VerifiedThresholdRecord {
+            presentation_id: presentation_id.clone(),
+            claims: proof.disclosed_claims(),
+            employee_node_id: proof.holder_node_id(),
+            issuer_node_id,
+            proof,
+            is_valid: check.is_valid,
+            is_trusted: check.is_trusted,
+            validity: check.validity,
+            is_revoked: check.is_revoked,
+            revocation_reason: check.revocation_reason.clone(),
+            status_check: check.status_check,
+            status_list_version: check.status_list_version,
+            holder_binding,
+            request_satisfied,
+            verified_at: chrono::Utc::now().to_rfc3339(),
+            verifier_node_id: self.node_id,
+        };
+        self.verified_threshold_proofs.insert(presentation_id.clone(), record).await?;
+        if presentation_request.is_some() {
+            self.answer_presentation_request(&presentation_id, presenter_node_id).await?;
+        }
+        
+        Ok(CredentialMessage::VerificationResult {
+            presentation_id,
+            is_valid: check.is_valid,
+            is_trusted: check.is_trusted,
+            legacy_signature: false,
+            validity: check.validity,
+            is_revoked: check.is_revoked,
+            revocation_reason: check.revocation_reason,
+            status_check: check.status_check,
+            status_list_version: check.status_list_version,
+            holder_binding,
+            request_satisfied,
+            signers: check.signers,
+            approvals: check.approvals,
+            issuer_node_id,
+            message: check.message,
+        })
+    }
+
+    /// Handle a bundled presentation (Verifier role)
+    /// Checks the holder signature once and every credential on its own
+    async fn handle_verify_presentation(
+        &self,
+        message: CredentialMessage,
+        presenter_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::PresentCredentials { presentation_id, presentation } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_verify_presentation"));
+        };
+        info!("Verifying {} credentials presented by {}", presentation.credentials.len(), presenter_node_id);
+        
+        let presentation_request = match self.open_presentation_request(&presentation_id).await {
+            Ok(request) => request,
+            Err(refusal) => return Ok(refusal),
+        };
+        
+        // One holder signature covers the whole bundle
+        let expected_nonce = presentation_request.as_ref().map(|request| request.nonce.as_str());
+        let holder_binding = match presentation.verify() {
+            Ok(true) => {
+                let credentials: Vec<_> = presentation.credentials.iter().collect();
+                self.check_envelope(&presentation.envelope, &credentials, expected_nonce).await
+            }
+            Ok(false) => HolderBinding::InvalidEnvelope,
+            Err(e) => {
+                tracing::warn!("Failed to verify presentation: {}", e);
+                HolderBinding::InvalidEnvelope
+            }
+        };
+        
+        let mut checks = Vec::with_capacity(presentation.credentials.len());
+        let mut request_satisfied = presentation_request.as_ref().map(|_| true);
+        for credential in &presentation.credentials {
+            let request_mismatch = presentation_request
+                .as_ref()
+                .and_then(|request| request.mismatch(credential, true));
+            if request_mismatch.is_some() {
+                request_satisfied = Some(false);
+            }
+            checks.push(self.check_credential(credential, holder_binding, request_mismatch.as_deref()).await);
+        }
+        
+        let failed = checks.iter().filter(|check| !check.is_valid).count();
+        let is_valid = !checks.is_empty() && failed == 0;
+        let message = if !holder_binding.is_bound() {
+            format!("Presentation rejected: {}", holder_binding.as_str())
+        } else if is_valid {
+            format!("All {} credentials verified", checks.len())
+        } else {
+            format!("{} of {} credentials failed verification", failed, checks.len())
+        };
+        info!("Presentation {} result: valid={}, holder={:?}, failed={}", presentation_id, is_valid, holder_binding, failed);
+        
+        // One record per credential, written in one go so a bundle reusing the
+        // presenter's ID replaces the earlier one whole
+        let bundle_size = checks.len() as u32;
+        let mut records = Vec::with_capacity(checks.len());
+        for (index, (credential, check)) in presentation.credentials.into_iter().zip(&checks).enumerate() {
+            let mut record = VerifiedCredentialRecord::new(
+                presentation_id.clone(),
+                credential,
+                check,
+                holder_binding,
+                request_satisfied,
+                self.node_id,
+            );
+            record.bundle_index = Some(index as u32);
+            record.bundle_size = Some(bundle_size);
+            records.push(record);
+        }
+        let bundle = VerifiedBundleRecord {
+            presentation_id: presentation_id.clone(),
+            presenter_node_id,
+            records,
+        };
+        self.verified_presentations
+            .insert(format!("{}/{}", presenter_node_id, presentation_id), bundle)
+            .await?;
+        if presentation_request.is_some() {
+            self.answer_presentation_request(&presentation_id, presenter_node_id).await?;
+        }
+        
+        Ok(CredentialMessage::PresentationResult(PresentationVerdict {
+            presentation_id,
+            is_valid,
+            holder_binding,
+            request_satisfied,
+            credentials: checks,
+            message,
+        }))
+    }
+
+    /// Looks up the presentation request a presentation answers, if any.
+    /// Requests that were already answered get an error reply instead.
+    async fn open_presentation_request(
+        &self,
+        presentation_id: &str,
+    ) -> Result<Option<SentPresentationRequest>, CredentialMessage> {
+        match self.sent_presentation_requests.get(presentation_id).await {
+            Some(request) if request.status != RequestStatus::Pending => Err(CredentialMessage::Error {
+                request_id: presentation_id.to_string(),
+                error_code: "REQUEST_ANSWERED".to_string(),
+                message: format!("Presentation request is no longer pending (status: {:?})", request.status),
+            }),
+            request => Ok(request),
+        }
+    }
+
+    /// Checks the signature, trust, validity window and revocation status of one
+    /// credential. `holder_binding` and `request_mismatch` come from the
+    /// presentation it arrived in.
+    async fn check_credential(
+        &self,
+        credential: &(impl PresentableCredential + Sync),
+        holder_binding: HolderBinding,
+        request_mismatch: Option<&str>,
+    ) -> CredentialCheck {
+        let policy = self.verification_policy.get().await;
+        CredentialVerifier::new(self, self, &SystemClock)
+            .with_max_age(policy.max_age())
+            .with_legacy_signatures(policy.accept_legacy_signatures)
+            .with_unavailable_status(policy.accept_unavailable_status)
+            .check_presented(credential, holder_binding, request_mismatch)
+            .await
+    }
+
+    /// Checks a holder envelope is addressed to this verifier, signed by the
+    /// holder of `credentials`, and carries a nonce this verifier issued:
+    /// `expected_nonce` for an answer to a presentation request, otherwise a
+    /// challenge nonce
+    async fn check_envelope<C: PresentableCredential>(
+        &self,
+        envelope: &PresentationEnvelope,
+        credentials: &[&C],
+        expected_nonce: Option<&str>,
+    ) -> HolderBinding {
+        if envelope.verifier_node_id != self.node_id {
+            tracing::warn!("Envelope addressed to verifier {}", envelope.verifier_node_id);
+            return HolderBinding::InvalidEnvelope;
+        }
+        match envelope.verify(credentials) {
+            Ok(true) => {}
+            Ok(false) => return HolderBinding::InvalidEnvelope,
+            Err(e) => {
+                tracing::warn!("Failed to verify envelope: {}", e);
+                return HolderBinding::InvalidEnvelope;
+            }
+        }
+        let nonce_valid = match expected_nonce {
+            // Request nonces are used up by answering the request
+            Some(expected) => envelope.nonce == expected,
+            None => self.challenges.consume(&envelope.nonce).await,
+        };
+        if !nonce_valid {
+            tracing::warn!("Envelope nonce {} is unknown, used or expired", envelope.nonce);
+            return HolderBinding::InvalidEnvelope;
+        }
+        HolderBinding::Envelope
+    }
+
     // State management methods
 
     /// Get all pending credential requests
     pub async fn get_pending_requests(&self) -> Vec<PendingCredentialRequest> {
-        let requests = self.pending_requests.read().await;
-        requests.values().cloned().collect()
+        self.pending_requests.values().await
     }
 
     /// Approve a pending credential request and send signed credential to employee
+    ///
+    /// Under an approval policy this signs the credential for officers to approve,
+    /// adding the node's own approval if it is an officer, and delivers it only
+    /// once the threshold is met.
     pub async fn approve_request(
         &self,
         request_id: String,
-        secret_key: &SecretKey,
+        validity: ValidityWindow,
         endpoint: &Endpoint,
     ) -> Result<(), anyhow::Error> {
-        // Get and update the request
-        let mut requests = self.pending_requests.write().await;
-        let request = requests.get_mut(&request_id)
-            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
+        validity.validate()?;
         
-        if request.status != RequestStatus::Pending {
-            return Err(anyhow::anyhow!("Request {} is not pending (status: {:?})", 
-                request_id, request.status));
+        if let Some(policy) = self.own_policy().await {
+            let request = self.pending_requests
+                .get(&request_id)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
+            if request.status != RequestStatus::Pending {
+                anyhow::bail!("Request {} is not pending (status: {:?})", request_id, request.status);
+            }
+            let draft = match request.draft {
+                Some(draft) => draft,
+                None => self.draft_credential(&request_id, validity).await?,
+            };
+            if policy.policy.is_officer(&self.node_id) {
+                let approval = OfficerApproval::sign(&self.secret_key, request_id, &draft, policy.version)?;
+                self.record_approval(approval, endpoint).await?;
+            } else if (request.approvals.len() as u32) < policy.policy.threshold {
+                info!(
+                    "Request {} awaits {} of {} officer approvals and has {}",
+                    request_id, policy.policy.threshold, policy.policy.officers.len(), request.approvals.len()
+                );
+            } else {
+                // Approvals may already meet the threshold if delivering failed after the last one
+                let credential_data = self.claim_request(&request_id).await?;
+                self.deliver_credential(credential_data, draft, None, endpoint).await?;
+            }
+            return Ok(());
         }
         
-        // Clone the data we need before releasing the lock
-        let employee_node_id = request.employee_node_id;
-        let employee_name = request.employee_name.clone();
-        let credential_data = request.clone();
+        let credential_data = self.claim_request(&request_id).await?;
+        self.issue_credential(credential_data, validity, endpoint).await
+    }
+
+    /// Marks a pending request `Approved`, returning it as it was
+    async fn claim_request(&self, request_id: &str) -> Result<PendingCredentialRequest, anyhow::Error> {
+        self.pending_requests
+            .update(request_id, |request| {
+                if request.status != RequestStatus::Pending {
+                    return Err(anyhow::anyhow!("Request {} is not pending (status: {:?})", 
+                        request_id, request.status));
+                }
+                let credential_data = request.clone();
+                request.status = RequestStatus::Approved;
+                Ok(credential_data)
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))
+    }
+
+    /// Puts an `Approved` request back in the queue after issuing failed, releasing
+    /// the status index allocated to the credential that was not issued
+    async fn reopen_request(&self, request_id: &str, status_index: Option<u32>) -> Result<(), anyhow::Error> {
+        if let Some(index) = status_index {
+            self.release_status_index(index).await?;
+        }
+        self.pending_requests
+            .update(request_id, |request| {
+                request.status = RequestStatus::Pending;
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn release_status_index(&self, index: u32) -> Result<(), anyhow::Error> {
+        self.status_list
+            .update(|state| {
+                state.release(index);
+                Ok::<_, anyhow::Error>(())
+            })
+            .await
+    }
+
+    /// Signs the credential for a request already marked `Approved` and delivers it,
+    /// reverting the request to `Pending` if the signer fails or a co-signer refuses
+    async fn issue_credential(
+        &self,
+        credential_data: PendingCredentialRequest,
+        validity: ValidityWindow,
+        endpoint: &Endpoint,
+    ) -> Result<(), anyhow::Error> {
+        let draft = match self.sign_credential(&credential_data, validity).await {
+            Ok(draft) => draft,
+            Err(e) => {
+                self.reopen_request(&credential_data.request_id, None).await?;
+                return Err(e);
+            }
+        };
+        let status_index = draft.credential.credential.status_index;
+        self.deliver_credential(credential_data, draft, status_index, endpoint).await
+    }
+
+    /// Signs the credential for a pending request of an organizational issuer and
+    /// keeps it on the request for officers to approve
+    async fn draft_credential(&self, request_id: &str, validity: ValidityWindow) -> Result<CredentialDraft, anyhow::Error> {
+        let request = self.pending_requests
+            .get(request_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
+        let draft = self.sign_credential(&request, validity).await?;
+        let stored = self.pending_requests
+            .update(request_id, |request| {
+                if request.status != RequestStatus::Pending || request.draft.is_some() {
+                    anyhow::bail!("Request {} changed while its credential was signed", request_id);
+                }
+                request.draft = Some(draft.clone());
+                Ok(())
+            })
+            .await;
+        if !matches!(stored, Ok(Some(()))) {
+            if let Some(index) = draft.credential.credential.status_index {
+                self.release_status_index(index).await?;
+            }
+            stored?;
+            anyhow::bail!("Request {} not found", request_id);
+        }
+        info!("Credential for request {} signed, awaiting officer approvals", request_id);
+        Ok(draft)
+    }
+
+    /// Allocates a status index and signs every form of the credential for
+    /// `credential_data`, releasing the index if signing fails
+    async fn sign_credential(
+        &self,
+        credential_data: &PendingCredentialRequest,
+        validity: ValidityWindow,
+    ) -> Result<CredentialDraft, anyhow::Error> {
+        let request_id = &credential_data.request_id;
+        if let Some(rotation) = self.revocations.rotation_log().await.rotation_of(&self.node_id) {
+            anyhow::bail!(
+                "This node's key was rotated to {}; restart it with the new key to issue",
+                rotation.rotation.new_node_id
+            );
+        }
+        let signing_key = self.signer.public_key();
+        if self.revocations.is_unbound(self.node_id, signing_key).await {
+            anyhow::bail!("Signing key {} was unbound; restart the node with a new one to issue", signing_key);
+        }
         
-        // Update status
-        request.status = RequestStatus::Approved;
-        drop(requests); // Release lock
+        info!("Approving request {} for {}", request_id, credential_data.employee_name);
         
-        info!("Approving request {} for {}", request_id, employee_name);
+        let status_index = self.status_list
+            .update(|state| Ok::<_, anyhow::Error>(state.allocate()))
+            .await?;
         
         // Create and sign the credential
-        let credential = IncomeCredential::new(
+        let profile = self.issuer_profile.get().await;
+        let mut credential = IncomeCredential::new(
             credential_data.employee_node_id,
-            credential_data.employee_name,
+            credential_data.employee_name.clone(),
             self.node_id,
-            "AscentHR Organization".to_string(), // TODO: Make configurable
-            credential_data.gross_salary,
-            credential_data.net_salary,
-            credential_data.currency,
-            credential_data.pay_period,
-            credential_data.payment_mode,
-        );
+            profile.organization_name,
+            credential_data.gross_salary.clone(),
+            credential_data.net_salary.clone(),
+            credential_data.currency.clone(),
+            credential_data.pay_period.clone(),
+            credential_data.payment_mode.clone(),
+        )
+        .with_validity(validity)
+        .with_status_index(status_index);
+        if let Some(processor) = profile.default_payroll_processor {
+            credential = credential.with_payroll_processor(processor.node_id, processor.name);
+        }
+        
+        // A separate signing key is named next to each signature; verifiers check its binding
+        let signed = async {
+            let selective_credential = credential.sign_selective_with(&*self.signer).await
+                .map_err(|e| anyhow::anyhow!("Failed to sign credential commitment: {}", e))?;
+            let threshold_credential = match credential.sign_amount_commitment_with(&*self.signer).await {
+                Ok(threshold_credential) => Some(threshold_credential),
+                // Amounts with more than two decimals cannot be committed to; issue without one
+                Err(e @ CredentialError::InvalidProof(_)) => {
+                    tracing::warn!("No net salary commitment for request {}: {}", request_id, e);
+                    None
+                }
+                Err(e) => anyhow::bail!("Failed to sign net salary commitment: {}", e),
+            };
+            let credential = credential.sign_with(&*self.signer).await
+                .map_err(|e| anyhow::anyhow!("Failed to sign credential: {}", e))?;
+            Ok(CredentialDraft { credential, selective_credential, threshold_credential })
+        };
+        // The signer may run out of process; the index is released if it fails
+        match signed.await {
+            Ok(draft) => Ok(draft),
+            Err(e) => {
+                self.release_status_index(status_index).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Attaches the officer approvals and co-signatures to the signed credential of a
+    /// request already marked `Approved`, then records and delivers it. On failure the
+    /// request reverts to `Pending`, releasing `releasable_index` if given.
+    async fn deliver_credential(
+        &self,
+        credential_data: PendingCredentialRequest,
+        mut draft: CredentialDraft,
+        releasable_index: Option<u32>,
+        endpoint: &Endpoint,
+    ) -> Result<(), anyhow::Error> {
+        let request_id = credential_data.request_id.clone();
+        let employee_node_id = credential_data.employee_node_id;
+        if let Some(rotation) = self.revocations.rotation_log().await.rotation_of(&self.node_id) {
+            self.reopen_request(&request_id, releasable_index).await?;
+            anyhow::bail!(
+                "This node's key was rotated to {}; restart it with the new key to issue",
+                rotation.rotation.new_node_id
+            );
+        }
+        for approval in &credential_data.approvals {
+            draft.attach(approval);
+        }
+        
+        // Declared co-signers sign every form before the credential is recorded or delivered
+        for (co_signer, role) in draft.credential.declared_co_signers() {
+            if let Err(e) = self.request_co_signature(&request_id, &mut draft, co_signer, endpoint).await {
+                self.reopen_request(&request_id, releasable_index).await?;
+                return Err(e.context(format!("{} {} did not co-sign request {}", role.as_str(), co_signer, request_id)));
+            }
+        }
+        let CredentialDraft { credential: signed_credential, selective_credential, threshold_credential } = draft;
+        // Exported once co-signed, so the documents carry every signature
+        let exported = async {
+            let verifiable_credential = signed_credential.to_verifiable_credential(&*self.signer).await?;
+            let jws = signed_credential.to_jws(&*self.signer).await?;
+            Ok::<_, CredentialError>((Box::new(verifiable_credential), jws))
+        };
+        let (verifiable_credential, jws) = match exported.await {
+            Ok(exported) => exported,
+            Err(e) => {
+                self.reopen_request(&request_id, releasable_index).await?;
+                anyhow::bail!("Failed to sign verifiable credential: {}", e);
+            }
+        };
         
-        let signed_credential = credential.sign(secret_key)
-            .map_err(|e| anyhow::anyhow!("Failed to sign credential: {}", e))?;
+        let issued = IssuedCredentialRecord {
+            credential_id: signed_credential.id().to_string(),
+            request_id: request_id.clone(),
+            employee_node_id,
+            issued_at: signed_credential.credential().issued_at.clone(),
+            status_index: signed_credential.credential().status_index,
+            revocation: None,
+            approvals: credential_data.approvals,
+        };
+        self.issued_credentials.insert(issued.credential_id.clone(), issued).await?;
         
         info!("Credential signed, connecting to employee {}", employee_node_id);
         
@@ -702,151 +2626,1286 @@ impl Peer {
         let message = CredentialMessage::IssueResponse {
             request_id: request_id.clone(),
             credential: Some(signed_credential),
+            selective_credential: Some(Box::new(selective_credential)),
+            threshold_credential: threshold_credential.map(Box::new),
+            verifiable_credential: Some(verifiable_credential),
+            jws: Some(jws),
             error: None,
         };
-        
-        // Initiate connection to employee
-        let endpoint = endpoint.clone();
-        task::spawn(async move {
-            let (event_sender, event_receiver) = async_channel::bounded(16);
-            
-            // Spawn a task to consume events (so the channel doesn't close)
-            let _event_consumer = task::spawn(async move {
-                while let Ok(_event) = event_receiver.recv().await {
-                    // Just consume events, we don't need to handle them
-                }
-            });
-            
-            if let Err(e) = connect(&endpoint, employee_node_id, message, event_sender).await {
-                tracing::error!("Failed to send credential to employee: {}", e);
-            } else {
-                info!("Credential successfully sent to employee {}", employee_node_id);
-            }
-        });
+        self.send_issue_response(endpoint, request_id, message).await?;
         
         info!("Credential send task initiated for employee {}", employee_node_id);
         Ok(())
     }
 
-    /// Reject a pending credential request
-    pub async fn reject_request(
+    /// Asks `co_signer` to co-sign every form of `draft` and attaches the checked co-signatures
+    async fn request_co_signature(
         &self,
-        request_id: String,
-        reason: Option<String>,
+        request_id: &str,
+        draft: &mut CredentialDraft,
+        co_signer: EndpointId,
         endpoint: &Endpoint,
     ) -> Result<(), anyhow::Error> {
-        // Get and update the request
-        let mut requests = self.pending_requests.write().await;
-        let request = requests.get_mut(&request_id)
-            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
-        
-        if request.status != RequestStatus::Pending {
-            return Err(anyhow::anyhow!("Request {} is not pending (status: {:?})", 
-                request_id, request.status));
-        }
-        
-        // Clone employee info before releasing lock
-        let employee_node_id = request.employee_node_id;
-        let employee_name = request.employee_name.clone();
-        
-        // Update status
-        request.status = RequestStatus::Rejected;
-        drop(requests); // Release lock
-        
-        info!("Rejecting request {} for {}", request_id, employee_name);
-        
-        // Send rejection message to employee
-        let message = CredentialMessage::IssueResponse {
-            request_id: request_id.clone(),
-            credential: None,
-            error: Some(reason.unwrap_or_else(|| "Request rejected by issuer".to_string())),
+        info!("Asking {} to co-sign credential {}", co_signer, draft.credential.id());
+        let message = CredentialMessage::CoSignRequest {
+            request_id: request_id.to_string(),
+            credential: draft.credential.clone(),
+            selective_credential: Some(Box::new(draft.selective_credential.clone())),
+            threshold_credential: draft.threshold_credential.clone().map(Box::new),
         };
-        
-        // Initiate connection to employee
-        let endpoint = endpoint.clone();
-        task::spawn(async move {
-            let (event_sender, event_receiver) = async_channel::bounded(16);
-            
-            // Spawn a task to consume events (so the channel doesn't close)
-            let _event_consumer = task::spawn(async move {
-                while let Ok(_event) = event_receiver.recv().await {
-                    // Just consume events, we don't need to handle them
+        match send_and_receive(endpoint, co_signer, message).await? {
+            Some(CredentialMessage::CoSignResponse {
+                request_id: id,
+                co_signature,
+                selective_co_signature,
+                threshold_co_signature,
+            }) if id == request_id => {
+                draft.credential.add_co_signature(co_signature)?;
+                let missing = |form: &str| anyhow::anyhow!("Co-signer {} did not co-sign the {}", co_signer, form);
+                let co_signature = selective_co_signature.ok_or_else(|| missing("selective disclosure commitment"))?;
+                draft.selective_credential.credential.add_co_signature(co_signature)?;
+                if let Some(threshold) = &mut draft.threshold_credential {
+                    let co_signature = threshold_co_signature.ok_or_else(|| missing("net salary commitment"))?;
+                    threshold.credential.add_co_signature(co_signature)?;
                 }
-            });
-            
-            if let Err(e) = connect(&endpoint, employee_node_id, message, event_sender).await {
-                tracing::error!("Failed to send rejection to employee: {}", e);
-            } else {
-                info!("Rejection successfully sent to employee {}", employee_node_id);
+                Ok(())
             }
-        });
-        
-        info!("Rejection send task initiated for employee {}", employee_node_id);
-        Ok(())
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Co-signer {} refused: {}", co_signer, message),
+            other => anyhow::bail!("Unexpected reply from co-signer {}: {:?}", co_signer, other),
+        }
     }
 
-    /// Add a trusted issuer to the trust list
-    pub async fn add_trusted_issuer(&self, node_id: EndpointId) -> Result<(), anyhow::Error> {
-        let mut issuers = self.trusted_issuers.write().await;
-        let was_new = issuers.insert(node_id);
-        drop(issuers);
-        
-        if was_new {
-            info!("Added trusted issuer: {}", node_id);
+    /// Handle a co-signing request (Processor role)
+    /// Co-signs credentials naming this node as payroll processor, for employers in its trusted list
+    async fn handle_co_sign_request(
+        &self,
+        message: CredentialMessage,
+        requester_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::CoSignRequest { request_id, credential, selective_credential, threshold_credential } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_co_sign_request"));
+        };
+        let employer_node_id = credential.issuer_node_id();
+        let refusal = if requester_node_id != employer_node_id {
+            Some(format!("Credential {} was not issued by the requesting node", credential.id()))
+        } else if credential.credential().payroll_processor_node_id != Some(self.node_id) {
+            Some(format!("Credential {} does not name this node as payroll processor", credential.id()))
+        } else if self.trusted_root(employer_node_id).await.is_none() {
+            Some(format!("Employer {} is not a client of this payroll processor", employer_node_id))
+        } else if !credential.verify()? {
+            Some("Invalid issuer signature".to_string())
+        } else if let Some(selective) = &selective_credential
+            && !selective.commits_to(credential.credential())?
+        {
+            Some(format!("The selective disclosure commitment is not credential {}", credential.id()))
+        } else if let Some(threshold) = &threshold_credential
+            && !threshold.commits_to(credential.credential())?
+        {
+            Some(format!("The net salary commitment is not credential {}", credential.id()))
         } else {
-            info!("Issuer {} was already trusted", node_id);
+            None
+        };
+        if let Some(message) = refusal {
+            tracing::warn!("Refusing to co-sign for {}: {}", requester_node_id, message);
+            return Ok(CredentialMessage::Error {
+                request_id,
+                error_code: "CO_SIGN_REFUSED".to_string(),
+                message,
+            });
         }
-        Ok(())
+        
+        let co_sign = |form: &dyn CoSigned| CoSignature::sign(form, SignerRole::PayrollProcessor, &self.secret_key);
+        let co_signature = co_sign(&credential)?;
+        let selective_co_signature = selective_credential.map(|selective| co_sign(&selective.credential)).transpose()?;
+        let threshold_co_signature = threshold_credential.map(|threshold| co_sign(&threshold.credential)).transpose()?;
+        info!("Co-signed credential {} issued by {} to {}", credential.id(), employer_node_id, credential.employee_node_id());
+        Ok(CredentialMessage::CoSignResponse { request_id, co_signature, selective_co_signature, threshold_co_signature })
     }
 
-    /// Remove a trusted issuer from the trust list
-    pub async fn remove_trusted_issuer(&self, node_id: EndpointId) -> Result<(), anyhow::Error> {
-        let mut issuers = self.trusted_issuers.write().await;
-        let was_present = issuers.remove(&node_id);
-        drop(issuers);
+    /// Checks an officer approval against the drafted credential and records it,
+    /// delivering the credential once the approvals meet the policy threshold
+    async fn record_approval(
+        &self,
+        approval: OfficerApproval,
+        endpoint: &Endpoint,
+    ) -> Result<ApprovalProgress, anyhow::Error> {
+        let policy = self.own_policy()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("This issuer has no approval policy"))?;
+        let officer = approval.officer_node_id;
+        if !policy.policy.is_officer(&officer) {
+            anyhow::bail!("{} is not an officer of this issuer", officer);
+        }
+        if approval.issuer_node_id != self.node_id {
+            anyhow::bail!("Approval is addressed to issuer {}", approval.issuer_node_id);
+        }
+        if approval.policy_version() != policy.version {
+            anyhow::bail!(
+                "Approval is under version {} of the approval policy; the current version is {}",
+                approval.policy_version(), policy.version
+            );
+        }
         
-        if was_present {
-            info!("Removed trusted issuer: {}", node_id);
-        } else {
-            info!("Issuer {} was not in trusted list", node_id);
+        let request_id = approval.request_id.clone();
+        let (progress, approved) = self.pending_requests
+            .update(&request_id, |request| {
+                if request.status != RequestStatus::Pending {
+                    anyhow::bail!("Request {} is not pending (status: {:?})", request_id, request.status);
+                }
+                let Some(draft) = &request.draft else {
+                    anyhow::bail!("The credential for request {} has not been signed yet", request_id);
+                };
+                if !approval.verify(draft)? {
+                    anyhow::bail!("Invalid signature on the approval of officer {}", officer);
+                }
+                request.approvals.retain(|existing| existing.officer_node_id != officer);
+                request.approvals.push(approval);
+                request.approvals_required = policy.policy.threshold;
+                
+                let approved = if request.approvals.len() as u32 >= policy.policy.threshold {
+                    let credential_data = request.clone();
+                    request.status = RequestStatus::Approved;
+                    Some(credential_data)
+                } else {
+                    None
+                };
+                Ok((request.approval_progress(policy.version), approved))
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
+        info!(
+            "Officer {} approved request {} ({}/{})",
+            officer, request_id, progress.approved_by.len(), progress.approvals_required
+        );
+        
+        if let Some(mut credential_data) = approved
+            && let Some(draft) = credential_data.draft.take()
+        {
+            self.deliver_credential(credential_data, draft, None, endpoint).await?;
         }
-        Ok(())
+        Ok(self.pending_requests
+            .get(&request_id)
+            .await
+            .map(|request| request.approval_progress(policy.version))
+            .unwrap_or(progress))
     }
 
-    /// Check if an issuer is trusted
+    /// Handle a request for the approval queue (Issuer role)
+    /// Answered only to the officers of the approval policy
+    async fn handle_approval_queue_request(
+        &self,
+        request_id: String,
+        officer_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let policy = self.own_policy().await;
+        let Some(policy) = policy.filter(|policy| policy.policy.is_officer(&officer_node_id)) else {
+            return Ok(CredentialMessage::Error {
+                request_id,
+                error_code: "NOT_AN_OFFICER".to_string(),
+                message: format!("{} is not an officer of this issuer", officer_node_id),
+            });
+        };
+        let requests = self.pending_requests
+            .values()
+            .await
+            .into_iter()
+            .filter(|request| request.status == RequestStatus::Pending)
+            .map(|request| request.approval_progress(policy.version))
+            .collect();
+        let policy_proposal = self.policy_proposal.get().await;
+        Ok(CredentialMessage::ApprovalQueue { request_id, requests, policy_proposal })
+    }
+
+    /// Handle an officer approval (Issuer role)
+    async fn handle_submit_approval(
+        &self,
+        message: CredentialMessage,
+        officer_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::SubmitApproval { approval } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_submit_approval"));
+        };
+        let request_id = approval.request_id.clone();
+        let result = if approval.officer_node_id != officer_node_id {
+            Err(anyhow::anyhow!("Approval of officer {} was not sent by that officer", approval.officer_node_id))
+        } else {
+            self.record_approval(approval, &self.endpoint).await
+        };
+        match result {
+            Ok(progress) => Ok(CredentialMessage::ApprovalRecorded(progress)),
+            Err(e) => {
+                tracing::warn!("Refusing approval from {}: {:#}", officer_node_id, e);
+                Ok(CredentialMessage::Error {
+                    request_id,
+                    error_code: "APPROVAL_REFUSED".to_string(),
+                    message: format!("{:#}", e),
+                })
+            }
+        }
+    }
+
+    /// Handle an officer's signature on the proposed approval policy (Issuer role)
+    async fn handle_submit_policy_approval(
+        &self,
+        message: CredentialMessage,
+        officer_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::SubmitPolicyApproval { version, signature } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_submit_policy_approval"));
+        };
+        let result = if signature.signer_node_id != officer_node_id {
+            Err(anyhow::anyhow!("Signature of {} was not sent by that officer", signature.signer_node_id))
+        } else {
+            self.record_policy_approval(version, signature).await
+        };
+        match result {
+            Ok(status) => Ok(CredentialMessage::PolicyApprovalRecorded(status)),
+            Err(e) => {
+                tracing::warn!("Refusing policy approval from {}: {:#}", officer_node_id, e);
+                Ok(CredentialMessage::Error {
+                    request_id: format!("approval-policy-{}", version),
+                    error_code: "APPROVAL_REFUSED".to_string(),
+                    message: format!("{:#}", e),
+                })
+            }
+        }
+    }
+
+    /// Reject a pending credential request
+    pub async fn reject_request(
+        &self,
+        request_id: String,
+        reason: Option<String>,
+        endpoint: &Endpoint,
+    ) -> Result<(), anyhow::Error> {
+        // Get and update the request
+        let (employee_node_id, employee_name, drafted_index) = self.pending_requests
+            .update(&request_id, |request| {
+                if request.status != RequestStatus::Pending {
+                    return Err(anyhow::anyhow!("Request {} is not pending (status: {:?})", 
+                        request_id, request.status));
+                }
+                request.status = RequestStatus::Rejected;
+                let drafted_index = request.draft.take().and_then(|draft| draft.credential.credential.status_index);
+                Ok((request.employee_node_id, request.employee_name.clone(), drafted_index))
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
+        
+        info!("Rejecting request {} for {}", request_id, employee_name);
+        // A credential drafted for officers to approve is never issued
+        if let Some(index) = drafted_index {
+            self.release_status_index(index).await?;
+        }
+        
+        // Send rejection message to employee
+        let message = CredentialMessage::IssueResponse {
+            request_id: request_id.clone(),
+            credential: None,
+            selective_credential: None,
+            threshold_credential: None,
+            verifiable_credential: None,
+            jws: None,
+            error: Some(reason.unwrap_or_else(|| "Request rejected by issuer".to_string())),
+        };
+        self.send_issue_response(endpoint, request_id, message).await?;
+        
+        info!("Rejection send task initiated for employee {}", employee_node_id);
+        Ok(())
+    }
+
+    /// Records `message` as undelivered, then delivers it in the background
+    async fn send_issue_response(
+        &self,
+        endpoint: &Endpoint,
+        request_id: String,
+        message: CredentialMessage,
+    ) -> Result<(), anyhow::Error> {
+        let employee_node_id = self.pending_requests
+            .update(&request_id, |request| {
+                request.undelivered_response = Some(message.clone());
+                Ok::<_, anyhow::Error>(request.employee_node_id)
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
+        
+        task::spawn(deliver_issue_response(
+            endpoint.clone(),
+            employee_node_id,
+            request_id,
+            message,
+            self.pending_requests.clone(),
+            self.event_sender.clone(),
+        ));
+        Ok(())
+    }
+
+    /// Resends every approval or rejection the employee has not acknowledged
+    pub async fn retry_deliveries(&self, endpoint: &Endpoint) -> usize {
+        let undelivered: Vec<_> = self.pending_requests
+            .values()
+            .await
+            .into_iter()
+            .filter_map(|request| Some((request.request_id, request.employee_node_id, request.undelivered_response?)))
+            .collect();
+        
+        for (request_id, employee_node_id, message) in &undelivered {
+            info!("Resending response for request {} to employee {}", request_id, employee_node_id);
+            task::spawn(deliver_issue_response(
+                endpoint.clone(),
+                *employee_node_id,
+                request_id.clone(),
+                message.clone(),
+                self.pending_requests.clone(),
+                self.event_sender.clone(),
+            ));
+        }
+        undelivered.len()
+    }
+
+    /// Get all credentials issued by this node
+    pub async fn get_issued_credentials(&self) -> Vec<IssuedCredentialRecord> {
+        self.issued_credentials.values().await
+    }
+
+    /// Revoke a credential issued by this node and publish the signed notice
+    pub async fn revoke_credential(
+        &self,
+        credential_id: String,
+        reason: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let (signed_notice, status_index) = self.issued_credentials
+            .update(&credential_id, |record| {
+                if record.revocation.is_some() {
+                    return Err(anyhow::anyhow!("Credential {} is already revoked", credential_id));
+                }
+                
+                let notice = RevocationNotice::new(credential_id.clone(), self.node_id, reason);
+                let signed_notice = notice.clone().sign(&self.secret_key)
+                    .map_err(|e| anyhow::anyhow!("Failed to sign revocation notice: {}", e))?;
+                record.revocation = Some(notice);
+                Ok((signed_notice, record.status_index))
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Credential {} was not issued by this node", credential_id))?;
+        
+        if let Some(index) = status_index {
+            self.status_list
+                .update(|state| {
+                    state.revoke(index);
+                    Ok::<_, anyhow::Error>(())
+                })
+                .await?;
+        }
+        
+        info!("Revoking credential {}", credential_id);
+        self.revocations.publish(signed_notice).await
+    }
+
+    /// Add a trusted issuer to the trust list and follow its revocations
+    pub async fn add_trusted_issuer(&self, node_id: EndpointId) -> Result<(), anyhow::Error> {
+        let was_new = self.trusted_issuers.insert(node_id.to_string(), node_id).await?.is_none();
+        
+        for key in self.revocations.rotation_log().await.successors(node_id) {
+            self.revocations.follow_issuer(key).await?;
+        }
+        
+        // Credentials without officer approvals are only refused once the issuer's
+        // approval policy is known, so learn it now rather than from a credential;
+        // trusting an issuer again refreshes it
+        let peer = self.clone();
+        task::spawn(async move {
+            if let Err(e) = peer.fetch_approval_policies(node_id).await {
+                tracing::warn!("Failed to fetch the approval policy of {}: {:#}", node_id, e);
+            }
+        });
+        
+        if was_new {
+            info!("Added trusted issuer: {}", node_id);
+        } else {
+            info!("Issuer {} was already trusted", node_id);
+        }
+        Ok(())
+    }
+
+    /// Remove a trusted issuer from the trust list
+    pub async fn remove_trusted_issuer(&self, node_id: EndpointId) -> Result<(), anyhow::Error> {
+        let was_present = self.trusted_issuers.remove(&node_id.to_string()).await?.is_some();
+        
+        for key in self.revocations.rotation_log().await.successors(node_id) {
+            self.revocations.unfollow_issuer(key).await;
+        }
+        
+        if was_present {
+            info!("Removed trusted issuer: {}", node_id);
+        } else {
+            info!("Issuer {} was not in trusted list", node_id);
+        }
+        Ok(())
+    }
+
+    /// Check if an issuer is trusted, directly or through the keys it replaced
     pub async fn is_trusted_issuer(&self, node_id: EndpointId) -> bool {
-        let issuers = self.trusted_issuers.read().await;
-        issuers.contains(&node_id)
+        self.trusted_root(node_id).await.is_some()
+    }
+
+    /// The trusted key `node_id` is, or descends from through known key rotations
+    async fn trusted_root(&self, node_id: EndpointId) -> Option<EndpointId> {
+        for key in self.revocations.rotation_log().await.predecessors(node_id) {
+            if self.trusted_issuers.contains_key(&key.to_string()).await {
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// Like [`Self::trusted_root`], asking `node_id` for its key history when none of
+    /// the keys it replaced are known
+    async fn resolve_trusted_root(&self, node_id: EndpointId) -> Option<EndpointId> {
+        if let Some(root) = self.trusted_root(node_id).await {
+            return Some(root);
+        }
+        if self.revocations.rotation_log().await.predecessors(node_id).len() > 1 {
+            return None;
+        }
+        match self.fetch_key_history(node_id).await {
+            Ok(0) => None,
+            Ok(_) => self.trusted_root(node_id).await,
+            Err(e) => {
+                tracing::warn!("Failed to fetch key history of {}: {:#}", node_id, e);
+                None
+            }
+        }
+    }
+
+    /// Asks `node_id` for the rotations leading to its key and records them;
+    /// returns how many were new
+    async fn fetch_key_history(&self, node_id: EndpointId) -> Result<usize, anyhow::Error> {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::KeyHistoryRequest { request_id: request_id.clone() };
+        let rotations = match send_and_receive(&self.endpoint, node_id, message).await? {
+            Some(CredentialMessage::KeyHistory { request_id: id, rotations }) if id == request_id => rotations,
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Issuer {} refused: {}", node_id, message),
+            other => anyhow::bail!("Unexpected reply from issuer {}: {:?}", node_id, other),
+        };
+        
+        // The history must be one unbroken chain ending at the key we asked
+        let mut expected = node_id;
+        for rotation in rotations.iter().rev() {
+            if rotation.rotation.new_node_id != expected {
+                anyhow::bail!("Key history of {} is not a chain", node_id);
+            }
+            expected = rotation.rotation.previous_node_id;
+        }
+        let mut recorded = 0;
+        for rotation in rotations {
+            if self.revocations.record_rotation(rotation).await? {
+                recorded += 1;
+            }
+        }
+        if recorded > 0 && self.trusted_root(node_id).await.is_some() {
+            self.revocations.follow_issuer(node_id).await?;
+        }
+        Ok(recorded)
+    }
+
+    /// Whether `signing_key` signs for issuer node `node_id`: a known binding, or an
+    /// assertion key of the node's resolved `did:iroh` document, and not unbound since
+    async fn is_assertion_key(&self, node_id: EndpointId, signing_key: PublicKey) -> bool {
+        if self.revocations.is_unbound(node_id, signing_key).await {
+            return false;
+        }
+        if let Some(binding) = self.key_bindings.get(&binding_key(node_id, signing_key)).await
+            && binding.binds(node_id, signing_key).unwrap_or(false)
+        {
+            return true;
+        }
+        let did = did_iroh(&node_id);
+        match self.did_resolver.resolve(&did).await {
+            // Resolving may have learned of an unbinding
+            Ok(document) => {
+                document.assertion_keys().is_ok_and(|keys| keys.contains(&signing_key))
+                    && !self.revocations.is_unbound(node_id, signing_key).await
+            }
+            Err(e) => {
+                tracing::warn!("Failed to resolve {}: {}", did, e);
+                false
+            }
+        }
+    }
+
+    /// The `did:iroh` document of `node_id`, if it is this node's key or one it
+    /// rotated from, with the bindings of the signing keys it lists; unbound keys
+    /// are left out
+    async fn did_document(&self, node_id: EndpointId) -> Option<(DidDocument, Vec<SignedKeyBinding>)> {
+        if !self.revocations.rotation_log().await.predecessors(self.node_id).contains(&node_id) {
+            return None;
+        }
+        let mut bindings = Vec::new();
+        for binding in self.key_bindings.values().await {
+            if binding.binding.node_id == node_id
+                && !self.revocations.is_unbound(node_id, binding.binding.signing_key).await
+            {
+                bindings.push(binding);
+            }
+        }
+        let signing_keys: Vec<_> = bindings.iter().map(|binding| binding.binding.signing_key).collect();
+        let document = DidDocument::for_iroh_node(&node_id, &signing_keys, Some(&self.endpoint.addr()));
+        Some((document, bindings))
+    }
+
+    /// The binding of this node's signing key, if it is not the node key
+    pub async fn key_binding(&self) -> Option<SignedKeyBinding> {
+        self.key_bindings.get(&binding_key(self.node_id, self.signer.public_key())).await
+    }
+
+    /// Retire `signing_key` from signing for this node, e.g. because it leaked, and
+    /// announce it to verifiers; credentials it signed stop verifying
+    pub async fn unbind_signing_key(&self, signing_key: PublicKey) -> Result<SignedKeyUnbinding, anyhow::Error> {
+        if signing_key == self.node_id {
+            anyhow::bail!("The node key cannot be unbound; rotate it instead");
+        }
+        if !self.key_bindings.contains_key(&binding_key(self.node_id, signing_key)).await {
+            anyhow::bail!("Signing key {} is not bound to this node", signing_key);
+        }
+        let unbinding = KeyUnbinding::new(self.node_id, signing_key).sign(&self.secret_key)?;
+        self.revocations.publish_unbinding(unbinding.clone()).await?;
+        info!("Unbound signing key {} from node {}", signing_key, self.node_id);
+        Ok(unbinding)
+    }
+
+    /// Replace this issuer's key by `new_secret_key`: signs a rotation with the current
+    /// key, announces it to verifiers and hands the wallet over to the new key. The
+    /// node keeps the old key, and refuses to issue, until it is restarted with the new one.
+    pub async fn rotate_key(
+        &self,
+        new_secret_key: &SecretKey,
+        reason: Option<String>,
+    ) -> Result<SignedKeyRotation, anyhow::Error> {
+        let new_node_id = new_secret_key.public();
+        let rotation = KeyRotation::new(self.node_id, new_node_id, reason)
+            .sign(&self.secret_key)
+            .map_err(|e| anyhow::anyhow!("Failed to sign key rotation: {}", e))?;
+        // The wallet follows first, so a published rotation never leaves it sealed to the retired key
+        self.received_credentials.rewrap_for_node_key(new_secret_key)?;
+        if let Err(e) = self.revocations.publish_rotation(rotation.clone()).await {
+            // A rotation that was recorded stands, and so does the wallet's new wrapping
+            if self.revocations.rotation_log().await.rotation_of(&self.node_id).is_none() {
+                self.received_credentials.undo_rewrap_for_node_key(new_secret_key)?;
+            }
+            return Err(e);
+        }
+        info!("Rotated issuer key {} to {}", self.node_id, new_node_id);
+        Ok(rotation)
+    }
+
+    /// Get all known issuer key rotations
+    pub async fn get_key_rotations(&self) -> Vec<SignedKeyRotation> {
+        self.revocations.rotations().await
+    }
+
+    /// Get all trusted issuers
+    pub async fn get_trusted_issuers(&self) -> Vec<EndpointId> {
+        self.trusted_issuers.values().await
+    }
+
+    /// Trusted issuers with the key rotations and bindings this node has verified
+    pub async fn export_trust_store(&self) -> OfflineTrustStore {
+        OfflineTrustStore {
+            trusted_issuers: self.trusted_issuers.values().await,
+            rotations: self.revocations.rotations().await,
+            key_bindings: self.key_bindings.values().await,
+            approval_policies: self.revocations.approval_policies().await,
+            key_unbindings: self.revocations.key_unbindings().await,
+        }
+    }
+
+    /// Cached status lists and received revocation notices
+    pub async fn export_revocations(&self) -> OfflineRevocations {
+        OfflineRevocations {
+            status_lists: self.status_lists.lists().await,
+            notices: self.revocations.revocations().await,
+        }
+    }
+
+    /// Get the verification policy
+    pub async fn get_verification_policy(&self) -> VerificationPolicy {
+        self.verification_policy.get().await
+    }
+
+    /// Replace the verification policy
+    pub async fn set_verification_policy(&self, policy: VerificationPolicy) -> Result<(), anyhow::Error> {
+        info!("Verification policy updated: {:?}", policy);
+        self.verification_policy.set(policy).await?;
+        Ok(())
+    }
+
+    /// Get the organization profile used on issued credentials
+    pub async fn get_issuer_profile(&self) -> IssuerProfile {
+        self.issuer_profile.get().await
+    }
+
+    /// Get this issuer's approval policy and any change awaiting officer signatures
+    pub async fn get_approval_policy(&self) -> ApprovalPolicyStatus {
+        ApprovalPolicyStatus {
+            published: self.own_policy_history().await.pop(),
+            proposed: self.policy_proposal.get().await,
+        }
+    }
+
+    /// Change the officers who must approve credential requests
+    ///
+    /// The first policy is published at once, signed by the node key. Later changes
+    /// are proposed to the current officers and published once `threshold` of them
+    /// sign, with the node's own signature if it is one of them.
+    pub async fn set_approval_policy(&self, policy: ApprovalPolicy) -> Result<ApprovalPolicyStatus, anyhow::Error> {
+        policy.validate()?;
+        let Some(current) = self.own_policy().await else {
+            let published = PublishedApprovalPolicy::first(self.node_id, policy)?;
+            let signature = published.sign(&self.secret_key)?;
+            self.adopt_policy(SignedApprovalPolicy { policy: published, signatures: vec![signature] }).await?;
+            return Ok(self.get_approval_policy().await);
+        };
+        
+        let mut proposal = SignedApprovalPolicy { policy: current.next(policy)?, signatures: Vec::new() };
+        if current.policy.is_officer(&self.node_id) {
+            let signature = proposal.policy.sign(&self.secret_key)?;
+            proposal.add_signature(signature, Some(&current))?;
+        }
+        if proposal.is_authorized(Some(&current))? {
+            self.adopt_policy(proposal).await?;
+        } else {
+            info!(
+                "Approval policy version {} proposed; awaits {} of the {} current officers",
+                proposal.policy.version, current.policy.threshold, current.policy.officers.len()
+            );
+            self.policy_proposal.set(Some(proposal)).await?;
+        }
+        Ok(self.get_approval_policy().await)
+    }
+
+    /// Checks an officer's signature on the proposed approval policy and records it,
+    /// publishing the proposal once enough officers signed
+    async fn record_policy_approval(
+        &self,
+        version: u32,
+        signature: PolicySignature,
+    ) -> Result<ApprovalPolicyStatus, anyhow::Error> {
+        let current = self.own_policy()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("This issuer has no approval policy"))?;
+        let signer = signature.signer_node_id;
+        let proposal = self.policy_proposal
+            .update(|proposal| {
+                let Some(proposal) = proposal.as_mut().filter(|proposal| proposal.policy.version == version) else {
+                    anyhow::bail!("No change to version {} of the approval policy is proposed", version);
+                };
+                proposal.add_signature(signature, Some(&current))?;
+                Ok(proposal.clone())
+            })
+            .await?;
+        info!("Officer {} signed version {} of the approval policy", signer, version);
+        
+        if proposal.is_authorized(Some(&current))? {
+            self.adopt_policy(proposal).await?;
+        }
+        Ok(self.get_approval_policy().await)
+    }
+
+    /// Publishes an authorized version of this issuer's approval policy. Pending
+    /// requests need approvals under the new version, so earlier ones are dropped.
+    async fn adopt_policy(&self, policy: SignedApprovalPolicy) -> Result<(), anyhow::Error> {
+        let version = policy.policy.version;
+        let threshold = policy.policy.policy.threshold;
+        self.revocations.publish_policy(policy).await?;
+        self.policy_proposal.set(None).await?;
+        for request in self.pending_requests.values().await {
+            if request.status != RequestStatus::Pending {
+                continue;
+            }
+            self.pending_requests
+                .update(&request.request_id, |request| {
+                    request.approvals.retain(|approval| approval.policy_version() == version);
+                    request.approvals_required = threshold;
+                    Ok::<_, anyhow::Error>(())
+                })
+                .await?;
+        }
+        info!("Approval policy version {} published: {} officer approvals required", version, threshold);
+        Ok(())
+    }
+
+    /// Versions of this issuer's approval policy, oldest first, including those
+    /// published under keys it rotated from
+    async fn own_policy_history(&self) -> Vec<SignedApprovalPolicy> {
+        self.revocations.policy_history(self.node_id).await
+    }
+
+    /// Current version of this issuer's approval policy
+    async fn own_policy(&self) -> Option<PublishedApprovalPolicy> {
+        self.own_policy_history().await.pop().map(|policy| policy.policy)
+    }
+
+    /// Known versions of the approval policy of issuer `node_id`, asking the issuer
+    /// when none are known or fewer than `min_version`
+    ///
+    /// A credential does not have to name a policy, so an issuer with no known
+    /// policy is asked on every check: an unapproved credential must not pass just
+    /// because the verifier never heard of the policy. Followers of the issuer
+    /// learn new versions over its revocation topic.
+    async fn approval_policies(&self, node_id: EndpointId, min_version: u32) -> Vec<SignedApprovalPolicy> {
+        let history = self.revocations.policy_history(node_id).await;
+        if history.last().is_some_and(|policy| policy.policy.version >= min_version) {
+            return history;
+        }
+        if let Err(e) = self.fetch_approval_policies(node_id).await {
+            tracing::warn!("Failed to fetch the approval policy of {}: {:#}", node_id, e);
+        }
+        self.revocations.policy_history(node_id).await
+    }
+
+    /// Asks issuer `node_id` for every version of its approval policy and records
+    /// the ones that extend the known history; returns how many were new
+    async fn fetch_approval_policies(&self, node_id: EndpointId) -> Result<usize, anyhow::Error> {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::ApprovalPolicyRequest { request_id: request_id.clone() };
+        let policies = match send_and_receive(&self.endpoint, node_id, message).await? {
+            Some(CredentialMessage::ApprovalPolicies { request_id: id, policies }) if id == request_id => policies,
+            Some(CredentialMessage::Error { message, .. }) => anyhow::bail!("Issuer {} refused: {}", node_id, message),
+            other => anyhow::bail!("Unexpected reply from issuer {}: {:?}", node_id, other),
+        };
+        
+        // Only chains started by this issuer's key or one it rotated from count
+        let keys = self.revocations.rotation_log().await.predecessors(node_id);
+        let mut recorded = 0;
+        for policy in policies {
+            if !keys.contains(&policy.policy.issuer_node_id) {
+                anyhow::bail!("Issuer {} sent the approval policy of {}", node_id, policy.policy.issuer_node_id);
+            }
+            if self.revocations.record_policy(policy).await? {
+                recorded += 1;
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Replace the organization profile used on issued credentials
+    pub async fn set_issuer_profile(&self, profile: IssuerProfile) -> Result<(), anyhow::Error> {
+        profile.validate()?;
+        info!("Issuer profile updated: {:?}", profile);
+        self.issuer_profile.set(profile).await?;
+        Ok(())
+    }
+
+    /// Handle a request for this issuer's signed profile
+    async fn handle_profile_request(&self, request_id: String) -> Result<CredentialMessage, anyhow::Error> {
+        let profile = self.issuer_profile.get().await.sign(&self.secret_key)?;
+        Ok(CredentialMessage::ProfileResponse { request_id, profile })
+    }
+
+    /// Send a presentation request to an employee
+    pub async fn request_presentation(
+        &self,
+        employee_node_id: EndpointId,
+        constraints: PresentationConstraints,
+        expires_in_minutes: Option<u32>,
+        endpoint: &Endpoint,
+    ) -> Result<SentPresentationRequest, anyhow::Error> {
+        constraints.validate()?;
+        let now = chrono::Utc::now();
+        let ttl = expires_in_minutes.unwrap_or(PRESENTATION_REQUEST_TTL_MINUTES);
+        let request = SentPresentationRequest {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            employee_node_id,
+            constraints,
+            nonce: generate_nonce(),
+            expires_at: (now + chrono::Duration::minutes(ttl.into())).to_rfc3339(),
+            sent_at: now.to_rfc3339(),
+            status: RequestStatus::Pending,
+            decline_reason: None,
+        };
+        
+        // Stored first so an answer can never arrive before the request is known
+        self.sent_presentation_requests.insert(request.request_id.clone(), request.clone()).await?;
+        let message = CredentialMessage::PresentationRequest {
+            request_id: request.request_id.clone(),
+            constraints: request.constraints.clone(),
+            nonce: request.nonce.clone(),
+            expires_at: request.expires_at.clone(),
+        };
+        let refusal = match send_and_receive(endpoint, employee_node_id, message).await {
+            Ok(Some(CredentialMessage::RequestQueued { .. })) => None,
+            Ok(Some(CredentialMessage::Error { message, .. })) => Some(anyhow::anyhow!("Employee {} refused: {}", employee_node_id, message)),
+            Ok(other) => Some(anyhow::anyhow!("Unexpected reply from employee {}: {:?}", employee_node_id, other)),
+            Err(e) => Some(e),
+        };
+        if let Some(e) = refusal {
+            self.sent_presentation_requests.remove(&request.request_id).await?;
+            return Err(e);
+        }
+        
+        info!("Presentation request {} sent to employee {}", request.request_id, employee_node_id);
+        Ok(request)
+    }
+
+    /// Get all presentation requests sent by this node
+    pub async fn get_sent_presentation_requests(&self) -> Vec<SentPresentationRequest> {
+        self.sent_presentation_requests.values().await
+    }
+
+    /// Marks a presentation request answered once its employee has presented
+    async fn answer_presentation_request(&self, request_id: &str, presenter_node_id: EndpointId) -> Result<(), anyhow::Error> {
+        self.sent_presentation_requests
+            .update(request_id, |request| {
+                // Presentations by anyone else do not use up the employee's request
+                if request.employee_node_id == presenter_node_id {
+                    request.status = RequestStatus::Approved;
+                }
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Handle an employee declining a presentation request (Verifier role)
+    async fn handle_presentation_declined(
+        &self,
+        message: CredentialMessage,
+        employee_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::PresentationDeclined { request_id, reason } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_presentation_declined"));
+        };
+        
+        self.sent_presentation_requests
+            .update(&request_id, |request| {
+                if request.employee_node_id != employee_node_id {
+                    return Err(anyhow::anyhow!("Request {} was not sent to {}", request_id, employee_node_id));
+                }
+                if request.status != RequestStatus::Pending {
+                    return Err(anyhow::anyhow!("Request {} is not pending (status: {:?})", 
+                        request_id, request.status));
+                }
+                request.status = RequestStatus::Rejected;
+                request.decline_reason = reason.clone();
+                Ok(())
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Request {} not found", request_id))?;
+        
+        info!("Employee {} declined presentation request {}", employee_node_id, request_id);
+        Ok(CredentialMessage::RequestQueued {
+            request_id,
+            message: "Decline recorded".to_string(),
+        })
+    }
+
+    /// Handle a verifier's presentation request (Employee role)
+    /// Queues the request for the holder's consent
+    async fn handle_presentation_request(
+        &self,
+        message: CredentialMessage,
+        verifier_node_id: EndpointId,
+    ) -> Result<CredentialMessage, anyhow::Error> {
+        let CredentialMessage::PresentationRequest { request_id, constraints, nonce, expires_at } = message else {
+            return Err(anyhow::anyhow!("Invalid message type for handle_presentation_request"));
+        };
+        
+        constraints.validate()?;
+        if is_expired(&expires_at) {
+            return Err(anyhow::anyhow!("Presentation request {} expired at {}", request_id, expires_at));
+        }
+        if self.presentation_requests.contains_key(&request_id).await {
+            return Err(anyhow::anyhow!("Presentation request {} was already received", request_id));
+        }
+        
+        info!("Queuing presentation request {} from verifier {}", request_id, verifier_node_id);
+        let request = ReceivedPresentationRequest {
+            request_id: request_id.clone(),
+            verifier_node_id,
+            constraints,
+            nonce,
+            expires_at,
+            received_at: chrono::Utc::now().to_rfc3339(),
+            status: RequestStatus::Pending,
+            presented_credential_ids: Vec::new(),
+            is_valid: None,
+            message: None,
+        };
+        self.presentation_requests.insert(request_id.clone(), request).await?;
+        
+        Ok(CredentialMessage::RequestQueued {
+            request_id,
+            message: "Presentation request queued for the holder's consent".to_string(),
+        })
+    }
+
+    /// Get all presentation requests received from verifiers
+    pub async fn get_presentation_requests(&self) -> Vec<ReceivedPresentationRequest> {
+        self.presentation_requests.values().await
+    }
+
+    /// Get the wallet's credentials that satisfy a presentation request
+    pub async fn get_matching_credentials(&self, request_id: &str) -> Result<Vec<SignedIncomeCredential>, anyhow::Error> {
+        let request = self.presentation_requests
+            .get(request_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Presentation request {} not found", request_id))?;
+        Ok(self.received_credentials
+            .values()
+            .await?
+            .into_iter()
+            .filter_map(|response| response.credential)
+            .filter(|credential| request.constraints.matches(credential.credential()))
+            .collect())
     }
 
-    /// Get all trusted issuers
-    pub async fn get_trusted_issuers(&self) -> Vec<EndpointId> {
-        let issuers = self.trusted_issuers.read().await;
-        issuers.iter().copied().collect()
+    /// Present wallet credentials in answer to a presentation request, bundled under one holder signature
+    pub async fn approve_presentation_request(
+        &self,
+        request_id: String,
+        credential_ids: Vec<String>,
+        endpoint: &Endpoint,
+    ) -> Result<ReceivedPresentationRequest, anyhow::Error> {
+        let request = self.presentation_requests
+            .get(&request_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Presentation request {} not found", request_id))?;
+        if is_expired(&request.expires_at) {
+            return Err(anyhow::anyhow!("Presentation request {} expired at {}", request_id, request.expires_at));
+        }
+        let credentials = self.find_credentials(&credential_ids).await?;
+        for credential in &credentials {
+            if let Some(reason) = request.constraints.mismatch(credential.credential()) {
+                return Err(anyhow::anyhow!("Credential {} does not satisfy request {}: {}", credential.id(), request_id, reason));
+            }
+        }
+        
+        // Claim the request so it is answered only once
+        self.claim_presentation_request(&request_id, RequestStatus::Approved).await?;
+        
+        info!("Presenting {} credentials to verifier {} for request {}", credentials.len(), request.verifier_node_id, request_id);
+        let presentation = VerifiablePresentation::sign(&self.secret_key, request.verifier_node_id, request.nonce.clone(), credentials)?;
+        let message = CredentialMessage::PresentCredentials {
+            presentation_id: request_id.clone(),
+            presentation,
+        };
+        let verdict = match send_and_receive(endpoint, request.verifier_node_id, message).await {
+            Ok(Some(CredentialMessage::PresentationResult(verdict))) => verdict,
+            Ok(reply) => {
+                self.reopen_presentation_request(&request_id).await?;
+                return Err(match reply {
+                    Some(CredentialMessage::Error { message, .. }) => anyhow::anyhow!("Verifier {} refused: {}", request.verifier_node_id, message),
+                    other => anyhow::anyhow!("Unexpected reply from verifier {}: {:?}", request.verifier_node_id, other),
+                });
+            }
+            Err(e) => {
+                self.reopen_presentation_request(&request_id).await?;
+                return Err(e);
+            }
+        };
+        
+        let answered = self.presentation_requests
+            .update(&request_id, |request| {
+                request.presented_credential_ids = credential_ids;
+                request.is_valid = Some(verdict.is_valid);
+                request.message = Some(verdict.message);
+                Ok::<_, anyhow::Error>(request.clone())
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Presentation request {} not found", request_id))?;
+        Ok(answered)
+    }
+
+    /// Answer a presentation request with a proof that the net salary reaches
+    /// the request's minimum, without revealing it
+    pub async fn prove_presentation_request(
+        &self,
+        request_id: String,
+        credential_id: String,
+        endpoint: &Endpoint,
+    ) -> Result<ReceivedPresentationRequest, anyhow::Error> {
+        let request = self.presentation_requests
+            .get(&request_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Presentation request {} not found", request_id))?;
+        if is_expired(&request.expires_at) {
+            return Err(anyhow::anyhow!("Presentation request {} expired at {}", request_id, request.expires_at));
+        }
+        let threshold = request.constraints.min_net_salary.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Presentation request {} asks for no minimum net salary", request_id))?;
+        let proof = self.find_threshold_credential(&credential_id).await?.prove(threshold)?;
+        if let Some(reason) = request.constraints.mismatch_claims(proof.issuer_node_id(), &proof.disclosed_claims()) {
+            return Err(anyhow::anyhow!("Credential {} does not satisfy request {}: {}", credential_id, request_id, reason));
+        }
+        
+        // Claim the request so it is answered only once
+        self.claim_presentation_request(&request_id, RequestStatus::Approved).await?;
+        
+        info!("Proving net salary of at least {} to verifier {} for request {}", proof.threshold, request.verifier_node_id, request_id);
+        let envelope = PresentationEnvelope::sign(&self.secret_key, request.verifier_node_id, request.nonce.clone(), &[&proof])?;
+        let message = CredentialMessage::PresentThresholdProof {
+            presentation_id: request_id.clone(),
+            proof,
+            envelope: Some(envelope),
+        };
+        let (is_valid, message) = match send_and_receive(endpoint, request.verifier_node_id, message).await {
+            Ok(Some(CredentialMessage::VerificationResult { is_valid, message, .. })) => (is_valid, message),
+            Ok(reply) => {
+                self.reopen_presentation_request(&request_id).await?;
+                return Err(match reply {
+                    Some(CredentialMessage::Error { message, .. }) => anyhow::anyhow!("Verifier {} refused: {}", request.verifier_node_id, message),
+                    other => anyhow::anyhow!("Unexpected reply from verifier {}: {:?}", request.verifier_node_id, other),
+                });
+            }
+            Err(e) => {
+                self.reopen_presentation_request(&request_id).await?;
+                return Err(e);
+            }
+        };
+        
+        let answered = self.presentation_requests
+            .update(&request_id, |request| {
+                request.presented_credential_ids = vec![credential_id];
+                request.is_valid = Some(is_valid);
+                request.message = Some(message);
+                Ok::<_, anyhow::Error>(request.clone())
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Presentation request {} not found", request_id))?;
+        Ok(answered)
+    }
+
+    /// Looks up wallet credentials by ID, in the given order
+    pub async fn find_credentials(&self, credential_ids: &[String]) -> Result<Vec<SignedIncomeCredential>, anyhow::Error> {
+        if credential_ids.is_empty() {
+            return Err(anyhow::anyhow!("No credentials selected"));
+        }
+        let mut wallet: std::collections::HashMap<_, _> = self.received_credentials
+            .values()
+            .await?
+            .into_iter()
+            .filter_map(|response| response.credential)
+            .map(|credential| (credential.id().to_string(), credential))
+            .collect();
+        credential_ids
+            .iter()
+            .map(|id| {
+                wallet
+                    .remove(id)
+                    .ok_or_else(|| anyhow::anyhow!("Credential {} not found in the wallet or selected twice", id))
+            })
+            .collect()
+    }
+
+    /// Decline a presentation request and tell the verifier
+    pub async fn reject_presentation_request(
+        &self,
+        request_id: String,
+        reason: Option<String>,
+        endpoint: &Endpoint,
+    ) -> Result<(), anyhow::Error> {
+        let request = self.claim_presentation_request(&request_id, RequestStatus::Rejected).await?;
+        
+        info!("Declining presentation request {} from verifier {}", request_id, request.verifier_node_id);
+        let message = CredentialMessage::PresentationDeclined {
+            request_id: request_id.clone(),
+            reason: reason.clone(),
+        };
+        let refusal = match send_and_receive(endpoint, request.verifier_node_id, message).await {
+            Ok(Some(CredentialMessage::RequestQueued { .. })) => None,
+            Ok(Some(CredentialMessage::Error { message, .. })) => Some(anyhow::anyhow!("Verifier {} refused: {}", request.verifier_node_id, message)),
+            Ok(other) => Some(anyhow::anyhow!("Unexpected reply from verifier {}: {:?}", request.verifier_node_id, other)),
+            Err(e) => Some(e),
+        };
+        if let Some(e) = refusal {
+            self.reopen_presentation_request(&request_id).await?;
+            return Err(e);
+        }
+        
+        self.presentation_requests
+            .update(&request_id, |request| {
+                request.message = reason;
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Moves a pending presentation request to `status`, returning it
+    async fn claim_presentation_request(
+        &self,
+        request_id: &str,
+        status: RequestStatus,
+    ) -> Result<ReceivedPresentationRequest, anyhow::Error> {
+        self.presentation_requests
+            .update(request_id, |request| {
+                if request.status != RequestStatus::Pending {
+                    return Err(anyhow::anyhow!("Presentation request {} is not pending (status: {:?})", 
+                        request_id, request.status));
+                }
+                request.status = status;
+                Ok(request.clone())
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Presentation request {} not found", request_id))
     }
 
-    /// Get all verified credentials (Verifier)
+    /// Returns a claimed presentation request to pending after its answer could not be delivered
+    async fn reopen_presentation_request(&self, request_id: &str) -> Result<(), anyhow::Error> {
+        self.presentation_requests
+            .update(request_id, |request| {
+                request.status = RequestStatus::Pending;
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Get all verified credentials, those of bundled presentations included (Verifier)
     pub async fn get_verified_credentials(&self) -> Vec<VerifiedCredentialRecord> {
-        let verified = self.verified_credentials.read().await;
-        verified.values().cloned().collect()
+        let mut records = self.verified_credentials.values().await;
+        for bundle in self.verified_presentations.values().await {
+            records.extend(bundle.records);
+        }
+        records
     }
 
     /// Get a specific verified credential (Verifier)
     pub async fn get_verified_credential(&self, presentation_id: &str) -> Option<VerifiedCredentialRecord> {
-        let verified = self.verified_credentials.read().await;
-        verified.get(presentation_id).cloned()
+        self.verified_credentials.get(presentation_id).await
+    }
+
+    /// Get the records of one presentation, in bundle order (Verifier). Bundles of
+    /// different presenters that chose the same ID follow each other.
+    pub async fn get_verified_presentation(&self, presentation_id: &str) -> Vec<VerifiedCredentialRecord> {
+        let mut bundles: Vec<_> = self.verified_presentations
+            .values()
+            .await
+            .into_iter()
+            .filter(|bundle| bundle.presentation_id == presentation_id)
+            .collect();
+        if !bundles.is_empty() {
+            bundles.sort_by_key(|bundle| bundle.presenter_node_id);
+            return bundles.into_iter().flat_map(|bundle| bundle.records).collect();
+        }
+        // Single credentials, and bundles recorded before they were stored whole
+        let mut records: Vec<_> = self.verified_credentials
+            .values()
+            .await
+            .into_iter()
+            .filter(|record| record.presentation_id == presentation_id)
+            .collect();
+        records.sort_by_key(|record| record.bundle_index);
+        records
+    }
+
+    /// Get all verified selective disclosures (Verifier)
+    pub async fn get_verified_disclosures(&self) -> Vec<VerifiedDisclosureRecord> {
+        self.verified_disclosures.values().await
+    }
+
+    /// Looks up the selective-disclosure form of a wallet credential by ID
+    pub async fn find_selective_credential(&self, credential_id: &str) -> Result<SelectiveCredential, anyhow::Error> {
+        self.received_credentials
+            .values()
+            .await?
+            .into_iter()
+            .filter_map(|response| response.selective_credential)
+            .find(|credential| credential.id() == credential_id)
+            .ok_or_else(|| anyhow::anyhow!("No selective-disclosure form of credential {} in the wallet", credential_id))
+    }
+
+    /// Get all verified net salary threshold proofs (Verifier)
+    pub async fn get_verified_threshold_proofs(&self) -> Vec<VerifiedThresholdRecord> {
+        self.verified_threshold_proofs.values().await
+    }
+
+    /// Looks up the net salary commitment of a wallet credential by ID
+    pub async fn find_threshold_credential(&self, credential_id: &str) -> Result<ThresholdCredential, anyhow::Error> {
+        self.received_credentials
+            .values()
+            .await?
+            .into_iter()
+            .filter_map(|response| response.threshold_credential)
+            .find(|credential| credential.credential.credential_id == credential_id)
+            .ok_or_else(|| anyhow::anyhow!("No net salary commitment for credential {} in the wallet", credential_id))
     }
 
     /// Get all received credentials (Employee)
-    pub async fn get_received_credentials(&self) -> Vec<ReceivedCredentialResponse> {
-        let credentials = self.received_credentials.read().await;
-        credentials.values().cloned().collect()
+    pub async fn get_received_credentials(&self) -> Result<Vec<ReceivedCredentialResponse>, WalletError> {
+        self.received_credentials.values().await
     }
 
     /// Get a specific received credential by request ID (Employee)
-    pub async fn get_received_credential(&self, request_id: String) -> Option<ReceivedCredentialResponse> {
-        let credentials = self.received_credentials.read().await;
-        credentials.get(&request_id).cloned()
+    pub async fn get_received_credential(&self, request_id: String) -> Result<Option<ReceivedCredentialResponse>, WalletError> {
+        self.received_credentials.get(&request_id).await
+    }
+
+    /// Get whether the wallet is locked and how it is protected (Employee)
+    pub async fn wallet_status(&self) -> Result<WalletStatus, WalletError> {
+        self.received_credentials.status().await
+    }
+
+    /// Lock the wallet, dropping its key from memory (Employee)
+    pub async fn lock_wallet(&self) {
+        self.received_credentials.lock().await
+    }
+
+    /// Unlock the wallet with its passphrase, or the node key if `None` (Employee)
+    pub async fn unlock_wallet(&self, passphrase: Option<&str>) -> Result<(), WalletError> {
+        self.received_credentials.unlock(passphrase).await
+    }
+
+    /// Protect the wallet with a passphrase, or the node key if `None` (Employee)
+    pub async fn set_wallet_passphrase(&self, passphrase: Option<&str>) -> Result<(), WalletError> {
+        self.received_credentials.set_passphrase(passphrase).await
+    }
+}
+
+/// Trusts the issuers in this node's list, asking issuers for their key history
+/// and DID documents when needed
+impl TrustStore for Peer {
+    fn trusted_root(&self, node_id: EndpointId) -> BoxFuture<Option<EndpointId>> {
+        let peer = self.clone();
+        Box::pin(async move { peer.trusted_root(node_id).await })
+    }
+
+    fn issuer_root(&self, node_id: EndpointId) -> BoxFuture<Option<EndpointId>> {
+        let peer = self.clone();
+        Box::pin(async move { peer.resolve_trusted_root(node_id).await })
+    }
+
+    fn rotation_log(&self) -> BoxFuture<RotationLog> {
+        let revocations = self.revocations.clone();
+        Box::pin(async move { revocations.rotation_log().await })
+    }
+
+    fn is_assertion_key(&self, node_id: EndpointId, signing_key: PublicKey) -> BoxFuture<bool> {
+        let peer = self.clone();
+        Box::pin(async move { peer.is_assertion_key(node_id, signing_key).await })
+    }
+
+    fn approval_policies(&self, node_id: EndpointId, min_version: u32) -> BoxFuture<Vec<SignedApprovalPolicy>> {
+        let peer = self.clone();
+        Box::pin(async move { peer.approval_policies(node_id, min_version).await })
+    }
+}
+
+/// Fetches issuers' status lists as the verification policy allows, and takes
+/// revocation notices from gossip
+impl RevocationSource for Peer {
+    fn status(&self, issuer: EndpointId, status_index: u32) -> BoxFuture<(StatusCheck, Option<u64>)> {
+        let (status_lists, policy) = (self.status_lists.clone(), self.verification_policy.clone());
+        Box::pin(async move {
+            let max_age = policy.get().await.status_list_max_age();
+            status_lists.check(issuer, status_index, max_age).await
+        })
+    }
+
+    fn revocation(&self, issuer: EndpointId, credential_id: String) -> BoxFuture<Option<SignedRevocationNotice>> {
+        let revocations = self.revocations.clone();
+        Box::pin(async move { revocations.revocation_for(issuer, &credential_id).await })
     }
 }
 
@@ -860,6 +3919,84 @@ impl ProtocolHandler for Peer {
     }
 }
 
+/// Sends an `IssueResponse` to the employee and reports the outcome as
+/// [`AcceptEvent::ResponseDelivered`]
+async fn deliver_issue_response(
+    endpoint: Endpoint,
+    employee_node_id: EndpointId,
+    request_id: String,
+    message: CredentialMessage,
+    pending_requests: StoredMap<PendingCredentialRequest>,
+    accept_events: broadcast::Sender<AcceptEvent>,
+) {
+    // The employee may not be discoverable yet, so retry with backoff
+    let mut backoff = std::time::Duration::from_secs(1);
+    let mut attempt = 1;
+    let result = loop {
+        match send_and_receive(&endpoint, employee_node_id, message.clone()).await {
+            Err(e) if attempt < DELIVERY_ATTEMPTS => {
+                tracing::warn!("Delivery attempt {} for request {} failed: {}", attempt, request_id, e);
+                n0_future::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => break result,
+        }
+    };
+    let error = match result {
+        Err(e) => Some(e.to_string()),
+        Ok(Some(CredentialMessage::Error { message, .. })) => Some(message),
+        Ok(_) => None,
+    };
+    
+    match &error {
+        Some(e) => tracing::error!("Failed to deliver response for request {} to employee {}: {}", request_id, employee_node_id, e),
+        None => {
+            info!("Response for request {} delivered to employee {}", request_id, employee_node_id);
+            let delivered = pending_requests
+                .update(&request_id, |request| {
+                    request.undelivered_response = None;
+                    request.delivered_at = Some(chrono::Utc::now().to_rfc3339());
+                    Ok::<_, anyhow::Error>(())
+                })
+                .await;
+            if let Err(e) = delivered {
+                tracing::error!("Failed to record delivery of request {}: {}", request_id, e);
+            }
+        }
+    }
+    accept_events
+        .send(AcceptEvent::ResponseDelivered {
+            node_id: employee_node_id,
+            request_id,
+            error,
+        })
+        .ok();
+}
+
+/// Sends `message` and returns the peer's reply, if any
+async fn send_and_receive(
+    endpoint: &Endpoint,
+    node_id: EndpointId,
+    message: CredentialMessage,
+) -> Result<Option<CredentialMessage>> {
+    let (event_sender, event_receiver) = async_channel::bounded(16);
+    
+    // Consume events so the channel doesn't close, keeping the reply
+    let event_consumer = task::spawn(async move {
+        let mut reply = None;
+        while let Ok(event) = event_receiver.recv().await {
+            if let ConnectEvent::ResponseReceived { message, .. } = event {
+                reply = Some(message);
+            }
+        }
+        reply
+    });
+    
+    connect(endpoint, node_id, message, event_sender).await?;
+    Ok(event_consumer.await.ok().flatten())
+}
+
 async fn connect(
     endpoint: &Endpoint,
     node_id: EndpointId,
@@ -905,4 +4042,691 @@ async fn connect(
     // Close the connection
     connection.close(1u8.into(), b"done");
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use super::*;
+    use crate::credentials::issuer_profile::PayrollProcessor;
+    use crate::credentials::status_list::StatusList;
+    use crate::status_list::CachedStatusList;
+    use crate::storage::MemoryStore;
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use iroh::discovery::static_provider::StaticProvider;
+
+    /// Spawns one node per role, each able to reach the others without relays or mDNS
+    async fn spawn_nodes(roles: &[Role]) -> Vec<PeerNode> {
+        let nodes: Vec<_> = roles.iter().map(|role| (role.clone(), None)).collect();
+        spawn_nodes_with_signers(&nodes).await
+    }
+
+    /// Like [`spawn_nodes`], giving some nodes a separate credential signer
+    async fn spawn_nodes_with_signers(roles: &[(Role, Option<Arc<dyn CredentialSigner>>)]) -> Vec<PeerNode> {
+        let discovery = StaticProvider::new();
+        let mut nodes = Vec::new();
+        for (role, signer) in roles {
+            let store = Arc::new(MemoryStore::default());
+            let node = PeerNode::spawn(None, role.clone(), store, None, signer.clone()).await.unwrap();
+            node.endpoint().discovery().add(discovery.clone());
+            nodes.push(node);
+        }
+        for node in &nodes {
+            discovery.add_endpoint_info(node.endpoint().addr());
+        }
+        nodes
+    }
+
+    /// Polls `condition` until it holds, failing the test after ten seconds
+    async fn wait_for<F: Future<Output = bool>>(what: &str, mut condition: impl FnMut() -> F) {
+        for _ in 0..100 {
+            if condition().await {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        panic!("timed out waiting for {what}");
+    }
+
+    /// Sends `employee`'s credential request for `net_salary` to `issuer`, returning its ID
+    async fn request_credential(employee: &PeerNode, issuer: &PeerNode, net_salary: &str) -> String {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let message = CredentialMessage::IssueRequest {
+            request_id: request_id.clone(),
+            employee_node_id: employee.endpoint().id(),
+            employee_name: "Alice".to_string(),
+            gross_salary: "9000.00".to_string(),
+            net_salary: net_salary.to_string(),
+            currency: "USD".to_string(),
+            pay_period: "2025-01".to_string(),
+            payment_mode: PaymentMode::BankTransfer,
+        };
+        let reply = send_and_receive(employee.endpoint(), issuer.endpoint().id(), message).await.unwrap();
+        assert!(matches!(reply, Some(CredentialMessage::RequestQueued { .. })), "{reply:?}");
+        request_id
+    }
+
+    /// Waits until `employee` received the response to `request_id`
+    async fn received(employee: &PeerNode, request_id: &str) -> ReceivedCredentialResponse {
+        wait_for("the issue response", || async {
+            employee.get_received_credential(request_id.to_string()).await.unwrap().is_some()
+        })
+        .await;
+        employee.get_received_credential(request_id.to_string()).await.unwrap().unwrap()
+    }
+
+    /// Has `issuer` issue `employee` a credential for `net_salary`
+    async fn issue(issuer: &PeerNode, employee: &PeerNode, net_salary: &str) -> ReceivedCredentialResponse {
+        let request_id = request_credential(employee, issuer, net_salary).await;
+        issuer.approve_request(request_id.clone(), ValidityWindow::default()).await.unwrap();
+        let response = received(employee, &request_id).await;
+        assert!(response.credential.is_some(), "{:?}", response.error);
+        response
+    }
+
+    #[tokio::test]
+    async fn test_presentation_request_is_answered_or_declined() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Verifier]).await;
+        let (issuer, employee, verifier) = (&nodes[0], &nodes[1], &nodes[2]);
+        let credential = issue(issuer, employee, "7200.00").await.credential.unwrap();
+        verifier.add_trusted_issuer(issuer.endpoint().id()).await.unwrap();
+
+        let constraints = PresentationConstraints {
+            issuers: vec![issuer.endpoint().id()],
+            min_net_salary: Some("5000.00".to_string()),
+            ..Default::default()
+        };
+        let sent = verifier.request_presentation(employee.endpoint().id(), constraints, None).await.unwrap();
+        assert_eq!(employee.get_presentation_requests().await.len(), 1);
+        let matching = employee.get_matching_credentials(sent.request_id.clone()).await.unwrap();
+        assert_eq!(matching.len(), 1);
+
+        let answered = employee
+            .approve_presentation_request(sent.request_id.clone(), vec![credential.id().to_string()])
+            .await
+            .unwrap();
+        assert_eq!(answered.is_valid, Some(true), "{:?}", answered.message);
+        let records = verifier.get_verified_presentation(&sent.request_id).await;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].request_satisfied, Some(true));
+        assert_eq!(records[0].holder_binding, HolderBinding::Envelope);
+        // A request is answered only once
+        assert!(employee
+            .approve_presentation_request(sent.request_id.clone(), vec![credential.id().to_string()])
+            .await
+            .is_err());
+
+        // A credential missing the constraints is refused before it is sent
+        let demanding = PresentationConstraints {
+            min_net_salary: Some("8000.00".to_string()),
+            ..Default::default()
+        };
+        let sent = verifier.request_presentation(employee.endpoint().id(), demanding, None).await.unwrap();
+        assert!(employee.get_matching_credentials(sent.request_id.clone()).await.unwrap().is_empty());
+        assert!(employee
+            .approve_presentation_request(sent.request_id.clone(), vec![credential.id().to_string()])
+            .await
+            .is_err());
+        // and the verifier does not accept it as an answer either
+        let nonce = employee.peer.presentation_requests.get(&sent.request_id).await.unwrap().nonce;
+        let presentation =
+            VerifiablePresentation::sign(employee.secret_key(), verifier.endpoint().id(), nonce, vec![credential.clone()]).unwrap();
+        let message = CredentialMessage::PresentCredentials {
+            presentation_id: sent.request_id.clone(),
+            presentation,
+        };
+        match send_and_receive(employee.endpoint(), verifier.endpoint().id(), message).await.unwrap() {
+            Some(CredentialMessage::PresentationResult(verdict)) => {
+                assert!(!verdict.is_valid);
+                assert_eq!(verdict.request_satisfied, Some(false));
+            }
+            other => panic!("unexpected reply {other:?}"),
+        }
+
+        // Declining reaches the verifier
+        let sent = verifier
+            .request_presentation(employee.endpoint().id(), PresentationConstraints::default(), None)
+            .await
+            .unwrap();
+        employee
+            .reject_presentation_request(sent.request_id.clone(), Some("not sharing".to_string()))
+            .await
+            .unwrap();
+        let declined = verifier
+            .get_sent_presentation_requests()
+            .await
+            .into_iter()
+            .find(|request| request.request_id == sent.request_id)
+            .unwrap();
+        assert_eq!(declined.status, RequestStatus::Rejected);
+        assert_eq!(declined.decline_reason.as_deref(), Some("not sharing"));
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bundled_presentation_checks_each_credential() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Verifier]).await;
+        let (issuer, employee, verifier) = (&nodes[0], &nodes[1], &nodes[2]);
+        let first = issue(issuer, employee, "7200.00").await.credential.unwrap();
+        let second = issue(issuer, employee, "7300.00").await.credential.unwrap();
+        verifier.add_trusted_issuer(issuer.endpoint().id()).await.unwrap();
+        let verifier_id = verifier.endpoint().id();
+
+        let verdict = employee.present_credentials(verifier_id, vec![first.clone(), second.clone()]).await.unwrap();
+        assert!(verdict.is_valid, "{}", verdict.message);
+        assert_eq!(verdict.holder_binding, HolderBinding::Envelope);
+        assert_eq!(verdict.credentials.len(), 2);
+        let records = verifier.get_verified_presentation(&verdict.presentation_id).await;
+        assert_eq!(records.iter().map(|record| record.bundle_index).collect::<Vec<_>>(), vec![Some(0), Some(1)]);
+        assert_eq!(records[1].credential.id(), second.id());
+
+        // A tampered credential fails on its own, failing the bundle
+        let mut raised = second.clone();
+        raised.credential.net_salary = "9000.00".to_string();
+        let verdict = employee.present_credentials(verifier_id, vec![first.clone(), raised]).await.unwrap();
+        assert!(!verdict.is_valid);
+        assert!(verdict.credentials[0].is_valid);
+        assert!(!verdict.credentials[1].is_valid);
+        assert_eq!(verdict.message, "1 of 2 credentials failed verification");
+
+        // A single credential presented under an ID shaped like a bundle record
+        // leaves the bundle alone
+        let presentation_id = verdict.presentation_id.clone();
+        let message = CredentialMessage::PresentCredential {
+            presentation_id: format!("{presentation_id}/1"),
+            credential: first.clone(),
+            envelope: None,
+        };
+        send_and_receive(employee.endpoint(), verifier_id, message).await.unwrap();
+        let records = verifier.get_verified_presentation(&presentation_id).await;
+        assert_eq!(records.len(), 2);
+        assert!(!records[1].is_valid);
+
+        // A smaller bundle reusing the ID replaces the earlier one whole
+        let challenge = employee.request_challenge(verifier_id).await.unwrap();
+        let message = CredentialMessage::PresentCredentials {
+            presentation_id: presentation_id.clone(),
+            presentation: VerifiablePresentation::sign(employee.secret_key(), verifier_id, challenge.nonce, vec![first.clone()])
+                .unwrap(),
+        };
+        send_and_receive(employee.endpoint(), verifier_id, message).await.unwrap();
+        let records = verifier.get_verified_presentation(&presentation_id).await;
+        assert_eq!(records.len(), 1);
+        assert!(records[0].is_valid && records[0].bundle_size == Some(1));
+
+        // A bundle replayed over a used nonce is not bound to its holder
+        let challenge = employee.request_challenge(verifier_id).await.unwrap();
+        let presentation =
+            VerifiablePresentation::sign(employee.secret_key(), verifier_id, challenge.nonce, vec![first.clone()]).unwrap();
+        for expected in [HolderBinding::Envelope, HolderBinding::InvalidEnvelope] {
+            let message = CredentialMessage::PresentCredentials {
+                presentation_id: uuid::Uuid::new_v4().to_string(),
+                presentation: presentation.clone(),
+            };
+            match send_and_receive(employee.endpoint(), verifier_id, message).await.unwrap() {
+                Some(CredentialMessage::PresentationResult(verdict)) => {
+                    assert_eq!(verdict.holder_binding, expected);
+                    assert_eq!(verdict.is_valid, expected == HolderBinding::Envelope);
+                }
+                other => panic!("unexpected reply {other:?}"),
+            }
+        }
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payroll_processor_co_signs_for_its_clients() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Processor, Role::Verifier]).await;
+        let (issuer, employee, processor, verifier) = (&nodes[0], &nodes[1], &nodes[2], &nodes[3]);
+        issuer
+            .set_issuer_profile(IssuerProfile {
+                default_payroll_processor: Some(PayrollProcessor {
+                    node_id: processor.endpoint().id(),
+                    name: "PayCo".to_string(),
+                }),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        // The processor refuses employers that are not its clients; the request stays open
+        let request_id = request_credential(employee, issuer, "7200.00").await;
+        let error = issuer.approve_request(request_id.clone(), ValidityWindow::default()).await.unwrap_err();
+        assert!(format!("{error:#}").contains("not a client"), "{error:#}");
+        let request = issuer.get_pending_requests().await.into_iter().find(|r| r.request_id == request_id).unwrap();
+        assert_eq!(request.status, RequestStatus::Pending);
+        assert!(issuer.get_issued_credentials().await.is_empty());
+
+        processor.add_trusted_issuer(issuer.endpoint().id()).await.unwrap();
+        issuer.approve_request(request_id.clone(), ValidityWindow::default()).await.unwrap();
+        let credential = received(employee, &request_id).await.credential.unwrap();
+        assert_eq!(credential.co_signatures.len(), 1);
+        assert_eq!(credential.co_signatures[0].signer_node_id, processor.endpoint().id());
+        // The status index of the refused attempt was reused
+        assert_eq!(credential.credential().status_index, Some(0));
+
+        verifier.add_trusted_issuer(issuer.endpoint().id()).await.unwrap();
+        verifier.add_trusted_issuer(processor.endpoint().id()).await.unwrap();
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![credential.clone()]).await.unwrap();
+        assert!(verdict.is_valid, "{}", verdict.message);
+        let signers = &verdict.credentials[0].signers;
+        assert_eq!(signers.len(), 2);
+        assert!(signers.iter().all(|signer| signer.signed && signer.is_trusted));
+        assert_eq!(signers[1].role, SignerRole::PayrollProcessor);
+
+        // Without the co-signature the credential no longer verifies
+        let mut stripped = credential.clone();
+        stripped.co_signatures.clear();
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![stripped]).await.unwrap();
+        assert!(!verdict.is_valid);
+        assert!(!verdict.credentials[0].signers[1].signed);
+
+        // The selective and threshold forms carry the processor's co-signature too,
+        // with its node ID disclosed even when every field is hidden
+        let verifier_id = verifier.endpoint().id();
+        for result in [
+            employee.present_disclosure(verifier_id, credential.id().to_string(), Vec::new()).await.unwrap(),
+            employee.present_threshold_proof(verifier_id, credential.id().to_string(), "5000".to_string()).await.unwrap(),
+        ] {
+            let CredentialMessage::VerificationResult { is_valid, signers, message, .. } = result else {
+                panic!("unexpected reply {result:?}");
+            };
+            assert!(is_valid, "{message}");
+            assert_eq!(signers.len(), 2);
+            assert!(signers.iter().all(|signer| signer.signed));
+        }
+
+        // And fail without it
+        let mut stripped = received(employee, &request_id).await.selective_credential.unwrap();
+        stripped.credential.co_signatures.clear();
+        let challenge = employee.request_challenge(verifier_id).await.unwrap();
+        let envelope = PresentationEnvelope::sign(employee.secret_key(), verifier_id, challenge.nonce, &[&stripped]).unwrap();
+        let message = CredentialMessage::PresentDisclosure {
+            presentation_id: uuid::Uuid::new_v4().to_string(),
+            credential: stripped,
+            envelope: Some(envelope),
+        };
+        match send_and_receive(employee.endpoint(), verifier_id, message).await.unwrap() {
+            Some(CredentialMessage::VerificationResult { is_valid, signers, .. }) => {
+                assert!(!is_valid);
+                assert!(!signers[1].signed);
+            }
+            other => panic!("unexpected reply {other:?}"),
+        }
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jws_presentation_is_verified_like_the_credential() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Verifier]).await;
+        let (issuer, employee, verifier) = (&nodes[0], &nodes[1], &nodes[2]);
+        let jws = issue(issuer, employee, "7200.00").await.jws.unwrap();
+        verifier.add_trusted_issuer(issuer.endpoint().id()).await.unwrap();
+        let verifier_id = verifier.endpoint().id();
+
+        match employee.present_jws(verifier_id, jws.clone()).await.unwrap() {
+            CredentialMessage::VerificationResult { presentation_id, is_valid, holder_binding, message, .. } => {
+                assert!(is_valid, "{message}");
+                assert_eq!(holder_binding, HolderBinding::Envelope);
+                assert!(verifier.get_verified_credential(&presentation_id).await.is_some());
+            }
+            other => panic!("unexpected reply {other:?}"),
+        }
+
+        // A token whose payload was changed is refused outright
+        let (header, rest) = jws.split_once('.').unwrap();
+        let (payload, signature) = rest.split_once('.').unwrap();
+        let mut claims: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap();
+        claims["vc"]["credentialSubject"]["netSalary"] = "9000.00".into();
+        let forged = format!("{header}.{}.{signature}", URL_SAFE_NO_PAD.encode(claims.to_string()));
+        let message = CredentialMessage::PresentJws {
+            presentation_id: uuid::Uuid::new_v4().to_string(),
+            jws: forged,
+            envelope: None,
+        };
+        match send_and_receive(employee.endpoint(), verifier_id, message).await.unwrap() {
+            Some(CredentialMessage::Error { error_code, .. }) => assert_eq!(error_code, "INVALID_JWS"),
+            other => panic!("unexpected reply {other:?}"),
+        }
+
+        // A copied token presented by another node is not bound to its holder
+        let message = CredentialMessage::PresentJws {
+            presentation_id: uuid::Uuid::new_v4().to_string(),
+            jws,
+            envelope: None,
+        };
+        match send_and_receive(issuer.endpoint(), verifier_id, message).await.unwrap() {
+            Some(CredentialMessage::VerificationResult { is_valid, holder_binding, .. }) => {
+                assert!(!is_valid);
+                assert_eq!(holder_binding, HolderBinding::Unbound);
+            }
+            other => panic!("unexpected reply {other:?}"),
+        }
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_officer_approvals_gate_issuance() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Employee, Role::Employee, Role::Verifier]).await;
+        let (issuer, employee, officer_a, officer_b, verifier) = (&nodes[0], &nodes[1], &nodes[2], &nodes[3], &nodes[4]);
+        let issuer_id = issuer.endpoint().id();
+        let officers = vec![officer_a.endpoint().id(), officer_b.endpoint().id()];
+        let zero = ApprovalPolicy { officers: officers.clone(), threshold: 0 };
+        assert!(issuer.set_approval_policy(zero).await.is_err());
+        let status = issuer.set_approval_policy(ApprovalPolicy { officers: officers.clone(), threshold: 2 }).await.unwrap();
+        assert_eq!(status.version(), 1);
+
+        // Below the threshold nothing is issued
+        let request_id = request_credential(employee, issuer, "7200.00").await;
+        issuer.approve_request(request_id.clone(), ValidityWindow::default()).await.unwrap();
+        let progress = officer_a.approve_as_officer(issuer_id, request_id.clone()).await.unwrap();
+        assert_eq!((progress.approved_by.len(), progress.status), (1, RequestStatus::Pending));
+        // Approving twice does not count twice
+        let progress = officer_a.approve_as_officer(issuer_id, request_id.clone()).await.unwrap();
+        assert_eq!(progress.approved_by.len(), 1);
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert!(employee.get_received_credential(request_id.clone()).await.unwrap().is_none());
+        assert!(issuer.get_issued_credentials().await.is_empty());
+
+        // The second officer meets it; every form carries both approvals
+        let progress = officer_b.approve_as_officer(issuer_id, request_id.clone()).await.unwrap();
+        assert_eq!(progress.status, RequestStatus::Approved);
+        let response = received(employee, &request_id).await;
+        let credential = response.credential.unwrap();
+        assert_eq!(credential.approvals.len(), 2);
+        assert_eq!(response.selective_credential.unwrap().credential.approvals.len(), 2);
+
+        verifier.add_trusted_issuer(issuer_id).await.unwrap();
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![credential.clone()]).await.unwrap();
+        assert!(verdict.is_valid, "{}", verdict.message);
+        let approvals = verdict.credentials[0].approvals.clone().unwrap();
+        assert_eq!((approvals.policy_version, approvals.approved_by.len()), (1, 2));
+
+        // Verifiers that know the policy refuse a credential short of approvals
+        let mut stripped = credential;
+        stripped.approvals.pop();
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![stripped]).await.unwrap();
+        assert!(!verdict.is_valid);
+        assert!(verdict.credentials[0].message.contains("approved by 1 officers"), "{}", verdict.credentials[0].message);
+
+        // The node key alone cannot lower the threshold; the current officers must agree
+        let status = issuer.set_approval_policy(ApprovalPolicy { officers: officers.clone(), threshold: 1 }).await.unwrap();
+        assert_eq!(status.version(), 1);
+        assert_eq!(status.proposed.unwrap().policy.version, 2);
+        let status = officer_a.approve_policy_as_officer(issuer_id, 2).await.unwrap();
+        assert_eq!(status.version(), 1);
+        let status = officer_b.approve_policy_as_officer(issuer_id, 2).await.unwrap();
+        assert_eq!(status.version(), 2);
+        assert!(status.proposed.is_none());
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unapproved_credential_fails_where_the_policy_was_never_seen() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Employee, Role::Verifier, Role::Verifier]).await;
+        let (issuer, employee, officer, follower, stranger) = (&nodes[0], &nodes[1], &nodes[2], &nodes[3], &nodes[4]);
+        let issuer_id = issuer.endpoint().id();
+
+        // Followers learn the policy over the issuer's topic
+        follower.add_trusted_issuer(issuer_id).await.unwrap();
+        let policy = ApprovalPolicy { officers: vec![officer.endpoint().id()], threshold: 1 };
+        issuer.set_approval_policy(policy).await.unwrap();
+        wait_for("the approval policy to reach the follower", || async {
+            !follower.export_trust_store().await.approval_policies.is_empty()
+        })
+        .await;
+
+        let request_id = request_credential(employee, issuer, "7200.00").await;
+        issuer.approve_request(request_id.clone(), ValidityWindow::default()).await.unwrap();
+        officer.approve_as_officer(issuer_id, request_id.clone()).await.unwrap();
+        let credential = received(employee, &request_id).await.credential.unwrap();
+        let mut stripped = credential.clone();
+        stripped.approvals.clear();
+
+        // A credential naming no policy version still gets the issuer's policy checked,
+        // even by a verifier that has never heard of it
+        assert!(stranger.export_trust_store().await.approval_policies.is_empty());
+        for verifier in [stranger, follower] {
+            let verdict = employee.present_credentials(verifier.endpoint().id(), vec![stripped.clone()]).await.unwrap();
+            assert!(!verdict.is_valid);
+            let message = &verdict.credentials[0].message;
+            assert!(message.contains("approved by 0 officers"), "{message}");
+        }
+        let verdict = employee.present_credentials(stranger.endpoint().id(), vec![credential]).await.unwrap();
+        assert!(verdict.is_valid, "{}", verdict.message);
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unbound_signing_key_stops_verifying() {
+        let signing_key = SecretKey::generate(&mut rand::rng());
+        let signer: Arc<dyn CredentialSigner> = Arc::new(MemorySigner::new(signing_key.clone()));
+        let nodes = spawn_nodes_with_signers(&[
+            (Role::Issuer, Some(signer)),
+            (Role::Employee, None),
+            (Role::Verifier, None),
+            (Role::Verifier, None),
+        ])
+        .await;
+        let (issuer, employee, follower, stranger) = (&nodes[0], &nodes[1], &nodes[2], &nodes[3]);
+        let issuer_id = issuer.endpoint().id();
+        let credential = issue(issuer, employee, "7200.00").await.credential.unwrap();
+        assert_eq!(credential.signing_key, Some(signing_key.public()));
+
+        // The follower caches the binding while the key is good
+        follower.add_trusted_issuer(issuer_id).await.unwrap();
+        let verdict = employee.present_credentials(follower.endpoint().id(), vec![credential.clone()]).await.unwrap();
+        assert!(verdict.is_valid, "{}", verdict.message);
+        assert_eq!(follower.export_trust_store().await.key_bindings.len(), 1);
+
+        // The key leaks; the node retires it and stops issuing with it
+        issuer.unbind_signing_key(signing_key.public()).await.unwrap();
+        assert!(issuer.key_binding().await.is_some());
+        let request_id = request_credential(employee, issuer, "7200.00").await;
+        let error = issuer.approve_request(request_id, ValidityWindow::default()).await.unwrap_err();
+        assert!(format!("{error:#}").contains("was unbound"), "{error:#}");
+
+        // The follower hears of it over gossip, despite its cached binding, and a
+        // verifier that never saw the key learns of it with the DID document
+        wait_for("the unbinding to reach the follower", || async {
+            !follower.export_trust_store().await.key_unbindings.is_empty()
+        })
+        .await;
+        for verifier in [follower, stranger] {
+            let verdict = employee.present_credentials(verifier.endpoint().id(), vec![credential.clone()]).await.unwrap();
+            assert!(!verdict.is_valid);
+            let message = &verdict.credentials[0].message;
+            assert!(message.contains("is not bound"), "{message}");
+        }
+        let document = stranger.resolve_did(issuer.did()).await.unwrap();
+        assert_eq!(document.assertion_keys().unwrap(), vec![issuer_id]);
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revocation_reaches_followers_over_gossip() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Verifier]).await;
+        let (issuer, employee, verifier) = (&nodes[0], &nodes[1], &nodes[2]);
+        let credential = issue(issuer, employee, "7200.00").await.credential.unwrap();
+        verifier.add_trusted_issuer(issuer.endpoint().id()).await.unwrap();
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![credential.clone()]).await.unwrap();
+        assert!(verdict.is_valid, "{}", verdict.message);
+
+        issuer
+            .revoke_credential(credential.id().to_string(), Some("terminated".to_string()))
+            .await
+            .unwrap();
+        wait_for("the revocation to reach the verifier", || async {
+            !verifier.get_revocations().await.is_empty()
+        })
+        .await;
+        // The reason comes from the gossiped notice, not the status list
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![credential]).await.unwrap();
+        let check = &verdict.credentials[0];
+        assert!(!verdict.is_valid && check.is_revoked);
+        assert_eq!(check.revocation_reason.as_deref(), Some("terminated"));
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotation_reaches_followers_over_gossip() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Verifier]).await;
+        let (issuer, employee, verifier) = (&nodes[0], &nodes[1], &nodes[2]);
+        verifier.add_trusted_issuer(issuer.endpoint().id()).await.unwrap();
+        let before = issue(issuer, employee, "7200.00").await.credential.unwrap();
+
+        let new_key = SecretKey::generate(&mut rand::rng());
+        issuer.rotate_key(&new_key, Some("scheduled".to_string())).await.unwrap();
+        wait_for("the rotation to reach the verifier", || async {
+            !verifier.get_key_rotations().await.is_empty()
+        })
+        .await;
+
+        // The issuer restarts under the new key, reachable by the others
+        let store = Arc::new(MemoryStore::default());
+        let rotated = PeerNode::spawn(Some(new_key), Role::Issuer, store, None, None).await.unwrap();
+        let discovery = StaticProvider::new();
+        for node in nodes.iter().chain([&rotated]) {
+            node.endpoint().discovery().add(discovery.clone());
+            discovery.add_endpoint_info(node.endpoint().addr());
+        }
+
+        // The verifier trusts the new key through the gossiped rotation alone
+        assert!(!verifier.get_trusted_issuers().await.contains(&rotated.endpoint().id()));
+        let after = issue(&rotated, employee, "7300.00").await.credential.unwrap();
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![after]).await.unwrap();
+        assert!(verdict.is_valid, "{}", verdict.message);
+
+        // The retired key's earlier credentials stand, those it signs afterwards do not
+        let check = verifier.peer.check_credential(&before, HolderBinding::Envelope, None).await;
+        assert!(check.is_trusted && !check.message.contains("rotated"), "{}", check.message);
+        // The old node refuses to issue, so whoever holds the retired key signs one itself
+        let mut forged = before.credential.clone();
+        forged.id = uuid::Uuid::new_v4().to_string();
+        forged.issued_at = chrono::Utc::now().to_rfc3339();
+        let forged = forged.sign(issuer.secret_key()).unwrap();
+        let verdict = employee.present_credentials(verifier.endpoint().id(), vec![forged]).await.unwrap();
+        let check = &verdict.credentials[0];
+        assert!(!verdict.is_valid && check.is_trusted);
+        assert!(check.message.contains("was rotated to"), "{}", check.message);
+
+        for node in nodes.iter().chain([&rotated]) {
+            node.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_list_is_fetched_kept_and_never_rolled_back() {
+        let nodes = spawn_nodes(&[Role::Issuer, Role::Employee, Role::Verifier, Role::Verifier]).await;
+        let (issuer, employee, verifier, stranger) = (&nodes[0], &nodes[1], &nodes[2], &nodes[3]);
+        let issuer_id = issuer.endpoint().id();
+        let kept = issue(issuer, employee, "7200.00").await.credential.unwrap();
+        let revoked = issue(issuer, employee, "7300.00").await.credential.unwrap();
+        let policy = VerificationPolicy {
+            status_list_max_age_minutes: Some(0),
+            ..Default::default()
+        };
+        verifier.set_verification_policy(policy).await.unwrap();
+
+        // The verifier fetches the list over the status list ALPN, and again once it changed
+        let check = verifier.peer.check_credential(&kept, HolderBinding::Envelope, None).await;
+        assert!(check.is_valid, "{}", check.message);
+        assert_eq!(check.status_check, StatusCheck::Active);
+        issuer.revoke_credential(revoked.id().to_string(), None).await.unwrap();
+        let check = verifier.peer.check_credential(&revoked, HolderBinding::Envelope, None).await;
+        assert!(!check.is_valid);
+        assert_eq!(check.status_check, StatusCheck::Revoked);
+        let version = check.status_list_version.unwrap();
+
+        // A list older than the cached one is refused, and the cached one kept
+        let newer = StatusListState {
+            version: version + 10,
+            ..Default::default()
+        };
+        let newer = StatusList::new(issuer_id, &newer, chrono::Duration::hours(1))
+            .unwrap()
+            .sign(issuer.secret_key())
+            .unwrap();
+        let lists = StoredMap::open(Arc::new(MemoryStore::default()), Collection::StatusListCache).unwrap();
+        let expired = "2020-01-01T00:00:00Z".to_string();
+        let cached = CachedStatusList {
+            list: newer,
+            fetched_at: expired.clone(),
+            next_update: expired,
+        };
+        lists.insert(issuer_id.to_string(), cached).await.unwrap();
+        let cache = StatusListCache::new(verifier.endpoint().clone(), lists.clone());
+        let status_index = revoked.credential.status_index.unwrap();
+        assert_eq!(cache.check(issuer_id, status_index, None).await, (StatusCheck::Active, Some(version + 10)));
+        assert_eq!(lists.get(&issuer_id.to_string()).await.unwrap().list.list.version, version + 10);
+
+        // A node that serves no status list stands in for an unreachable issuer: its
+        // stale list still answers, and without any list the credential fails unless
+        // the policy accepts an unavailable status
+        let offline_id = employee.endpoint().id();
+        let stale = StatusList::new(offline_id, &StatusListState::default(), chrono::Duration::hours(1))
+            .unwrap()
+            .sign(employee.secret_key())
+            .unwrap();
+        let expired = "2020-01-01T00:00:00Z".to_string();
+        let cached = CachedStatusList {
+            list: stale,
+            fetched_at: expired.clone(),
+            next_update: expired,
+        };
+        lists.insert(offline_id.to_string(), cached).await.unwrap();
+        assert_eq!(cache.check(offline_id, 0, None).await, (StatusCheck::Active, Some(0)));
+        let credential = IncomeCredential::new(
+            offline_id,
+            "Alice".to_string(),
+            offline_id,
+            "Offline Corp".to_string(),
+            "9000.00".to_string(),
+            "7200.00".to_string(),
+            "USD".to_string(),
+            "2025-01".to_string(),
+            PaymentMode::BankTransfer,
+        )
+        .with_status_index(0)
+        .sign(employee.secret_key())
+        .unwrap();
+        let check = stranger.peer.check_credential(&credential, HolderBinding::Envelope, None).await;
+        assert!(!check.is_valid && !check.is_revoked);
+        assert_eq!(check.status_check, StatusCheck::Unavailable);
+        assert!(check.message.contains("status list is unavailable"), "{}", check.message);
+        let policy = VerificationPolicy {
+            accept_unavailable_status: true,
+            ..Default::default()
+        };
+        stranger.set_verification_policy(policy).await.unwrap();
+        let check = stranger.peer.check_credential(&credential, HolderBinding::Envelope, None).await;
+        assert!(check.is_valid, "{}", check.message);
+
+        for node in &nodes {
+            node.shutdown().await.unwrap();
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Credential signing outside the issuer process.
+//!
+//! A signing daemon holds the key, standing in for an HSM or KMS, and speaks
+//! newline-delimited JSON over a Unix socket: every line a client writes is a
+//! [`SignerRequest`] answered by one [`ControlResponse`] line.
+
+use anyhow::{Context, Result};
+use iroh::{PublicKey, SecretKey};
+use iroh_base::Signature;
+use n0_future::boxed::BoxFuture;
+use n0_future::task::AbortOnDropHandle;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::UnixStream;
+use tracing::info;
+
+use crate::control::{ControlResponse, bind_private_socket, write_line};
+use crate::credentials::income_credential::CredentialError;
+use crate::credentials::signer::CredentialSigner;
+
+/// File name of the signer socket inside the signing daemon's data directory
+pub const SIGNER_SOCKET: &str = "signer.sock";
+
+/// A command sent to the signer socket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum SignerRequest {
+    /// The key signatures verify against
+    PublicKey,
+    /// Answered with the hex-encoded Ed25519 signature
+    Sign {
+        /// Hex-encoded bytes to sign
+        payload: String,
+    },
+}
+
+/// Serves signatures by `secret_key` until dropped
+#[derive(Debug)]
+pub struct SignerServer {
+    path: PathBuf,
+    _task: AbortOnDropHandle<()>,
+}
+
+impl SignerServer {
+    /// Listens on `path`, replacing a stale socket left by a crashed daemon
+    pub async fn bind(path: impl AsRef<Path>, secret_key: SecretKey) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // Anyone who can connect can sign credentials
+        let listener = bind_private_socket(&path).await?;
+        info!("signer for {} listening on {}", secret_key.public(), path.display());
+
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let secret_key = secret_key.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_connection(stream, secret_key).await {
+                                tracing::warn!("signer connection failed: {e:#}");
+                            }
+                        });
+                    }
+                    Err(e) => tracing::error!("failed to accept signer connection: {e}"),
+                }
+            }
+        });
+        Ok(Self {
+            path,
+            _task: AbortOnDropHandle::new(task),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SignerServer {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+async fn serve_connection(stream: UnixStream, secret_key: SecretKey) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = serde_json::from_str::<SignerRequest>(&line)
+            .context("invalid request")
+            .and_then(|request| handle(&secret_key, request));
+        write_line(&mut writer, &ControlResponse::from_result(result)).await?;
+    }
+    Ok(())
+}
+
+fn handle(secret_key: &SecretKey, request: SignerRequest) -> Result<serde_json::Value> {
+    let value = match request {
+        SignerRequest::PublicKey => serde_json::to_value(secret_key.public())?,
+        SignerRequest::Sign { payload } => {
+            let payload = hex::decode(payload).context("payload is not hex")?;
+            serde_json::Value::String(hex::encode(secret_key.sign(&payload).to_bytes()))
+        }
+    };
+    Ok(value)
+}
+
+/// [`CredentialSigner`] backed by a signing daemon
+#[derive(Debug, Clone)]
+pub struct SocketSigner {
+    path: PathBuf,
+    public_key: PublicKey,
+}
+
+impl SocketSigner {
+    /// Connects to the daemon listening on `path` and learns its key
+    pub async fn connect(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let public_key = serde_json::from_value(request(&path, &SignerRequest::PublicKey).await?)?;
+        Ok(Self { path, public_key })
+    }
+
+    async fn sign_remote(path: PathBuf, public_key: PublicKey, payload: Vec<u8>) -> Result<Signature> {
+        let request = SignerRequest::Sign {
+            payload: hex::encode(&payload),
+        };
+        let signature: String = serde_json::from_value(self::request(&path, &request).await?)?;
+        let bytes: [u8; 64] = hex::decode(signature)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("signature is not 64 bytes"))?;
+        let signature = Signature::from_bytes(&bytes);
+        // The daemon may have been restarted with another key
+        public_key
+            .verify(&payload, &signature)
+            .with_context(|| format!("signer did not sign with {public_key}"))?;
+        Ok(signature)
+    }
+}
+
+impl CredentialSigner for SocketSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign(&self, payload: Vec<u8>) -> BoxFuture<Result<Signature, CredentialError>> {
+        let signature = Self::sign_remote(self.path.clone(), self.public_key, payload);
+        Box::pin(async move { signature.await.map_err(|e| CredentialError::SigningError(format!("{e:#}"))) })
+    }
+}
+
+/// Sends `request` on a fresh connection and returns its result, or the daemon's error
+async fn request(path: &Path, request: &SignerRequest) -> Result<serde_json::Value> {
+    let stream = UnixStream::connect(path)
+        .await
+        .with_context(|| format!("failed to connect to signer socket {}", path.display()))?;
+    let (reader, mut writer) = stream.into_split();
+    write_line(&mut writer, request).await?;
+    let line = BufReader::new(reader)
+        .lines()
+        .next_line()
+        .await?
+        .context("signer socket closed")?;
+    serde_json::from_str::<ControlResponse>(&line)?.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::income_credential::{IncomeCredential, PaymentMode};
+
+    #[tokio::test]
+    async fn test_socket_signer_round_trip() {
+        let dir = std::env::temp_dir().join(format!("trust-triangle-signer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SIGNER_SOCKET);
+
+        let signing_key = SecretKey::generate(&mut rand::rng());
+        let server = SignerServer::bind(&path, signing_key.clone()).await.unwrap();
+        let signer = SocketSigner::connect(&path).await.unwrap();
+        assert_eq!(signer.public_key(), signing_key.public());
+
+        let employer_key = SecretKey::generate(&mut rand::rng());
+        let credential = IncomeCredential::new(
+            SecretKey::generate(&mut rand::rng()).public(),
+            "Jane Doe".to_string(),
+            employer_key.public(),
+            "Acme Corporation".to_string(),
+            "10000.00".to_string(),
+            "8000.00".to_string(),
+            "USD".to_string(),
+            "2024-01".to_string(),
+            PaymentMode::BankTransfer,
+        );
+        let signed = credential.sign_with(&signer).await.unwrap();
+        assert_eq!(signed.signing_key, Some(signing_key.public()));
+        assert!(signed.verify().unwrap());
+
+        let error = request(&path, &SignerRequest::Sign { payload: "zz".to_string() }).await.unwrap_err();
+        assert!(error.to_string().contains("not hex"));
+
+        // Signing fails once the daemon is gone
+        drop(server);
+        assert!(signer.sign(b"payload".to_vec()).await.is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
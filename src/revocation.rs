@@ -0,0 +1,463 @@
+use anyhow::Result;
+use bytes::Bytes;
+use iroh::{EndpointId, PublicKey};
+use iroh_gossip::{
+    api::{Event, GossipReceiver, GossipSender},
+    net::Gossip,
+    proto::TopicId,
+};
+use n0_future::{StreamExt, task};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{broadcast, Mutex};
+use tracing::info;
+
+use crate::credentials::key_binding::{KeyUnbinding, SignedKeyUnbinding, binding_key};
+use crate::credentials::key_rotation::{KeyRotation, RotationLog, SignedKeyRotation};
+use crate::credentials::officer_approval::{ApprovalPolicyLog, SignedApprovalPolicy};
+use crate::credentials::revocation::SignedRevocationNotice;
+use crate::peer_node::AcceptEvent;
+use crate::storage::StoredMap;
+
+/// Key-derivation context for per-issuer revocation topics
+const REVOCATION_TOPIC_CONTEXT: &str = "trust-triangle revocation topic v1";
+
+/// Gossip topic on which `issuer` publishes its revocation notices
+pub fn revocation_topic(issuer: EndpointId) -> TopicId {
+    TopicId::from_bytes(blake3::derive_key(REVOCATION_TOPIC_CONTEXT, issuer.as_bytes()))
+}
+
+/// Key under which a revocation is stored: credential IDs are only unique per issuer
+pub fn revocation_key(issuer: EndpointId, credential_id: &str) -> String {
+    format!("{issuer}:{credential_id}")
+}
+
+/// Messages published on an issuer's revocation topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum IssuerAnnouncement {
+    Revocation { notice: SignedRevocationNotice },
+    /// The issuer's key was replaced; published on the retired key's topic
+    Rotation { rotation: SignedKeyRotation },
+    /// A version of the issuer's approval policy, so followers know it issues
+    /// only with officer approvals
+    ApprovalPolicy { policy: SignedApprovalPolicy },
+    /// A credential signing key no longer signs for the issuer
+    KeyUnbinding { unbinding: SignedKeyUnbinding },
+}
+
+/// Revocation notices, issuer key rotations, approval policies and signing key
+/// unbindings known to this node, kept in sync over iroh-gossip.
+///
+/// Issuers publish on their own topic; verifiers follow the topics of their
+/// trusted issuers and only accept notices signed by the topic's issuer. A
+/// rotation makes followers follow the new key's topic as well.
+#[derive(Debug, Clone)]
+pub struct RevocationRegistry {
+    gossip: Gossip,
+    node_id: EndpointId,
+    event_sender: broadcast::Sender<AcceptEvent>,
+    /// Keyed by [`revocation_key`]
+    revocations: StoredMap<SignedRevocationNotice>,
+    /// Keyed by the retired key
+    rotations: StoredMap<SignedKeyRotation>,
+    /// Own approval policy versions (Issuer) and those of issuers (Verifier),
+    /// keyed by issuer and version
+    approval_policies: StoredMap<SignedApprovalPolicy>,
+    /// Own retired signing keys (Issuer) and those of issuers (Verifier), keyed by
+    /// [`binding_key`]
+    key_unbindings: StoredMap<SignedKeyUnbinding>,
+    /// Sender for our own topic, created on first publish (Issuer role)
+    publisher: Arc<Mutex<Option<GossipSender>>>,
+    /// Receive loops for followed issuer topics (Verifier role), `None` while the
+    /// topic is being joined
+    subscriptions: Arc<Mutex<HashMap<EndpointId, Option<task::AbortOnDropHandle<()>>>>>,
+}
+
+impl RevocationRegistry {
+    pub fn new(
+        gossip: Gossip,
+        node_id: EndpointId,
+        event_sender: broadcast::Sender<AcceptEvent>,
+        revocations: StoredMap<SignedRevocationNotice>,
+        rotations: StoredMap<SignedKeyRotation>,
+        approval_policies: StoredMap<SignedApprovalPolicy>,
+        key_unbindings: StoredMap<SignedKeyUnbinding>,
+    ) -> Self {
+        Self {
+            gossip,
+            node_id,
+            event_sender,
+            revocations,
+            rotations,
+            approval_policies,
+            key_unbindings,
+            publisher: Arc::new(Mutex::new(None)),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Publish one of our own revocation notices on our topic (Issuer role)
+    pub async fn publish(&self, notice: SignedRevocationNotice) -> Result<()> {
+        if notice.notice.issuer_node_id != self.node_id {
+            return Err(anyhow::anyhow!("Can only publish revocations for credentials issued by this node"));
+        }
+
+        let key = revocation_key(self.node_id, &notice.notice.credential_id);
+        self.revocations.insert(key, notice.clone()).await?;
+
+        let sender = self.start_publishing().await?;
+        let announcement = IssuerAnnouncement::Revocation { notice };
+        sender.broadcast(Bytes::from(serde_json::to_vec(&announcement)?)).await?;
+        info!("Published revocation on topic {}", revocation_topic(self.node_id));
+        Ok(())
+    }
+
+    /// Record the rotation of our own key and publish it on our topic (Issuer role)
+    pub async fn publish_rotation(&self, rotation: SignedKeyRotation) -> Result<()> {
+        if rotation.rotation.previous_node_id != self.node_id {
+            return Err(anyhow::anyhow!("Can only publish rotations of this node's key"));
+        }
+        self.record_rotation(rotation.clone()).await?;
+
+        let sender = self.start_publishing().await?;
+        let announcement = IssuerAnnouncement::Rotation { rotation };
+        sender.broadcast(Bytes::from(serde_json::to_vec(&announcement)?)).await?;
+        info!("Published key rotation on topic {}", revocation_topic(self.node_id));
+        Ok(())
+    }
+
+    /// Checks `rotation` against the known ones and stores it; returns whether it was new
+    pub async fn record_rotation(&self, rotation: SignedKeyRotation) -> Result<bool> {
+        self.rotation_log().await.check(&rotation)?;
+        let KeyRotation { previous_node_id, new_node_id, .. } = rotation.rotation.clone();
+        let was_new = self.rotations.insert(previous_node_id.to_string(), rotation).await?.is_none();
+        if was_new {
+            info!("Issuer key {} rotated to {}", previous_node_id, new_node_id);
+            self.event_sender
+                .send(AcceptEvent::KeyRotated {
+                    previous_node_id,
+                    new_node_id,
+                })
+                .ok();
+        }
+        Ok(was_new)
+    }
+
+    /// All known rotations, indexed for following chains
+    pub async fn rotation_log(&self) -> RotationLog {
+        RotationLog::new(self.rotations.values().await)
+    }
+
+    /// Get all known key rotations
+    pub async fn rotations(&self) -> Vec<SignedKeyRotation> {
+        self.rotations.values().await
+    }
+
+    /// Record an authorized version of our own approval policy and publish it on
+    /// our topic (Issuer role)
+    pub async fn publish_policy(&self, policy: SignedApprovalPolicy) -> Result<()> {
+        if !self.rotation_log().await.predecessors(self.node_id).contains(&policy.policy.issuer_node_id) {
+            return Err(anyhow::anyhow!("Can only publish the approval policy of this node's key"));
+        }
+        self.record_policy(policy.clone()).await?;
+
+        let sender = self.start_publishing().await?;
+        let announcement = IssuerAnnouncement::ApprovalPolicy { policy };
+        sender.broadcast(Bytes::from(serde_json::to_vec(&announcement)?)).await?;
+        info!("Published approval policy on topic {}", revocation_topic(self.node_id));
+        Ok(())
+    }
+
+    /// Records `policy` if it is authorized as the next version of its issuer's
+    /// policy; returns whether it was new
+    pub async fn record_policy(&self, policy: SignedApprovalPolicy) -> Result<bool> {
+        let key = format!("{}/{}", policy.policy.issuer_node_id, policy.policy.version);
+        if self.approval_policies.get(&key).await.is_some_and(|known| known == policy) {
+            return Ok(false);
+        }
+        self.approval_policy_log().await.check(&policy)?;
+        self.approval_policies.insert(key, policy).await?;
+        Ok(true)
+    }
+
+    /// Verified approval policy versions of every known issuer
+    pub async fn approval_policy_log(&self) -> ApprovalPolicyLog {
+        ApprovalPolicyLog::new(self.approval_policies.values().await)
+    }
+
+    /// Known versions of the approval policy of issuer key `node_id`, oldest first,
+    /// including those published under keys it rotated from
+    pub async fn policy_history(&self, node_id: EndpointId) -> Vec<SignedApprovalPolicy> {
+        let keys = self.rotation_log().await.predecessors(node_id);
+        self.approval_policy_log().await.history_of(&keys).to_vec()
+    }
+
+    /// Get all known approval policy versions
+    pub async fn approval_policies(&self) -> Vec<SignedApprovalPolicy> {
+        self.approval_policies.values().await
+    }
+
+    /// Record the retirement of one of our signing keys and publish it on our topic (Issuer role)
+    pub async fn publish_unbinding(&self, unbinding: SignedKeyUnbinding) -> Result<()> {
+        if unbinding.unbinding.node_id != self.node_id {
+            return Err(anyhow::anyhow!("Can only publish unbindings of this node's signing keys"));
+        }
+        self.record_unbinding(unbinding.clone()).await?;
+
+        let sender = self.start_publishing().await?;
+        let announcement = IssuerAnnouncement::KeyUnbinding { unbinding };
+        sender.broadcast(Bytes::from(serde_json::to_vec(&announcement)?)).await?;
+        info!("Published signing key unbinding on topic {}", revocation_topic(self.node_id));
+        Ok(())
+    }
+
+    /// Checks `unbinding` and stores it; returns whether it was new
+    pub async fn record_unbinding(&self, unbinding: SignedKeyUnbinding) -> Result<bool> {
+        if !unbinding.verify()? {
+            return Err(anyhow::anyhow!("invalid unbinding signature"));
+        }
+        let KeyUnbinding { node_id, signing_key, .. } = unbinding.unbinding.clone();
+        let was_new = self.key_unbindings.insert(binding_key(node_id, signing_key), unbinding).await?.is_none();
+        if was_new {
+            info!("Signing key {} unbound from issuer {}", signing_key, node_id);
+        }
+        Ok(was_new)
+    }
+
+    /// Whether `signing_key` was retired from signing for `node_id`
+    pub async fn is_unbound(&self, node_id: EndpointId, signing_key: PublicKey) -> bool {
+        self.key_unbindings.contains_key(&binding_key(node_id, signing_key)).await
+    }
+
+    /// Known unbindings of `node_id`'s signing keys
+    pub async fn unbindings_of(&self, node_id: EndpointId) -> Vec<SignedKeyUnbinding> {
+        self.key_unbindings
+            .values()
+            .await
+            .into_iter()
+            .filter(|unbinding| unbinding.unbinding.node_id == node_id)
+            .collect()
+    }
+
+    /// Get all known signing key unbindings
+    pub async fn key_unbindings(&self) -> Vec<SignedKeyUnbinding> {
+        self.key_unbindings.values().await
+    }
+
+    /// Join our own topic so joining verifiers receive our stored revocations (Issuer role)
+    pub async fn start_publishing(&self) -> Result<GossipSender> {
+        let mut publisher = self.publisher.lock().await;
+        if let Some(sender) = publisher.as_ref() {
+            return Ok(sender.clone());
+        }
+        let (sender, receiver) = self
+            .gossip
+            .subscribe(revocation_topic(self.node_id), vec![])
+            .await?
+            .split();
+        task::spawn(self.clone().run_publisher(sender.clone(), receiver));
+        *publisher = Some(sender.clone());
+        Ok(sender)
+    }
+
+    /// Whether this node has published any revocations of its own, an approval
+    /// policy or a signing key unbinding, or replaces a rotated key
+    pub async fn has_own_announcements(&self) -> bool {
+        let own_revocation = self
+            .revocations
+            .values()
+            .await
+            .iter()
+            .any(|notice| notice.notice.issuer_node_id == self.node_id);
+        own_revocation
+            || !self.rotation_log().await.history(self.node_id).is_empty()
+            || !self.policy_history(self.node_id).await.is_empty()
+            || !self.unbindings_of(self.node_id).await.is_empty()
+    }
+
+    /// Re-announce our revocations, the rotations leading to our key, our
+    /// approval policy and our retired signing keys to every new neighbor so
+    /// late joiners catch up
+    async fn run_publisher(self, sender: GossipSender, mut receiver: GossipReceiver) {
+        while let Some(event) = receiver.next().await {
+            match event {
+                Ok(Event::NeighborUp(neighbor)) => {
+                    info!("Revocation subscriber {} joined, re-announcing revocations", neighbor);
+                    let own: Vec<_> = self
+                        .revocations
+                        .values()
+                        .await
+                        .into_iter()
+                        .filter(|notice| notice.notice.issuer_node_id == self.node_id)
+                        .collect();
+                    let history = self.rotation_log().await.history(self.node_id);
+                    let policies = self.policy_history(self.node_id).await;
+                    let unbindings = self.unbindings_of(self.node_id).await;
+                    let announcements = own
+                        .into_iter()
+                        .map(|notice| IssuerAnnouncement::Revocation { notice })
+                        .chain(history.into_iter().map(|rotation| IssuerAnnouncement::Rotation { rotation }))
+                        .chain(policies.into_iter().map(|policy| IssuerAnnouncement::ApprovalPolicy { policy }))
+                        .chain(unbindings.into_iter().map(|unbinding| IssuerAnnouncement::KeyUnbinding { unbinding }));
+                    for announcement in announcements {
+                        let Ok(bytes) = serde_json::to_vec(&announcement) else { continue };
+                        if let Err(e) = sender.broadcast_neighbors(Bytes::from(bytes)).await {
+                            tracing::warn!("Failed to re-announce on revocation topic: {}", e);
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Revocation topic error: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Subscribe to `issuer`'s revocation topic (Verifier role)
+    ///
+    /// Spelled out as `Send` because the follower task it spawns calls it again when
+    /// the issuer rotates its key.
+    #[allow(clippy::manual_async_fn)]
+    pub fn follow_issuer(&self, issuer: EndpointId) -> impl Future<Output = Result<()>> + Send + '_ {
+        async move {
+            // Claim the issuer, then join outside the lock so an unreachable issuer
+            // does not hold up following or unfollowing the others
+            let mut subscriptions = self.subscriptions.lock().await;
+            if subscriptions.contains_key(&issuer) {
+                return Ok(());
+            }
+            subscriptions.insert(issuer, None);
+            drop(subscriptions);
+
+            let topic = match self.gossip.subscribe(revocation_topic(issuer), vec![issuer]).await {
+                Ok(topic) => topic,
+                Err(e) => {
+                    let mut subscriptions = self.subscriptions.lock().await;
+                    if subscriptions.get(&issuer).is_some_and(Option::is_none) {
+                        subscriptions.remove(&issuer);
+                    }
+                    return Err(e.into());
+                }
+            };
+            let (sender, receiver) = topic.split();
+
+            // Unless the issuer was unfollowed, or followed again, in the meantime
+            let mut subscriptions = self.subscriptions.lock().await;
+            if let Some(slot @ None) = subscriptions.get_mut(&issuer) {
+                let handle = task::spawn(self.clone().run_follower(issuer, sender, receiver));
+                *slot = Some(task::AbortOnDropHandle::new(handle));
+                info!("Following revocations of issuer {}", issuer);
+            }
+            Ok(())
+        }
+    }
+
+    /// Leave `issuer`'s revocation topic; already known revocations are kept
+    pub async fn unfollow_issuer(&self, issuer: EndpointId) {
+        if self.subscriptions.lock().await.remove(&issuer).is_some() {
+            info!("Stopped following revocations of issuer {}", issuer);
+        }
+    }
+
+    // The sender is held so the topic stays joined for as long as we follow it
+    async fn run_follower(self, issuer: EndpointId, _sender: GossipSender, mut receiver: GossipReceiver) {
+        while let Some(event) = receiver.next().await {
+            match event {
+                Ok(Event::Received(message)) => {
+                    if let Err(e) = self.handle_announcement(issuer, &message.content).await {
+                        tracing::warn!("Ignoring announcement on revocation topic of {}: {}", issuer, e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Revocation topic error for issuer {}: {}", issuer, e);
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn handle_announcement(&self, issuer: EndpointId, content: &[u8]) -> Result<()> {
+        let notice = match serde_json::from_slice(content)? {
+            IssuerAnnouncement::Revocation { notice } => notice,
+            IssuerAnnouncement::Rotation { rotation } => return self.handle_rotation(issuer, rotation).await,
+            IssuerAnnouncement::ApprovalPolicy { policy } => return self.handle_policy(issuer, policy).await,
+            IssuerAnnouncement::KeyUnbinding { unbinding } => {
+                // Signed by the node key, so only the topic's issuer can retire its keys
+                if unbinding.unbinding.node_id != issuer {
+                    return Err(anyhow::anyhow!("unbinding names issuer {}", unbinding.unbinding.node_id));
+                }
+                self.record_unbinding(unbinding).await?;
+                return Ok(());
+            }
+        };
+        if notice.notice.issuer_node_id != issuer {
+            return Err(anyhow::anyhow!("notice names issuer {}", notice.notice.issuer_node_id));
+        }
+        if !notice.verify()? {
+            return Err(anyhow::anyhow!("invalid notice signature"));
+        }
+
+        let credential_id = notice.notice.credential_id.clone();
+        let key = revocation_key(issuer, &credential_id);
+        let was_new = self.revocations.insert(key, notice).await?.is_none();
+        if was_new {
+            info!("Credential {} revoked by issuer {}", credential_id, issuer);
+            self.event_sender
+                .send(AcceptEvent::RevocationReceived {
+                    issuer_node_id: issuer,
+                    credential_id,
+                })
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Records a rotation announced on `issuer`'s topic, following the new key if it
+    /// replaces `issuer`. Only rotations of `issuer` or of a key it is known to have
+    /// replaced are accepted: anyone can post on the topic, and a rotation of some
+    /// other key into `issuer` would claim its history.
+    async fn handle_rotation(&self, issuer: EndpointId, rotation: SignedKeyRotation) -> Result<()> {
+        let KeyRotation { previous_node_id, new_node_id, .. } = rotation.rotation.clone();
+        if !self.rotation_log().await.predecessors(issuer).contains(&previous_node_id) {
+            return Err(anyhow::anyhow!(
+                "Rotation of {} is not of issuer {} or a key it replaced",
+                previous_node_id,
+                issuer
+            ));
+        }
+        if self.record_rotation(rotation).await? && previous_node_id == issuer {
+            self.follow_issuer(new_node_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Records a version of the approval policy announced on `issuer`'s topic, if it
+    /// was published under `issuer` or a key it replaced
+    async fn handle_policy(&self, issuer: EndpointId, policy: SignedApprovalPolicy) -> Result<()> {
+        let published_by = policy.policy.issuer_node_id;
+        if !self.rotation_log().await.predecessors(issuer).contains(&published_by) {
+            return Err(anyhow::anyhow!("Approval policy of {} is not of issuer {}", published_by, issuer));
+        }
+        let version = policy.policy.version;
+        if self.record_policy(policy).await? {
+            info!("Issuer {} published version {} of its approval policy", issuer, version);
+        }
+        Ok(())
+    }
+
+    /// Look up a revocation of `credential_id` by `issuer`
+    pub async fn revocation_for(&self, issuer: EndpointId, credential_id: &str) -> Option<SignedRevocationNotice> {
+        self.revocations.get(&revocation_key(issuer, credential_id)).await
+    }
+
+    /// Get all known revocations
+    pub async fn revocations(&self) -> Vec<SignedRevocationNotice> {
+        self.revocations.values().await
+    }
+}
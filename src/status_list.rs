@@ -0,0 +1,204 @@
+use anyhow::Result;
+use iroh::{
+    Endpoint, EndpointId, SecretKey,
+    endpoint::Connection,
+    protocol::{AcceptError, ProtocolHandler},
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+pub use crate::credentials::status_list::StatusCheck;
+use crate::credentials::status_list::{SignedStatusList, StatusList, StatusListState};
+use crate::storage::{StoredMap, StoredValue};
+
+pub const STATUS_LIST_ALPN: &[u8] = b"social-id/status-list/v1";
+
+/// How long a served status list snapshot stays fresh
+const STATUS_LIST_TTL_MINUTES: i64 = 60;
+
+/// Verifier → Issuer: fetch the issuer's status list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusListRequest {
+    /// Version the verifier already holds, if any
+    pub known_version: Option<u64>,
+}
+
+/// Issuer → Verifier: the current list, or confirmation the cached one is current
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum StatusListResponse {
+    List { list: SignedStatusList },
+    NotModified { version: u64, next_update: String },
+}
+
+/// Serves this issuer's signed status list over [`STATUS_LIST_ALPN`] (Issuer role)
+#[derive(Debug, Clone)]
+pub struct StatusListProtocol {
+    secret_key: SecretKey,
+    state: StoredValue<StatusListState>,
+}
+
+impl StatusListProtocol {
+    pub fn new(secret_key: SecretKey, state: StoredValue<StatusListState>) -> Self {
+        Self { secret_key, state }
+    }
+
+    async fn handle_connection(&self, connection: Connection) -> Result<()> {
+        let (mut send, mut recv) = connection.accept_bi().await?;
+        let buffer = recv.read_to_end(1024).await?;
+        let request: StatusListRequest = serde_json::from_slice(&buffer)?;
+
+        let state = self.state.read().await;
+        let ttl = chrono::Duration::minutes(STATUS_LIST_TTL_MINUTES);
+        let response = if request.known_version == Some(state.version) {
+            StatusListResponse::NotModified {
+                version: state.version,
+                next_update: (chrono::Utc::now() + ttl).to_rfc3339(),
+            }
+        } else {
+            let list = StatusList::new(self.secret_key.public(), &state, ttl)?.sign(&self.secret_key)?;
+            StatusListResponse::List { list }
+        };
+        drop(state);
+
+        send.write_all(&serde_json::to_vec(&response)?).await?;
+        send.finish()?;
+        info!("Served status list to {}", connection.remote_id());
+        connection.closed().await;
+        Ok(())
+    }
+}
+
+impl ProtocolHandler for StatusListProtocol {
+    async fn accept(&self, connection: Connection) -> std::result::Result<(), AcceptError> {
+        self.handle_connection(connection).await.map_err(|e| {
+            tracing::warn!("Status list request failed: {}", e);
+            AcceptError::from_err(std::io::Error::other(e.to_string()))
+        })
+    }
+}
+
+/// Fetches `issuer`'s status list
+pub async fn fetch_status_list(
+    endpoint: &Endpoint,
+    issuer: EndpointId,
+    known_version: Option<u64>,
+) -> Result<StatusListResponse> {
+    let connection = endpoint.connect(issuer, STATUS_LIST_ALPN).await?;
+    let (mut send, mut recv) = connection.open_bi().await?;
+    send.write_all(&serde_json::to_vec(&StatusListRequest { known_version })?).await?;
+    send.finish()?;
+    let response = recv.read_to_end(1024 * 1024).await?;
+    connection.close(1u8.into(), b"done");
+    Ok(serde_json::from_slice(&response)?)
+}
+
+/// A status list held by a verifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedStatusList {
+    pub list: SignedStatusList,
+    /// When the issuer last confirmed this version (RFC3339 format)
+    pub fetched_at: String,
+    /// Refetch after this time (RFC3339 format)
+    pub next_update: String,
+}
+
+impl CachedStatusList {
+    fn is_fresh(&self, now: chrono::DateTime<chrono::Utc>, max_age: Option<chrono::Duration>) -> bool {
+        let parse = |value: &str| chrono::DateTime::parse_from_rfc3339(value).map(|t| t.with_timezone(&chrono::Utc));
+        let (Ok(fetched_at), Ok(next_update)) = (parse(&self.fetched_at), parse(&self.next_update)) else {
+            return false;
+        };
+        now < next_update && max_age.is_none_or(|max_age| now - fetched_at < max_age)
+    }
+}
+
+/// Verifier-side cache of issuers' status lists.
+///
+/// Lists are fetched whole, so the issuer never learns which credential is being checked.
+#[derive(Debug, Clone)]
+pub struct StatusListCache {
+    endpoint: Endpoint,
+    /// Keyed by issuer node ID
+    lists: StoredMap<CachedStatusList>,
+}
+
+impl StatusListCache {
+    pub fn new(endpoint: Endpoint, lists: StoredMap<CachedStatusList>) -> Self {
+        Self { endpoint, lists }
+    }
+
+    /// Every cached list, fresh or not
+    pub async fn lists(&self) -> Vec<SignedStatusList> {
+        self.lists.values().await.into_iter().map(|cached| cached.list).collect()
+    }
+
+    /// Checks `status_index` in `issuer`'s list, refreshing the cached copy when stale.
+    ///
+    /// Returns the check outcome and the list version it was based on. If the
+    /// issuer is unreachable a stale cached copy is still used.
+    pub async fn check(
+        &self,
+        issuer: EndpointId,
+        status_index: u32,
+        max_age: Option<chrono::Duration>,
+    ) -> (StatusCheck, Option<u64>) {
+        let now = chrono::Utc::now();
+        let cached = self.lists.get(&issuer.to_string()).await;
+        let cached = match cached {
+            Some(cached) if cached.is_fresh(now, max_age) => Some(cached),
+            cached => match self.refresh(issuer, cached.clone()).await {
+                Ok(refreshed) => Some(refreshed),
+                Err(e) => {
+                    tracing::warn!("Failed to refresh status list of {}: {}", issuer, e);
+                    cached
+                }
+            },
+        };
+
+        let Some(cached) = cached else {
+            return (StatusCheck::Unavailable, None);
+        };
+        match cached.list.list.is_revoked(status_index) {
+            Ok(true) => (StatusCheck::Revoked, Some(cached.list.list.version)),
+            Ok(false) => (StatusCheck::Active, Some(cached.list.list.version)),
+            Err(e) => {
+                tracing::error!("Cached status list of {} is corrupt: {}", issuer, e);
+                (StatusCheck::Unavailable, None)
+            }
+        }
+    }
+
+    async fn refresh(&self, issuer: EndpointId, cached: Option<CachedStatusList>) -> Result<CachedStatusList> {
+        let known_version = cached.as_ref().map(|cached| cached.list.list.version);
+        let fetched_at = chrono::Utc::now().to_rfc3339();
+        let refreshed = match fetch_status_list(&self.endpoint, issuer, known_version).await? {
+            StatusListResponse::List { list } => {
+                if list.list.issuer_node_id != issuer || !list.verify()? {
+                    return Err(anyhow::anyhow!("status list is not signed by {}", issuer));
+                }
+                if known_version.is_some_and(|known| list.list.version < known) {
+                    return Err(anyhow::anyhow!(
+                        "status list version {} is older than cached version {}",
+                        list.list.version,
+                        known_version.unwrap_or_default()
+                    ));
+                }
+                info!("Fetched status list version {} from {}", list.list.version, issuer);
+                let next_update = list.list.next_update.clone();
+                CachedStatusList { list, fetched_at, next_update }
+            }
+            StatusListResponse::NotModified { version, next_update } => {
+                let cached = cached
+                    .filter(|cached| cached.list.list.version == version)
+                    .ok_or_else(|| anyhow::anyhow!("issuer confirmed version {} we do not hold", version))?;
+                CachedStatusList { fetched_at, next_update, ..cached }
+            }
+        };
+
+        self.lists.insert(issuer.to_string(), refreshed.clone()).await?;
+        Ok(refreshed)
+    }
+}
@@ -0,0 +1,225 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::{Collection, StorageError, Store};
+
+/// Extension of committed record files
+const RECORD_EXTENSION: &str = "json";
+
+/// Longest key stored under its hex encoding; longer ones would exceed the
+/// 255-byte file name limit of common file systems
+const MAX_PLAIN_KEY_LEN: usize = 120;
+
+/// File name prefix of records whose key is too long to be the name. Such a
+/// file is named after the key's blake3 hash and starts with the hex-encoded
+/// key on its own line.
+const HASHED_PREFIX: &str = "h-";
+
+/// File in the data directory locked for as long as a store has it open
+const LOCK_FILE: &str = ".lock";
+
+/// Store that keeps one JSON file per record under a data directory:
+/// `<root>/<collection>/<hex(key)>.json`, or `<root>/<collection>/h-<blake3(key)>.json`
+/// for long keys.
+///
+/// Records are written to a temporary file, synced and renamed into place, and
+/// the directory is synced after, so a crash never leaves a truncated or lost
+/// record behind. Record files are readable by their owner only, as they hold
+/// salary data.
+///
+/// Only one process may have a data directory open: each caches every record in
+/// memory and would silently overwrite the other's writes.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    root: PathBuf,
+    /// Holds the exclusive lock on the data directory until the last clone drops
+    _lock: Arc<File>,
+}
+
+impl FileStore {
+    /// Opens (creating if needed) the data directory at `root`, failing with
+    /// [`StorageError::Locked`] while another process has it open
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        let lock = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(root.join(LOCK_FILE))?;
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Err(StorageError::Locked(root)),
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+        Ok(Self { root, _lock: Arc::new(lock) })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn collection_dir(&self, collection: Collection) -> PathBuf {
+        self.root.join(collection.as_str())
+    }
+
+    // Keys are hex-encoded so any string is a safe file name
+    fn record_path(&self, collection: Collection, key: &str) -> PathBuf {
+        let stem = if key.len() > MAX_PLAIN_KEY_LEN {
+            format!("{}{}", HASHED_PREFIX, blake3::hash(key.as_bytes()).to_hex())
+        } else {
+            hex::encode(key)
+        };
+        self.collection_dir(collection).join(format!("{}.{}", stem, RECORD_EXTENSION))
+    }
+
+    fn create_collection_dir(&self, collection: Collection) -> Result<PathBuf, StorageError> {
+        let dir = self.collection_dir(collection);
+        if !dir.is_dir() {
+            let mut builder = fs::DirBuilder::new();
+            builder.recursive(true);
+            #[cfg(unix)]
+            std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+            builder.create(&dir)?;
+            sync_dir(&self.root)?;
+        }
+        Ok(dir)
+    }
+}
+
+/// Reads a record file back into its key and value
+fn read_record(path: &Path) -> Result<Option<(String, Vec<u8>)>, StorageError> {
+    let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return Ok(None);
+    };
+    let contents = fs::read(path)?;
+    if stem.starts_with(HASHED_PREFIX) {
+        let Some(newline) = contents.iter().position(|&byte| byte == b'\n') else {
+            return Ok(None);
+        };
+        let key = hex::decode(&contents[..newline]).ok().and_then(|key| String::from_utf8(key).ok());
+        return Ok(key.map(|key| (key, contents[newline + 1..].to_vec())));
+    }
+    let key = hex::decode(stem).ok().and_then(|key| String::from_utf8(key).ok());
+    Ok(key.map(|key| (key, contents)))
+}
+
+/// Makes the creation, renaming or removal of entries in `dir` durable
+fn sync_dir(dir: &Path) -> Result<(), StorageError> {
+    // Directories cannot be opened for syncing on every platform
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+impl Store for FileStore {
+    fn load(&self, collection: Collection) -> Result<Vec<(String, Vec<u8>)>, StorageError> {
+        let entries = match fs::read_dir(self.collection_dir(collection)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut records = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(RECORD_EXTENSION) {
+                continue; // Leftover temporary file
+            }
+            match read_record(&path)? {
+                Some(record) => records.push(record),
+                None => tracing::warn!("Ignoring unexpected file in data directory: {}", path.display()),
+            }
+        }
+        Ok(records)
+    }
+
+    fn put(&self, collection: Collection, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        let dir = self.create_collection_dir(collection)?;
+        let path = self.record_path(collection, key);
+        let tmp_path = path.with_extension("tmp");
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path)?;
+        if key.len() > MAX_PLAIN_KEY_LEN {
+            file.write_all(hex::encode(key).as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+        file.write_all(value)?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp_path, &path)?;
+        sync_dir(&dir)
+    }
+
+    fn delete(&self, collection: Collection, key: &str) -> Result<(), StorageError> {
+        match fs::remove_file(self.record_path(collection, key)) {
+            Ok(()) => sync_dir(&self.collection_dir(collection)),
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let root = std::env::temp_dir().join(format!("trust-triangle-store-{}", uuid::Uuid::new_v4()));
+        let store = FileStore::open(&root).unwrap();
+
+        // Keys that are not valid file names are still stored safely
+        store.put(Collection::TrustedIssuers, "a/b:c", b"1").unwrap();
+        store.put(Collection::TrustedIssuers, "d", b"2").unwrap();
+        store.put(Collection::TrustedIssuers, "d", b"3").unwrap();
+        store.delete(Collection::TrustedIssuers, "missing").unwrap();
+
+        let mut records = store.load(Collection::TrustedIssuers).unwrap();
+        records.sort();
+        assert_eq!(
+            records,
+            vec![("a/b:c".to_string(), b"1".to_vec()), ("d".to_string(), b"3".to_vec())]
+        );
+
+        store.delete(Collection::TrustedIssuers, "d").unwrap();
+        assert_eq!(store.load(Collection::TrustedIssuers).unwrap().len(), 1);
+        assert!(store.load(Collection::Revocations).unwrap().is_empty());
+
+        // Keys too long for a file name are hashed, and kept inside the record
+        let long_key = "p".repeat(400);
+        store.put(Collection::VerifiedCredentials, &long_key, b"{}").unwrap();
+        assert_eq!(
+            store.load(Collection::VerifiedCredentials).unwrap(),
+            vec![(long_key.clone(), b"{}".to_vec())]
+        );
+        store.delete(Collection::VerifiedCredentials, &long_key).unwrap();
+        assert!(store.load(Collection::VerifiedCredentials).unwrap().is_empty());
+
+        // Records are readable by their owner only
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let path = store.record_path(Collection::TrustedIssuers, "a/b:c");
+            assert_eq!(fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        // A second process cannot open the directory until the first lets go
+        assert!(matches!(FileStore::open(&root), Err(StorageError::Locked(_))));
+        drop(store);
+        let store = FileStore::open(&root).unwrap();
+        assert_eq!(store.load(Collection::TrustedIssuers).unwrap().len(), 1);
+
+        drop(store);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+use std::sync::{Arc, Mutex};
+
+use js_sys::{Array, Promise, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbFactory, IdbRequest, IdbTransaction, IdbTransactionMode};
+
+use super::{Collection, MemoryStore, StorageError, Store};
+
+/// Schema version; bump when object stores are added
+const DB_VERSION: u32 = 10;
+
+/// Database name used when the caller does not pick one
+pub const DEFAULT_DB_NAME: &str = "trust-triangle";
+
+#[derive(Debug)]
+enum WriteOp {
+    Put { collection: Collection, key: String, value: Vec<u8> },
+    Delete { collection: Collection, key: String },
+    /// Signals once every earlier write was handled
+    Flush(async_channel::Sender<()>),
+}
+
+/// Browser store persisting each [`Collection`] to an IndexedDB object store.
+///
+/// IndexedDB is asynchronous while [`Store`] is not, so the database is read
+/// once in [`IndexedDbStore::open`] and writes are applied in order by a
+/// background task. `put` and `delete` return before the browser commits; a
+/// write the browser fails is returned by [`IndexedDbStore::flush`] and by every
+/// later `put` and `delete`, and no later write is applied, as memory and the
+/// database no longer agree until the store is reopened.
+#[derive(Debug)]
+pub struct IndexedDbStore {
+    name: String,
+    snapshot: MemoryStore,
+    writes: async_channel::Sender<WriteOp>,
+    /// First write the browser failed to commit
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+impl IndexedDbStore {
+    /// Opens (creating if needed) the database `name` and loads its contents
+    pub async fn open(name: &str) -> Result<Self, StorageError> {
+        let db = open_database(name).await?;
+
+        let snapshot = MemoryStore::default();
+        for (collection, records) in load_all(&db).await? {
+            for (key, value) in records {
+                snapshot.put(collection, &key, &value)?;
+            }
+        }
+
+        let (writes, receiver) = async_channel::unbounded();
+        let failure = Arc::new(Mutex::new(None));
+        wasm_bindgen_futures::spawn_local(run_writer(name.to_string(), db, receiver, failure.clone()));
+        tracing::info!("IndexedDB store {} opened", name);
+        Ok(Self {
+            name: name.to_string(),
+            snapshot,
+            writes,
+            failure,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Waits until every write made so far is committed, failing if any write
+    /// since the store was opened was not
+    pub async fn flush(&self) -> Result<(), StorageError> {
+        let (done, flushed) = async_channel::bounded(1);
+        self.enqueue(WriteOp::Flush(done))?;
+        flushed.recv().await.map_err(|_| self.closed())?;
+        self.check()
+    }
+
+    /// Fails once a write was lost
+    fn check(&self) -> Result<(), StorageError> {
+        match self.failure.lock().expect("failure lock poisoned").as_ref() {
+            Some(error) => Err(StorageError::Backend(format!(
+                "IndexedDB store {} failed to save an earlier change, so no further changes are saved until it is reopened: {}",
+                self.name, error
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn enqueue(&self, op: WriteOp) -> Result<(), StorageError> {
+        self.check()?;
+        self.writes.try_send(op).map_err(|_| self.closed())
+    }
+
+    fn closed(&self) -> StorageError {
+        StorageError::Backend(format!("IndexedDB store {} is closed", self.name))
+    }
+}
+
+impl Store for IndexedDbStore {
+    fn load(&self, collection: Collection) -> Result<Vec<(String, Vec<u8>)>, StorageError> {
+        self.snapshot.load(collection)
+    }
+
+    fn put(&self, collection: Collection, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        self.enqueue(WriteOp::Put {
+            collection,
+            key: key.to_string(),
+            value: value.to_vec(),
+        })?;
+        self.snapshot.put(collection, key, value)
+    }
+
+    fn delete(&self, collection: Collection, key: &str) -> Result<(), StorageError> {
+        self.enqueue(WriteOp::Delete {
+            collection,
+            key: key.to_string(),
+        })?;
+        self.snapshot.delete(collection, key)
+    }
+}
+
+async fn open_database(name: &str) -> Result<IdbDatabase, StorageError> {
+    // Works in both window and worker contexts
+    let factory = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("indexedDB"))
+        .ok()
+        .filter(|factory| !factory.is_undefined() && !factory.is_null())
+        .ok_or_else(|| StorageError::Backend("IndexedDB is not available".to_string()))?
+        .unchecked_into::<IdbFactory>();
+    let request = factory.open_with_u32(name, DB_VERSION).map_err(js_error)?;
+
+    let on_upgrade = {
+        let request = request.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            let Ok(db) = request.result() else { return };
+            let db = db.unchecked_into::<IdbDatabase>();
+            for collection in Collection::ALL {
+                if !db.object_store_names().contains(collection.as_str())
+                    && let Err(e) = db.create_object_store(collection.as_str())
+                {
+                    tracing::error!("Failed to create object store {}: {:?}", collection.as_str(), e);
+                }
+            }
+        })
+    };
+    request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+    let db = request_result(&request).await;
+    request.set_onupgradeneeded(None);
+    Ok(db?.unchecked_into())
+}
+
+async fn load_all(db: &IdbDatabase) -> Result<Vec<(Collection, Vec<(String, Vec<u8>)>)>, StorageError> {
+    let names: Array = Collection::ALL
+        .iter()
+        .map(|collection| JsValue::from_str(collection.as_str()))
+        .collect();
+    let transaction = db
+        .transaction_with_str_sequence_and_mode(&names, IdbTransactionMode::Readonly)
+        .map_err(js_error)?;
+
+    // Issue every request before awaiting any so the transaction stays active
+    let mut requests = Vec::new();
+    for collection in Collection::ALL {
+        let store = transaction.object_store(collection.as_str()).map_err(js_error)?;
+        let keys = store.get_all_keys().map_err(js_error)?;
+        let values = store.get_all().map_err(js_error)?;
+        requests.push((collection, keys, values));
+    }
+
+    let mut collections = Vec::new();
+    for (collection, keys, values) in requests {
+        let keys: Array = request_result(&keys).await?.unchecked_into();
+        let values: Array = request_result(&values).await?.unchecked_into();
+        let records = keys
+            .iter()
+            .zip(values.iter())
+            .filter_map(|(key, value)| Some((key.as_string()?, Uint8Array::new(&value).to_vec())))
+            .collect();
+        collections.push((collection, records));
+    }
+    Ok(collections)
+}
+
+async fn run_writer(
+    name: String,
+    db: IdbDatabase,
+    receiver: async_channel::Receiver<WriteOp>,
+    failure: Arc<Mutex<Option<String>>>,
+) {
+    while let Ok(op) = receiver.recv().await {
+        let (collection, record) = match &op {
+            WriteOp::Flush(done) => {
+                done.try_send(()).ok();
+                continue;
+            }
+            WriteOp::Put { collection, key, .. } | WriteOp::Delete { collection, key } => {
+                (*collection, format!("{} {}", collection.as_str(), key))
+            }
+        };
+        // Writes after a lost one would leave the database matching neither state
+        if failure.lock().expect("failure lock poisoned").is_some() {
+            continue;
+        }
+        if let Err(e) = apply(&db, collection, &op).await {
+            tracing::error!("Failed to persist {} to IndexedDB store {}: {}", record, name, e);
+            failure.lock().expect("failure lock poisoned").get_or_insert(format!("{}: {}", record, e));
+        }
+    }
+}
+
+/// Applies `op` in its own transaction, returning once the transaction committed
+async fn apply(db: &IdbDatabase, collection: Collection, op: &WriteOp) -> Result<(), StorageError> {
+    let transaction = db
+        .transaction_with_str_and_mode(collection.as_str(), IdbTransactionMode::Readwrite)
+        .map_err(js_error)?;
+    let store = transaction.object_store(collection.as_str()).map_err(js_error)?;
+    match op {
+        WriteOp::Put { key, value, .. } => {
+            store.put_with_key(&Uint8Array::from(value.as_slice()), &JsValue::from_str(key))
+        }
+        WriteOp::Delete { key, .. } => store.delete(&JsValue::from_str(key)),
+        WriteOp::Flush(_) => unreachable!("flushes are not applied"),
+    }
+    .map_err(js_error)?;
+    // A failed request aborts the transaction, and a commit can fail after the request succeeded
+    transaction_result(&transaction).await
+}
+
+/// Resolves once `transaction` committed, failing if it was aborted
+async fn transaction_result(transaction: &IdbTransaction) -> Result<(), StorageError> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_complete = Closure::once_into_js(move || {
+            resolve.call0(&JsValue::UNDEFINED).ok();
+        });
+        let on_abort = {
+            let transaction = transaction.clone();
+            Closure::once_into_js(move || {
+                let error = transaction.error().map(JsValue::from).unwrap_or(JsValue::UNDEFINED);
+                reject.call1(&JsValue::UNDEFINED, &error).ok();
+            })
+        };
+        transaction.set_oncomplete(Some(on_complete.unchecked_ref()));
+        transaction.set_onabort(Some(on_abort.unchecked_ref()));
+    });
+    JsFuture::from(promise).await.map_err(js_error)?;
+    Ok(())
+}
+
+/// Resolves once `request` succeeds, with its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, StorageError> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_success = {
+            let request = request.clone();
+            Closure::once_into_js(move || {
+                let result = request.result().unwrap_or(JsValue::UNDEFINED);
+                resolve.call1(&JsValue::UNDEFINED, &result).ok();
+            })
+        };
+        let on_error = {
+            let request = request.clone();
+            Closure::once_into_js(move || {
+                let error = request.error().ok().flatten().map(JsValue::from).unwrap_or(JsValue::UNDEFINED);
+                reject.call1(&JsValue::UNDEFINED, &error).ok();
+            })
+        };
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await.map_err(js_error)
+}
+
+fn js_error(value: JsValue) -> StorageError {
+    StorageError::Backend(format!("IndexedDB error: {:?}", value))
+}
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::{Collection, StorageError, Store};
+
+/// Volatile store; state is lost when the node stops
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    records: Mutex<HashMap<Collection, HashMap<String, Vec<u8>>>>,
+}
+
+impl Store for MemoryStore {
+    fn load(&self, collection: Collection) -> Result<Vec<(String, Vec<u8>)>, StorageError> {
+        let records = self.records.lock().expect("memory store poisoned");
+        Ok(records
+            .get(&collection)
+            .map(|entries| entries.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default())
+    }
+
+    fn put(&self, collection: Collection, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        let mut records = self.records.lock().expect("memory store poisoned");
+        records
+            .entry(collection)
+            .or_default()
+            .insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, collection: Collection, key: &str) -> Result<(), StorageError> {
+        let mut records = self.records.lock().expect("memory store poisoned");
+        if let Some(entries) = records.get_mut(&collection) {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+}
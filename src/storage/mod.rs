@@ -0,0 +1,353 @@
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+pub mod memory;
+#[cfg(not(target_family = "wasm"))]
+pub mod file;
+#[cfg(target_family = "wasm")]
+pub mod indexed_db;
+
+pub use memory::MemoryStore;
+#[cfg(not(target_family = "wasm"))]
+pub use file::FileStore;
+#[cfg(target_family = "wasm")]
+pub use indexed_db::IndexedDbStore;
+
+/// Errors raised by storage backends
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to (de)serialize stored record: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+
+    #[error("Data directory {0} is in use by another process, such as a running daemon")]
+    Locked(std::path::PathBuf),
+}
+
+/// Groups of records kept by a [`Peer`](crate::peer_node::Peer)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Collection {
+    PendingRequests,
+    TrustedIssuers,
+    ReceivedCredentials,
+    VerifiedCredentials,
+    IssuedCredentials,
+    Revocations,
+    /// This issuer's own status list
+    StatusList,
+    /// Status lists fetched from other issuers
+    StatusListCache,
+    /// Single-record settings such as the verification policy
+    Settings,
+    /// Wrapped data keys of the encrypted wallet
+    WalletKeys,
+    /// Presentation requests received from verifiers, awaiting holder consent
+    PresentationRequests,
+    /// Presentation requests this verifier sent to employees
+    SentPresentationRequests,
+    /// Selective disclosures this verifier checked
+    VerifiedDisclosures,
+    /// Net salary threshold proofs this verifier checked
+    VerifiedThresholdProofs,
+    /// Issuer key rotations, keyed by the retired key
+    KeyRotations,
+    /// Bindings of credential signing keys to issuer nodes
+    KeyBindings,
+    /// Versions of issuers' approval policies, keyed by issuer and version
+    ApprovalPolicies,
+    /// Retirements of credential signing keys, keyed like their bindings
+    KeyUnbindings,
+    /// Bundled presentations this verifier checked, keyed by presenter and presentation ID
+    VerifiedPresentations,
+}
+
+impl Collection {
+    pub const ALL: [Collection; 19] = [
+        Collection::PendingRequests,
+        Collection::TrustedIssuers,
+        Collection::ReceivedCredentials,
+        Collection::VerifiedCredentials,
+        Collection::IssuedCredentials,
+        Collection::Revocations,
+        Collection::StatusList,
+        Collection::StatusListCache,
+        Collection::Settings,
+        Collection::WalletKeys,
+        Collection::PresentationRequests,
+        Collection::SentPresentationRequests,
+        Collection::VerifiedDisclosures,
+        Collection::VerifiedThresholdProofs,
+        Collection::KeyRotations,
+        Collection::KeyBindings,
+        Collection::ApprovalPolicies,
+        Collection::KeyUnbindings,
+        Collection::VerifiedPresentations,
+    ];
+
+    /// Stable name used by backends for directories, object stores, etc.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Collection::PendingRequests => "pending_requests",
+            Collection::TrustedIssuers => "trusted_issuers",
+            Collection::ReceivedCredentials => "received_credentials",
+            Collection::VerifiedCredentials => "verified_credentials",
+            Collection::IssuedCredentials => "issued_credentials",
+            Collection::Revocations => "revocations",
+            Collection::StatusList => "status_list",
+            Collection::StatusListCache => "status_list_cache",
+            Collection::Settings => "settings",
+            Collection::WalletKeys => "wallet_keys",
+            Collection::PresentationRequests => "presentation_requests",
+            Collection::SentPresentationRequests => "sent_presentation_requests",
+            Collection::VerifiedDisclosures => "verified_disclosures",
+            Collection::VerifiedThresholdProofs => "verified_threshold_proofs",
+            Collection::KeyRotations => "key_rotations",
+            Collection::KeyBindings => "key_bindings",
+            Collection::ApprovalPolicies => "approval_policies",
+            Collection::KeyUnbindings => "key_unbindings",
+            Collection::VerifiedPresentations => "verified_presentations",
+        }
+    }
+}
+
+/// Byte-level key/value storage backend for peer state.
+///
+/// Calls are synchronous: every collection is loaded once when the node
+/// starts and then served from memory, so backends only see writes. A backend
+/// may persist writes after returning, as `IndexedDbStore` does in the browser;
+/// it must then fail every later write once one was lost, so a lost write
+/// surfaces as an error rather than as state that vanishes on restart.
+pub trait Store: fmt::Debug + Send + Sync {
+    /// Loads every record of `collection`
+    fn load(&self, collection: Collection) -> Result<Vec<(String, Vec<u8>)>, StorageError>;
+
+    /// Inserts or replaces the record stored under `key`
+    fn put(&self, collection: Collection, key: &str, value: &[u8]) -> Result<(), StorageError>;
+
+    /// Removes the record stored under `key`, if any
+    fn delete(&self, collection: Collection, key: &str) -> Result<(), StorageError>;
+}
+
+/// Typed map backed by a [`Store`]: reads are served from memory, writes go
+/// to the store before they become visible.
+pub struct StoredMap<V> {
+    store: Arc<dyn Store>,
+    collection: Collection,
+    entries: Arc<RwLock<HashMap<String, V>>>,
+}
+
+impl<V> Clone for StoredMap<V> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            collection: self.collection,
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<V> fmt::Debug for StoredMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredMap")
+            .field("collection", &self.collection)
+            .finish()
+    }
+}
+
+impl<V: Serialize + DeserializeOwned + Clone> StoredMap<V> {
+    /// Loads `collection` from `store`
+    pub fn open(store: Arc<dyn Store>, collection: Collection) -> Result<Self, StorageError> {
+        let mut entries = HashMap::new();
+        for (key, bytes) in store.load(collection)? {
+            entries.insert(key, serde_json::from_slice(&bytes)?);
+        }
+        Ok(Self {
+            store,
+            collection,
+            entries: Arc::new(RwLock::new(entries)),
+        })
+    }
+
+    pub async fn get(&self, key: &str) -> Option<V> {
+        self.entries.read().await.get(key).cloned()
+    }
+
+    pub async fn contains_key(&self, key: &str) -> bool {
+        self.entries.read().await.contains_key(key)
+    }
+
+    pub async fn values(&self) -> Vec<V> {
+        self.entries.read().await.values().cloned().collect()
+    }
+
+    /// Inserts `value`, returning the previous value stored under `key`
+    pub async fn insert(&self, key: String, value: V) -> Result<Option<V>, StorageError> {
+        let bytes = serde_json::to_vec(&value)?;
+        let mut entries = self.entries.write().await;
+        self.store.put(self.collection, &key, &bytes)?;
+        Ok(entries.insert(key, value))
+    }
+
+    /// Removes and returns the value stored under `key`
+    pub async fn remove(&self, key: &str) -> Result<Option<V>, StorageError> {
+        let mut entries = self.entries.write().await;
+        if !entries.contains_key(key) {
+            return Ok(None);
+        }
+        self.store.delete(self.collection, key)?;
+        Ok(entries.remove(key))
+    }
+
+    /// Applies `f` to the value under `key` and persists the result.
+    ///
+    /// The map stays locked while `f` runs, so check-then-modify sequences are
+    /// atomic. If `f` fails, nothing is changed. Returns `None` if `key` is absent.
+    pub async fn update<R, E>(&self, key: &str, f: impl FnOnce(&mut V) -> Result<R, E>) -> Result<Option<R>, E>
+    where
+        E: From<StorageError>,
+    {
+        let mut entries = self.entries.write().await;
+        let Some(current) = entries.get(key) else {
+            return Ok(None);
+        };
+        let mut updated = current.clone();
+        let result = f(&mut updated)?;
+        let bytes = serde_json::to_vec(&updated).map_err(StorageError::from)?;
+        self.store.put(self.collection, key, &bytes)?;
+        entries.insert(key.to_string(), updated);
+        Ok(Some(result))
+    }
+}
+
+/// Single typed record backed by a [`Store`], defaulted when absent
+pub struct StoredValue<V> {
+    store: Arc<dyn Store>,
+    collection: Collection,
+    key: &'static str,
+    value: Arc<RwLock<V>>,
+}
+
+impl<V> Clone for StoredValue<V> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            collection: self.collection,
+            key: self.key,
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<V> fmt::Debug for StoredValue<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredValue")
+            .field("collection", &self.collection)
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl<V: Serialize + DeserializeOwned + Clone + Default> StoredValue<V> {
+    /// Loads the record stored under `key` in `collection`
+    pub fn open(store: Arc<dyn Store>, collection: Collection, key: &'static str) -> Result<Self, StorageError> {
+        let value = match store.load(collection)?.into_iter().find(|(k, _)| k == key) {
+            Some((_, bytes)) => serde_json::from_slice(&bytes)?,
+            None => V::default(),
+        };
+        Ok(Self {
+            store,
+            collection,
+            key,
+            value: Arc::new(RwLock::new(value)),
+        })
+    }
+
+    pub async fn read(&self) -> RwLockReadGuard<'_, V> {
+        self.value.read().await
+    }
+
+    pub async fn get(&self) -> V {
+        self.value.read().await.clone()
+    }
+
+    pub async fn set(&self, value: V) -> Result<(), StorageError> {
+        self.update(|current| {
+            *current = value;
+            Ok::<_, StorageError>(())
+        })
+        .await
+    }
+
+    /// Applies `f` and persists the result; if `f` fails nothing is changed
+    pub async fn update<R, E>(&self, f: impl FnOnce(&mut V) -> Result<R, E>) -> Result<R, E>
+    where
+        E: From<StorageError>,
+    {
+        let mut value = self.value.write().await;
+        let mut updated = value.clone();
+        let result = f(&mut updated)?;
+        let bytes = serde_json::to_vec(&updated).map_err(StorageError::from)?;
+        self.store.put(self.collection, self.key, &bytes)?;
+        *value = updated;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n0_future::future::block_on;
+
+    #[test]
+    fn test_stored_map_persists_writes() {
+        block_on(async {
+            let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+            let map = StoredMap::<u32>::open(store.clone(), Collection::PendingRequests).unwrap();
+
+            assert_eq!(map.insert("a".to_string(), 1).await.unwrap(), None);
+            assert_eq!(map.insert("b".to_string(), 2).await.unwrap(), None);
+            map.update("a", |value| {
+                *value += 10;
+                Ok::<_, StorageError>(())
+            })
+            .await
+            .unwrap();
+            map.remove("b").await.unwrap();
+
+            // A failed update leaves the record untouched
+            let failed = map
+                .update("a", |value| {
+                    *value = 0;
+                    Err::<(), _>(StorageError::Backend("rejected".to_string()))
+                })
+                .await;
+            assert!(failed.is_err());
+            assert_eq!(map.get("a").await, Some(11));
+
+            let reopened = StoredMap::<u32>::open(store, Collection::PendingRequests).unwrap();
+            assert_eq!(reopened.get("a").await, Some(11));
+            assert!(!reopened.contains_key("b").await);
+        });
+    }
+
+    #[test]
+    fn test_stored_value_defaults_and_persists() {
+        block_on(async {
+            let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+            let value = StoredValue::<Vec<u8>>::open(store.clone(), Collection::Settings, "bytes").unwrap();
+            assert!(value.get().await.is_empty());
+
+            value.set(vec![1, 2, 3]).await.unwrap();
+            let reopened = StoredValue::<Vec<u8>>::open(store, Collection::Settings, "bytes").unwrap();
+            assert_eq!(reopened.get().await, vec![1, 2, 3]);
+        });
+    }
+}
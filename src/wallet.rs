@@ -0,0 +1,576 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    Key, XChaCha20Poly1305, XNonce,
+    aead::{Aead, KeyInit, Payload},
+};
+use iroh::SecretKey;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::peer_node::ReceivedCredentialResponse;
+use crate::storage::{Collection, StorageError, Store};
+
+/// Key-derivation context for the node-key key-encryption key
+const NODE_KEY_CONTEXT: &str = "trust-triangle wallet key-encryption key v1";
+
+/// Record names in [`Collection::WalletKeys`]
+const NODE_WRAPPED_KEY: &str = "node";
+const PASSPHRASE_WRAPPED_KEY: &str = "passphrase";
+
+/// Format of wallets written before every record was encrypted, which may
+/// hold plaintext records
+const LEGACY_FORMAT: u32 = 0;
+
+/// Wallet format in which every record is encrypted. Recorded in the wrapped
+/// data key, so it cannot be stripped without the key-encryption key.
+const SEALED_RECORDS_FORMAT: u32 = 1;
+
+/// Errors raised by the encrypted wallet
+#[derive(Debug, thiserror::Error)]
+pub enum WalletError {
+    #[error("Wallet is locked")]
+    Locked,
+
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+
+    #[error("Wallet is protected by a passphrase")]
+    PassphraseRequired,
+
+    #[error("Wallet cryptography error: {0}")]
+    Crypto(String),
+
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// How the wallet's data key is protected at rest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyProtection {
+    /// Wrapped with a key derived from the node secret key; unlocked at startup
+    NodeKey,
+    /// Wrapped with a key derived from a user passphrase (Argon2id)
+    Passphrase,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletStatus {
+    pub locked: bool,
+    pub protection: KeyProtection,
+}
+
+/// Argon2id parameters stored next to a passphrase-wrapped key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PassphraseKdf {
+    salt: Vec<u8>,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl PassphraseKdf {
+    fn generate() -> Self {
+        let params = Params::default();
+        Self {
+            salt: random_bytes::<16>().to_vec(),
+            m_cost: params.m_cost(),
+            t_cost: params.t_cost(),
+            p_cost: params.p_cost(),
+        }
+    }
+
+    fn derive(&self, passphrase: &str) -> Result<[u8; 32], WalletError> {
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32)).map_err(crypto_error)?;
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &self.salt, &mut key)
+            .map_err(crypto_error)?;
+        Ok(key)
+    }
+}
+
+/// Data key encrypted under a key-encryption key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WrappedKey {
+    /// Present when the key-encryption key comes from a passphrase
+    kdf: Option<PassphraseKdf>,
+    /// Wallet format, bound as associated data
+    #[serde(default)]
+    format: u32,
+    sealed: Sealed,
+}
+
+/// AEAD ciphertext with its nonce
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl Sealed {
+    fn seal(key: &[u8; 32], aad: &[u8], plaintext: &[u8]) -> Result<Self, WalletError> {
+        let nonce = random_bytes::<24>();
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key))
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+            .map_err(crypto_error)?;
+        Ok(Self {
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Returns `None` if the key is wrong or the data was tampered with
+    fn open(&self, key: &[u8; 32], aad: &[u8]) -> Option<Vec<u8>> {
+        if self.nonce.len() != 24 {
+            return None;
+        }
+        XChaCha20Poly1305::new(Key::from_slice(key))
+            .decrypt(
+                XNonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad,
+                },
+            )
+            .ok()
+    }
+}
+
+#[derive(Debug)]
+enum WalletState {
+    Locked,
+    Unlocked {
+        data_key: [u8; 32],
+        credentials: HashMap<String, ReceivedCredentialResponse>,
+    },
+}
+
+/// Employee credential wallet, encrypted at rest with XChaCha20-Poly1305.
+///
+/// Records are encrypted under a random data key, which is itself stored
+/// wrapped either by a key derived from the node secret key or by a
+/// passphrase. While locked, neither the data key nor any plaintext is held.
+#[derive(Debug, Clone)]
+pub struct Wallet {
+    store: Arc<dyn Store>,
+    node_kek: [u8; 32],
+    state: Arc<RwLock<WalletState>>,
+}
+
+impl Wallet {
+    /// Opens the wallet in `store`, creating a data key on first use.
+    ///
+    /// Wallets protected by the node key are unlocked right away; passphrase
+    /// protected wallets start locked.
+    pub fn open(store: Arc<dyn Store>, secret_key: &SecretKey) -> Result<Self, WalletError> {
+        let node_kek = blake3::derive_key(NODE_KEY_CONTEXT, &secret_key.to_bytes());
+        let mut wallet = Self {
+            store,
+            node_kek,
+            state: Arc::new(RwLock::new(WalletState::Locked)),
+        };
+
+        let state = match wallet.wrapped_key(NODE_WRAPPED_KEY)? {
+            Some(wrapped) => {
+                let data_key = unwrap_key(&wrapped, &node_kek).ok_or(WalletError::Crypto(
+                    "wallet key is not wrapped with this node key".to_string(),
+                ))?;
+                wallet.decrypt_all(NODE_WRAPPED_KEY, wrapped, data_key, &node_kek)?
+            }
+            None if wallet.wrapped_key(PASSPHRASE_WRAPPED_KEY)?.is_some() => WalletState::Locked,
+            None => {
+                // Records stored before the wallet was encrypted are migrated below
+                let data_key = random_bytes::<32>();
+                wallet.put_wrapped_key(NODE_WRAPPED_KEY, &data_key, &node_kek, None, LEGACY_FORMAT)?;
+                info!("Created wallet data key");
+                let wrapped = wallet.wrapped_key(NODE_WRAPPED_KEY)?.expect("just stored");
+                wallet.decrypt_all(NODE_WRAPPED_KEY, wrapped, data_key, &node_kek)?
+            }
+        };
+        wallet.state = Arc::new(RwLock::new(state));
+        Ok(wallet)
+    }
+
+    pub async fn status(&self) -> Result<WalletStatus, WalletError> {
+        let locked = matches!(*self.state.read().await, WalletState::Locked);
+        let protection = if self.wrapped_key(PASSPHRASE_WRAPPED_KEY)?.is_some() {
+            KeyProtection::Passphrase
+        } else {
+            KeyProtection::NodeKey
+        };
+        Ok(WalletStatus { locked, protection })
+    }
+
+    /// Forgets the data key and all decrypted credentials
+    pub async fn lock(&self) {
+        *self.state.write().await = WalletState::Locked;
+        info!("Wallet locked");
+    }
+
+    /// Unlocks with `passphrase`, or with the node key if `None`
+    pub async fn unlock(&self, passphrase: Option<&str>) -> Result<(), WalletError> {
+        let state = match passphrase {
+            Some(passphrase) => {
+                let wrapped = self
+                    .wrapped_key(PASSPHRASE_WRAPPED_KEY)?
+                    .ok_or_else(|| WalletError::Crypto("wallet has no passphrase".to_string()))?;
+                let kdf = wrapped
+                    .kdf
+                    .as_ref()
+                    .ok_or_else(|| WalletError::Crypto("passphrase key has no KDF parameters".to_string()))?;
+                let kek = kdf.derive(passphrase)?;
+                let data_key = unwrap_key(&wrapped, &kek).ok_or(WalletError::WrongPassphrase)?;
+                self.decrypt_all(PASSPHRASE_WRAPPED_KEY, wrapped, data_key, &kek)?
+            }
+            None => {
+                let wrapped = self
+                    .wrapped_key(NODE_WRAPPED_KEY)?
+                    .ok_or(WalletError::PassphraseRequired)?;
+                let data_key = unwrap_key(&wrapped, &self.node_kek)
+                    .ok_or_else(|| WalletError::Crypto("wallet key is not wrapped with this node key".to_string()))?;
+                self.decrypt_all(NODE_WRAPPED_KEY, wrapped, data_key, &self.node_kek)?
+            }
+        };
+
+        *self.state.write().await = state;
+        info!("Wallet unlocked");
+        Ok(())
+    }
+
+    /// Protects the data key with `passphrase`, or with the node key again if
+    /// `None`. The wallet must be unlocked.
+    pub async fn set_passphrase(&self, passphrase: Option<&str>) -> Result<(), WalletError> {
+        let state = self.state.read().await;
+        let WalletState::Unlocked { data_key, .. } = &*state else {
+            return Err(WalletError::Locked);
+        };
+
+        // Write the new wrapping before removing the old one
+        match passphrase {
+            Some(passphrase) => {
+                let kdf = PassphraseKdf::generate();
+                let kek = kdf.derive(passphrase)?;
+                self.put_wrapped_key(PASSPHRASE_WRAPPED_KEY, data_key, &kek, Some(kdf), SEALED_RECORDS_FORMAT)?;
+                self.store.delete(Collection::WalletKeys, NODE_WRAPPED_KEY)?;
+                info!("Wallet is now protected by a passphrase");
+            }
+            None => {
+                self.put_wrapped_key(NODE_WRAPPED_KEY, data_key, &self.node_kek, None, SEALED_RECORDS_FORMAT)?;
+                self.store.delete(Collection::WalletKeys, PASSPHRASE_WRAPPED_KEY)?;
+                info!("Wallet is now protected by the node key");
+            }
+        }
+        Ok(())
+    }
+
+    /// Wraps the data key for `new_secret_key`, the key this node rotates to.
+    /// Passphrase protected wallets are left as they are.
+    pub fn rewrap_for_node_key(&self, new_secret_key: &SecretKey) -> Result<(), WalletError> {
+        let new_kek = blake3::derive_key(NODE_KEY_CONTEXT, &new_secret_key.to_bytes());
+        if self.rewrap_node_key(&self.node_kek, &new_kek)? {
+            info!("Wallet key wrapped for the new node key");
+        }
+        Ok(())
+    }
+
+    /// Undoes [`Self::rewrap_for_node_key`] when the rotation to `new_secret_key`
+    /// could not be published.
+    pub fn undo_rewrap_for_node_key(&self, new_secret_key: &SecretKey) -> Result<(), WalletError> {
+        let new_kek = blake3::derive_key(NODE_KEY_CONTEXT, &new_secret_key.to_bytes());
+        if self.rewrap_node_key(&new_kek, &self.node_kek)? {
+            info!("Wallet key wrapped for this node key again");
+        }
+        Ok(())
+    }
+
+    fn rewrap_node_key(&self, from: &[u8; 32], to: &[u8; 32]) -> Result<bool, WalletError> {
+        let Some(wrapped) = self.wrapped_key(NODE_WRAPPED_KEY)? else {
+            return Ok(false);
+        };
+        let data_key = unwrap_key(&wrapped, from)
+            .ok_or_else(|| WalletError::Crypto("wallet key is not wrapped with this node key".to_string()))?;
+        self.put_wrapped_key(NODE_WRAPPED_KEY, &data_key, to, None, wrapped.format)?;
+        Ok(true)
+    }
+
+    /// Encrypts and stores a received credential response
+    pub async fn insert(&self, response: ReceivedCredentialResponse) -> Result<(), WalletError> {
+        let mut state = self.state.write().await;
+        let WalletState::Unlocked { data_key, credentials } = &mut *state else {
+            return Err(WalletError::Locked);
+        };
+        self.put_record(data_key, &response)?;
+        credentials.insert(response.request_id.clone(), response);
+        Ok(())
+    }
+
+    pub async fn get(&self, request_id: &str) -> Result<Option<ReceivedCredentialResponse>, WalletError> {
+        match &*self.state.read().await {
+            WalletState::Unlocked { credentials, .. } => Ok(credentials.get(request_id).cloned()),
+            WalletState::Locked => Err(WalletError::Locked),
+        }
+    }
+
+    pub async fn values(&self) -> Result<Vec<ReceivedCredentialResponse>, WalletError> {
+        match &*self.state.read().await {
+            WalletState::Unlocked { credentials, .. } => Ok(credentials.values().cloned().collect()),
+            WalletState::Locked => Err(WalletError::Locked),
+        }
+    }
+
+    fn wrapped_key(&self, name: &str) -> Result<Option<WrappedKey>, WalletError> {
+        let record = self
+            .store
+            .load(Collection::WalletKeys)?
+            .into_iter()
+            .find(|(key, _)| key == name);
+        match record {
+            Some((_, bytes)) => Ok(Some(serde_json::from_slice(&bytes).map_err(StorageError::from)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put_wrapped_key(
+        &self,
+        name: &str,
+        data_key: &[u8; 32],
+        kek: &[u8; 32],
+        kdf: Option<PassphraseKdf>,
+        format: u32,
+    ) -> Result<(), WalletError> {
+        let wrapped = WrappedKey {
+            kdf,
+            format,
+            sealed: Sealed::seal(kek, &wrapping_aad(name, format), data_key)?,
+        };
+        let bytes = serde_json::to_vec(&wrapped).map_err(StorageError::from)?;
+        self.store.put(Collection::WalletKeys, name, &bytes)?;
+        Ok(())
+    }
+
+    // The record key is bound as associated data so records cannot be swapped
+    fn put_record(&self, data_key: &[u8; 32], response: &ReceivedCredentialResponse) -> Result<(), WalletError> {
+        let plaintext = serde_json::to_vec(response).map_err(StorageError::from)?;
+        let sealed = Sealed::seal(data_key, response.request_id.as_bytes(), &plaintext)?;
+        let bytes = serde_json::to_vec(&sealed).map_err(StorageError::from)?;
+        self.store.put(Collection::ReceivedCredentials, &response.request_id, &bytes)?;
+        Ok(())
+    }
+
+    /// Decrypts every stored record with the data key unwrapped from `wrapped`.
+    ///
+    /// A legacy wallet may hold records from before it was encrypted: they are
+    /// encrypted once, then the key is wrapped again at
+    /// [`SEALED_RECORDS_FORMAT`] with `kek`. From then on an unencrypted record
+    /// is refused, as anyone able to write to the store could have planted it.
+    fn decrypt_all(
+        &self,
+        name: &str,
+        wrapped: WrappedKey,
+        data_key: [u8; 32],
+        kek: &[u8; 32],
+    ) -> Result<WalletState, WalletError> {
+        let migrate = wrapped.format < SEALED_RECORDS_FORMAT;
+        let mut credentials = HashMap::new();
+        for (key, bytes) in self.store.load(Collection::ReceivedCredentials)? {
+            let response: ReceivedCredentialResponse = match serde_json::from_slice::<Sealed>(&bytes) {
+                Ok(sealed) => {
+                    let plaintext = sealed
+                        .open(&data_key, key.as_bytes())
+                        .ok_or_else(|| WalletError::Crypto(format!("failed to decrypt wallet record {key}")))?;
+                    serde_json::from_slice(&plaintext).map_err(StorageError::from)?
+                }
+                Err(_) if migrate => {
+                    let response = serde_json::from_slice(&bytes).map_err(StorageError::from)?;
+                    self.put_record(&data_key, &response)?;
+                    info!("Encrypted plaintext wallet record {}", key);
+                    response
+                }
+                Err(_) => return Err(WalletError::Crypto(format!("wallet record {key} is not encrypted"))),
+            };
+            credentials.insert(key, response);
+        }
+        if migrate {
+            self.put_wrapped_key(name, &data_key, kek, wrapped.kdf, SEALED_RECORDS_FORMAT)?;
+            info!("Wallet records are all encrypted; unencrypted records are refused from now on");
+        }
+        Ok(WalletState::Unlocked { data_key, credentials })
+    }
+}
+
+fn unwrap_key(wrapped: &WrappedKey, kek: &[u8; 32]) -> Option<[u8; 32]> {
+    let name = if wrapped.kdf.is_some() { PASSPHRASE_WRAPPED_KEY } else { NODE_WRAPPED_KEY };
+    wrapped.sealed.open(kek, &wrapping_aad(name, wrapped.format))?.try_into().ok()
+}
+
+// Legacy wrapped keys bind only their record name
+fn wrapping_aad(name: &str, format: u32) -> Vec<u8> {
+    match format {
+        LEGACY_FORMAT => name.as_bytes().to_vec(),
+        format => format!("{name}/v{format}").into_bytes(),
+    }
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+fn crypto_error(e: impl std::fmt::Display) -> WalletError {
+    WalletError::Crypto(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStore;
+    use n0_future::future::block_on;
+
+    fn sample_response(request_id: &str) -> ReceivedCredentialResponse {
+        ReceivedCredentialResponse {
+            request_id: request_id.to_string(),
+            credential: None,
+            selective_credential: None,
+            threshold_credential: None,
+            verifiable_credential: None,
+            jws: None,
+            error: Some("gross salary 98765".to_string()),
+            received_at: "2025-01-01T00:00:00Z".to_string(),
+            issuer_node_id: SecretKey::generate(&mut rand::rng()).public(),
+        }
+    }
+
+    #[test]
+    fn test_wallet_is_encrypted_at_rest() {
+        block_on(async {
+            let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+            let secret_key = SecretKey::generate(&mut rand::rng());
+            let wallet = Wallet::open(store.clone(), &secret_key).unwrap();
+            wallet.insert(sample_response("request-1")).await.unwrap();
+
+            let (_, stored) = store.load(Collection::ReceivedCredentials).unwrap().remove(0);
+            assert!(!String::from_utf8_lossy(&stored).contains("98765"));
+
+            // Reopening with the node key unlocks automatically
+            let reopened = Wallet::open(store.clone(), &secret_key).unwrap();
+            assert_eq!(reopened.get("request-1").await.unwrap().unwrap().request_id, "request-1");
+
+            // Another node key cannot read it
+            let other_key = SecretKey::generate(&mut rand::rng());
+            assert!(Wallet::open(store, &other_key).is_err());
+        });
+    }
+
+    #[test]
+    fn test_wallet_passphrase_lock_and_unlock() {
+        block_on(async {
+            let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+            let secret_key = SecretKey::generate(&mut rand::rng());
+            let wallet = Wallet::open(store.clone(), &secret_key).unwrap();
+            wallet.insert(sample_response("request-1")).await.unwrap();
+            wallet.set_passphrase(Some("correct horse")).await.unwrap();
+
+            wallet.lock().await;
+            assert!(matches!(wallet.values().await, Err(WalletError::Locked)));
+            assert!(matches!(wallet.insert(sample_response("request-2")).await, Err(WalletError::Locked)));
+
+            // The node key alone no longer unlocks the wallet
+            let reopened = Wallet::open(store, &secret_key).unwrap();
+            let status = reopened.status().await.unwrap();
+            assert!(status.locked);
+            assert_eq!(status.protection, KeyProtection::Passphrase);
+            assert!(matches!(reopened.unlock(None).await, Err(WalletError::PassphraseRequired)));
+            assert!(matches!(reopened.unlock(Some("wrong")).await, Err(WalletError::WrongPassphrase)));
+
+            reopened.unlock(Some("correct horse")).await.unwrap();
+            assert_eq!(reopened.values().await.unwrap().len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_wallet_follows_node_key_rotation() {
+        block_on(async {
+            let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+            let old_key = SecretKey::generate(&mut rand::rng());
+            let new_key = SecretKey::generate(&mut rand::rng());
+            let wallet = Wallet::open(store.clone(), &old_key).unwrap();
+            wallet.insert(sample_response("request-1")).await.unwrap();
+            wallet.rewrap_for_node_key(&new_key).unwrap();
+
+            let reopened = Wallet::open(store.clone(), &new_key).unwrap();
+            assert_eq!(reopened.values().await.unwrap().len(), 1);
+            assert!(Wallet::open(store.clone(), &old_key).is_err());
+
+            wallet.undo_rewrap_for_node_key(&new_key).unwrap();
+            assert_eq!(Wallet::open(store.clone(), &old_key).unwrap().values().await.unwrap().len(), 1);
+            assert!(Wallet::open(store, &new_key).is_err());
+        });
+    }
+
+    #[test]
+    fn test_plaintext_records_are_migrated() {
+        block_on(async {
+            let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+            let response = sample_response("request-1");
+            store
+                .put(Collection::ReceivedCredentials, "request-1", &serde_json::to_vec(&response).unwrap())
+                .unwrap();
+
+            let secret_key = SecretKey::generate(&mut rand::rng());
+            let wallet = Wallet::open(store.clone(), &secret_key).unwrap();
+            assert_eq!(wallet.values().await.unwrap().len(), 1);
+            let (_, stored) = store.load(Collection::ReceivedCredentials).unwrap().remove(0);
+            assert!(serde_json::from_slice::<Sealed>(&stored).is_ok());
+            assert_eq!(wallet.wrapped_key(NODE_WRAPPED_KEY).unwrap().unwrap().format, SEALED_RECORDS_FORMAT);
+
+            // Only once: a plaintext record planted afterwards is refused
+            let forged = serde_json::to_vec(&sample_response("request-2")).unwrap();
+            store.put(Collection::ReceivedCredentials, "request-2", &forged).unwrap();
+            let error = Wallet::open(store.clone(), &secret_key).unwrap_err();
+            assert!(error.to_string().contains("is not encrypted"), "{error}");
+
+            // and resetting the marker breaks the key wrapping instead of reopening the migration
+            let mut wrapped = wallet.wrapped_key(NODE_WRAPPED_KEY).unwrap().unwrap();
+            wrapped.format = LEGACY_FORMAT;
+            store
+                .put(Collection::WalletKeys, NODE_WRAPPED_KEY, &serde_json::to_vec(&wrapped).unwrap())
+                .unwrap();
+            assert!(Wallet::open(store, &secret_key).is_err());
+        });
+    }
+
+    #[test]
+    fn test_legacy_passphrase_wallet_is_migrated_on_unlock() {
+        block_on(async {
+            let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+            let secret_key = SecretKey::generate(&mut rand::rng());
+            let wallet = Wallet::open(store.clone(), &secret_key).unwrap();
+            wallet.set_passphrase(Some("correct horse")).await.unwrap();
+
+            // Wrapped the way wallets were before the format was recorded
+            let kdf = PassphraseKdf::generate();
+            let data_key = random_bytes::<32>();
+            wallet
+                .put_wrapped_key(PASSPHRASE_WRAPPED_KEY, &data_key, &kdf.derive("correct horse").unwrap(), Some(kdf), LEGACY_FORMAT)
+                .unwrap();
+            let response = serde_json::to_vec(&sample_response("request-1")).unwrap();
+            store.put(Collection::ReceivedCredentials, "request-1", &response).unwrap();
+
+            let reopened = Wallet::open(store.clone(), &secret_key).unwrap();
+            reopened.unlock(Some("correct horse")).await.unwrap();
+            assert_eq!(reopened.values().await.unwrap().len(), 1);
+            let wrapped = reopened.wrapped_key(PASSPHRASE_WRAPPED_KEY).unwrap().unwrap();
+            assert_eq!(wrapped.format, SEALED_RECORDS_FORMAT);
+            assert!(wrapped.kdf.is_some());
+        });
+    }
+}
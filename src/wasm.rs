@@ -1,8 +1,21 @@
+use crate::credentials::compact::verify_compact;
+use crate::credentials::income_credential::ValidityWindow;
+use crate::credentials::income_credential::SignedIncomeCredential;
+use crate::credentials::issuer_profile::IssuerProfile;
+use crate::credentials::officer_approval::ApprovalPolicy;
+use crate::credentials::presentation::PresentationConstraints;
+use crate::credentials::selective_disclosure::DisclosableField;
+use crate::credentials::signer::{CredentialSigner, MemorySigner};
+use crate::credentials::verification::{
+    Clock, CredentialVerifier, OfflineRevocations, OfflineTrustStore, SystemClock, decode_credential,
+};
 use crate::peer_node;
+use crate::storage::indexed_db::{IndexedDbStore, DEFAULT_DB_NAME};
 
 use anyhow::{Context, Result};
+use std::sync::Arc;
 use n0_future::{Stream, StreamExt};
-use iroh::SecretKey;
+use iroh::{PublicKey, SecretKey};
 use serde::Serialize;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber_wasm::MakeConsoleWriter;
@@ -29,7 +42,7 @@ fn start() {
 }
 
 #[wasm_bindgen]
-pub struct PeerNode(peer_node::PeerNode);
+pub struct PeerNode(peer_node::PeerNode, Arc<IndexedDbStore>);
 
 #[wasm_bindgen]
 impl PeerNode {
@@ -37,8 +50,19 @@ impl PeerNode {
     /// 
     /// # Arguments
     /// * `secret_key_str` - Optional hex-encoded secret key string. If None, a new key is generated.
-    /// * `role_str` - The role as a string: "employee", "issuer", or "verifier"
-    pub async fn spawn(secret_key_str: Option<String>, role_str: String) -> Result<Self, JsError> {
+    /// * `role_str` - The role as a string: "employee", "issuer", "verifier", or "processor"
+    /// * `db_name` - Optional IndexedDB database holding the node state, so several
+    ///   wallets can coexist in one browser. Defaults to "trust-triangle".
+    /// * `issuer_profile` - Optional JSON issuer profile replacing the stored one
+    /// * `signing_key_str` - Optional hex-encoded key signing credentials instead of
+    ///   the node key
+    pub async fn spawn(
+        secret_key_str: Option<String>,
+        role_str: String,
+        db_name: Option<String>,
+        issuer_profile: Option<String>,
+        signing_key_str: Option<String>,
+    ) -> Result<Self, JsError> {
         // Parse the optional secret key
         let secret_key = if let Some(key_str) = secret_key_str {
             Some(
@@ -56,10 +80,40 @@ impl PeerNode {
             "employee" => peer_node::Role::Employee,
             "issuer" => peer_node::Role::Issuer,
             "verifier" => peer_node::Role::Verifier,
-            _ => return Err(JsError::new(&format!("Invalid role: {}. Must be 'employee', 'issuer', or 'verifier'", role_str))),
+            "processor" => peer_node::Role::Processor,
+            _ => return Err(JsError::new(&format!("Invalid role: {}. Must be 'employee', 'issuer', 'verifier', or 'processor'", role_str))),
         };
 
-        Ok(Self(peer_node::PeerNode::spawn(secret_key, role).await.map_err(to_js_err)?))
+        let signer = signing_key_str
+            .map(|key_str| key_str.parse::<SecretKey>())
+            .transpose()
+            .context("failed to parse signing key")
+            .map_err(to_js_err)?
+            .map(|key| Arc::new(MemorySigner::new(key)) as Arc<dyn CredentialSigner>);
+
+        let issuer_profile = issuer_profile
+            .map(|profile| serde_json::from_str::<IssuerProfile>(&profile))
+            .transpose()
+            .context("failed to parse issuer profile from JSON")
+            .map_err(to_js_err)?;
+
+        let db_name = db_name.unwrap_or_else(|| DEFAULT_DB_NAME.to_string());
+        let store = Arc::new(
+            IndexedDbStore::open(&db_name)
+                .await
+                .context("failed to open IndexedDB store")
+                .map_err(to_js_err)?
+        );
+        let node = peer_node::PeerNode::spawn(secret_key, role, store.clone(), issuer_profile, signer)
+            .await
+            .map_err(to_js_err)?;
+        Ok(Self(node, store))
+    }
+
+    /// Resolves once every change so far is saved to IndexedDB; fails if any
+    /// change since the node started was not, after which no change is saved
+    pub async fn flush(&self) -> Result<(), JsError> {
+        self.1.flush().await.map_err(to_js_err)
     }
 
     pub fn events(&self) -> JsReadableStream {
@@ -103,9 +157,16 @@ impl PeerNode {
             .map_err(to_js_err)
     }
 
-    /// Approve a pending credential request
-    pub async fn approve_request(&self, request_id: String) -> Result<(), JsError> {
-        self.0.approve_request(request_id)
+    /// Approve a pending credential request, optionally restricting the credential
+    /// to a validity window (RFC3339 timestamps)
+    pub async fn approve_request(
+        &self,
+        request_id: String,
+        valid_from: Option<String>,
+        expires_at: Option<String>,
+    ) -> Result<(), JsError> {
+        let validity = ValidityWindow { valid_from, expires_at };
+        self.0.approve_request(request_id, validity)
             .await
             .map_err(to_js_err)
     }
@@ -117,8 +178,199 @@ impl PeerNode {
             .map_err(to_js_err)
     }
 
+    /// Get all credentials issued by this node (returns JSON string)
+    pub async fn get_issued_credentials(&self) -> Result<String, JsError> {
+        let issued = self.0.get_issued_credentials().await;
+        serde_json::to_string(&issued)
+            .context("failed to serialize issued credentials")
+            .map_err(to_js_err)
+    }
+
+    /// Revoke a credential issued by this node
+    pub async fn revoke_credential(&self, credential_id: String, reason: Option<String>) -> Result<(), JsError> {
+        self.0.revoke_credential(credential_id, reason)
+            .await
+            .map_err(to_js_err)
+    }
+
+    /// Get the organization profile used on issued credentials (returns JSON string)
+    pub async fn get_issuer_profile(&self) -> Result<String, JsError> {
+        let profile = self.0.get_issuer_profile().await;
+        serde_json::to_string(&profile)
+            .context("failed to serialize issuer profile")
+            .map_err(to_js_err)
+    }
+
+    /// Replace the organization profile used on issued credentials (JSON string)
+    pub async fn set_issuer_profile(&self, profile: String) -> Result<(), JsError> {
+        let profile: IssuerProfile = serde_json::from_str(&profile)
+            .context("failed to parse issuer profile from JSON")
+            .map_err(to_js_err)?;
+        self.0.set_issuer_profile(profile)
+            .await
+            .map_err(to_js_err)
+    }
+
+    /// Replace this issuer's key by the hex-encoded `new_secret_key`, announcing the signed
+    /// rotation to verifiers (returns JSON string). Respawn the node with the new key afterwards.
+    pub async fn rotate_key(&self, new_secret_key: String, reason: Option<String>) -> Result<String, JsError> {
+        let new_secret_key = new_secret_key
+            .parse::<SecretKey>()
+            .context("failed to parse new secret key")
+            .map_err(to_js_err)?;
+        let rotation = self.0.rotate_key(&new_secret_key, reason).await.map_err(to_js_err)?;
+        serde_json::to_string(&rotation)
+            .context("failed to serialize key rotation")
+            .map_err(to_js_err)
+    }
+
+    /// Get the binding of the credential signing key to this node (returns JSON
+    /// string, `null` if the node key signs credentials)
+    pub async fn get_key_binding(&self) -> Result<String, JsError> {
+        let binding = self.0.key_binding().await;
+        serde_json::to_string(&binding)
+            .context("failed to serialize key binding")
+            .map_err(to_js_err)
+    }
+
+    /// Unbind the hex-encoded `signing_key` from this node, so verifiers refuse every
+    /// credential it signed (returns JSON string)
+    pub async fn unbind_signing_key(&self, signing_key: String) -> Result<String, JsError> {
+        let signing_key = signing_key
+            .parse::<PublicKey>()
+            .context("failed to parse signing key")
+            .map_err(to_js_err)?;
+        let unbinding = self.0.unbind_signing_key(signing_key).await.map_err(to_js_err)?;
+        serde_json::to_string(&unbinding)
+            .context("failed to serialize key unbinding")
+            .map_err(to_js_err)
+    }
+
+    /// Get this node's `did:iroh`
+    pub fn did(&self) -> String {
+        self.0.did()
+    }
+
+    /// Get this node's DID document (returns JSON string)
+    pub async fn get_did_document(&self) -> Result<String, JsError> {
+        let document = self.0.did_document().await;
+        serde_json::to_string(&document)
+            .context("failed to serialize DID document")
+            .map_err(to_js_err)
+    }
+
+    /// Resolve a `did:iroh` or `did:key` to its verified DID document (returns JSON string)
+    pub async fn resolve_did(&self, did: String) -> Result<String, JsError> {
+        let document = self.0.resolve_did(did).await.map_err(to_js_err)?;
+        serde_json::to_string(&document)
+            .context("failed to serialize DID document")
+            .map_err(to_js_err)
+    }
+
+    /// Get the published approval policy and any change awaiting officers (returns JSON string)
+    pub async fn get_approval_policy(&self) -> Result<String, JsError> {
+        let status = self.0.get_approval_policy().await;
+        serde_json::to_string(&status)
+            .context("failed to serialize approval policy")
+            .map_err(to_js_err)
+    }
+
+    /// Change the officers who must approve credential requests (JSON string); once a
+    /// policy is published, the change awaits the current officers (returns JSON string)
+    pub async fn set_approval_policy(&self, policy: String) -> Result<String, JsError> {
+        let policy: ApprovalPolicy = serde_json::from_str(&policy)
+            .context("failed to parse approval policy from JSON")
+            .map_err(to_js_err)?;
+        let status = self.0.set_approval_policy(policy)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&status)
+            .context("failed to serialize approval policy")
+            .map_err(to_js_err)
+    }
+
+    // Officer methods
+
+    /// Fetch an issuer's requests awaiting officer approval (returns JSON string)
+    pub async fn request_approval_queue(&self, issuer_node_id: String) -> Result<String, JsError> {
+        let issuer_node_id = issuer_node_id
+            .parse()
+            .context("failed to parse issuer node id")
+            .map_err(to_js_err)?;
+        let requests = self.0.request_approval_queue(issuer_node_id).await.map_err(to_js_err)?;
+        serde_json::to_string(&requests)
+            .context("failed to serialize approval queue")
+            .map_err(to_js_err)
+    }
+
+    /// Fetch the change of an issuer's approval policy awaiting officers (returns JSON string)
+    pub async fn request_policy_proposal(&self, issuer_node_id: String) -> Result<String, JsError> {
+        let issuer_node_id = issuer_node_id
+            .parse()
+            .context("failed to parse issuer node id")
+            .map_err(to_js_err)?;
+        let proposal = self.0.request_policy_proposal(issuer_node_id).await.map_err(to_js_err)?;
+        serde_json::to_string(&proposal)
+            .context("failed to serialize policy proposal")
+            .map_err(to_js_err)
+    }
+
+    /// Check and approve the credential an issuer signed for one of its requests, as
+    /// its officer (returns JSON string)
+    pub async fn approve_as_officer(&self, issuer_node_id: String, request_id: String) -> Result<String, JsError> {
+        let issuer_node_id = issuer_node_id
+            .parse()
+            .context("failed to parse issuer node id")
+            .map_err(to_js_err)?;
+        let progress = self.0.approve_as_officer(issuer_node_id, request_id).await.map_err(to_js_err)?;
+        serde_json::to_string(&progress)
+            .context("failed to serialize approval progress")
+            .map_err(to_js_err)
+    }
+
+    /// Sign the proposed version of an issuer's approval policy as its officer
+    /// (returns JSON string)
+    pub async fn approve_policy_as_officer(&self, issuer_node_id: String, version: u32) -> Result<String, JsError> {
+        let issuer_node_id = issuer_node_id
+            .parse()
+            .context("failed to parse issuer node id")
+            .map_err(to_js_err)?;
+        let status = self.0.approve_policy_as_officer(issuer_node_id, version).await.map_err(to_js_err)?;
+        serde_json::to_string(&status)
+            .context("failed to serialize approval policy")
+            .map_err(to_js_err)
+    }
+
     // Verifier methods
 
+    /// Fetch and verify an issuer's signed profile (returns JSON string)
+    pub async fn request_issuer_profile(&self, node_id: String) -> Result<String, JsError> {
+        let node_id = node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let profile = self.0.request_issuer_profile(node_id).await.map_err(to_js_err)?;
+        serde_json::to_string(&profile)
+            .context("failed to serialize issuer profile")
+            .map_err(to_js_err)
+    }
+
+    /// Get all known revocation notices (returns JSON string)
+    pub async fn get_revocations(&self) -> Result<String, JsError> {
+        let revocations = self.0.get_revocations().await;
+        serde_json::to_string(&revocations)
+            .context("failed to serialize revocations")
+            .map_err(to_js_err)
+    }
+
+    /// Get all known issuer key rotations (returns JSON string)
+    pub async fn get_key_rotations(&self) -> Result<String, JsError> {
+        let rotations = self.0.get_key_rotations().await;
+        serde_json::to_string(&rotations)
+            .context("failed to serialize key rotations")
+            .map_err(to_js_err)
+    }
+
     /// Add a trusted issuer
     pub async fn add_trusted_issuer(&self, node_id: String) -> Result<(), JsError> {
         let node_id = node_id
@@ -159,6 +411,42 @@ impl PeerNode {
             .map_err(to_js_err)
     }
 
+    /// Export the trusted issuers with their key rotations and signing key bindings,
+    /// for `verify_credential_file` (returns JSON string)
+    pub async fn export_trust_store(&self) -> Result<String, JsError> {
+        let trust_store = self.0.export_trust_store().await;
+        serde_json::to_string(&trust_store)
+            .context("failed to serialize trust store")
+            .map_err(to_js_err)
+    }
+
+    /// Export the cached status lists and revocation notices, for
+    /// `verify_credential_file` (returns JSON string)
+    pub async fn export_revocations(&self) -> Result<String, JsError> {
+        let revocations = self.0.export_revocations().await;
+        serde_json::to_string(&revocations)
+            .context("failed to serialize revocations")
+            .map_err(to_js_err)
+    }
+
+    /// Get the verification policy (returns JSON string)
+    pub async fn get_verification_policy(&self) -> Result<String, JsError> {
+        let policy = self.0.get_verification_policy().await;
+        serde_json::to_string(&policy)
+            .context("failed to serialize verification policy")
+            .map_err(to_js_err)
+    }
+
+    /// Replace the verification policy (JSON string, e.g. `{"maxAgeDays": 90}`)
+    pub async fn set_verification_policy(&self, policy: String) -> Result<(), JsError> {
+        let policy: peer_node::VerificationPolicy = serde_json::from_str(&policy)
+            .context("failed to parse verification policy from JSON")
+            .map_err(to_js_err)?;
+        self.0.set_verification_policy(policy)
+            .await
+            .map_err(to_js_err)
+    }
+
     /// Get all verified credentials (returns JSON string)
     pub async fn get_verified_credentials(&self) -> Result<String, JsError> {
         let credentials = self.0.get_verified_credentials().await;
@@ -167,6 +455,30 @@ impl PeerNode {
             .map_err(to_js_err)
     }
 
+    /// Get every credential verified in one presentation, in bundle order (returns JSON string)
+    pub async fn get_verified_presentation(&self, presentation_id: String) -> Result<String, JsError> {
+        let records = self.0.get_verified_presentation(&presentation_id).await;
+        serde_json::to_string(&records)
+            .context("failed to serialize verified credentials")
+            .map_err(to_js_err)
+    }
+
+    /// Get all verified selective disclosures (returns JSON string)
+    pub async fn get_verified_disclosures(&self) -> Result<String, JsError> {
+        let records = self.0.get_verified_disclosures().await;
+        serde_json::to_string(&records)
+            .context("failed to serialize verified disclosures")
+            .map_err(to_js_err)
+    }
+
+    /// Get all verified net salary threshold proofs (returns JSON string)
+    pub async fn get_verified_threshold_proofs(&self) -> Result<String, JsError> {
+        let records = self.0.get_verified_threshold_proofs().await;
+        serde_json::to_string(&records)
+            .context("failed to serialize verified threshold proofs")
+            .map_err(to_js_err)
+    }
+
     /// Get a specific verified credential by presentation ID (returns JSON string or null)
     pub async fn get_verified_credential(&self, presentation_id: String) -> Result<Option<String>, JsError> {
         let credential = self.0.get_verified_credential(&presentation_id).await;
@@ -183,9 +495,9 @@ impl PeerNode {
 
     // Employee methods
 
-    /// Get all received credentials (returns JSON string)
+    /// Get all received credentials (returns JSON string); fails while the wallet is locked
     pub async fn get_received_credentials(&self) -> Result<String, JsError> {
-        let credentials = self.0.get_received_credentials().await;
+        let credentials = self.0.get_received_credentials().await.map_err(to_js_err)?;
         serde_json::to_string(&credentials)
             .context("failed to serialize received credentials")
             .map_err(to_js_err)
@@ -193,7 +505,7 @@ impl PeerNode {
 
     /// Get a specific received credential by request ID (returns JSON string or null)
     pub async fn get_received_credential(&self, request_id: String) -> Result<Option<String>, JsError> {
-        let credential = self.0.get_received_credential(request_id).await;
+        let credential = self.0.get_received_credential(request_id).await.map_err(to_js_err)?;
         match credential {
             Some(cred) => {
                 let json = serde_json::to_string(&cred)
@@ -204,6 +516,225 @@ impl PeerNode {
             None => Ok(None),
         }
     }
+
+    /// Ask a verifier for a presentation nonce (returns JSON string)
+    pub async fn request_challenge(&self, verifier_node_id: String) -> Result<String, JsError> {
+        let verifier_node_id = verifier_node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let challenge = self.0.request_challenge(verifier_node_id).await.map_err(to_js_err)?;
+        serde_json::to_string(&challenge)
+            .context("failed to serialize challenge")
+            .map_err(to_js_err)
+    }
+
+    /// Sign credentials (JSON array) over a verifier's nonce (returns JSON envelope)
+    pub fn sign_presentation(
+        &self,
+        verifier_node_id: String,
+        nonce: String,
+        credentials: String,
+    ) -> Result<String, JsError> {
+        let verifier_node_id = verifier_node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let credentials: Vec<SignedIncomeCredential> = serde_json::from_str(&credentials)
+            .context("failed to parse credentials from JSON")
+            .map_err(to_js_err)?;
+        let credentials: Vec<_> = credentials.iter().collect();
+        let envelope = self.0
+            .sign_presentation(verifier_node_id, nonce, &credentials)
+            .map_err(to_js_err)?;
+        serde_json::to_string(&envelope)
+            .context("failed to serialize presentation envelope")
+            .map_err(to_js_err)
+    }
+
+    /// Ask an employee for a credential matching JSON constraints (returns JSON request)
+    pub async fn request_presentation(
+        &self,
+        employee_node_id: String,
+        constraints: String,
+        expires_in_minutes: Option<u32>,
+    ) -> Result<String, JsError> {
+        let employee_node_id = employee_node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let constraints: PresentationConstraints = serde_json::from_str(&constraints)
+            .context("failed to parse constraints from JSON")
+            .map_err(to_js_err)?;
+        let request = self.0
+            .request_presentation(employee_node_id, constraints, expires_in_minutes)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&request)
+            .context("failed to serialize presentation request")
+            .map_err(to_js_err)
+    }
+
+    /// Get all presentation requests sent to employees (returns JSON string)
+    pub async fn get_sent_presentation_requests(&self) -> Result<String, JsError> {
+        let requests = self.0.get_sent_presentation_requests().await;
+        serde_json::to_string(&requests)
+            .context("failed to serialize presentation requests")
+            .map_err(to_js_err)
+    }
+
+    /// Get all presentation requests received from verifiers (returns JSON string)
+    pub async fn get_presentation_requests(&self) -> Result<String, JsError> {
+        let requests = self.0.get_presentation_requests().await;
+        serde_json::to_string(&requests)
+            .context("failed to serialize presentation requests")
+            .map_err(to_js_err)
+    }
+
+    /// Get the wallet credentials satisfying a presentation request (returns JSON string)
+    pub async fn get_matching_credentials(&self, request_id: String) -> Result<String, JsError> {
+        let credentials = self.0.get_matching_credentials(request_id).await.map_err(to_js_err)?;
+        serde_json::to_string(&credentials)
+            .context("failed to serialize credentials")
+            .map_err(to_js_err)
+    }
+
+    /// Present credentials in answer to a presentation request (returns JSON request)
+    pub async fn approve_presentation_request(&self, request_id: String, credential_ids: Vec<String>) -> Result<String, JsError> {
+        let request = self.0
+            .approve_presentation_request(request_id, credential_ids)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&request)
+            .context("failed to serialize presentation request")
+            .map_err(to_js_err)
+    }
+
+    /// Prove the net salary reaches a presentation request's minimum (returns JSON request)
+    pub async fn prove_presentation_request(&self, request_id: String, credential_id: String) -> Result<String, JsError> {
+        let request = self.0
+            .prove_presentation_request(request_id, credential_id)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&request)
+            .context("failed to serialize presentation request")
+            .map_err(to_js_err)
+    }
+
+    /// Decline a presentation request
+    pub async fn reject_presentation_request(&self, request_id: String, reason: Option<String>) -> Result<(), JsError> {
+        self.0.reject_presentation_request(request_id, reason)
+            .await
+            .map_err(to_js_err)
+    }
+
+    /// Present credentials (JSON array) to a verifier as one bundle (returns JSON verdict)
+    pub async fn present_credentials(&self, verifier_node_id: String, credentials: String) -> Result<String, JsError> {
+        let verifier_node_id = verifier_node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let credentials: Vec<SignedIncomeCredential> = serde_json::from_str(&credentials)
+            .context("failed to parse credentials from JSON")
+            .map_err(to_js_err)?;
+        let verdict = self.0
+            .present_credentials(verifier_node_id, credentials)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&verdict)
+            .context("failed to serialize presentation verdict")
+            .map_err(to_js_err)
+    }
+
+    /// Present a wallet credential revealing only `fields`, e.g. `["net_salary", "pay_period"]`
+    /// (returns JSON verification result)
+    pub async fn present_disclosure(
+        &self,
+        verifier_node_id: String,
+        credential_id: String,
+        fields: Vec<String>,
+    ) -> Result<String, JsError> {
+        let verifier_node_id = verifier_node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let fields = fields
+            .into_iter()
+            .map(|field| serde_json::from_value(serde_json::Value::String(field)))
+            .collect::<Result<Vec<DisclosableField>, _>>()
+            .context("failed to parse disclosed fields")
+            .map_err(to_js_err)?;
+        let result = self.0
+            .present_disclosure(verifier_node_id, credential_id, fields)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&result)
+            .context("failed to serialize verification result")
+            .map_err(to_js_err)
+    }
+
+    /// Present a credential as the issuer's compact JWS (returns JSON verification result)
+    pub async fn present_jws(&self, verifier_node_id: String, jws: String) -> Result<String, JsError> {
+        let verifier_node_id = verifier_node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let result = self.0
+            .present_jws(verifier_node_id, jws)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&result)
+            .context("failed to serialize verification result")
+            .map_err(to_js_err)
+    }
+
+    /// Prove a wallet credential's net salary reaches `threshold`, e.g. "5000.00",
+    /// without revealing it (returns JSON verification result)
+    pub async fn present_threshold_proof(
+        &self,
+        verifier_node_id: String,
+        credential_id: String,
+        threshold: String,
+    ) -> Result<String, JsError> {
+        let verifier_node_id = verifier_node_id
+            .parse()
+            .context("failed to parse node id")
+            .map_err(to_js_err)?;
+        let result = self.0
+            .present_threshold_proof(verifier_node_id, credential_id, threshold)
+            .await
+            .map_err(to_js_err)?;
+        serde_json::to_string(&result)
+            .context("failed to serialize verification result")
+            .map_err(to_js_err)
+    }
+
+    /// Get the wallet status (returns JSON string, e.g. `{"locked": false, "protection": "node_key"}`)
+    pub async fn wallet_status(&self) -> Result<String, JsError> {
+        let status = self.0.wallet_status().await.map_err(to_js_err)?;
+        serde_json::to_string(&status)
+            .context("failed to serialize wallet status")
+            .map_err(to_js_err)
+    }
+
+    /// Lock the credential wallet
+    pub async fn lock_wallet(&self) {
+        self.0.lock_wallet().await
+    }
+
+    /// Unlock the credential wallet with its passphrase, or the node key if omitted
+    pub async fn unlock_wallet(&self, passphrase: Option<String>) -> Result<(), JsError> {
+        self.0.unlock_wallet(passphrase)
+            .await
+            .map_err(to_js_err)
+    }
+
+    /// Protect the credential wallet with a passphrase, or the node key if omitted
+    pub async fn set_wallet_passphrase(&self, passphrase: Option<String>) -> Result<(), JsError> {
+        self.0.set_wallet_passphrase(passphrase)
+            .await
+            .map_err(to_js_err)
+    }
 }
 
 fn to_js_err(err: impl Into<anyhow::Error>) -> JsError {
@@ -218,6 +749,69 @@ fn into_js_readable_stream<T: Serialize>(
     ReadableStream::from_stream(stream).into_raw()
 }
 
+/// Encode a credential (JSON) as base45 text for a QR code
+#[wasm_bindgen]
+pub fn credential_to_qr(credential: String) -> Result<String, JsError> {
+    let signed: SignedIncomeCredential = serde_json::from_str(&credential)
+        .context("failed to parse credential from JSON")
+        .map_err(to_js_err)?;
+    signed.to_qr_text().map_err(to_js_err)
+}
+
+/// Check the signatures of a scanned compact credential without network access
+/// (returns the credential as JSON)
+#[wasm_bindgen]
+pub fn verify_qr_credential(text: String) -> Result<String, JsError> {
+    let signed = verify_compact(&text).map_err(to_js_err)?;
+    serde_json::to_string(&signed)
+        .context("failed to serialize credential")
+        .map_err(to_js_err)
+}
+
+/// Verify a credential file (W3C VC JSON, JWT-VC or compact text) without a node,
+/// against a trust store and revocations as exported by a verifier node, at `now`
+/// (RFC3339) or the current time; legacy statement signatures are refused unless
+/// `accept_legacy_signatures`, and credentials whose issuer has no status list in
+/// `revocations` unless `accept_unavailable_status` (returns JSON check)
+#[wasm_bindgen]
+pub async fn verify_credential_file(
+    contents: String,
+    trust_store: String,
+    revocations: Option<String>,
+    now: Option<String>,
+    max_age_days: Option<u32>,
+    accept_legacy_signatures: Option<bool>,
+    accept_unavailable_status: Option<bool>,
+) -> Result<String, JsError> {
+    let credential = decode_credential(&contents).map_err(to_js_err)?;
+    let trust_store: OfflineTrustStore = serde_json::from_str(&trust_store)
+        .context("failed to parse trust store from JSON")
+        .map_err(to_js_err)?;
+    let revocations: OfflineRevocations = match revocations {
+        Some(revocations) => serde_json::from_str(&revocations)
+            .context("failed to parse revocations from JSON")
+            .map_err(to_js_err)?,
+        None => OfflineRevocations::default(),
+    };
+    let clock: Box<dyn Clock> = match now {
+        Some(now) => Box::new(
+            now.parse::<chrono::DateTime<chrono::Utc>>()
+                .context("failed to parse time")
+                .map_err(to_js_err)?,
+        ),
+        None => Box::new(SystemClock),
+    };
+    let check = CredentialVerifier::new(&trust_store, &revocations, &*clock)
+        .with_max_age(max_age_days.map(|days| chrono::Duration::days(days.into())))
+        .with_legacy_signatures(accept_legacy_signatures.unwrap_or_default())
+        .with_unavailable_status(accept_unavailable_status.unwrap_or_default())
+        .check(&credential)
+        .await;
+    serde_json::to_string(&check)
+        .context("failed to serialize credential check")
+        .map_err(to_js_err)
+}
+
 #[wasm_bindgen]
 pub fn generate_key() -> String {
     let secret_key = SecretKey::generate(&mut rand::rng());